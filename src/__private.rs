@@ -0,0 +1,110 @@
+//! Support for the expansions of the `windows-permissions-macros` crate.
+//!
+//! Nothing here is part of the public API.
+
+use crate::{sddl, SecurityDescriptor, Sid};
+use std::io;
+
+/// A SID with `N` sub-authorities, laid out as in its binary form
+#[repr(C)]
+pub struct StaticSid<const N: usize> {
+    revision: u8,
+    sub_authority_count: u8,
+    id_authority: [u8; 6],
+    sub_authorities: [u32; N],
+}
+
+impl<const N: usize> StaticSid<N> {
+    /// Lay out a SID
+    ///
+    /// # Panics
+    ///
+    /// Panics, at compile time when used in a constant, if there are more
+    /// than 15 sub-authorities.
+    pub const fn new(id_authority: [u8; 6], mut sub_authorities: [u32; N]) -> Self {
+        assert!(N <= 15, "A SID has at most 15 sub-authorities");
+
+        // The binary form is little-endian
+        let mut index = 0;
+        while index < N {
+            sub_authorities[index] = sub_authorities[index].to_le();
+            index += 1;
+        }
+
+        Self {
+            revision: 1,
+            sub_authority_count: N as u8,
+            id_authority,
+            sub_authorities,
+        }
+    }
+
+    /// Use the layout as a SID
+    pub const fn as_sid(&'static self) -> &'static Sid {
+        unsafe { &*(self as *const Self as *const Sid) }
+    }
+}
+
+/// Bytes aligned like the Windows security structures
+///
+/// The field is private, so [`from_sddl_bytes`](Self::from_sddl_bytes) is
+/// the only way to build one.
+#[repr(C, align(4))]
+pub struct Aligned<const N: usize>([u8; N]);
+
+impl<const N: usize> Aligned<N> {
+    /// Wrap a self-relative security descriptor
+    ///
+    /// Only the `sddl!` expansion calls this, with bytes that [`sddl_bytes`]
+    /// built and so are known to be valid. Nothing else may call it.
+    ///
+    /// # Panics
+    ///
+    /// Panics, at compile time when used in a constant, if the bytes do not
+    /// start with a self-relative security descriptor header.
+    pub const fn from_sddl_bytes(bytes: [u8; N]) -> Self {
+        use crate::winnt::SE_SELF_RELATIVE;
+
+        assert!(
+            N >= 20
+                && bytes[0] == 1
+                && u16::from_le_bytes([bytes[2], bytes[3]]) & SE_SELF_RELATIVE != 0,
+            "Not a self-relative security descriptor"
+        );
+
+        Aligned(bytes)
+    }
+
+    /// Use the bytes as a security descriptor
+    pub const fn as_security_descriptor(&'static self) -> &'static SecurityDescriptor {
+        // Safety: `from_sddl_bytes` only gets valid self-relative security
+        // descriptors from the `sddl!` expansion
+        unsafe { &*(self as *const Self as *const SecurityDescriptor) }
+    }
+}
+
+/// Parse a SID string or alias into its ID authority and sub-authorities
+pub fn parse_sid(s: &str) -> io::Result<([u8; 6], Vec<u32>)> {
+    sddl::parse_sid(s)
+}
+
+/// Parse an SDDL string into a self-relative security descriptor
+pub fn sddl_bytes(s: &str) -> io::Result<Vec<u8>> {
+    sddl::parse_sd(s)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::LocalBox;
+
+    #[test]
+    fn static_sid() {
+        const SID: &StaticSid<2> = &StaticSid::new([0, 0, 0, 0, 0, 5], [32, 544]);
+        let parsed: LocalBox<Sid> = "S-1-5-32-544".parse().unwrap();
+
+        assert_eq!(SID.as_sid(), &*parsed);
+        assert_eq!(SID.as_sid().to_bytes(), parsed.to_bytes());
+        assert_eq!(SID.as_sid().sub_authorities(), &[32, 544]);
+    }
+}
@@ -0,0 +1,314 @@
+//! Audit entries in the SACL, and when they fire
+
+use super::{AccessCheck, ConditionResult, ObjectTypeList};
+use crate::constants::{AccessRights, AceFlags, AceType};
+use crate::{Ace, Guid};
+
+/// Whether an audit entry fires for a successful or a failed access
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AuditKind {
+    /// The access was granted, and the entry has the `SuccessfulAccess` flag
+    Success,
+    /// The access was denied, and the entry has the `FailedAccess` flag
+    Failure,
+}
+
+/// An audit entry in the SACL that fires for an access
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AuditEvent {
+    index: u32,
+    kind: AuditKind,
+    rights: AccessRights,
+    object_type: Option<Guid>,
+}
+
+impl AuditEvent {
+    /// Get the index of the audit entry in the SACL
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Get whether the entry fired for a successful or a failed access
+    pub fn kind(&self) -> AuditKind {
+        self.kind
+    }
+
+    /// Get the rights that the entry audits
+    ///
+    /// For a successful access, these are the granted rights that the entry
+    /// lists. For a failed access, they are the desired rights that it lists.
+    pub fn rights(&self) -> AccessRights {
+        self.rights
+    }
+
+    /// Get the object type of the entry, for object audit entries
+    pub fn object_type(&self) -> Option<Guid> {
+        self.object_type
+    }
+}
+
+impl AccessCheck<'_> {
+    /// Find the audit entries in the SACL that fire when the token asks for
+    /// `desired` and is given `granted`
+    ///
+    /// The access is successful if every desired right is granted, and
+    /// failed otherwise. An entry fires if:
+    ///
+    /// - it is a `SYSTEM_AUDIT_ACE_TYPE` or `SYSTEM_AUDIT_OBJECT_ACE_TYPE`
+    ///   entry, or a callback variant of one, that is not inherit-only
+    /// - its SID is the token's user or one of its enabled or deny-only
+    ///   groups
+    /// - its condition is true, for callback entries; a condition that is
+    ///   unknown, such as one that reads a missing claim, does not fire
+    /// - it has the `SuccessfulAccess` or `FailedAccess` flag for the
+    ///   outcome
+    /// - it lists one of the granted rights (on success) or desired rights
+    ///   (on failure), after generic rights are mapped
+    ///
+    /// Of the inheritance flags, only `InheritOnly` is checked. The others
+    /// only control how the entry is copied to child objects, so they have
+    /// no effect here.
+    ///
+    /// Object audit entries for specific object types are skipped; use
+    /// [`audit_by_type`](Self::audit_by_type) to include them.
+    ///
+    /// ```
+    /// use windows_permissions::access::{AccessCheck, AuditKind};
+    /// use windows_permissions::constants::{AccessRights, GroupAttributes};
+    /// use windows_permissions::{LocalBox, SecurityDescriptor, Token};
+    ///
+    /// // Audit failed writes by anyone, and successful deletes by users
+    /// let sd: LocalBox<SecurityDescriptor> =
+    ///     "D:(A;;FR;;;BU)S:(AU;FA;FW;;;WD)(AU;SA;SD;;;BU)".parse().unwrap();
+    /// let token = Token::new("S-1-5-21-1-2-3-1001".parse().unwrap())
+    ///     .with_group("S-1-1-0".parse().unwrap(), GroupAttributes::Enabled)
+    ///     .with_group("S-1-5-32-545".parse().unwrap(), GroupAttributes::Enabled);
+    ///
+    /// let check = AccessCheck::new(&sd, &token);
+    /// let desired = AccessRights::FileGenericWrite;
+    /// let result = check.check(desired);
+    ///
+    /// let events = check.audit(desired, result.granted());
+    /// assert_eq!(events.len(), 1);
+    /// assert_eq!(events[0].index(), 0);
+    /// assert_eq!(events[0].kind(), AuditKind::Failure);
+    /// ```
+    pub fn audit(&self, desired: AccessRights, granted: AccessRights) -> Vec<AuditEvent> {
+        self.audit_events(desired, granted, None)
+    }
+
+    /// Find the audit entries in the SACL that fire when the token asks for
+    /// `desired` on an object type tree and is given `granted`
+    ///
+    /// This is the same as [`audit`](Self::audit), except that object audit
+    /// entries also fire if their object type is in `types`.
+    pub fn audit_by_type(
+        &self,
+        types: &ObjectTypeList,
+        desired: AccessRights,
+        granted: AccessRights,
+    ) -> Vec<AuditEvent> {
+        self.audit_events(desired, granted, Some(types))
+    }
+
+    fn audit_events(
+        &self,
+        desired: AccessRights,
+        granted: AccessRights,
+        types: Option<&ObjectTypeList>,
+    ) -> Vec<AuditEvent> {
+        let sacl = match self.sacl {
+            Some(sacl) => sacl,
+            None => return vec![],
+        };
+
+        let desired = self.mapping.map(desired);
+        let granted = self.mapping.map(granted);
+        let (kind, flag, rights) = if granted.contains(desired) {
+            (AuditKind::Success, AceFlags::SuccessfulAccess, granted)
+        } else {
+            (AuditKind::Failure, AceFlags::FailedAccess, desired)
+        };
+
+        let sids = self.discretionary_sids();
+        let resource_claims = self.resource_claims();
+
+        sacl.aces()
+            .enumerate()
+            .filter_map(|(index, ace)| {
+                if !is_audit(ace)
+                    || ace.flags().contains(AceFlags::InheritOnly)
+                    || !ace.flags().contains(flag)
+                {
+                    return None;
+                }
+
+                let object_type = ace.object_type();
+                if let Some(object_type) = object_type {
+                    types?.position(object_type)?;
+                }
+
+                let ace_sid = ace.sid()?;
+                if !sids.iter().any(|s| s.denies && s.sid == ace_sid) {
+                    return None;
+                }
+
+                // Member_of counts deny-only groups, as the SID match does,
+                // but only a condition that is known to be true fires
+                if ace.is_callback_ace()
+                    && self.condition_result(ace, &sids, false, &resource_claims)
+                        != ConditionResult::True
+                {
+                    return None;
+                }
+
+                let rights = self.mapping.map(ace.mask()) & rights;
+                if rights.is_empty() {
+                    return None;
+                }
+
+                Some(AuditEvent {
+                    index: index as u32,
+                    kind,
+                    rights,
+                    object_type,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Whether an ACE is an audit entry
+fn is_audit(ace: &Ace) -> bool {
+    matches!(
+        ace.ace_type(),
+        AceType::SYSTEM_AUDIT_ACE_TYPE
+            | AceType::SYSTEM_AUDIT_OBJECT_ACE_TYPE
+            | AceType::SYSTEM_AUDIT_CALLBACK_ACE_TYPE
+            | AceType::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::constants::GroupAttributes;
+    use crate::{LocalBox, SecurityDescriptor, Sid, Token};
+
+    fn sid(s: &str) -> LocalBox<Sid> {
+        s.parse().unwrap()
+    }
+
+    fn audit(sddl: &str, token: &Token, desired: AccessRights) -> Vec<(u32, AuditKind)> {
+        let sd: LocalBox<SecurityDescriptor> = sddl.parse().unwrap();
+        let check = AccessCheck::new(&sd, token);
+        let granted = check.check(desired).granted();
+
+        check
+            .audit(desired, granted)
+            .iter()
+            .map(|event| (event.index(), event.kind()))
+            .collect()
+    }
+
+    #[test]
+    fn success_and_failure() {
+        let token = Token::new(sid("S-1-5-21-1-2-3-1001"))
+            .with_group(sid("BU"), GroupAttributes::Enabled)
+            .with_group(sid("BA"), GroupAttributes::UseForDenyOnly)
+            .with_group(sid("AU"), GroupAttributes::empty());
+        let read = AccessRights::FileGenericRead;
+        let write = AccessRights::FileGenericWrite;
+        let sacl = "S:(AU;SAFA;FA;;;BU)(AU;SA;FR;;;BU)(AU;FA;FW;;;BU)(AU;SAFA;FA;;;BA)\
+                    (AU;SAFA;FA;;;AU)(AU;SAFAIO;FA;;;BU)(AU;SA;GR;;;BU)";
+
+        assert_eq!(
+            audit(&format!("D:(A;;FR;;;BU){}", sacl), &token, read),
+            vec![
+                (0, AuditKind::Success),
+                (1, AuditKind::Success),
+                (3, AuditKind::Success),
+                (6, AuditKind::Success),
+            ]
+        );
+        assert_eq!(
+            audit(&format!("D:(A;;FR;;;BU){}", sacl), &token, write),
+            vec![
+                (0, AuditKind::Failure),
+                (2, AuditKind::Failure),
+                (3, AuditKind::Failure),
+            ]
+        );
+
+        // Only the rights the entry lists are reported
+        let sd: LocalBox<SecurityDescriptor> = "S:(AU;SA;SDWD;;;BU)".parse().unwrap();
+        let events =
+            AccessCheck::new(&sd, &token).audit(AccessRights::Delete, AccessRights::Delete);
+        assert_eq!(events[0].rights(), AccessRights::Delete);
+
+        // No SACL, no events
+        assert!(audit("D:(A;;FR;;;BU)", &token, read).is_empty());
+    }
+
+    #[test]
+    fn conditions() {
+        let token = Token::new(sid("S-1-5-21-1-2-3-1001"))
+            .with_group(sid("BU"), GroupAttributes::Enabled)
+            .with_group(sid("BA"), GroupAttributes::UseForDenyOnly);
+        let read = AccessRights::FileGenericRead;
+
+        assert_eq!(
+            audit(
+                "D:(A;;FR;;;BU)S:(XU;SA;FR;;;BU;(Member_of {SID(BA)}))\
+                 (XU;SA;FR;;;BU;(Member_of {SID(AU)}))(XU;SA;FR;;;BU;(@User.Missing == 1))",
+                &token,
+                read
+            ),
+            vec![(0, AuditKind::Success)]
+        );
+    }
+
+    #[test]
+    fn unknown_conditions_do_not_fire() {
+        let token =
+            Token::new(sid("S-1-5-21-1-2-3-1001")).with_group(sid("BU"), GroupAttributes::Enabled);
+        let read = AccessRights::FileGenericRead;
+
+        let sd: LocalBox<SecurityDescriptor> =
+            "D:(A;;FR;;;BU)S:(XU;SA;FR;;;BU;(@User.Missing == 1))(XU;FA;FR;;;BU;(@User.Missing == 1))"
+                .parse()
+                .unwrap();
+        let check = AccessCheck::new(&sd, &token);
+        let ace = sd.sacl().unwrap().get_ace(0).unwrap();
+        let sids = check.discretionary_sids();
+        assert_eq!(
+            check.condition_result(ace, &sids, false, &[]),
+            ConditionResult::Unknown
+        );
+
+        assert!(check.audit(read, read).is_empty());
+        assert!(check.audit(read, AccessRights::empty()).is_empty());
+    }
+
+    #[test]
+    fn object_types() {
+        let token = Token::new(sid("S-1-5-21-1-2-3-1001"));
+        let guid = |n: u8| Guid::from_bytes([n; 16]);
+        let list = ObjectTypeList::new(guid(1)).with_property_set(guid(2));
+        let sddl = format!(
+            "S:(OU;SA;0x20;{};;S-1-5-21-1-2-3-1001)(OU;SA;0x20;{};;S-1-5-21-1-2-3-1001)",
+            guid(2),
+            guid(3)
+        );
+        let sd: LocalBox<SecurityDescriptor> = sddl.parse().unwrap();
+        let check = AccessCheck::new(&sd, &token);
+        let prop = AccessRights::Bit5;
+
+        assert!(check.audit(prop, prop).is_empty());
+
+        let events = check.audit_by_type(&list, prop, prop);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].index(), 0);
+        assert_eq!(events[0].object_type(), Some(guid(2)));
+    }
+}
@@ -0,0 +1,509 @@
+//! Evaluation of conditional expressions against claims and group SIDs
+
+use crate::constants::{ClaimFlags, GroupAttributes};
+use crate::structures::condition::*;
+use crate::token::{ClaimAttribute, ClaimValues};
+use crate::{Condition, LocalBox, Sid, Token};
+use std::cmp::Ordering;
+
+/// The outcome of a conditional expression
+///
+/// Conditions use three-valued logic. An expression is `Unknown` when it
+/// refers to a missing attribute or compares values of different types.
+/// Conditional allow ACEs only apply when their condition is `True`, but
+/// conditional deny ACEs apply unless their condition is `False`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConditionResult {
+    /// The condition holds
+    True,
+    /// The condition does not hold
+    False,
+    /// The condition could not be evaluated
+    Unknown,
+}
+
+impl ConditionResult {
+    fn from_bool(value: bool) -> Self {
+        if value {
+            ConditionResult::True
+        } else {
+            ConditionResult::False
+        }
+    }
+
+    fn not(self) -> Self {
+        match self {
+            ConditionResult::True => ConditionResult::False,
+            ConditionResult::False => ConditionResult::True,
+            ConditionResult::Unknown => ConditionResult::Unknown,
+        }
+    }
+
+    fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (ConditionResult::False, _) | (_, ConditionResult::False) => ConditionResult::False,
+            (ConditionResult::True, ConditionResult::True) => ConditionResult::True,
+            _ => ConditionResult::Unknown,
+        }
+    }
+
+    fn or(self, other: Self) -> Self {
+        match (self, other) {
+            (ConditionResult::True, _) | (_, ConditionResult::True) => ConditionResult::True,
+            (ConditionResult::False, ConditionResult::False) => ConditionResult::False,
+            _ => ConditionResult::Unknown,
+        }
+    }
+}
+
+/// Everything a condition can refer to
+pub(crate) struct ClaimContext<'a> {
+    /// Whether a SID counts for `Member_of`
+    pub(crate) member_of: &'a dyn Fn(&Sid) -> bool,
+    /// Whether a SID counts for `Device_Member_of`
+    pub(crate) device_member_of: &'a dyn Fn(&Sid) -> bool,
+    pub(crate) user_claims: &'a [ClaimAttribute],
+    pub(crate) device_claims: &'a [ClaimAttribute],
+    pub(crate) resource_claims: &'a [ClaimAttribute],
+}
+
+impl Condition {
+    /// Evaluate the condition for a token, given the resource attributes of
+    /// the object
+    ///
+    /// `Member_of` looks at the token's user and enabled groups, and
+    /// `Device_Member_of` at its enabled device groups. Disabled claims are
+    /// treated as missing.
+    ///
+    /// ```
+    /// use windows_permissions::access::ConditionResult;
+    /// use windows_permissions::constants::{ClaimFlags, GroupAttributes};
+    /// use windows_permissions::token::{ClaimAttribute, ClaimValues};
+    /// use windows_permissions::{Condition, Token};
+    ///
+    /// let condition: Condition =
+    ///     "(Member_of {SID(BA)}) && (@Resource.Confidentiality <= @User.Clearance)"
+    ///         .parse()
+    ///         .unwrap();
+    ///
+    /// let clearance = |level| {
+    ///     ClaimAttribute::new("Clearance", ClaimFlags::empty(), ClaimValues::Int64(vec![level]))
+    /// };
+    /// let confidentiality = [ClaimAttribute::new(
+    ///     "Confidentiality",
+    ///     ClaimFlags::empty(),
+    ///     ClaimValues::Uint64(vec![2]),
+    /// )];
+    ///
+    /// let admin = Token::new("S-1-5-21-1-2-3-500".parse().unwrap())
+    ///     .with_group("S-1-5-32-544".parse().unwrap(), GroupAttributes::Enabled);
+    ///
+    /// assert_eq!(
+    ///     condition.evaluate(&admin.clone().with_user_claim(clearance(3)), &confidentiality),
+    ///     ConditionResult::True
+    /// );
+    /// assert_eq!(
+    ///     condition.evaluate(&admin.clone().with_user_claim(clearance(1)), &confidentiality),
+    ///     ConditionResult::False
+    /// );
+    /// assert_eq!(condition.evaluate(&admin, &confidentiality), ConditionResult::Unknown);
+    /// ```
+    pub fn evaluate(&self, token: &Token, resource_claims: &[ClaimAttribute]) -> ConditionResult {
+        let enabled = |attributes: GroupAttributes| {
+            attributes.contains(GroupAttributes::Enabled)
+                && !attributes.contains(GroupAttributes::UseForDenyOnly)
+        };
+
+        let member_of = |sid: &Sid| {
+            (token.user().sid() == sid
+                && !token
+                    .user()
+                    .attributes()
+                    .contains(GroupAttributes::UseForDenyOnly))
+                || token
+                    .groups()
+                    .iter()
+                    .any(|group| group.sid() == sid && enabled(group.attributes()))
+        };
+        let device_member_of = |sid: &Sid| {
+            token
+                .device_groups()
+                .iter()
+                .any(|group| group.sid() == sid && enabled(group.attributes()))
+        };
+
+        self.evaluate_in(&ClaimContext {
+            member_of: &member_of,
+            device_member_of: &device_member_of,
+            user_claims: token.user_claims(),
+            device_claims: token.device_claims(),
+            resource_claims,
+        })
+    }
+
+    /// Evaluate the condition with everything it can refer to
+    pub(crate) fn evaluate_in(&self, context: &ClaimContext) -> ConditionResult {
+        truth(&evaluate(self.root(), context))
+    }
+}
+
+/// A single value that a condition works with
+#[derive(Debug, Clone)]
+enum Value {
+    Integer(i128),
+    String(String),
+    Sid(LocalBox<Sid>),
+    Octets(Vec<u8>),
+}
+
+/// The result of evaluating a node
+enum Operand {
+    /// A missing attribute, or a value that could not be worked out
+    Null,
+    /// One or more values, and whether strings compare case-sensitively
+    Values(Vec<Value>, bool),
+    /// The result of a logical, relational, or membership operator
+    Result(ConditionResult),
+}
+
+fn literal_values(literal: &Literal, values: &mut Vec<Value>) {
+    match literal {
+        Literal::Integer(n, _) => values.push(Value::Integer(*n as i128)),
+        Literal::String(s) => values.push(Value::String(s.clone())),
+        Literal::Octets(octets) => values.push(Value::Octets(octets.clone())),
+        Literal::Sid(sid) => values.push(Value::Sid(sid.clone())),
+        Literal::Composite(elements) => {
+            for element in elements {
+                literal_values(element, values);
+            }
+        }
+    }
+}
+
+fn claim_values(values: &ClaimValues) -> Vec<Value> {
+    match values {
+        ClaimValues::Int64(v) => v.iter().map(|n| Value::Integer(*n as i128)).collect(),
+        ClaimValues::Uint64(v) => v.iter().map(|n| Value::Integer(*n as i128)).collect(),
+        ClaimValues::Boolean(v) => v.iter().map(|b| Value::Integer(*b as i128)).collect(),
+        ClaimValues::String(v) => v.iter().map(|s| Value::String(s.clone())).collect(),
+        ClaimValues::Fqbn(v) => v
+            .iter()
+            .map(|(_, name)| Value::String(name.clone()))
+            .collect(),
+        ClaimValues::Sid(v) => v.iter().map(|sid| Value::Sid(sid.clone())).collect(),
+        ClaimValues::OctetString(v) => v.iter().map(|o| Value::Octets(o.clone())).collect(),
+    }
+}
+
+/// Find an enabled attribute by name
+fn find_attribute<'a>(
+    source: AttributeSource,
+    name: &str,
+    context: &ClaimContext<'a>,
+) -> Option<&'a ClaimAttribute> {
+    let claims = match source {
+        AttributeSource::User => context.user_claims,
+        AttributeSource::Device => context.device_claims,
+        AttributeSource::Resource => context.resource_claims,
+        // Only applications supply local attributes
+        AttributeSource::Local => &[],
+    };
+
+    claims.iter().find(|claim| {
+        claim.name().eq_ignore_ascii_case(name) && !claim.flags().contains(ClaimFlags::Disabled)
+    })
+}
+
+fn evaluate(node: &Node, context: &ClaimContext) -> Operand {
+    match node {
+        Node::Literal(literal) => {
+            let mut values = Vec::new();
+            literal_values(literal, &mut values);
+            Operand::Values(values, false)
+        }
+        Node::Attribute(source, name) => match find_attribute(*source, name, context) {
+            Some(claim) => Operand::Values(
+                claim_values(claim.values()),
+                claim.flags().contains(ClaimFlags::ValueCaseSensitive),
+            ),
+            None => Operand::Null,
+        },
+        Node::Unary(op, operand) => Operand::Result(match *op {
+            NOT => truth(&evaluate(operand, context)).not(),
+            EXISTS | NOT_EXISTS => match &**operand {
+                Node::Attribute(source, name) => {
+                    let exists = find_attribute(*source, name, context).is_some();
+                    ConditionResult::from_bool(exists == (*op == EXISTS))
+                }
+                _ => ConditionResult::Unknown,
+            },
+            op => membership(op, &evaluate(operand, context), context),
+        }),
+        Node::Binary(AND, left, right) => {
+            Operand::Result(truth(&evaluate(left, context)).and(truth(&evaluate(right, context))))
+        }
+        Node::Binary(OR, left, right) => {
+            Operand::Result(truth(&evaluate(left, context)).or(truth(&evaluate(right, context))))
+        }
+        Node::Binary(op, left, right) => Operand::Result(relational(
+            *op,
+            &evaluate(left, context),
+            &evaluate(right, context),
+        )),
+    }
+}
+
+/// Work out whether an operand counts as true, for logical operators
+fn truth(operand: &Operand) -> ConditionResult {
+    match operand {
+        Operand::Result(result) => *result,
+        Operand::Values(values, _) => match values.as_slice() {
+            [Value::Integer(n)] => ConditionResult::from_bool(*n != 0),
+            [Value::String(s)] => ConditionResult::from_bool(!s.is_empty()),
+            _ => ConditionResult::Unknown,
+        },
+        Operand::Null => ConditionResult::Unknown,
+    }
+}
+
+fn membership(op: u8, operand: &Operand, context: &ClaimContext) -> ConditionResult {
+    let sids = match operand {
+        Operand::Values(values, _) => values
+            .iter()
+            .map(|value| match value {
+                Value::Sid(sid) => Some(sid),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>(),
+        _ => None,
+    };
+
+    let sids = match sids {
+        Some(sids) if !sids.is_empty() => sids,
+        _ => return ConditionResult::Unknown,
+    };
+
+    let (is_member, any, negate) = match op {
+        MEMBER_OF => (context.member_of, false, false),
+        MEMBER_OF_ANY => (context.member_of, true, false),
+        NOT_MEMBER_OF => (context.member_of, false, true),
+        NOT_MEMBER_OF_ANY => (context.member_of, true, true),
+        DEVICE_MEMBER_OF => (context.device_member_of, false, false),
+        DEVICE_MEMBER_OF_ANY => (context.device_member_of, true, false),
+        NOT_DEVICE_MEMBER_OF => (context.device_member_of, false, true),
+        NOT_DEVICE_MEMBER_OF_ANY => (context.device_member_of, true, true),
+        _ => return ConditionResult::Unknown,
+    };
+
+    let member = if any {
+        sids.iter().any(|sid| is_member(sid))
+    } else {
+        sids.iter().all(|sid| is_member(sid))
+    };
+
+    ConditionResult::from_bool(member != negate)
+}
+
+/// Compare two values, or return `None` if they have different types
+fn compare(left: &Value, right: &Value, case_sensitive: bool) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Integer(l), Value::Integer(r)) => Some(l.cmp(r)),
+        (Value::String(l), Value::String(r)) if case_sensitive => Some(l.cmp(r)),
+        (Value::String(l), Value::String(r)) => Some(l.to_lowercase().cmp(&r.to_lowercase())),
+        _ => None,
+    }
+}
+
+fn equal(left: &Value, right: &Value, case_sensitive: bool) -> Option<bool> {
+    match (left, right) {
+        (Value::Sid(l), Value::Sid(r)) => Some(l == r),
+        (Value::Octets(l), Value::Octets(r)) => Some(l == r),
+        _ => compare(left, right, case_sensitive).map(|order| order == Ordering::Equal),
+    }
+}
+
+fn relational(op: u8, left: &Operand, right: &Operand) -> ConditionResult {
+    let (left, right, case_sensitive) = match (left, right) {
+        (Operand::Values(left, l_case), Operand::Values(right, r_case)) => {
+            (left, right, *l_case || *r_case)
+        }
+        _ => return ConditionResult::Unknown,
+    };
+
+    let equal = |l: &Value, r: &Value| equal(l, r, case_sensitive).unwrap_or(false);
+    let contains =
+        |set: &[Value], subset: &[Value]| subset.iter().all(|r| set.iter().any(|l| equal(l, r)));
+    let any_of = || left.iter().any(|l| right.iter().any(|r| equal(l, r)));
+
+    let single = match (left.as_slice(), right.as_slice()) {
+        ([l], [r]) => Some((l, r)),
+        _ => None,
+    };
+
+    let result = match op {
+        EQUALS | NOT_EQUALS => {
+            let same = match single {
+                Some((l, r)) => self::equal(l, r, case_sensitive),
+                None => Some(contains(left, right) && contains(right, left)),
+            };
+            same.map(|same| same == (op == EQUALS))
+        }
+        LESS_THAN | LESS_THAN_OR_EQUAL | GREATER_THAN | GREATER_THAN_OR_EQUAL => single
+            .and_then(|(l, r)| compare(l, r, case_sensitive))
+            .map(|order| match op {
+                LESS_THAN => order == Ordering::Less,
+                LESS_THAN_OR_EQUAL => order != Ordering::Greater,
+                GREATER_THAN => order == Ordering::Greater,
+                _ => order != Ordering::Less,
+            }),
+        CONTAINS => Some(contains(left, right)),
+        NOT_CONTAINS => Some(!contains(left, right)),
+        ANY_OF => Some(any_of()),
+        NOT_ANY_OF => Some(!any_of()),
+        _ => None,
+    };
+
+    result.map_or(ConditionResult::Unknown, ConditionResult::from_bool)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sid(s: &str) -> LocalBox<Sid> {
+        s.parse().unwrap()
+    }
+
+    fn claim(name: &str, values: ClaimValues) -> ClaimAttribute {
+        ClaimAttribute::new(name, ClaimFlags::empty(), values)
+    }
+
+    fn strings(values: &[&str]) -> ClaimValues {
+        ClaimValues::String(values.iter().map(|s| s.to_string()).collect())
+    }
+
+    fn token() -> Token {
+        Token::new(sid("S-1-5-21-1-2-3-1001"))
+            .with_group(sid("BU"), GroupAttributes::Enabled)
+            .with_group(sid("BA"), GroupAttributes::UseForDenyOnly)
+            .with_group(sid("AU"), GroupAttributes::empty())
+            .with_device_group(sid("S-1-5-21-1-2-3-2001"), GroupAttributes::Enabled)
+            .with_user_claim(claim("Title", strings(&["PM"])))
+            .with_user_claim(claim("Projects", strings(&["Alpha", "Beta"])))
+            .with_user_claim(claim("Clearance", ClaimValues::Int64(vec![3])))
+            .with_user_claim(ClaimAttribute::new(
+                "Code",
+                ClaimFlags::ValueCaseSensitive,
+                strings(&["abc"]),
+            ))
+            .with_user_claim(ClaimAttribute::new(
+                "Hidden",
+                ClaimFlags::Disabled,
+                ClaimValues::Int64(vec![1]),
+            ))
+            .with_device_claim(claim("Managed", ClaimValues::Boolean(vec![true])))
+    }
+
+    fn check(condition: &str) -> ConditionResult {
+        let resource = [
+            claim("Secrecy", ClaimValues::Uint64(vec![2])),
+            claim("Owners", ClaimValues::Sid(vec![sid("S-1-5-21-1-2-3-1001")])),
+        ];
+        condition
+            .parse::<Condition>()
+            .unwrap()
+            .evaluate(&token(), &resource)
+    }
+
+    use ConditionResult::{False, True, Unknown};
+
+    #[test]
+    fn membership() {
+        assert_eq!(check("Member_of {SID(BU)}"), True);
+        assert_eq!(check("Member_of {SID(S-1-5-21-1-2-3-1001)}"), True);
+        assert_eq!(check("Member_of {SID(BU), SID(AU)}"), False);
+        assert_eq!(check("Member_of_Any {SID(BU), SID(AU)}"), True);
+        assert_eq!(check("Member_of {SID(BA)}"), False, "Deny-only");
+        assert_eq!(check("Not_Member_of {SID(BA)}"), True);
+        assert_eq!(check("Not_Member_of_Any {SID(BA), SID(BU)}"), False);
+        assert_eq!(check("Device_Member_of {SID(S-1-5-21-1-2-3-2001)}"), True);
+        assert_eq!(check("Device_Member_of {SID(BU)}"), False);
+        assert_eq!(
+            check("Device_Member_of_Any {SID(BU), SID(S-1-5-21-1-2-3-2001)}"),
+            True
+        );
+        assert_eq!(check("Not_Device_Member_of {SID(BU)}"), True);
+        assert_eq!(
+            check("Not_Device_Member_of_Any {SID(S-1-5-21-1-2-3-2001)}"),
+            False
+        );
+        assert_eq!(check("Member_of {\"BU\"}"), Unknown);
+        assert_eq!(check("Member_of @User.Missing"), Unknown);
+        assert_eq!(check("Member_of @Resource.Owners"), True);
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!(check("@User.Title == \"pm\""), True, "Case-insensitive");
+        assert_eq!(check("@User.Code == \"ABC\""), False, "Case-sensitive");
+        assert_eq!(check("@User.Title != \"Dev\""), True);
+        assert_eq!(check("@User.Clearance >= @Resource.Secrecy"), True);
+        assert_eq!(check("@User.Clearance < 3"), False);
+        assert_eq!(check("@User.Clearance <= 0x3"), True);
+        assert_eq!(check("@User.Clearance > -1"), True);
+        assert_eq!(check("@User.Clearance == \"3\""), Unknown, "Type mismatch");
+        assert_eq!(
+            check("@User.Projects < \"Z\""),
+            Unknown,
+            "Not a single value"
+        );
+        assert_eq!(check("@User.Projects == {\"beta\", \"alpha\"}"), True);
+        assert_eq!(check("@User.Missing == 1"), Unknown);
+        assert_eq!(check("@User.Hidden == 1"), Unknown, "Disabled claim");
+        assert_eq!(check("@Device.Managed == 1"), True);
+    }
+
+    #[test]
+    fn sets() {
+        assert_eq!(check("@User.Projects Contains {\"Alpha\"}"), True);
+        assert_eq!(
+            check("@User.Projects Contains {\"Alpha\", \"Gamma\"}"),
+            False
+        );
+        assert_eq!(check("@User.Projects Not_Contains \"Gamma\""), True);
+        assert_eq!(check("@User.Projects Any_of {\"Gamma\", \"Beta\"}"), True);
+        assert_eq!(check("@User.Title Any_of {\"Dev\", \"Test\"}"), False);
+        assert_eq!(check("@User.Title Not_Any_of {\"Dev\", \"Test\"}"), True);
+        assert_eq!(check("@User.Missing Contains {\"Alpha\"}"), Unknown);
+    }
+
+    #[test]
+    fn three_valued_logic() {
+        assert_eq!(check("Exists @User.Title"), True);
+        assert_eq!(check("Exists @User.Missing"), False);
+        assert_eq!(check("Not_Exists @User.Missing"), True);
+        assert_eq!(check("Exists @User.Hidden"), False);
+
+        let unknown = "(@User.Missing == 1)";
+        assert_eq!(check(&format!("!{}", unknown)), Unknown);
+        assert_eq!(
+            check(&format!("{} && Exists @User.Title", unknown)),
+            Unknown
+        );
+        assert_eq!(
+            check(&format!("{} && Exists @User.Missing", unknown)),
+            False
+        );
+        assert_eq!(check(&format!("{} || Exists @User.Title", unknown)), True);
+        assert_eq!(
+            check(&format!("{} || Exists @User.Missing", unknown)),
+            Unknown
+        );
+
+        // Bare values are true if they are non-zero or non-empty
+        assert_eq!(check("@Device.Managed"), True);
+        assert_eq!(check("@User.Title && @User.Clearance"), True);
+        assert_eq!(check("@User.Projects"), Unknown);
+        assert_eq!(check("TRUE"), Unknown, "Local attributes are never set");
+    }
+}
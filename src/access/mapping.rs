@@ -0,0 +1,551 @@
+//! Generic mappings, which give the generic rights their meaning for each
+//! type of object
+
+use crate::constants::{AccessRights, AceFlags, AceType};
+use crate::winnt::*;
+use crate::{Ace, Acl, LocalBox};
+use std::io;
+
+/// Build an access mask in a constant
+const fn rights(bits: u32) -> AccessRights {
+    AccessRights::from_bits_truncate(bits)
+}
+
+/// How the generic access rights map onto the specific rights of an object
+///
+/// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-generic_mapping)
+/// for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GenericMapping {
+    /// The rights that `GENERIC_READ` stands for
+    pub read: AccessRights,
+    /// The rights that `GENERIC_WRITE` stands for
+    pub write: AccessRights,
+    /// The rights that `GENERIC_EXECUTE` stands for
+    pub execute: AccessRights,
+    /// The rights that `GENERIC_ALL` stands for
+    pub all: AccessRights,
+}
+
+impl GenericMapping {
+    /// The generic mapping for files
+    pub const FILE: GenericMapping = GenericMapping {
+        read: AccessRights::FileGenericRead,
+        write: AccessRights::FileGenericWrite,
+        execute: AccessRights::FileGenericExecute,
+        all: AccessRights::FileAllAccess,
+    };
+
+    /// The generic mapping for directories, which is the same as for files
+    pub const DIRECTORY: GenericMapping = GenericMapping::FILE;
+
+    /// The generic mapping for registry keys
+    pub const KEY: GenericMapping = GenericMapping {
+        read: AccessRights::KeyRead,
+        write: AccessRights::KeyWrite,
+        execute: AccessRights::KeyExecute,
+        all: AccessRights::KeyAllAccess,
+    };
+
+    /// The generic mapping for services
+    pub const SERVICE: GenericMapping = GenericMapping {
+        read: rights(
+            STANDARD_RIGHTS_READ
+                | SERVICE_QUERY_CONFIG
+                | SERVICE_QUERY_STATUS
+                | SERVICE_INTERROGATE
+                | SERVICE_ENUMERATE_DEPENDENTS,
+        ),
+        write: rights(STANDARD_RIGHTS_WRITE | SERVICE_CHANGE_CONFIG),
+        execute: rights(
+            STANDARD_RIGHTS_EXECUTE
+                | SERVICE_START
+                | SERVICE_STOP
+                | SERVICE_PAUSE_CONTINUE
+                | SERVICE_USER_DEFINED_CONTROL,
+        ),
+        all: rights(SERVICE_ALL_ACCESS),
+    };
+
+    /// The generic mapping for the service control manager
+    pub const SERVICE_MANAGER: GenericMapping = GenericMapping {
+        read: rights(
+            STANDARD_RIGHTS_READ | SC_MANAGER_ENUMERATE_SERVICE | SC_MANAGER_QUERY_LOCK_STATUS,
+        ),
+        write: rights(
+            STANDARD_RIGHTS_WRITE | SC_MANAGER_CREATE_SERVICE | SC_MANAGER_MODIFY_BOOT_CONFIG,
+        ),
+        execute: rights(STANDARD_RIGHTS_EXECUTE | SC_MANAGER_CONNECT | SC_MANAGER_LOCK),
+        all: rights(SC_MANAGER_ALL_ACCESS),
+    };
+
+    /// The generic mapping for processes
+    pub const PROCESS: GenericMapping = GenericMapping {
+        read: rights(STANDARD_RIGHTS_READ | PROCESS_VM_READ | PROCESS_QUERY_INFORMATION),
+        write: rights(
+            STANDARD_RIGHTS_WRITE
+                | PROCESS_CREATE_PROCESS
+                | PROCESS_CREATE_THREAD
+                | PROCESS_VM_OPERATION
+                | PROCESS_VM_WRITE
+                | PROCESS_DUP_HANDLE
+                | PROCESS_SET_QUOTA
+                | PROCESS_SET_INFORMATION
+                | PROCESS_SUSPEND_RESUME,
+        ),
+        execute: rights(
+            STANDARD_RIGHTS_EXECUTE
+                | SYNCHRONIZE
+                | PROCESS_QUERY_LIMITED_INFORMATION
+                | PROCESS_TERMINATE,
+        ),
+        all: rights(PROCESS_ALL_ACCESS),
+    };
+
+    /// The generic mapping for threads
+    pub const THREAD: GenericMapping = GenericMapping {
+        read: rights(STANDARD_RIGHTS_READ | THREAD_GET_CONTEXT | THREAD_QUERY_INFORMATION),
+        write: rights(
+            STANDARD_RIGHTS_WRITE
+                | THREAD_TERMINATE
+                | THREAD_SUSPEND_RESUME
+                // THREAD_ALERT, which is not documented
+                | 0x0004
+                | THREAD_SET_CONTEXT
+                | THREAD_SET_INFORMATION,
+        ),
+        execute: rights(
+            STANDARD_RIGHTS_EXECUTE
+                | SYNCHRONIZE
+                | THREAD_SET_LIMITED_INFORMATION
+                | THREAD_QUERY_LIMITED_INFORMATION
+                | THREAD_RESUME,
+        ),
+        all: rights(THREAD_ALL_ACCESS),
+    };
+
+    /// The generic mapping for access tokens
+    pub const TOKEN: GenericMapping = GenericMapping {
+        read: rights(STANDARD_RIGHTS_READ | TOKEN_QUERY),
+        write: rights(
+            STANDARD_RIGHTS_WRITE
+                | TOKEN_ADJUST_PRIVILEGES
+                | TOKEN_ADJUST_GROUPS
+                | TOKEN_ADJUST_DEFAULT,
+        ),
+        execute: rights(STANDARD_RIGHTS_EXECUTE),
+        all: rights(TOKEN_ALL_ACCESS),
+    };
+
+    /// The generic mapping for desktops
+    pub const DESKTOP: GenericMapping = GenericMapping {
+        read: rights(STANDARD_RIGHTS_READ | DESKTOP_READOBJECTS | DESKTOP_ENUMERATE),
+        write: rights(
+            STANDARD_RIGHTS_WRITE
+                | DESKTOP_WRITEOBJECTS
+                | DESKTOP_CREATEWINDOW
+                | DESKTOP_CREATEMENU
+                | DESKTOP_HOOKCONTROL
+                | DESKTOP_JOURNALRECORD
+                | DESKTOP_JOURNALPLAYBACK,
+        ),
+        execute: rights(STANDARD_RIGHTS_EXECUTE | DESKTOP_SWITCHDESKTOP),
+        all: rights(STANDARD_RIGHTS_REQUIRED | 0x01FF),
+    };
+
+    /// The generic mapping for window stations
+    pub const WINDOW_STATION: GenericMapping = GenericMapping {
+        read: rights(
+            STANDARD_RIGHTS_READ
+                | WINSTA_ENUMDESKTOPS
+                | WINSTA_READATTRIBUTES
+                | WINSTA_ENUMERATE
+                | WINSTA_READSCREEN,
+        ),
+        write: rights(
+            STANDARD_RIGHTS_WRITE
+                | WINSTA_ACCESSCLIPBOARD
+                | WINSTA_CREATEDESKTOP
+                | WINSTA_WRITEATTRIBUTES,
+        ),
+        execute: rights(STANDARD_RIGHTS_EXECUTE | WINSTA_ACCESSGLOBALATOMS | WINSTA_EXITWINDOWS),
+        all: rights(STANDARD_RIGHTS_REQUIRED | WINSTA_ALL_ACCESS),
+    };
+
+    /// The generic mapping for Active Directory objects
+    pub const DS: GenericMapping = GenericMapping {
+        read: rights(
+            STANDARD_RIGHTS_READ | ACTRL_DS_LIST | ACTRL_DS_READ_PROP | ACTRL_DS_LIST_OBJECT,
+        ),
+        write: rights(STANDARD_RIGHTS_WRITE | ACTRL_DS_SELF | ACTRL_DS_WRITE_PROP),
+        execute: rights(STANDARD_RIGHTS_EXECUTE | ACTRL_DS_LIST),
+        all: rights(STANDARD_RIGHTS_REQUIRED | 0x01FF),
+    };
+
+    /// Replace the generic rights in `rights` with the specific rights they
+    /// stand for
+    ///
+    /// ```
+    /// use windows_permissions::access::GenericMapping;
+    /// use windows_permissions::constants::AccessRights;
+    ///
+    /// assert_eq!(
+    ///     GenericMapping::FILE.map(AccessRights::GenericRead | AccessRights::Delete),
+    ///     AccessRights::FileGenericRead | AccessRights::Delete,
+    /// );
+    /// ```
+    pub fn map(&self, rights: AccessRights) -> AccessRights {
+        let generic_bits = [
+            (AccessRights::GenericRead, self.read),
+            (AccessRights::GenericWrite, self.write),
+            (AccessRights::GenericExecute, self.execute),
+            (AccessRights::GenericAll, self.all),
+        ];
+
+        generic_bits
+            .iter()
+            .fold(rights, |mapped, &(generic, specific)| {
+                if rights.contains(generic) {
+                    (mapped - generic) | specific
+                } else {
+                    mapped
+                }
+            })
+    }
+
+    /// Replace specific rights in `rights` with the generic rights that
+    /// stand for them, where that can be done exactly
+    ///
+    /// A generic right is used if every right it stands for is present.
+    /// `GENERIC_ALL` is preferred, and the others can be combined. Mapping
+    /// the result gives back `rights`.
+    ///
+    /// ```
+    /// use windows_permissions::access::GenericMapping;
+    /// use windows_permissions::constants::AccessRights;
+    ///
+    /// let mapping = GenericMapping::FILE;
+    /// let read_execute = AccessRights::FileGenericRead | AccessRights::FileGenericExecute;
+    ///
+    /// assert_eq!(
+    ///     mapping.collapse(read_execute),
+    ///     AccessRights::GenericRead | AccessRights::GenericExecute
+    /// );
+    /// assert_eq!(
+    ///     mapping.collapse(AccessRights::FileAllAccess),
+    ///     AccessRights::GenericAll
+    /// );
+    /// assert_eq!(mapping.map(mapping.collapse(read_execute)), read_execute);
+    /// ```
+    pub fn collapse(&self, rights: AccessRights) -> AccessRights {
+        let generic_bits: &[_] = if !self.all.is_empty() && rights.contains(self.all) {
+            &[(AccessRights::GenericAll, self.all)]
+        } else {
+            &[
+                (AccessRights::GenericRead, self.read),
+                (AccessRights::GenericWrite, self.write),
+                (AccessRights::GenericExecute, self.execute),
+            ]
+        };
+
+        let (generic, covered) = generic_bits.iter().fold(
+            (AccessRights::empty(), AccessRights::empty()),
+            |(generic, covered), &(bit, specific)| {
+                if !specific.is_empty() && rights.contains(specific) {
+                    (generic | bit, covered | specific)
+                } else {
+                    (generic, covered)
+                }
+            },
+        );
+
+        (rights - covered) | generic
+    }
+
+    /// Copy an ACE, with its generic rights mapped
+    ///
+    /// ACE types whose mask does not hold access rights, like mandatory
+    /// labels, are copied as they are.
+    pub fn map_ace(&self, ace: &Ace) -> io::Result<LocalBox<Ace>> {
+        let mut bytes = ace.as_bytes().to_vec();
+        self.rewrite_mask(&mut bytes, |rights| self.map(rights));
+        unsafe { LocalBox::from_bytes(&bytes) }
+    }
+
+    /// Copy an ACE, with its specific rights collapsed to generic rights
+    /// where that can be done exactly
+    ///
+    /// See [`collapse`](Self::collapse).
+    pub fn collapse_ace(&self, ace: &Ace) -> io::Result<LocalBox<Ace>> {
+        let mut bytes = ace.as_bytes().to_vec();
+        self.rewrite_mask(&mut bytes, |rights| self.collapse(rights));
+        unsafe { LocalBox::from_bytes(&bytes) }
+    }
+
+    /// Copy an ACL, with the generic rights of each ACE mapped
+    ///
+    /// Inherit-only ACEs are left alone, because their generic rights are
+    /// mapped for each object that inherits them, which may be of another
+    /// type. This is what Windows does when it applies inherited ACEs.
+    ///
+    /// ```
+    /// use windows_permissions::access::GenericMapping;
+    /// use windows_permissions::constants::AccessRights;
+    /// use windows_permissions::{LocalBox, SecurityDescriptor};
+    ///
+    /// let sd: LocalBox<SecurityDescriptor> = "D:(A;;GR;;;BU)(A;OICIIO;GA;;;CO)".parse().unwrap();
+    /// let acl = GenericMapping::KEY.map_acl(sd.dacl().unwrap()).unwrap();
+    ///
+    /// assert_eq!(acl.get_ace(0).unwrap().mask(), AccessRights::KeyRead);
+    /// assert_eq!(acl.get_ace(1).unwrap().mask(), AccessRights::GenericAll);
+    /// ```
+    pub fn map_acl(&self, acl: &Acl) -> io::Result<LocalBox<Acl>> {
+        self.rewrite_acl(acl, true, |rights| self.map(rights))
+    }
+
+    /// Copy an ACL, with the rights of each ACE collapsed to generic rights
+    /// where that can be done exactly
+    ///
+    /// See [`collapse`](Self::collapse).
+    pub fn collapse_acl(&self, acl: &Acl) -> io::Result<LocalBox<Acl>> {
+        self.rewrite_acl(acl, false, |rights| self.collapse(rights))
+    }
+
+    /// Rewrite the mask of every ACE in a copy of an ACL
+    fn rewrite_acl<F: Fn(AccessRights) -> AccessRights>(
+        &self,
+        acl: &Acl,
+        skip_inherit_only: bool,
+        f: F,
+    ) -> io::Result<LocalBox<Acl>> {
+        let mut bytes = acl.as_bytes().to_vec();
+        let count = u16::from_le_bytes([bytes[4], bytes[5]]);
+        let mut offset = 8;
+
+        for _ in 0..count {
+            let size = u16::from_le_bytes([bytes[offset + 2], bytes[offset + 3]]) as usize;
+            let ace = &mut bytes[offset..offset + size];
+            let inherit_only = AceFlags::from_bits_truncate(ace[1]).contains(AceFlags::InheritOnly);
+
+            if !(skip_inherit_only && inherit_only) {
+                self.rewrite_mask(ace, &f);
+            }
+
+            offset += size;
+        }
+
+        unsafe { LocalBox::from_bytes(&bytes) }
+    }
+
+    /// Rewrite the mask of an ACE, if it holds access rights
+    fn rewrite_mask<F: Fn(AccessRights) -> AccessRights>(&self, ace: &mut [u8], f: F) {
+        let holds_rights = !matches!(
+            AceType::from_raw(ace[0]),
+            Some(AceType::SYSTEM_MANDATORY_LABEL_ACE_TYPE)
+                | Some(AceType::SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE)
+                | Some(AceType::SYSTEM_SCOPED_POLICY_ID_ACE_TYPE)
+                | None
+        );
+
+        if holds_rights {
+            let mask = u32::from_le_bytes([ace[4], ace[5], ace[6], ace[7]]);
+            let mask = f(AccessRights::from_bits_truncate(mask));
+            ace[4..8].copy_from_slice(&mask.bits().to_le_bytes());
+        }
+    }
+
+    /// The rights that count as writes for write-restricted tokens
+    ///
+    /// These are the specific rights of `GENERIC_WRITE`, other than the
+    /// read-only standard rights, plus `DELETE`, `WRITE_DAC`, and
+    /// `WRITE_OWNER`.
+    pub fn write_rights(&self) -> AccessRights {
+        (self.write - AccessRights::ReadControl - AccessRights::Synchronize)
+            | AccessRights::Delete
+            | AccessRights::WriteDac
+            | AccessRights::WriteOwner
+    }
+}
+
+impl Default for GenericMapping {
+    fn default() -> Self {
+        Self::FILE
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::SecurityDescriptor;
+
+    #[test]
+    fn generic_mapping() {
+        let mapping = GenericMapping::FILE;
+
+        assert_eq!(
+            mapping.map(AccessRights::GenericAll),
+            AccessRights::FileAllAccess
+        );
+        assert_eq!(
+            mapping.map(AccessRights::GenericRead | AccessRights::GenericExecute),
+            AccessRights::FileGenericRead | AccessRights::FileGenericExecute
+        );
+        assert!(!mapping.write_rights().contains(AccessRights::ReadControl));
+        assert!(mapping.write_rights().contains(AccessRights::WriteOwner));
+    }
+
+    #[test]
+    fn tables() {
+        let bits = |mapping: GenericMapping| {
+            [
+                mapping.read.bits(),
+                mapping.write.bits(),
+                mapping.execute.bits(),
+                mapping.all.bits(),
+            ]
+        };
+
+        assert_eq!(
+            bits(GenericMapping::FILE),
+            [0x120089, 0x120116, 0x1200a0, 0x1f01ff]
+        );
+        assert_eq!(bits(GenericMapping::DIRECTORY), bits(GenericMapping::FILE));
+        assert_eq!(
+            bits(GenericMapping::KEY),
+            [0x20019, 0x20006, 0x20019, 0xf003f]
+        );
+        assert_eq!(
+            bits(GenericMapping::SERVICE),
+            [0x2008d, 0x20002, 0x20170, 0xf01ff]
+        );
+        assert_eq!(
+            bits(GenericMapping::SERVICE_MANAGER),
+            [0x20014, 0x20022, 0x20009, 0xf003f]
+        );
+        assert_eq!(
+            bits(GenericMapping::PROCESS),
+            [0x20410, 0x20bea, 0x121001, 0x1fffff]
+        );
+        assert_eq!(
+            bits(GenericMapping::THREAD),
+            [0x20048, 0x20037, 0x121c00, 0x1fffff]
+        );
+        assert_eq!(
+            bits(GenericMapping::TOKEN),
+            [0x20008, 0x200e0, 0x20000, 0xf01ff]
+        );
+        assert_eq!(
+            bits(GenericMapping::DESKTOP),
+            [0x20041, 0x200be, 0x20100, 0xf01ff]
+        );
+        assert_eq!(
+            bits(GenericMapping::WINDOW_STATION),
+            [0x20303, 0x2001c, 0x20060, 0xf037f]
+        );
+        assert_eq!(
+            bits(GenericMapping::DS),
+            [0x20094, 0x20028, 0x20004, 0xf01ff]
+        );
+    }
+
+    #[test]
+    fn collapse_is_exact() {
+        let mappings = [
+            GenericMapping::FILE,
+            GenericMapping::KEY,
+            GenericMapping::SERVICE,
+            GenericMapping::SERVICE_MANAGER,
+            GenericMapping::PROCESS,
+            GenericMapping::THREAD,
+            GenericMapping::TOKEN,
+            GenericMapping::DESKTOP,
+            GenericMapping::WINDOW_STATION,
+            GenericMapping::DS,
+        ];
+
+        for mapping in mappings.iter() {
+            let masks = [
+                mapping.read,
+                mapping.write | AccessRights::Delete,
+                mapping.read | mapping.execute,
+                mapping.all,
+                mapping.all | AccessRights::AccessSystemSecurity,
+                AccessRights::ReadControl,
+                AccessRights::empty(),
+            ];
+
+            for &mask in masks.iter() {
+                let collapsed = mapping.collapse(mask);
+                assert_eq!(mapping.map(collapsed), mask, "{:?} {:?}", mapping, mask);
+            }
+
+            assert_eq!(mapping.collapse(mapping.all), AccessRights::GenericAll);
+        }
+
+        // Partial sets stay specific
+        let file = GenericMapping::FILE;
+        let partial = AccessRights::FileGenericRead - AccessRights::Bit3;
+        assert_eq!(file.collapse(partial), partial);
+        assert_eq!(
+            file.collapse(AccessRights::FileGenericRead | AccessRights::Delete),
+            AccessRights::GenericRead | AccessRights::Delete
+        );
+    }
+
+    #[test]
+    fn aces_and_acls() {
+        let sd: LocalBox<SecurityDescriptor> =
+            "D:(A;;GRGW;;;BU)(D;OICIIO;GA;;;WD)(A;;0x1f01ff;;;SY)\
+             S:(AU;FA;GA;;;WD)(ML;;NW;;;LW)"
+                .parse()
+                .unwrap();
+        let dacl = sd.dacl().unwrap();
+        let sacl = sd.sacl().unwrap();
+        let file = GenericMapping::FILE;
+
+        let masks = |acl: &Acl| acl.aces().map(|ace| ace.mask()).collect::<Vec<_>>();
+
+        let mapped = file.map_acl(dacl).unwrap();
+        assert_eq!(
+            masks(&mapped),
+            vec![
+                AccessRights::FileGenericRead | AccessRights::FileGenericWrite,
+                AccessRights::GenericAll,
+                AccessRights::FileAllAccess,
+            ]
+        );
+        assert_eq!(
+            mapped.get_ace(1).unwrap().sid(),
+            dacl.get_ace(1).unwrap().sid()
+        );
+        assert_eq!(mapped.len(), dacl.len());
+
+        let collapsed = file.collapse_acl(&mapped).unwrap();
+        assert_eq!(
+            masks(&collapsed),
+            vec![
+                AccessRights::GenericRead | AccessRights::GenericWrite,
+                AccessRights::GenericAll,
+                AccessRights::GenericAll,
+            ]
+        );
+
+        // Mandatory labels keep their policy bits
+        let mapped = file.map_acl(sacl).unwrap();
+        assert_eq!(
+            masks(&mapped),
+            vec![
+                AccessRights::FileAllAccess,
+                AccessRights::MandatoryLabelNoWriteUp
+            ]
+        );
+
+        let ace = file.map_ace(dacl.get_ace(1).unwrap()).unwrap();
+        assert_eq!(ace.mask(), AccessRights::FileAllAccess);
+        assert_eq!(ace.flags(), dacl.get_ace(1).unwrap().flags());
+        assert_eq!(
+            file.collapse_ace(&ace).unwrap().mask(),
+            AccessRights::GenericAll
+        );
+    }
+}
@@ -0,0 +1,1708 @@
+//! Pure-Rust access checks
+//!
+//! This module evaluates a [`SecurityDescriptor`] (or a bare DACL) against a
+//! [`Token`], the way the Windows
+//! [`AccessCheck`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-accesscheck)
+//! function does. Because it never calls into Windows, it works for tokens
+//! built by hand or parsed from `whoami` output, on any platform.
+//!
+//! Each access check starts with the token's privileges, which can grant
+//! some rights outright. The rest is made up of one or more passes over the
+//! DACL. Every
+//! token gets a discretionary pass over its user and group SIDs. Restricted
+//! tokens also get a second pass over their restricting SIDs, and
+//! AppContainer tokens get one over their package and capability SIDs. The
+//! token is only granted the rights that every pass allows.
+//!
+//! Finally, the mandatory label in the SACL is compared with the token's
+//! integrity level. It can take away rights that the DACL allowed, which is
+//! reported as a [`Pass::MandatoryLabel`].
+//!
+//! Objects with parts, like directory objects with property sets and
+//! properties, can be checked one part at a time with an [`ObjectTypeList`].
+//!
+//! Conditional ACEs take part when their condition holds, given the token's
+//! user and device claims and the resource attributes in the SACL. Allow
+//! entries need their condition to be true, but deny entries apply unless
+//! their condition is false; see [`ConditionResult`].
+//!
+//! A SACL can also refer to central access policies, whose rules further
+//! limit access; see [`AccessCheck::check_with_policies`].
+//!
+//! Files reached through a share are checked against both security
+//! descriptors with a [`NetworkAccessCheck`].
+//!
+//! The audit entries in the SACL that an access would fire can be found with
+//! [`AccessCheck::audit`].
+//!
+//! ```
+//! use windows_permissions::access::{AccessCheck, Pass};
+//! use windows_permissions::constants::{AccessRights, GroupAttributes};
+//! use windows_permissions::{LocalBox, SecurityDescriptor, Token};
+//!
+//! let sd: LocalBox<SecurityDescriptor> =
+//!     "O:BAD:(A;;FA;;;BU)(A;;FR;;;S-1-5-12)".parse().unwrap();
+//!
+//! // A member of BUILTIN\Users, restricted to the RESTRICTED SID
+//! let token = Token::new("S-1-5-21-1-2-3-1001".parse().unwrap())
+//!     .with_group("S-1-5-32-545".parse().unwrap(), GroupAttributes::Enabled)
+//!     .with_restricting_sid("S-1-5-12".parse().unwrap(), GroupAttributes::Enabled);
+//!
+//! let read = AccessCheck::new(&sd, &token).check(AccessRights::FileGenericRead);
+//! assert!(read.is_granted());
+//!
+//! let write = AccessCheck::new(&sd, &token).check(AccessRights::FileGenericWrite);
+//! assert!(!write.is_granted());
+//! assert_eq!(write.denied_by(), Some(Pass::Restricted));
+//! ```
+
+use crate::constants::{AccessRights, AceFlags, AceType, GroupAttributes};
+use crate::token::{ClaimAttribute, SidAndAttributes};
+use crate::winnt::MAXIMUM_ALLOWED;
+use crate::{Ace, Acl, Condition, LocalBox, Privilege, SecurityDescriptor, Sid, Token};
+
+mod audit;
+mod condition;
+mod mapping;
+mod object_type;
+mod policy;
+mod share;
+
+pub use audit::{AuditEvent, AuditKind};
+pub use condition::ConditionResult;
+pub use mapping::GenericMapping;
+pub use object_type::{ObjectTypeList, ObjectTypeResult};
+pub use policy::{CentralAccessPolicy, CentralAccessResult, CentralAccessRule, RuleResult};
+pub use share::{AccessLayer, NetworkAccessCheck, NetworkAccessResult, ShareRights};
+
+use condition::ClaimContext;
+
+/// One pass over the DACL during an access check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pass {
+    /// The privilege check, which comes before the DACL
+    ///
+    /// Privileges grant some rights regardless of the DACL, and
+    /// `ACCESS_SYSTEM_SECURITY` can only be granted by a privilege. See
+    /// [`AccessCheck::with_backup_intent`] for the rights each privilege
+    /// grants.
+    Privileges,
+    /// The normal pass, using the token's user and group SIDs
+    Discretionary,
+    /// The second pass for restricted tokens, using the restricting SIDs
+    Restricted,
+    /// The second pass for AppContainer tokens, using the package SID,
+    /// `ALL APPLICATION PACKAGES` (except for LPAC tokens),
+    /// `ALL RESTRICTED APPLICATION PACKAGES`, and the enabled capabilities
+    AppContainer,
+    /// The mandatory integrity check, comparing the mandatory label in the
+    /// SACL with the token's integrity level
+    ///
+    /// Objects without a label are treated as Medium integrity with the
+    /// no-write-up policy, and tokens without an integrity level are treated
+    /// as Medium integrity.
+    MandatoryLabel,
+}
+
+/// The outcome of one [`Pass`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PassResult {
+    pass: Pass,
+    granted: AccessRights,
+}
+
+impl PassResult {
+    /// Get the pass this result is for
+    pub fn pass(&self) -> Pass {
+        self.pass
+    }
+
+    /// Get the desired rights that this pass would allow
+    ///
+    /// The passes over the DACL allow every right that was granted by a
+    /// privilege. For write-restricted tokens, the restricted pass also
+    /// allows every right that is not a write.
+    pub fn granted(&self) -> AccessRights {
+        self.granted
+    }
+}
+
+/// What decided whether a right was granted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessSource {
+    /// An ACE in the DACL, given by its index, that lists the right directly
+    Ace {
+        /// The pass that used the ACE
+        pass: Pass,
+        /// The index of the ACE in the DACL
+        index: u32,
+    },
+    /// An ACE in the DACL, given by its index, with a generic right that the
+    /// generic mapping turned into this right
+    GenericAce {
+        /// The pass that used the ACE
+        pass: Pass,
+        /// The index of the ACE in the DACL
+        index: u32,
+        /// The generic right in the ACE
+        generic: AccessRights,
+    },
+    /// The implicit `READ_CONTROL` and `WRITE_DAC` rights of the owner
+    OwnerRights {
+        /// The pass that found the token to be the owner
+        pass: Pass,
+    },
+    /// The object has no DACL, or a null DACL, which grants everything
+    NullDacl,
+    /// The mandatory label of the object
+    MandatoryLabel,
+    /// A privilege that the token holds and has enabled
+    Privilege(Privilege),
+    /// A privilege that the token would need, but does not hold or has not
+    /// enabled
+    MissingPrivilege(Privilege),
+    /// No ACE in the DACL granted the right
+    NotGranted {
+        /// The pass that did not grant the right
+        pass: Pass,
+    },
+}
+
+/// Why a single right was granted or denied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RightExplanation {
+    right: AccessRights,
+    granted: bool,
+    source: AccessSource,
+}
+
+impl RightExplanation {
+    /// Get the right being explained, which is always a single bit
+    pub fn right(&self) -> AccessRights {
+        self.right
+    }
+
+    /// Check whether the right was granted
+    pub fn is_granted(&self) -> bool {
+        self.granted
+    }
+
+    /// Get what decided the right
+    ///
+    /// For granted rights, this is what granted it in the discretionary
+    /// pass. For denied rights, this is what denied it in the first pass
+    /// that did not grant it.
+    pub fn source(&self) -> AccessSource {
+        self.source
+    }
+}
+
+/// The outcome of an [`AccessCheck`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AccessCheckResult {
+    desired: AccessRights,
+    granted: AccessRights,
+    passes: Vec<PassResult>,
+    rights: Vec<RightExplanation>,
+}
+
+impl AccessCheckResult {
+    /// Get the desired rights, after generic rights were mapped
+    ///
+    /// For [`AccessCheck::maximum_allowed`], these are all of the rights that
+    /// were considered.
+    pub fn desired(&self) -> AccessRights {
+        self.desired
+    }
+
+    /// Get the desired rights that every pass allowed
+    ///
+    /// Unlike the WinAPI, this is filled in even if some of the desired
+    /// rights were denied.
+    pub fn granted(&self) -> AccessRights {
+        self.granted
+    }
+
+    /// Check whether every desired right was granted
+    pub fn is_granted(&self) -> bool {
+        self.granted.contains(self.desired)
+    }
+
+    /// Get the result of each pass, in the order they ran
+    pub fn passes(&self) -> &[PassResult] {
+        &self.passes
+    }
+
+    /// Get the first pass that did not allow all of the desired rights
+    pub fn denied_by(&self) -> Option<Pass> {
+        self.passes
+            .iter()
+            .find(|result| !result.granted.contains(self.desired))
+            .map(|result| result.pass)
+    }
+
+    /// Get the result of a single pass, if it ran
+    pub fn pass(&self, pass: Pass) -> Option<&PassResult> {
+        self.passes.iter().find(|result| result.pass == pass)
+    }
+
+    /// Get an explanation for each desired right, from the lowest bit to the
+    /// highest
+    pub fn rights(&self) -> &[RightExplanation] {
+        &self.rights
+    }
+
+    /// Get the explanation for a single desired right
+    ///
+    /// Returns `None` if `right` is not a single desired bit.
+    pub fn explain(&self, right: AccessRights) -> Option<&RightExplanation> {
+        self.rights
+            .iter()
+            .find(|explanation| explanation.right == right)
+    }
+}
+
+/// An access check of a token against a security descriptor
+///
+/// See the [module documentation](self) for an overview.
+#[derive(Debug, Clone, Copy)]
+pub struct AccessCheck<'a> {
+    token: &'a Token,
+    dacl: Option<&'a Acl>,
+    sacl: Option<&'a Acl>,
+    owner: Option<&'a Sid>,
+    mapping: GenericMapping,
+    backup_intent: bool,
+}
+
+impl<'a> AccessCheck<'a> {
+    /// Prepare to check `token` against the DACL, SACL, and owner of `sd`
+    ///
+    /// If `sd` has no DACL, or a null DACL, all access is granted.
+    pub fn new(sd: &'a SecurityDescriptor, token: &'a Token) -> Self {
+        Self::for_dacl(sd.non_null_dacl(), token)
+            .with_sacl(sd.non_null_sacl())
+            .with_owner(sd.owner())
+    }
+
+    /// Prepare to check `token` against a bare DACL
+    ///
+    /// A `dacl` of `None` is treated as a null DACL, which grants all access.
+    /// Without an owner, nobody gets the owner's implicit rights; use
+    /// [`with_owner`](Self::with_owner) to set one.
+    pub fn for_dacl(dacl: Option<&'a Acl>, token: &'a Token) -> Self {
+        Self {
+            token,
+            dacl,
+            sacl: None,
+            owner: None,
+            mapping: GenericMapping::default(),
+            backup_intent: false,
+        }
+    }
+
+    /// Set the owner of the object
+    ///
+    /// The owner is implicitly granted `READ_CONTROL` and `WRITE_DAC`, unless
+    /// the DACL has an `OWNER RIGHTS` (`S-1-3-4`) entry.
+    pub fn with_owner(mut self, owner: Option<&'a Sid>) -> Self {
+        self.owner = owner;
+        self
+    }
+
+    /// Set the SACL of the object, which holds its mandatory label
+    pub fn with_sacl(mut self, sacl: Option<&'a Acl>) -> Self {
+        self.sacl = sacl;
+        self
+    }
+
+    /// Set the generic mapping of the object
+    ///
+    /// This defaults to [`GenericMapping::FILE`].
+    pub fn with_generic_mapping(mut self, mapping: GenericMapping) -> Self {
+        self.mapping = mapping;
+        self
+    }
+
+    /// Set whether the access is for backup or restore, as with
+    /// `FILE_FLAG_BACKUP_SEMANTICS`
+    ///
+    /// Privileges grant these rights, as long as the token has them enabled:
+    ///
+    /// - `SeSecurityPrivilege`: `ACCESS_SYSTEM_SECURITY`
+    /// - `SeTakeOwnershipPrivilege`: `WRITE_OWNER`
+    /// - `SeBackupPrivilege`, with backup intent: every read and execute
+    ///   right, `READ_CONTROL`, and `ACCESS_SYSTEM_SECURITY`
+    /// - `SeRestorePrivilege`, with backup intent: every write right,
+    ///   `DELETE`, `WRITE_DAC`, `WRITE_OWNER`, and `ACCESS_SYSTEM_SECURITY`
+    ///
+    /// ```
+    /// use windows_permissions::access::{AccessCheck, AccessSource};
+    /// use windows_permissions::constants::{AccessRights, PrivilegeAttributes};
+    /// use windows_permissions::{LocalBox, Privilege, SecurityDescriptor, Token};
+    ///
+    /// let sd: LocalBox<SecurityDescriptor> = "D:".parse().unwrap();
+    /// let token = Token::new("S-1-5-21-1-2-3-1001".parse().unwrap())
+    ///     .with_privilege(Privilege::Backup, PrivilegeAttributes::Enabled);
+    /// let read = AccessRights::FileGenericRead;
+    ///
+    /// assert!(!AccessCheck::new(&sd, &token).check(read).is_granted());
+    ///
+    /// let result = AccessCheck::new(&sd, &token)
+    ///     .with_backup_intent(true)
+    ///     .check(read);
+    /// assert!(result.is_granted());
+    /// assert_eq!(
+    ///     result.explain(AccessRights::ReadControl).unwrap().source(),
+    ///     AccessSource::Privilege(Privilege::Backup)
+    /// );
+    /// ```
+    pub fn with_backup_intent(mut self, backup_intent: bool) -> Self {
+        self.backup_intent = backup_intent;
+        self
+    }
+
+    /// Check whether the token is granted the `desired` rights
+    ///
+    /// Generic rights in `desired` and in the DACL are mapped with the
+    /// generic mapping first.
+    pub fn check(&self, desired: AccessRights) -> AccessCheckResult {
+        self.run(self.mapping.map(desired), None).remove(0)
+    }
+
+    /// Find every right that the token is granted, like the
+    /// `MAXIMUM_ALLOWED` access right does
+    ///
+    /// The rights considered are the specific rights of the generic mapping,
+    /// the standard rights, and any other rights listed in the DACL. Each of
+    /// them is explained in [`AccessCheckResult::rights`].
+    ///
+    /// ```
+    /// use windows_permissions::access::{AccessCheck, AccessSource, Pass};
+    /// use windows_permissions::constants::{AccessRights, GroupAttributes};
+    /// use windows_permissions::{LocalBox, SecurityDescriptor, Token};
+    ///
+    /// let sd: LocalBox<SecurityDescriptor> =
+    ///     "D:(D;;SD;;;BU)(A;;GA;;;BU)".parse().unwrap();
+    /// let token = Token::new("S-1-5-21-1-2-3-1001".parse().unwrap())
+    ///     .with_group("S-1-5-32-545".parse().unwrap(), GroupAttributes::Enabled);
+    ///
+    /// let result = AccessCheck::new(&sd, &token).maximum_allowed();
+    /// assert_eq!(
+    ///     result.granted(),
+    ///     AccessRights::FileAllAccess - AccessRights::Delete
+    /// );
+    ///
+    /// let delete = result.explain(AccessRights::Delete).unwrap();
+    /// assert!(!delete.is_granted());
+    /// assert_eq!(
+    ///     delete.source(),
+    ///     AccessSource::Ace { pass: Pass::Discretionary, index: 0 }
+    /// );
+    /// ```
+    pub fn maximum_allowed(&self) -> AccessCheckResult {
+        self.run(self.maximum_rights(), None).remove(0)
+    }
+
+    /// Check whether the token is granted the `desired` rights on each node
+    /// of an object type tree, like
+    /// [`AccessCheckByTypeResultList`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-accesscheckbytyperesultlist)
+    ///
+    /// Object ACEs whose object type is in `types` apply to that node and
+    /// everything below it. Object ACEs for other object types are ignored,
+    /// and ACEs without an object type apply to every node. A node is
+    /// denied a right when any node below it is denied it, and is allowed a
+    /// right once all of its children are allowed it.
+    ///
+    /// The results are in the same order as `types`.
+    ///
+    /// ```
+    /// use windows_permissions::access::{AccessCheck, ObjectTypeList};
+    /// use windows_permissions::constants::{AccessRights, GroupAttributes};
+    /// use windows_permissions::{Guid, LocalBox, SecurityDescriptor, Token};
+    ///
+    /// let guid = |s: &str| s.parse::<Guid>().unwrap();
+    /// let user = guid("bf967aba-0de6-11d0-a285-00aa003049e2");
+    /// let personal_information = guid("77b5b886-944a-11d1-aebd-0000f80367c1");
+    /// let telephone_number = guid("bf967a49-0de6-11d0-a285-00aa003049e2");
+    /// let general_information = guid("59ba2f42-79a2-11d0-9020-00c04fc2d3cf");
+    ///
+    /// // Authenticated users can write (WP) personal information only
+    /// let sd: LocalBox<SecurityDescriptor> =
+    ///     "D:(OA;;WP;77b5b886-944a-11d1-aebd-0000f80367c1;;AU)".parse().unwrap();
+    /// let token = Token::new("S-1-5-21-1-2-3-1001".parse().unwrap())
+    ///     .with_group("S-1-5-11".parse().unwrap(), GroupAttributes::Enabled);
+    ///
+    /// let list = ObjectTypeList::new(user)
+    ///     .with_property_set(personal_information)
+    ///     .with_property(telephone_number)
+    ///     .with_property_set(general_information);
+    ///
+    /// // ADS_RIGHT_DS_WRITE_PROP
+    /// let results = AccessCheck::new(&sd, &token).check_by_type(&list, AccessRights::Bit5);
+    ///
+    /// let granted: Vec<bool> = results.iter().map(|r| r.result().is_granted()).collect();
+    /// assert_eq!(granted, vec![false, true, true, false]);
+    /// ```
+    pub fn check_by_type(
+        &self,
+        types: &ObjectTypeList,
+        desired: AccessRights,
+    ) -> Vec<ObjectTypeResult> {
+        self.results_by_type(types, self.mapping.map(desired))
+    }
+
+    /// Find every right that the token is granted on each node of an object
+    /// type tree
+    ///
+    /// This combines [`maximum_allowed`](Self::maximum_allowed) with
+    /// [`check_by_type`](Self::check_by_type).
+    pub fn maximum_allowed_by_type(&self, types: &ObjectTypeList) -> Vec<ObjectTypeResult> {
+        self.results_by_type(types, self.maximum_rights())
+    }
+
+    /// Run every pass for each node of `types`, and pair up the results
+    /// with their nodes
+    fn results_by_type(
+        &self,
+        types: &ObjectTypeList,
+        desired: AccessRights,
+    ) -> Vec<ObjectTypeResult> {
+        self.run(desired, Some(types))
+            .into_iter()
+            .enumerate()
+            .map(|(index, result)| {
+                let (level, object_type) = types.get(index).expect("Result without a node");
+                ObjectTypeResult::new(level, object_type, result)
+            })
+            .collect()
+    }
+
+    /// The rights considered by [`maximum_allowed`](Self::maximum_allowed)
+    fn maximum_rights(&self) -> AccessRights {
+        let standard = AccessRights::Delete
+            | AccessRights::ReadControl
+            | AccessRights::WriteDac
+            | AccessRights::WriteOwner
+            | AccessRights::Synchronize;
+
+        let listed = self
+            .dacl
+            .map(|dacl| {
+                dacl.aces()
+                    .filter(|ace| ace_kind(ace).is_some())
+                    .fold(AccessRights::empty(), |listed, ace| {
+                        listed | self.mapping.map(ace.mask())
+                    })
+            })
+            .unwrap_or_else(AccessRights::empty);
+
+        (self.mapping.all | standard | listed) & !UNCHECKED_RIGHTS
+    }
+
+    /// Run every pass over the `desired` rights, which are already mapped,
+    /// for each node of `types`
+    fn run(&self, desired: AccessRights, types: Option<&ObjectTypeList>) -> Vec<AccessCheckResult> {
+        let count = types.map_or(1, ObjectTypeList::len);
+        let privileges = self.privilege_decisions();
+        let privileged = privileges.recorded(true);
+
+        let mut passes = vec![
+            (Pass::Privileges, vec![privileges; count]),
+            (
+                Pass::Discretionary,
+                self.evaluate(Pass::Discretionary, &self.discretionary_sids(), types),
+            ),
+        ];
+
+        if self.token.is_restricted() {
+            let restricting_sids = self
+                .token
+                .restricting_sids()
+                .iter()
+                .map(|sid| ActiveSid::from_attributes(sid, true))
+                .collect::<Vec<_>>();
+
+            let mut nodes = self.evaluate(Pass::Restricted, &restricting_sids, types);
+            if self.token.is_write_restricted() {
+                for decisions in &mut nodes {
+                    decisions.unchecked |= desired - self.mapping.write_rights();
+                }
+            }
+
+            passes.push((Pass::Restricted, nodes));
+        }
+
+        if let Some(package) = self.token.app_container_sid() {
+            let all_packages = app_package_sid(ALL_APP_PACKAGES);
+            let all_restricted_packages = app_package_sid(ALL_RESTRICTED_APP_PACKAGES);
+
+            let mut package_sids = vec![
+                ActiveSid::enabled(package),
+                ActiveSid::enabled(&all_restricted_packages),
+            ];
+            if !self.token.is_less_privileged_app_container() {
+                package_sids.push(ActiveSid::enabled(&all_packages));
+            }
+            package_sids.extend(
+                self.token
+                    .capabilities()
+                    .iter()
+                    .map(|capability| ActiveSid::from_attributes(capability, false)),
+            );
+
+            passes.push((
+                Pass::AppContainer,
+                self.evaluate(Pass::AppContainer, &package_sids, types),
+            ));
+        }
+
+        // Rights granted by privileges skip the passes over the DACL
+        for (_, nodes) in passes.iter_mut().skip(1) {
+            for decisions in nodes {
+                decisions.unchecked |= privileged;
+            }
+        }
+
+        passes.push((Pass::MandatoryLabel, vec![self.label_decisions(); count]));
+
+        (0..count)
+            .map(|node| {
+                let node_passes = passes
+                    .iter()
+                    .map(|(pass, nodes)| (*pass, &nodes[node]))
+                    .collect::<Vec<_>>();
+                summarize(desired, &node_passes)
+            })
+            .collect()
+    }
+
+    /// The user and group SIDs that take part in the discretionary pass
+    fn discretionary_sids(&self) -> Vec<ActiveSid<'a>> {
+        std::iter::once(ActiveSid::from_attributes(self.token.user(), true))
+            .chain(
+                self.token
+                    .groups()
+                    .iter()
+                    .map(|group| ActiveSid::from_attributes(group, false)),
+            )
+            .collect()
+    }
+
+    /// Work out which rights the token's privileges grant, and whether it is
+    /// missing the privilege for `ACCESS_SYSTEM_SECURITY`
+    fn privilege_decisions(&self) -> Decisions {
+        let mut decisions = Decisions {
+            unchecked: AccessRights::all() - AccessRights::AccessSystemSecurity,
+            ..Decisions::default()
+        };
+
+        let mut privileges = vec![
+            (Privilege::Security, Privilege::Security.security_rights()),
+            (
+                Privilege::TakeOwnership,
+                Privilege::TakeOwnership.security_rights(),
+            ),
+        ];
+
+        if self.backup_intent {
+            privileges.push((
+                Privilege::Backup,
+                Privilege::Backup.security_rights() | self.mapping.read | self.mapping.execute,
+            ));
+            privileges.push((
+                Privilege::Restore,
+                Privilege::Restore.security_rights() | self.mapping.write | AccessRights::Delete,
+            ));
+        }
+
+        for (privilege, rights) in privileges {
+            if self.token.has_enabled_privilege(privilege) {
+                decisions.record(rights, true, |_| AccessSource::Privilege(privilege));
+            }
+        }
+
+        decisions.record(AccessRights::AccessSystemSecurity, false, |_| {
+            AccessSource::MissingPrivilege(Privilege::Security)
+        });
+
+        decisions
+    }
+
+    /// Work out which rights the mandatory label takes away from the token
+    fn label_decisions(&self) -> Decisions {
+        let mut decisions = Decisions::everything();
+
+        let label = self.sacl.and_then(|sacl| {
+            sacl.aces().find(|ace| {
+                ace.ace_type() == AceType::SYSTEM_MANDATORY_LABEL_ACE_TYPE
+                    && !ace.flags().contains(AceFlags::InheritOnly)
+            })
+        });
+
+        let (object_level, policy) = match label {
+            Some(ace) => (
+                ace.sid()
+                    .and_then(integrity_rid)
+                    .unwrap_or(MEDIUM_INTEGRITY),
+                ace.mask(),
+            ),
+            None => (MEDIUM_INTEGRITY, AccessRights::MandatoryLabelNoWriteUp),
+        };
+
+        let token_level = self
+            .token
+            .integrity_level()
+            .and_then(integrity_rid)
+            .unwrap_or(MEDIUM_INTEGRITY);
+
+        if token_level >= object_level {
+            return decisions;
+        }
+
+        // Reading the security descriptor is always allowed
+        let always_allowed = AccessRights::ReadControl | AccessRights::Synchronize;
+        let policies = [
+            (
+                AccessRights::MandatoryLabelNoWriteUp,
+                self.mapping.write_rights(),
+            ),
+            (AccessRights::MandatoryLabelNoReadUp, self.mapping.read),
+            (
+                AccessRights::MandatoryLabelNoExecuteUp,
+                self.mapping.execute,
+            ),
+        ];
+
+        let denied = policies
+            .iter()
+            .filter(|(flag, _)| policy.contains(*flag))
+            .fold(AccessRights::empty(), |denied, (_, rights)| {
+                denied | *rights
+            })
+            - always_allowed;
+
+        decisions.record(denied, false, |_| AccessSource::MandatoryLabel);
+        decisions
+    }
+
+    /// Walk the DACL with a set of SIDs, recording which rights they are
+    /// allowed and denied for each node of `types`
+    ///
+    /// Without `types`, there is a single node for the whole object, and
+    /// object ACEs for specific object types are skipped.
+    ///
+    /// The owner's implicit rights are decided first. After that, each right
+    /// is decided by the first ACE that mentions it. Object ACEs apply to
+    /// their node and everything below it. Denials also apply to every node
+    /// above it, and a node above it is allowed a right once all of its
+    /// children are.
+    fn evaluate(
+        &self,
+        pass: Pass,
+        sids: &[ActiveSid],
+        types: Option<&ObjectTypeList>,
+    ) -> Vec<Decisions> {
+        let count = types.map_or(1, ObjectTypeList::len);
+
+        let dacl = match self.dacl {
+            Some(dacl) => dacl,
+            None => return vec![Decisions::everything(); count],
+        };
+
+        let mut nodes = vec![Decisions::default(); count];
+        let resource_claims = self.resource_claims();
+
+        let is_owner = self
+            .owner
+            .is_some_and(|owner| sids.iter().any(|s| s.allows && s.sid == owner));
+        let owner_rights_ace = dacl.aces().any(|ace| {
+            ace_kind(ace).is_some()
+                && !ace.flags().contains(AceFlags::InheritOnly)
+                && ace.sid().is_some_and(is_owner_rights)
+        });
+
+        if is_owner && !owner_rights_ace {
+            for node in &mut nodes {
+                node.record(
+                    AccessRights::ReadControl | AccessRights::WriteDac,
+                    true,
+                    |_| AccessSource::OwnerRights { pass },
+                );
+            }
+        }
+
+        for (index, ace) in dacl.aces().enumerate() {
+            let index = index as u32;
+
+            if ace.flags().contains(AceFlags::InheritOnly) {
+                continue;
+            }
+
+            let is_allow = match ace_kind(ace) {
+                Some(is_allow) => is_allow,
+                None => continue,
+            };
+
+            let ace_sid = match ace.sid() {
+                Some(sid) => sid,
+                None => continue,
+            };
+
+            let matches = if is_owner_rights(ace_sid) {
+                is_owner
+            } else {
+                sids.iter().any(|s| {
+                    let active = if is_allow { s.allows } else { s.denies };
+                    active && s.sid == ace_sid
+                })
+            };
+
+            if !matches {
+                continue;
+            }
+
+            if ace.is_callback_ace() {
+                let holds = match self.condition_result(ace, sids, is_allow, &resource_claims) {
+                    ConditionResult::True => true,
+                    ConditionResult::False => false,
+                    // Unknown conditions are safe to deny but not to allow
+                    ConditionResult::Unknown => !is_allow,
+                };
+
+                if !holds {
+                    continue;
+                }
+            }
+
+            let mask = ace.mask();
+            let mapped = self.mapping.map(mask);
+            let source = |right| {
+                if mask.contains(right) {
+                    AccessSource::Ace { pass, index }
+                } else {
+                    AccessSource::GenericAce {
+                        pass,
+                        index,
+                        generic: self.generic_source(mask, right),
+                    }
+                }
+            };
+
+            let (types, node) = match (ace.object_type(), types) {
+                (None, _) => {
+                    for node in &mut nodes {
+                        node.record(mapped, is_allow, source);
+                    }
+                    continue;
+                }
+                (Some(object_type), Some(types)) => match types.position(object_type) {
+                    Some(node) => (types, node),
+                    None => continue,
+                },
+                (Some(_), None) => continue,
+            };
+
+            for below in types.subtree(node) {
+                nodes[below].record(mapped, is_allow, source);
+            }
+
+            let mut child = node;
+            while let Some(parent) = types.parent(child) {
+                let rights = if is_allow {
+                    types.children(parent).fold(mapped, |rights, sibling| {
+                        rights & nodes[sibling].recorded(true)
+                    })
+                } else {
+                    mapped
+                };
+
+                nodes[parent].record(rights, is_allow, source);
+                child = parent;
+            }
+        }
+
+        nodes
+    }
+
+    /// Get the resource attributes of the object, from the SACL
+    fn resource_claims(&self) -> Vec<ClaimAttribute> {
+        self.sacl
+            .map(|sacl| {
+                sacl.aces()
+                    .filter(|ace| !ace.flags().contains(AceFlags::InheritOnly))
+                    .filter_map(Ace::resource_attribute)
+                    .filter_map(Result::ok)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Evaluate the condition of a callback ACE
+    ///
+    /// `Member_of` checks the SIDs of the pass that can match the ACE, so a
+    /// deny-only group counts for deny ACEs but not for allow ACEs. An ACE
+    /// with a missing or malformed condition is `Unknown`.
+    fn condition_result(
+        &self,
+        ace: &Ace,
+        sids: &[ActiveSid],
+        is_allow: bool,
+        resource_claims: &[ClaimAttribute],
+    ) -> ConditionResult {
+        match ace.condition() {
+            Some(Ok(condition)) => {
+                self.evaluate_condition(&condition, sids, is_allow, resource_claims)
+            }
+            _ => ConditionResult::Unknown,
+        }
+    }
+
+    /// Evaluate a condition, where `Member_of` checks the SIDs in `sids`
+    /// that can match an allow (or deny) ACE
+    fn evaluate_condition(
+        &self,
+        condition: &Condition,
+        sids: &[ActiveSid],
+        is_allow: bool,
+        resource_claims: &[ClaimAttribute],
+    ) -> ConditionResult {
+        let member_of = |sid: &Sid| {
+            sids.iter().any(|s| {
+                let active = if is_allow { s.allows } else { s.denies };
+                active && s.sid == sid
+            })
+        };
+        let device_member_of = |sid: &Sid| {
+            self.token
+                .device_groups()
+                .iter()
+                .map(|group| ActiveSid::from_attributes(group, false))
+                .any(|s| {
+                    let active = if is_allow { s.allows } else { s.denies };
+                    active && s.sid == sid
+                })
+        };
+
+        condition.evaluate_in(&ClaimContext {
+            member_of: &member_of,
+            device_member_of: &device_member_of,
+            user_claims: self.token.user_claims(),
+            device_claims: self.token.device_claims(),
+            resource_claims,
+        })
+    }
+
+    /// Find the generic right in `mask` that maps to `right`
+    fn generic_source(&self, mask: AccessRights, right: AccessRights) -> AccessRights {
+        [
+            (AccessRights::GenericRead, self.mapping.read),
+            (AccessRights::GenericWrite, self.mapping.write),
+            (AccessRights::GenericExecute, self.mapping.execute),
+            (AccessRights::GenericAll, self.mapping.all),
+        ]
+        .iter()
+        .find(|(generic, specific)| mask.contains(*generic) && specific.contains(right))
+        .map(|(generic, _)| *generic)
+        .unwrap_or(AccessRights::GenericAll)
+    }
+}
+
+/// Combine the decisions of every pass into a result
+fn summarize(desired: AccessRights, passes: &[(Pass, &Decisions)]) -> AccessCheckResult {
+    let rights = (0..32)
+        .map(|bit| AccessRights::from_bits_truncate(1 << bit))
+        .filter(|right| desired.contains(*right))
+        .map(|right| {
+            let denial = passes
+                .iter()
+                .map(|(pass, decisions)| decisions.decide(*pass, right))
+                .find(|(granted, _)| !granted);
+
+            let (granted, source) = denial.unwrap_or_else(|| {
+                let source = passes
+                    .iter()
+                    .find_map(|(_, decisions)| decisions.grant_source(right))
+                    .expect("Granted right has no source");
+                (true, source)
+            });
+
+            RightExplanation {
+                right,
+                granted,
+                source,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let granted = rights
+        .iter()
+        .filter(|explanation| explanation.granted)
+        .fold(AccessRights::empty(), |granted, explanation| {
+            granted | explanation.right
+        });
+
+    let passes = passes
+        .iter()
+        .map(|(pass, decisions)| PassResult {
+            pass: *pass,
+            granted: decisions.granted(*pass, desired),
+        })
+        .collect();
+
+    AccessCheckResult {
+        desired,
+        granted,
+        passes,
+        rights,
+    }
+}
+
+/// Rights that are never granted by the DACL: `ACCESS_SYSTEM_SECURITY`,
+/// `MAXIMUM_ALLOWED`, and the generic rights
+const UNCHECKED_RIGHTS: AccessRights = AccessRights::from_bits_truncate(
+    AccessRights::AccessSystemSecurity.bits()
+        | MAXIMUM_ALLOWED
+        | AccessRights::GenericRead.bits()
+        | AccessRights::GenericWrite.bits()
+        | AccessRights::GenericExecute.bits()
+        | AccessRights::GenericAll.bits(),
+);
+
+/// Whether an ACE takes part in access checks, and if so, whether it allows
+/// (rather than denies) access
+fn ace_kind(ace: &Ace) -> Option<bool> {
+    match ace.ace_type() {
+        AceType::ACCESS_ALLOWED_ACE_TYPE
+        | AceType::ACCESS_ALLOWED_OBJECT_ACE_TYPE
+        | AceType::ACCESS_ALLOWED_CALLBACK_ACE_TYPE
+        | AceType::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE => Some(true),
+        AceType::ACCESS_DENIED_ACE_TYPE
+        | AceType::ACCESS_DENIED_OBJECT_ACE_TYPE
+        | AceType::ACCESS_DENIED_CALLBACK_ACE_TYPE
+        | AceType::ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE => Some(false),
+        _ => None,
+    }
+}
+
+/// The decision that one pass made for each right
+#[derive(Debug, Clone)]
+struct Decisions {
+    /// For each bit, whether it was granted, and why
+    bits: [Option<(bool, AccessSource)>; 32],
+    /// Rights that this pass does not restrict, even if it denied them, such
+    /// as reads for the restricted pass of write-restricted tokens
+    unchecked: AccessRights,
+    /// Whether every right without a decision is granted, as with a null
+    /// DACL
+    grant_undecided: Option<AccessSource>,
+}
+
+impl Default for Decisions {
+    fn default() -> Self {
+        Self {
+            bits: [None; 32],
+            unchecked: AccessRights::empty(),
+            grant_undecided: None,
+        }
+    }
+}
+
+impl Decisions {
+    /// Decisions that grant every right
+    fn everything() -> Self {
+        Self {
+            grant_undecided: Some(AccessSource::NullDacl),
+            ..Self::default()
+        }
+    }
+
+    /// Decide the undecided rights in `rights`
+    fn record<F: Fn(AccessRights) -> AccessSource>(
+        &mut self,
+        rights: AccessRights,
+        granted: bool,
+        source: F,
+    ) {
+        for (bit, decision) in self.bits.iter_mut().enumerate() {
+            let right = AccessRights::from_bits_truncate(1 << bit);
+            if decision.is_none() && rights.contains(right) {
+                *decision = Some((granted, source(right)));
+            }
+        }
+    }
+
+    /// Get the rights that were decided, and were either granted or denied
+    fn recorded(&self, granted: bool) -> AccessRights {
+        self.bits
+            .iter()
+            .enumerate()
+            .filter(|(_, decision)| matches!(decision, Some((g, _)) if *g == granted))
+            .fold(AccessRights::empty(), |rights, (bit, _)| {
+                rights | AccessRights::from_bits_truncate(1 << bit)
+            })
+    }
+
+    /// Get what granted a right, if this pass granted it for a reason of its
+    /// own
+    fn grant_source(&self, right: AccessRights) -> Option<AccessSource> {
+        let bit = right.bits().trailing_zeros() as usize;
+
+        match self.bits[bit] {
+            Some((true, source)) => Some(source),
+            Some((false, _)) => None,
+            None if self.unchecked.contains(right) => None,
+            None => self.grant_undecided,
+        }
+    }
+
+    /// Get whether a single right is granted, and why
+    fn decide(&self, pass: Pass, right: AccessRights) -> (bool, AccessSource) {
+        let bit = right.bits().trailing_zeros() as usize;
+
+        if let Some(source) = self.grant_source(right) {
+            return (true, source);
+        }
+
+        // Unchecked rights are granted, but it's up to another pass to say why
+        if self.unchecked.contains(right) {
+            return (true, AccessSource::NotGranted { pass });
+        }
+
+        self.bits[bit].unwrap_or((false, AccessSource::NotGranted { pass }))
+    }
+
+    /// Get the rights in `desired` that are granted
+    fn granted(&self, pass: Pass, desired: AccessRights) -> AccessRights {
+        (0..32)
+            .map(|bit| AccessRights::from_bits_truncate(1 << bit))
+            .filter(|right| desired.contains(*right) && self.decide(pass, *right).0)
+            .fold(AccessRights::empty(), |granted, right| granted | right)
+    }
+}
+
+/// A SID from the token that takes part in a pass
+#[derive(Debug, Clone, Copy)]
+struct ActiveSid<'a> {
+    sid: &'a Sid,
+    /// Whether the SID matches access-allowed ACEs
+    allows: bool,
+    /// Whether the SID matches access-denied ACEs
+    denies: bool,
+}
+
+impl<'a> ActiveSid<'a> {
+    /// A SID that matches all ACEs
+    fn enabled(sid: &'a Sid) -> Self {
+        Self {
+            sid,
+            allows: true,
+            denies: true,
+        }
+    }
+
+    /// Work out how a SID takes part, given whether it counts without being
+    /// enabled (as the user SID and restricting SIDs do)
+    fn from_attributes(sid: &'a SidAndAttributes, always_enabled: bool) -> Self {
+        let attributes = sid.attributes();
+        let deny_only = attributes.contains(GroupAttributes::UseForDenyOnly);
+        let enabled = always_enabled || attributes.contains(GroupAttributes::Enabled);
+
+        Self {
+            sid: sid.sid(),
+            allows: enabled && !deny_only,
+            denies: enabled || deny_only,
+        }
+    }
+}
+
+/// The RID of the Medium mandatory integrity level (`S-1-16-8192`)
+const MEDIUM_INTEGRITY: u32 = 0x2000;
+
+/// Get the level of a mandatory integrity label SID (`S-1-16-*`)
+fn integrity_rid(sid: &Sid) -> Option<u32> {
+    if sid.id_authority() == &[0, 0, 0, 0, 0, 16] {
+        sid.sub_authority(0)
+    } else {
+        None
+    }
+}
+
+/// The last sub-authority of `ALL APPLICATION PACKAGES` (`S-1-15-2-1`)
+const ALL_APP_PACKAGES: u32 = 1;
+
+/// The last sub-authority of `ALL RESTRICTED APPLICATION PACKAGES`
+/// (`S-1-15-2-2`)
+const ALL_RESTRICTED_APP_PACKAGES: u32 = 2;
+
+/// Build one of the `S-1-15-2-*` application package SIDs
+fn app_package_sid(rid: u32) -> LocalBox<Sid> {
+    Sid::new([0, 0, 0, 0, 0, 15], &[2, rid]).expect("Failed to build a well-known SID")
+}
+
+/// Check whether a SID is `OWNER RIGHTS` (`S-1-3-4`)
+fn is_owner_rights(sid: &Sid) -> bool {
+    sid.id_authority() == &[0, 0, 0, 0, 0, 3] && sid.sub_authorities() == [4]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::constants::PrivilegeAttributes;
+
+    const USER: &str = "S-1-5-21-1-2-3-1001";
+    const USERS: &str = "S-1-5-32-545";
+    const ADMINS: &str = "S-1-5-32-544";
+    const RESTRICTED: &str = "S-1-5-12";
+
+    fn sd(sddl: &str) -> LocalBox<SecurityDescriptor> {
+        sddl.parse().unwrap()
+    }
+
+    fn sid(s: &str) -> LocalBox<Sid> {
+        s.parse().unwrap()
+    }
+
+    fn user_token() -> Token {
+        Token::new(sid(USER)).with_group(sid(USERS), GroupAttributes::Enabled)
+    }
+
+    fn check(sddl: &str, token: &Token, desired: AccessRights) -> AccessCheckResult {
+        AccessCheck::new(&sd(sddl), token).check(desired)
+    }
+
+    #[test]
+    fn discretionary() {
+        let token = user_token();
+        let read = AccessRights::FileGenericRead;
+        let write = AccessRights::FileGenericWrite;
+
+        assert!(check("D:(A;;FR;;;BU)", &token, read).is_granted());
+        assert!(!check("D:(A;;FR;;;BU)", &token, write).is_granted());
+        assert!(!check("D:(A;;FR;;;BA)", &token, read).is_granted());
+        assert!(check("D:(A;;GR;;;BU)", &token, AccessRights::GenericRead).is_granted());
+
+        // The first ACE to mention a right decides it
+        assert!(!check("D:(D;;FR;;;BU)(A;;FA;;;BU)", &token, read).is_granted());
+        assert!(check("D:(A;;FA;;;BU)(D;;FR;;;BU)", &token, read).is_granted());
+
+        // Inherit-only ACEs don't apply to the object itself
+        assert!(!check("D:(A;IO;FA;;;BU)", &token, read).is_granted());
+
+        // No DACL and a null DACL grant everything, an empty DACL nothing
+        assert!(check("", &token, AccessRights::FileAllAccess).is_granted());
+        assert!(check("D:NO_ACCESS_CONTROL", &token, AccessRights::FileAllAccess).is_granted());
+        assert!(!check("D:", &token, read).is_granted());
+
+        let partial = check(
+            "D:(A;;0x1;;;BU)",
+            &token,
+            AccessRights::Bit0 | AccessRights::Bit1,
+        );
+        assert_eq!(partial.granted(), AccessRights::Bit0);
+        assert_eq!(partial.denied_by(), Some(Pass::Discretionary));
+    }
+
+    #[test]
+    fn disabled_and_deny_only_groups() {
+        let read = AccessRights::FileGenericRead;
+
+        let token = Token::new(sid(USER))
+            .with_group(sid(USERS), GroupAttributes::UseForDenyOnly)
+            .with_group(sid(ADMINS), GroupAttributes::empty());
+
+        // Neither group can be used to allow access...
+        assert!(!check("D:(A;;FR;;;BU)", &token, read).is_granted());
+        assert!(!check("D:(A;;FR;;;BA)", &token, read).is_granted());
+
+        // ...but the deny-only group is still denied
+        let sddl = "D:(D;;FR;;;BU)(A;;FR;;;S-1-5-21-1-2-3-1001)";
+        assert!(!check(sddl, &token, read).is_granted());
+        let sddl = "D:(D;;FR;;;BA)(A;;FR;;;S-1-5-21-1-2-3-1001)";
+        assert!(check(sddl, &token, read).is_granted());
+    }
+
+    #[test]
+    fn owner_rights() {
+        let token = user_token();
+        let rights = AccessRights::ReadControl | AccessRights::WriteDac;
+        let owned = format!("O:{}D:", USER);
+
+        assert!(check(&owned, &token, rights).is_granted());
+        assert!(!check("O:BAD:", &token, rights).is_granted());
+
+        // Implicit owner rights can't be denied by the DACL...
+        let denied = format!("O:{}D:(D;;WD;;;BU)", USER);
+        assert!(check(&denied, &token, rights).is_granted());
+
+        // ...but an OWNER RIGHTS ACE replaces them
+        let replaced = format!("O:{}D:(A;;RC;;;OW)", USER);
+        assert!(check(&replaced, &token, AccessRights::ReadControl).is_granted());
+        assert!(!check(&replaced, &token, AccessRights::WriteDac).is_granted());
+
+        // A bare DACL has no owner unless one is given
+        let sd = sd("D:");
+        let owner = sid(USER);
+        let bare = AccessCheck::for_dacl(sd.dacl(), &token);
+        assert!(!bare.check(rights).is_granted());
+        assert!(bare.with_owner(Some(&owner)).check(rights).is_granted());
+    }
+
+    #[test]
+    fn restricted() {
+        let token = user_token().with_restricting_sid(sid(RESTRICTED), GroupAttributes::Enabled);
+        let read = AccessRights::FileGenericRead;
+        let write = AccessRights::FileGenericWrite;
+        let sddl = "D:(A;;FA;;;BU)(A;;FR;;;RC)";
+
+        let result = check(sddl, &token, read);
+        assert!(result.is_granted());
+        assert!(result
+            .pass(Pass::Restricted)
+            .unwrap()
+            .granted()
+            .contains(read));
+
+        let result = check(sddl, &token, write);
+        assert!(!result.is_granted());
+        assert_eq!(result.denied_by(), Some(Pass::Restricted));
+        assert_eq!(result.pass(Pass::Discretionary).unwrap().granted(), write);
+
+        // The restricting SIDs alone grant nothing
+        let result = check("D:(A;;FA;;;RC)", &token, read);
+        assert_eq!(result.denied_by(), Some(Pass::Discretionary));
+
+        // Deny-only restricting SIDs only match deny ACEs
+        let token = user_token()
+            .with_restricting_sid(sid(USERS), GroupAttributes::UseForDenyOnly)
+            .with_restricting_sid(sid(RESTRICTED), GroupAttributes::Enabled);
+        assert!(!check("D:(A;;FA;;;BU)", &token, read).is_granted());
+        assert!(!check("D:(D;;FR;;;BU)(A;;FA;;;BU)(A;;FA;;;RC)", &token, read).is_granted());
+        assert!(check("D:(A;;FA;;;BU)(A;;FA;;;RC)", &token, read).is_granted());
+    }
+
+    #[test]
+    fn restricted_owner() {
+        let rights = AccessRights::ReadControl | AccessRights::WriteDac;
+        let owned = format!("O:{}D:", USER);
+
+        // The owner must also be one of the restricting SIDs
+        let token = user_token().with_restricting_sid(sid(RESTRICTED), GroupAttributes::Enabled);
+        assert_eq!(
+            check(&owned, &token, rights).denied_by(),
+            Some(Pass::Restricted)
+        );
+
+        let token = user_token().with_restricting_sid(sid(USER), GroupAttributes::Enabled);
+        assert!(check(&owned, &token, rights).is_granted());
+    }
+
+    #[test]
+    fn write_restricted() {
+        let token = user_token()
+            .with_restricting_sid(sid(RESTRICTED), GroupAttributes::Enabled)
+            .with_write_restricted(true);
+        let sddl = "D:(A;;FA;;;BU)";
+
+        // Reads only need the normal pass
+        let result = check(sddl, &token, AccessRights::FileGenericRead);
+        assert!(result.is_granted());
+
+        // Writes need both
+        let result = check(sddl, &token, AccessRights::FileGenericWrite);
+        assert_eq!(result.denied_by(), Some(Pass::Restricted));
+        assert!(check(
+            "D:(A;;FA;;;BU)(A;;FW;;;RC)",
+            &token,
+            AccessRights::FileGenericWrite
+        )
+        .is_granted());
+    }
+
+    #[test]
+    fn app_container() {
+        const PACKAGE: &str = "S-1-15-2-1-2-3-4-5-6-7";
+        const CAPABILITY: &str = "S-1-15-3-1";
+
+        let token = user_token()
+            .with_app_container_sid(sid(PACKAGE))
+            .with_capability(sid(CAPABILITY), GroupAttributes::Enabled);
+        let read = AccessRights::FileGenericRead;
+
+        // The user has access, but the package doesn't
+        let result = check("D:(A;;FA;;;BU)", &token, read);
+        assert_eq!(result.denied_by(), Some(Pass::AppContainer));
+        assert!(result
+            .pass(Pass::Discretionary)
+            .unwrap()
+            .granted()
+            .contains(read));
+
+        // The package SID, ALL APPLICATION PACKAGES, and capabilities all work
+        for grantee in &[PACKAGE, "AC", "S-1-15-2-2", CAPABILITY] {
+            let sddl = format!("D:(A;;FA;;;BU)(A;;FR;;;{})", grantee);
+            assert!(check(&sddl, &token, read).is_granted(), "{}", grantee);
+        }
+
+        // The package can't grant more than the user has
+        let result = check(
+            "D:(A;;FR;;;BU)(A;;FA;;;AC)",
+            &token,
+            read | AccessRights::Delete,
+        );
+        assert_eq!(result.denied_by(), Some(Pass::Discretionary));
+
+        // Disabled capabilities don't count
+        let token = user_token()
+            .with_app_container_sid(sid(PACKAGE))
+            .with_capability(sid(CAPABILITY), GroupAttributes::empty());
+        let sddl = format!("D:(A;;FA;;;BU)(A;;FR;;;{})", CAPABILITY);
+        assert!(!check(&sddl, &token, read).is_granted());
+    }
+
+    #[test]
+    fn less_privileged_app_container() {
+        let token = user_token()
+            .with_app_container_sid(sid("S-1-15-2-1-2-3-4-5-6-7"))
+            .with_less_privileged_app_container(true);
+        let read = AccessRights::FileGenericRead;
+
+        assert!(!check("D:(A;;FA;;;BU)(A;;FR;;;AC)", &token, read).is_granted());
+        assert!(check("D:(A;;FA;;;BU)(A;;FR;;;S-1-15-2-2)", &token, read).is_granted());
+    }
+
+    #[test]
+    fn mandatory_label() {
+        let low = user_token().with_integrity_level(sid("LW"));
+        let medium = user_token().with_integrity_level(sid("ME"));
+        let read = AccessRights::FileGenericRead;
+        let write = AccessRights::FileGenericWrite;
+        let execute = AccessRights::FileGenericExecute;
+
+        // Unlabelled objects are Medium with no-write-up
+        let result = check("D:(A;;FA;;;BU)", &low, write);
+        assert_eq!(result.denied_by(), Some(Pass::MandatoryLabel));
+        assert!(result.granted().contains(AccessRights::ReadControl));
+        assert!(check("D:(A;;FA;;;BU)", &low, read).is_granted());
+        assert!(check("D:(A;;FA;;;BU)", &medium, write).is_granted());
+
+        // An explicit label can lower the level, or add other policies
+        assert!(check("D:(A;;FA;;;BU)S:(ML;;NW;;;LW)", &low, write).is_granted());
+        let sddl = "D:(A;;FA;;;BU)S:(ML;;NWNRNX;;;HI)";
+        for rights in &[read, write, execute] {
+            let result = check(sddl, &medium, *rights);
+            assert_eq!(result.denied_by(), Some(Pass::MandatoryLabel));
+        }
+        assert!(check(sddl, &medium, AccessRights::ReadControl).is_granted());
+
+        // The DACL is still reported when it denies access too
+        let result = check("D:(A;;FR;;;BU)", &low, write);
+        assert_eq!(result.denied_by(), Some(Pass::Discretionary));
+
+        // Inherit-only labels only apply to children
+        assert!(check("D:(A;;FA;;;BU)S:(ML;OIIO;NW;;;HI)", &medium, write).is_granted());
+
+        // Tokens without a level are Medium
+        assert!(check("D:(A;;FA;;;BU)", &user_token(), write).is_granted());
+        assert!(!check("D:(A;;FA;;;BU)S:(ML;;NW;;;HI)", &user_token(), write).is_granted());
+    }
+
+    #[test]
+    fn maximum_allowed() {
+        let token = user_token().with_integrity_level(sid("LW"));
+        let owned = format!(
+            "O:{}D:(A;;GR;;;BU)(D;;WP;;;BU)(A;;0x10116;;;BU)(A;;0x100000;;;WD)",
+            USER
+        );
+        let sd = sd(&owned);
+        let result = AccessCheck::new(&sd, &token).maximum_allowed();
+
+        let source = |right| result.explain(right).unwrap().source();
+        let discretionary = Pass::Discretionary;
+
+        assert_eq!(result.granted(), AccessRights::FileGenericRead);
+
+        // The owner's implicit rights come before the ACEs
+        assert_eq!(
+            source(AccessRights::ReadControl),
+            AccessSource::OwnerRights {
+                pass: discretionary
+            }
+        );
+        assert_eq!(
+            source(AccessRights::Bit0),
+            AccessSource::GenericAce {
+                pass: discretionary,
+                index: 0,
+                generic: AccessRights::GenericRead
+            }
+        );
+        assert_eq!(
+            source(AccessRights::Bit5),
+            AccessSource::Ace {
+                pass: discretionary,
+                index: 1
+            }
+        );
+
+        // The DACL allows these, but the label takes them away
+        for right in &[
+            AccessRights::Bit1,
+            AccessRights::Delete,
+            AccessRights::WriteDac,
+        ] {
+            let explanation = result.explain(*right).unwrap();
+            assert!(!explanation.is_granted());
+            assert_eq!(explanation.source(), AccessSource::MandatoryLabel);
+        }
+
+        assert_eq!(
+            source(AccessRights::WriteOwner),
+            AccessSource::NotGranted {
+                pass: discretionary
+            }
+        );
+        assert!(result.explain(AccessRights::AccessSystemSecurity).is_none());
+        assert!(result
+            .explain(AccessRights::Bit0 | AccessRights::Bit1)
+            .is_none());
+    }
+
+    #[test]
+    fn maximum_allowed_passes() {
+        let result = check("D:NO_ACCESS_CONTROL", &user_token(), AccessRights::Delete);
+        assert_eq!(result.rights()[0].source(), AccessSource::NullDacl);
+
+        let token = user_token().with_restricting_sid(sid(RESTRICTED), GroupAttributes::Enabled);
+        let sd = sd("D:(A;;FA;;;BU)(A;;FR;;;RC)");
+        let result = AccessCheck::new(&sd, &token).maximum_allowed();
+
+        assert_eq!(result.granted(), AccessRights::FileGenericRead);
+        assert_eq!(
+            result.pass(Pass::Discretionary).unwrap().granted(),
+            AccessRights::FileAllAccess
+        );
+        assert_eq!(
+            result.explain(AccessRights::Delete).unwrap().source(),
+            AccessSource::NotGranted {
+                pass: Pass::Restricted
+            }
+        );
+        assert_eq!(
+            result.explain(AccessRights::ReadControl).unwrap().source(),
+            AccessSource::Ace {
+                pass: Pass::Discretionary,
+                index: 0
+            }
+        );
+    }
+
+    #[test]
+    fn privileges() {
+        let enabled = PrivilegeAttributes::Enabled;
+        let sacl = AccessRights::AccessSystemSecurity;
+        let sddl = "D:(A;;FA;;;BU)";
+
+        // ACCESS_SYSTEM_SECURITY always needs SeSecurityPrivilege
+        let result = check(sddl, &user_token(), sacl);
+        assert_eq!(result.denied_by(), Some(Pass::Privileges));
+        assert_eq!(
+            result.rights()[0].source(),
+            AccessSource::MissingPrivilege(Privilege::Security)
+        );
+        let token = user_token().with_privilege(Privilege::Security, enabled);
+        assert!(check("D:", &token, sacl).is_granted());
+
+        // Disabled privileges don't count
+        let token =
+            user_token().with_privilege(Privilege::TakeOwnership, PrivilegeAttributes::empty());
+        assert!(!check("D:", &token, AccessRights::WriteOwner).is_granted());
+
+        let token = user_token().with_privilege(Privilege::TakeOwnership, enabled);
+        let result = check("D:(D;;WO;;;BU)", &token, AccessRights::WriteOwner);
+        assert!(result.is_granted());
+        assert_eq!(
+            result.rights()[0].source(),
+            AccessSource::Privilege(Privilege::TakeOwnership)
+        );
+
+        // Backup and restore only apply with backup intent
+        let token = user_token()
+            .with_privilege(Privilege::Backup, enabled)
+            .with_privilege(Privilege::Restore, enabled);
+        let all = AccessRights::FileGenericRead
+            | AccessRights::FileGenericWrite
+            | AccessRights::Delete
+            | AccessRights::WriteDac
+            | AccessRights::WriteOwner
+            | sacl;
+        assert!(!check("D:", &token, AccessRights::FileGenericRead).is_granted());
+
+        let sd = sd("D:(D;;FA;;;BU)");
+        let backup = AccessCheck::new(&sd, &token).with_backup_intent(true);
+        assert!(backup.check(all).is_granted());
+        assert_eq!(
+            backup.check(AccessRights::Delete).rights()[0].source(),
+            AccessSource::Privilege(Privilege::Restore)
+        );
+
+        // Privileges skip the restricted pass, but not the mandatory label
+        let token = token
+            .with_restricting_sid(sid(RESTRICTED), GroupAttributes::Enabled)
+            .with_integrity_level(sid("LW"));
+        let backup = AccessCheck::new(&sd, &token).with_backup_intent(true);
+        assert!(backup.check(AccessRights::FileGenericRead).is_granted());
+        assert_eq!(
+            backup.check(AccessRights::FileGenericWrite).denied_by(),
+            Some(Pass::MandatoryLabel)
+        );
+    }
+
+    #[test]
+    fn object_types() {
+        let token = user_token();
+        let guid = |n: u8| crate::Guid::from_bytes([n; 16]);
+        let prop = AccessRights::Bit5;
+        let both = AccessRights::Bit4 | AccessRights::Bit5;
+
+        // class
+        // ├── set_a
+        // │   ├── a1
+        // │   └── a2
+        // └── set_b
+        let (class, set_a, a1, a2, set_b) = (guid(1), guid(2), guid(3), guid(4), guid(5));
+        let list = ObjectTypeList::new(class)
+            .with_property_set(set_a)
+            .with_property(a1)
+            .with_property(a2)
+            .with_property_set(set_b);
+
+        let by_type = |sddl: &str, desired| {
+            AccessCheck::new(&sd(sddl), &token)
+                .check_by_type(&list, desired)
+                .iter()
+                .map(|node| node.result().granted())
+                .collect::<Vec<_>>()
+        };
+        let none = AccessRights::empty();
+
+        // A grant on a property set covers its properties
+        assert_eq!(
+            by_type(&format!("D:(OA;;0x20;{};;BU)", set_a), prop),
+            vec![none, prop, prop, prop, none]
+        );
+
+        // A denial on a property also denies everything above it
+        assert_eq!(
+            by_type(&format!("D:(OD;;0x20;{};;BU)(A;;0x30;;;BU)", a1), both),
+            vec![both - prop, both - prop, both - prop, both, both]
+        );
+
+        // Granting every child grants the parent
+        assert_eq!(
+            by_type(
+                &format!("D:(OA;;0x20;{};;BU)(OA;;0x20;{};;BU)", set_a, set_b),
+                prop
+            ),
+            vec![prop, prop, prop, prop, prop]
+        );
+
+        // Object types outside the list are ignored
+        let results = AccessCheck::new(&sd(&format!("D:(OA;;0x20;{};;BU)", guid(9))), &token)
+            .check_by_type(&list, prop);
+        assert!(results.iter().all(|node| !node.result().is_granted()));
+        assert_eq!(results[2].level(), 2);
+        assert_eq!(results[2].object_type(), a1);
+
+        // Without a list, object ACEs for specific object types are skipped
+        let sddl = format!("D:(OA;;0x20;{};;BU)(OA;;0x10;;;BU)", class);
+        assert_eq!(check(&sddl, &token, both).granted(), AccessRights::Bit4);
+
+        // Maximum allowed is explained per node
+        let maximum = AccessCheck::new(&sd(&format!("D:(OA;;0x20;{};;BU)", a2)), &token)
+            .maximum_allowed_by_type(&list);
+        assert_eq!(
+            maximum
+                .iter()
+                .map(|n| n.result().granted())
+                .collect::<Vec<_>>(),
+            vec![none, none, none, prop, none]
+        );
+        assert_eq!(
+            maximum[3].result().explain(prop).unwrap().source(),
+            AccessSource::Ace {
+                pass: Pass::Discretionary,
+                index: 0
+            }
+        );
+    }
+
+    #[test]
+    fn conditional_aces() {
+        use crate::constants::ClaimFlags;
+        use crate::token::ClaimValues;
+
+        let read = AccessRights::FileGenericRead;
+        let token = user_token()
+            .with_group(sid(ADMINS), GroupAttributes::UseForDenyOnly)
+            .with_device_group(sid("S-1-5-21-1-2-3-2001"), GroupAttributes::Enabled)
+            .with_user_claim(ClaimAttribute::new(
+                "Clearance",
+                ClaimFlags::empty(),
+                ClaimValues::Int64(vec![2]),
+            ))
+            .with_device_claim(ClaimAttribute::new(
+                "Managed",
+                ClaimFlags::empty(),
+                ClaimValues::Boolean(vec![true]),
+            ));
+        let granted = |sddl: &str| check(sddl, &token, read).is_granted();
+
+        // Membership, using the SIDs that can match the ACE
+        assert!(granted("D:(XA;;FR;;;BU;(Member_of {SID(BU)}))"));
+        assert!(!granted("D:(XA;;FR;;;BU;(Member_of {SID(BA)}))"));
+        assert!(!granted(
+            "D:(XD;;FR;;;BU;(Member_of {SID(BA)}))(A;;FR;;;BU)"
+        ));
+        assert!(granted(
+            "D:(XA;;FR;;;BU;(Device_Member_of {SID(S-1-5-21-1-2-3-2001)}))"
+        ));
+        assert!(granted("D:(XA;;FR;;;BU;(@Device.Managed == 1))"));
+
+        // Resource attributes come from the SACL
+        let resource = |level: u64| format!("S:(RA;;;;;WD;(\"Secrecy\",TU,0x0,{}))", level);
+        let compare = "D:(XA;;FR;;;BU;(@User.Clearance >= @Resource.Secrecy))";
+        assert!(granted(&format!("{}{}", compare, resource(1))));
+        assert!(!granted(&format!("{}{}", compare, resource(3))));
+        assert!(!granted(compare));
+
+        // Unknown conditions never allow, but always deny
+        let unknown = "(@User.Missing == 1)";
+        assert!(!granted(&format!("D:(XA;;FR;;;BU;{})", unknown)));
+        assert!(!granted(&format!(
+            "D:(XD;;FR;;;BU;{})(A;;FR;;;BU)",
+            unknown
+        )));
+        assert!(granted("D:(XD;;FR;;;BU;(@User.Clearance > 5))(A;;FR;;;BU)"));
+
+        // A denial is explained by the conditional ACE
+        let result = check(
+            "D:(XD;;FR;;;BU;(Exists @User.Clearance))(A;;FR;;;BU)",
+            &token,
+            read,
+        );
+        assert_eq!(result.denied_by(), Some(Pass::Discretionary));
+
+        // Conditions are evaluated in the restricted pass with its own SIDs
+        let restricted = token
+            .clone()
+            .with_restricting_sid(sid(RESTRICTED), GroupAttributes::Enabled);
+        let sddl = "D:(A;;FR;;;BU)(XA;;FR;;;RC;(Member_of {SID(RC)}))";
+        assert!(check(sddl, &restricted, read).is_granted());
+        let sddl = "D:(A;;FR;;;BU)(XA;;FR;;;RC;(Member_of {SID(BU)}))";
+        assert_eq!(
+            check(sddl, &restricted, read).denied_by(),
+            Some(Pass::Restricted)
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Object type lists, for access checks on parts of an object
+
+use super::AccessCheckResult;
+use crate::Guid;
+use std::io;
+use std::ops::Range;
+
+/// The most deeply nested level allowed in an object type list, matching
+/// `ACCESS_MAX_LEVEL`
+const MAX_LEVEL: u16 = 4;
+
+/// A tree of object types, such as a directory object class with its
+/// property sets and properties.
+///
+/// This is the portable equivalent of the array of
+/// [`OBJECT_TYPE_LIST`](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-object_type_list)
+/// structures passed to `AccessCheckByTypeResultList`. The tree is stored in
+/// depth-first order, with each node's level giving its depth.
+///
+/// ```
+/// use windows_permissions::access::ObjectTypeList;
+/// use windows_permissions::Guid;
+///
+/// let guid = |s: &str| s.parse::<Guid>().unwrap();
+///
+/// // user → Personal-Information → Telephone-Number
+/// let list = ObjectTypeList::new(guid("bf967aba-0de6-11d0-a285-00aa003049e2"))
+///     .with_property_set(guid("77b5b886-944a-11d1-aebd-0000f80367c1"))
+///     .with_property(guid("bf967a49-0de6-11d0-a285-00aa003049e2"));
+///
+/// assert_eq!(list.len(), 3);
+/// assert_eq!(list.get(2), Some((2, guid("bf967a49-0de6-11d0-a285-00aa003049e2"))));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ObjectTypeList {
+    nodes: Vec<(u16, Guid)>,
+}
+
+impl ObjectTypeList {
+    /// Create a list holding only the object itself, at level 0
+    pub fn new(object_type: Guid) -> Self {
+        Self {
+            nodes: vec![(0, object_type)],
+        }
+    }
+
+    /// Build a list from `(level, object type)` pairs in depth-first order
+    ///
+    /// The first pair must be the only one at level 0. Each other pair must
+    /// be at most one level deeper than the pair before it, and no deeper
+    /// than level 4.
+    pub fn from_levels(nodes: &[(u16, Guid)]) -> io::Result<Self> {
+        let invalid = |reason: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid object type list: {}", reason),
+            )
+        };
+
+        match nodes.first() {
+            Some((0, _)) => (),
+            Some(_) => return Err(invalid("the first object type must be at level 0")),
+            None => return Err(invalid("the list is empty")),
+        }
+
+        for pair in nodes.windows(2) {
+            let (previous, level) = (pair[0].0, pair[1].0);
+
+            if level == 0 {
+                return Err(invalid("only the first object type can be at level 0"));
+            }
+            if level > MAX_LEVEL {
+                return Err(invalid("object type level exceeds 4"));
+            }
+            if level > previous + 1 {
+                return Err(invalid("an object type skips a level"));
+            }
+        }
+
+        Ok(Self {
+            nodes: nodes.to_vec(),
+        })
+    }
+
+    /// Add a property set, at level 1
+    pub fn with_property_set(mut self, property_set: Guid) -> Self {
+        self.nodes.push((1, property_set));
+        self
+    }
+
+    /// Add a property to the most recently added property set, at level 2
+    ///
+    /// # Panics
+    ///
+    /// Panics if no property set has been added yet.
+    pub fn with_property(mut self, property: Guid) -> Self {
+        assert!(
+            self.nodes.len() > 1,
+            "A property must be added after its property set"
+        );
+        self.nodes.push((2, property));
+        self
+    }
+
+    /// Get the number of object types in the list
+    #[allow(clippy::len_without_is_empty)] // Never empty
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Get the level and object type at an index
+    pub fn get(&self, index: usize) -> Option<(u16, Guid)> {
+        self.nodes.get(index).copied()
+    }
+
+    /// Find the index of an object type
+    pub(crate) fn position(&self, object_type: Guid) -> Option<usize> {
+        self.nodes.iter().position(|(_, guid)| *guid == object_type)
+    }
+
+    /// Get the indices of a node and everything below it
+    pub(crate) fn subtree(&self, index: usize) -> Range<usize> {
+        let level = self.nodes[index].0;
+        let end = self.nodes[index + 1..]
+            .iter()
+            .position(|(l, _)| *l <= level)
+            .map_or(self.nodes.len(), |offset| index + 1 + offset);
+
+        index..end
+    }
+
+    /// Get the index of a node's parent
+    pub(crate) fn parent(&self, index: usize) -> Option<usize> {
+        let level = self.nodes[index].0;
+        self.nodes[..index].iter().rposition(|(l, _)| *l < level)
+    }
+
+    /// Get the indices of a node's children
+    pub(crate) fn children(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let level = self.nodes[index].0;
+        self.subtree(index)
+            .skip(1)
+            .filter(move |child| self.nodes[*child].0 == level + 1)
+    }
+}
+
+/// The outcome of an access check for one node of an [`ObjectTypeList`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ObjectTypeResult {
+    level: u16,
+    object_type: Guid,
+    result: AccessCheckResult,
+}
+
+impl ObjectTypeResult {
+    pub(crate) fn new(level: u16, object_type: Guid, result: AccessCheckResult) -> Self {
+        Self {
+            level,
+            object_type,
+            result,
+        }
+    }
+
+    /// Get the level of the node in the tree
+    pub fn level(&self) -> u16 {
+        self.level
+    }
+
+    /// Get the object type of the node
+    pub fn object_type(&self) -> Guid {
+        self.object_type
+    }
+
+    /// Get the access check result for the node
+    pub fn result(&self) -> &AccessCheckResult {
+        &self.result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn guid(n: u8) -> Guid {
+        Guid::from_bytes([n; 16])
+    }
+
+    #[test]
+    fn tree_navigation() {
+        // 0
+        // ├── 1
+        // │   ├── 2
+        // │   └── 3
+        // └── 4
+        //     └── 5
+        let list = ObjectTypeList::from_levels(&[
+            (0, guid(0)),
+            (1, guid(1)),
+            (2, guid(2)),
+            (2, guid(3)),
+            (1, guid(4)),
+            (2, guid(5)),
+        ])
+        .unwrap();
+
+        assert_eq!(list.subtree(0), 0..6);
+        assert_eq!(list.subtree(1), 1..4);
+        assert_eq!(list.subtree(3), 3..4);
+        assert_eq!(list.subtree(4), 4..6);
+        assert_eq!(list.parent(0), None);
+        assert_eq!(list.parent(3), Some(1));
+        assert_eq!(list.parent(4), Some(0));
+        assert_eq!(list.children(0).collect::<Vec<_>>(), vec![1, 4]);
+        assert_eq!(list.children(1).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(list.position(guid(5)), Some(5));
+        assert_eq!(list.position(guid(9)), None);
+    }
+
+    #[test]
+    fn invalid_levels() {
+        assert!(ObjectTypeList::from_levels(&[]).is_err());
+        assert!(ObjectTypeList::from_levels(&[(1, guid(0))]).is_err());
+        assert!(ObjectTypeList::from_levels(&[(0, guid(0)), (0, guid(1))]).is_err());
+
+        let skipped = ObjectTypeList::from_levels(&[(0, guid(0)), (2, guid(1))]).unwrap_err();
+        assert!(skipped.to_string().contains("skips a level"));
+
+        let too_deep = ObjectTypeList::from_levels(&[
+            (0, guid(0)),
+            (1, guid(1)),
+            (2, guid(2)),
+            (3, guid(3)),
+            (4, guid(4)),
+            (5, guid(5)),
+        ])
+        .unwrap_err();
+        assert!(too_deep.to_string().contains("level exceeds 4"));
+    }
+}
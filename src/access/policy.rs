@@ -0,0 +1,435 @@
+//! Central access policies, applied through scoped policy ID ACEs
+
+use super::{AccessCheck, AccessCheckResult, ConditionResult};
+use crate::constants::{AccessRights, AceFlags, AceType};
+use crate::{Condition, LocalBox, SecurityDescriptor, Sid};
+
+/// The DACL of the recovery policy, which Windows applies when an object
+/// refers to a central access policy that it does not know about
+const RECOVERY_POLICY: &str = "D:(A;;GA;;;OW)(A;;GA;;;BA)(A;;GA;;;SY)";
+
+/// A rule of a [`CentralAccessPolicy`]
+///
+/// A rule applies to the objects that its applies-to condition holds for,
+/// usually based on their resource attributes. For those objects, it limits
+/// access to what the DACL of its effective permissions allows. Proposed
+/// permissions can be staged alongside them, to see how a change would
+/// affect access before making it.
+#[derive(Debug)]
+pub struct CentralAccessRule {
+    name: String,
+    applies_to: Option<Condition>,
+    effective: LocalBox<SecurityDescriptor>,
+    staged: Option<LocalBox<SecurityDescriptor>>,
+}
+
+impl CentralAccessRule {
+    /// Create a rule that applies to every object, with the DACL of
+    /// `effective` as its permissions
+    pub fn new(name: &str, effective: LocalBox<SecurityDescriptor>) -> Self {
+        Self {
+            name: name.to_string(),
+            applies_to: None,
+            effective,
+            staged: None,
+        }
+    }
+
+    /// The rule of the recovery policy
+    fn recovery() -> Self {
+        let dacl = RECOVERY_POLICY
+            .parse()
+            .expect("Failed to parse the recovery policy");
+        Self::new("Recovery policy", dacl)
+    }
+
+    /// Only apply the rule to objects that a condition is true for
+    pub fn with_applies_to(mut self, condition: Condition) -> Self {
+        self.applies_to = Some(condition);
+        self
+    }
+
+    /// Stage proposed permissions for the rule
+    pub fn with_staged(mut self, staged: LocalBox<SecurityDescriptor>) -> Self {
+        self.staged = Some(staged);
+        self
+    }
+
+    /// Get the name of the rule
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the condition that decides which objects the rule applies to
+    pub fn applies_to(&self) -> Option<&Condition> {
+        self.applies_to.as_ref()
+    }
+
+    /// Get the permissions that the rule enforces
+    pub fn effective(&self) -> &SecurityDescriptor {
+        &self.effective
+    }
+
+    /// Get the proposed permissions of the rule, if any are staged
+    pub fn staged(&self) -> Option<&SecurityDescriptor> {
+        self.staged.as_deref()
+    }
+}
+
+/// A central access policy: a set of rules that objects can refer to by ID
+///
+/// Objects refer to a policy with a `SYSTEM_SCOPED_POLICY_ID_ACE_TYPE` ACE in
+/// their SACL, whose SID is the ID of the policy (`S-1-17-*`). Every rule of
+/// the policy that applies to the object has to allow a right, as well as
+/// the object's DACL.
+///
+/// This is a portable model of the policies that Windows reads from Active
+/// Directory. See
+/// [Central Access Policies](https://docs.microsoft.com/en-us/windows-server/identity/solution-guides/scenario--central-access-policy).
+#[derive(Debug)]
+pub struct CentralAccessPolicy {
+    id: LocalBox<Sid>,
+    name: String,
+    rules: Vec<CentralAccessRule>,
+}
+
+impl CentralAccessPolicy {
+    /// Create a policy with no rules
+    pub fn new(id: LocalBox<Sid>, name: &str) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+            rules: vec![],
+        }
+    }
+
+    /// Create the recovery policy that Windows uses in place of an unknown
+    /// policy, which allows full control to the owner, administrators, and
+    /// `SYSTEM`
+    pub fn recovery(id: LocalBox<Sid>) -> Self {
+        Self::new(id, "Recovery policy").with_rule(CentralAccessRule::recovery())
+    }
+
+    /// Add a rule to the policy
+    pub fn with_rule(mut self, rule: CentralAccessRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Get the ID of the policy
+    pub fn id(&self) -> &Sid {
+        &self.id
+    }
+
+    /// Get the name of the policy
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the rules of the policy
+    pub fn rules(&self) -> &[CentralAccessRule] {
+        &self.rules
+    }
+}
+
+/// What one applicable rule of a central access policy allowed
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RuleResult<'a> {
+    policy: &'a Sid,
+    rule: String,
+    effective: AccessRights,
+    staged: AccessRights,
+}
+
+impl<'a> RuleResult<'a> {
+    /// Get the ID of the policy that the rule belongs to
+    pub fn policy(&self) -> &'a Sid {
+        self.policy
+    }
+
+    /// Get the name of the rule
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    /// Get the desired rights that the rule's effective permissions allow
+    pub fn effective(&self) -> AccessRights {
+        self.effective
+    }
+
+    /// Get the desired rights that the rule's staged permissions allow
+    ///
+    /// Rules without staged permissions stage their effective permissions.
+    pub fn staged(&self) -> AccessRights {
+        self.staged
+    }
+}
+
+/// The outcome of an access check that includes central access policies
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CentralAccessResult<'a> {
+    dacl: AccessCheckResult,
+    rules: Vec<RuleResult<'a>>,
+}
+
+impl<'a> CentralAccessResult<'a> {
+    /// Get the result of the check against the object's own DACL
+    pub fn dacl(&self) -> &AccessCheckResult {
+        &self.dacl
+    }
+
+    /// Get the results of the policy rules that apply to the object
+    pub fn rules(&self) -> &[RuleResult<'a>] {
+        &self.rules
+    }
+
+    /// Get the rights granted by the DACL and every applicable rule
+    pub fn granted(&self) -> AccessRights {
+        self.rules
+            .iter()
+            .fold(self.dacl.granted(), |granted, rule| {
+                granted & rule.effective
+            })
+    }
+
+    /// Check whether every desired right is granted
+    pub fn is_granted(&self) -> bool {
+        self.granted() == self.dacl.desired()
+    }
+
+    /// Get the rights that would be granted if the staged permissions of
+    /// every rule took effect
+    pub fn staged_granted(&self) -> AccessRights {
+        self.rules
+            .iter()
+            .fold(self.dacl.granted(), |granted, rule| granted & rule.staged)
+    }
+
+    /// Check whether every desired right would be granted if the staged
+    /// permissions took effect
+    pub fn is_staged_granted(&self) -> bool {
+        self.staged_granted() == self.dacl.desired()
+    }
+}
+
+impl<'a> AccessCheck<'a> {
+    /// Check whether the token is granted the `desired` rights, including
+    /// the central access policies that the SACL refers to
+    ///
+    /// Each policy is looked up by ID in `policies`. An unknown ID is
+    /// replaced by the [recovery policy](CentralAccessPolicy::recovery). A
+    /// rule applies when it has no applies-to condition, or when its
+    /// condition is true for the token and the object's resource attributes.
+    /// Each applicable rule is checked like the DACL, but with the DACL of its
+    /// permissions.
+    ///
+    /// ```
+    /// use windows_permissions::access::{AccessCheck, CentralAccessPolicy, CentralAccessRule};
+    /// use windows_permissions::constants::{AccessRights, GroupAttributes};
+    /// use windows_permissions::{LocalBox, SecurityDescriptor, Token};
+    ///
+    /// let policy = CentralAccessPolicy::new("S-1-17-1".parse().unwrap(), "Finance").with_rule(
+    ///     CentralAccessRule::new("Finance documents", "D:(A;;FR;;;BU)".parse().unwrap())
+    ///         .with_applies_to("(@Resource.Department == \"Finance\")".parse().unwrap())
+    ///         .with_staged("D:(A;;FR;;;BA)".parse().unwrap()),
+    /// );
+    ///
+    /// let sd: LocalBox<SecurityDescriptor> =
+    ///     "D:(A;;FA;;;BU)S:(SP;;;;;S-1-17-1)(RA;;;;;WD;(\"Department\",TS,0x0,\"Finance\"))"
+    ///         .parse()
+    ///         .unwrap();
+    /// let token = Token::new("S-1-5-21-1-2-3-1001".parse().unwrap())
+    ///     .with_group("S-1-5-32-545".parse().unwrap(), GroupAttributes::Enabled);
+    ///
+    /// let policies = [policy];
+    /// let check = AccessCheck::new(&sd, &token);
+    ///
+    /// // The DACL allows writes, but the policy does not
+    /// let write = check.check_with_policies(&policies, AccessRights::FileGenericWrite);
+    /// assert!(write.dacl().is_granted());
+    /// assert!(!write.is_granted());
+    ///
+    /// // Reads are allowed now, but would not be once the staged rule is used
+    /// let read = check.check_with_policies(&policies, AccessRights::FileGenericRead);
+    /// assert!(read.is_granted());
+    /// assert!(!read.is_staged_granted());
+    /// ```
+    pub fn check_with_policies(
+        &self,
+        policies: &[CentralAccessPolicy],
+        desired: AccessRights,
+    ) -> CentralAccessResult<'a> {
+        let dacl = self.check(desired);
+        let desired = dacl.desired();
+
+        let sacl = match self.sacl {
+            Some(sacl) => sacl,
+            None => {
+                return CentralAccessResult {
+                    dacl,
+                    rules: vec![],
+                }
+            }
+        };
+
+        let sids = self.discretionary_sids();
+        let resource_claims = self.resource_claims();
+        let mut rules = vec![];
+
+        let policy_ids = sacl.aces().filter(|ace| {
+            ace.ace_type() == AceType::SYSTEM_SCOPED_POLICY_ID_ACE_TYPE
+                && !ace.flags().contains(AceFlags::InheritOnly)
+        });
+
+        for ace in policy_ids {
+            let id = ace.raw_sid();
+            let recovery;
+            let policy_rules = match policies.iter().find(|policy| policy.id() == id) {
+                Some(policy) => policy.rules(),
+                None => {
+                    recovery = CentralAccessRule::recovery();
+                    std::slice::from_ref(&recovery)
+                }
+            };
+
+            for rule in policy_rules {
+                let applies = rule.applies_to().is_none_or(|condition| {
+                    self.evaluate_condition(condition, &sids, true, &resource_claims)
+                        == ConditionResult::True
+                });
+                if !applies {
+                    continue;
+                }
+
+                let check_dacl = |sd: &SecurityDescriptor| {
+                    AccessCheck {
+                        dacl: sd.non_null_dacl(),
+                        ..*self
+                    }
+                    .check(desired)
+                    .granted()
+                };
+                let effective = check_dacl(rule.effective());
+
+                rules.push(RuleResult {
+                    policy: id,
+                    rule: rule.name().to_string(),
+                    effective,
+                    staged: rule.staged().map_or(effective, check_dacl),
+                });
+            }
+        }
+
+        CentralAccessResult { dacl, rules }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::constants::GroupAttributes;
+    use crate::Token;
+
+    fn sid(s: &str) -> LocalBox<Sid> {
+        s.parse().unwrap()
+    }
+
+    fn sd(sddl: &str) -> LocalBox<SecurityDescriptor> {
+        sddl.parse().unwrap()
+    }
+
+    fn policies() -> Vec<CentralAccessPolicy> {
+        vec![
+            CentralAccessPolicy::new(sid("S-1-17-1"), "Confidential")
+                .with_rule(
+                    CentralAccessRule::new("Readers", sd("D:(A;;FR;;;BU)"))
+                        .with_applies_to("(@Resource.Secret == 1)".parse().unwrap()),
+                )
+                .with_rule(
+                    CentralAccessRule::new("Admins", sd("D:(A;;FA;;;BA)(A;;FR;;;BU)"))
+                        .with_staged(sd("D:(A;;FA;;;BA)")),
+                ),
+            CentralAccessPolicy::new(sid("S-1-17-2"), "Open")
+                .with_rule(CentralAccessRule::new("Everyone", sd("D:(A;;FA;;;WD)"))),
+        ]
+    }
+
+    #[test]
+    fn policies_limit_access() {
+        let token = Token::new(sid("S-1-5-21-1-2-3-1001"))
+            .with_group(sid("BU"), GroupAttributes::Enabled)
+            .with_group(sid("WD"), GroupAttributes::Enabled);
+        let read = AccessRights::FileGenericRead;
+        let write = AccessRights::FileGenericWrite;
+        fn check<'a>(
+            sd: &'a SecurityDescriptor,
+            token: &'a Token,
+            desired: AccessRights,
+        ) -> CentralAccessResult<'a> {
+            AccessCheck::new(sd, token).check_with_policies(&policies(), desired)
+        }
+
+        // Without a scoped policy ID, only the DACL counts
+        let object = sd("D:(A;;FA;;;BU)");
+        let result = check(&object, &token, write);
+        assert!(result.is_granted());
+        assert!(result.rules().is_empty());
+
+        // Rules whose condition is false are skipped
+        let object = sd("D:(A;;FA;;;BU)S:(SP;;;;;S-1-17-1)");
+        let result = check(&object, &token, read);
+        assert_eq!(
+            result
+                .rules()
+                .iter()
+                .map(RuleResult::rule)
+                .collect::<Vec<_>>(),
+            vec!["Admins"]
+        );
+        assert!(result.is_granted());
+        assert!(!result.is_staged_granted());
+        assert_eq!(result.staged_granted(), AccessRights::empty());
+
+        // The policy and the DACL both have to allow a right
+        let secret = "S:(SP;;;;;S-1-17-1)(RA;;;;;WD;(\"Secret\",TI,0x0,1))";
+        let object = sd(&format!("D:(A;;FA;;;BU){}", secret));
+        let result = check(&object, &token, write);
+        assert!(result.dacl().is_granted());
+        assert!(!result.is_granted());
+        assert_eq!(result.rules().len(), 2);
+        let object = sd(&format!("D:(A;;FW;;;BU){}", secret));
+        assert!(check(&object, &token, read)
+            .rules()
+            .iter()
+            .all(|rule| rule.effective() == read));
+        assert!(!check(&object, &token, read).is_granted());
+
+        // Inherit-only scoped policy IDs are skipped
+        assert!(check(&sd("D:(A;;FA;;;BU)S:(SP;IO;;;;S-1-17-1)"), &token, write).is_granted());
+
+        // Policies that allow everything change nothing
+        assert!(check(&sd("D:(A;;FA;;;BU)S:(SP;;;;;S-1-17-2)"), &token, write).is_granted());
+    }
+
+    #[test]
+    fn recovery_policy() {
+        let user =
+            Token::new(sid("S-1-5-21-1-2-3-1001")).with_group(sid("BU"), GroupAttributes::Enabled);
+        let admin = user.clone().with_group(sid("BA"), GroupAttributes::Enabled);
+        let object = sd("O:S-1-5-21-1-2-3-1002D:(A;;FA;;;BU)S:(SP;;;;;S-1-17-9)");
+        let write = AccessRights::FileGenericWrite;
+
+        let result = AccessCheck::new(&object, &user).check_with_policies(&policies(), write);
+        assert!(!result.is_granted());
+        assert_eq!(result.rules()[0].rule(), "Recovery policy");
+        assert_eq!(result.rules()[0].policy(), &*sid("S-1-17-9"));
+
+        let result = AccessCheck::new(&object, &admin).check_with_policies(&policies(), write);
+        assert!(result.is_granted());
+
+        // The owner is allowed through OWNER RIGHTS
+        let object = sd("O:S-1-5-21-1-2-3-1001D:(A;;FA;;;BU)S:(SP;;;;;S-1-17-9)");
+        let result = AccessCheck::new(&object, &user).check_with_policies(&[], write);
+        assert!(result.is_granted());
+    }
+}
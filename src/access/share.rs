@@ -0,0 +1,322 @@
+//! Effective access over the network, through a share and its files
+
+use super::{AccessCheck, AccessCheckResult};
+use crate::constants::AccessRights;
+use crate::{SecurityDescriptor, Token};
+
+/// The permission levels that can be given on a share
+///
+/// These are the levels shown on the Share Permissions tab. Each one is a
+/// set of file rights, and each includes the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ShareRights {
+    /// Read and execute files and list directories (`0x1200a9`)
+    Read,
+    /// Read, plus write and delete files (`0x1301bf`)
+    Change,
+    /// Every file right (`0x1f01ff`)
+    FullControl,
+}
+
+impl ShareRights {
+    /// Get the file rights that the level allows
+    ///
+    /// ```
+    /// use windows_permissions::access::ShareRights;
+    /// use windows_permissions::constants::AccessRights;
+    ///
+    /// assert_eq!(ShareRights::Read.rights().bits(), 0x1200a9);
+    /// assert_eq!(ShareRights::FullControl.rights(), AccessRights::FileAllAccess);
+    /// ```
+    pub fn rights(self) -> AccessRights {
+        let read = AccessRights::FileGenericRead | AccessRights::FileGenericExecute;
+
+        match self {
+            ShareRights::Read => read,
+            ShareRights::Change => read | AccessRights::FileGenericWrite | AccessRights::Delete,
+            ShareRights::FullControl => AccessRights::FileAllAccess,
+        }
+    }
+
+    /// Find the highest level whose rights are all in `rights`
+    ///
+    /// Returns `None` if `rights` does not even allow `Read`.
+    pub fn from_rights(rights: AccessRights) -> Option<Self> {
+        [
+            ShareRights::FullControl,
+            ShareRights::Change,
+            ShareRights::Read,
+        ]
+        .iter()
+        .copied()
+        .find(|level| rights.contains(level.rights()))
+    }
+}
+
+/// The security descriptor that limited an access over the network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessLayer {
+    /// The share's permissions
+    Share,
+    /// The file's permissions
+    File,
+    /// Both the share's and the file's permissions
+    Both,
+}
+
+/// An access check of a token against a file reached through a share
+///
+/// Over the network, the server checks the token against the share's
+/// security descriptor as well as the file's. The token is only granted the
+/// rights that both of them allow.
+///
+/// ```
+/// use windows_permissions::access::{AccessLayer, NetworkAccessCheck, ShareRights};
+/// use windows_permissions::constants::{AccessRights, GroupAttributes};
+/// use windows_permissions::{LocalBox, SecurityDescriptor, Token};
+///
+/// // Everyone can read the share, and users can modify the file
+/// let share: LocalBox<SecurityDescriptor> = "D:(A;;0x1200a9;;;WD)".parse().unwrap();
+/// let file: LocalBox<SecurityDescriptor> = "D:(A;;0x1301bf;;;BU)".parse().unwrap();
+/// let token = Token::new("S-1-5-21-1-2-3-1001".parse().unwrap())
+///     .with_group("S-1-1-0".parse().unwrap(), GroupAttributes::Enabled)
+///     .with_group("S-1-5-32-545".parse().unwrap(), GroupAttributes::Enabled);
+///
+/// let check = NetworkAccessCheck::new(&share, &file, &token);
+///
+/// let write = check.check(AccessRights::FileGenericWrite);
+/// assert!(!write.is_granted());
+/// assert_eq!(write.limited_by(), Some(AccessLayer::Share));
+///
+/// let maximum = check.maximum_allowed();
+/// assert_eq!(maximum.granted(), ShareRights::Read.rights());
+/// assert_eq!(maximum.share_rights(), Some(ShareRights::Read));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct NetworkAccessCheck<'a> {
+    share: AccessCheck<'a>,
+    file: AccessCheck<'a>,
+}
+
+impl<'a> NetworkAccessCheck<'a> {
+    /// Prepare to check `token` against a share and a file on it
+    pub fn new(
+        share: &'a SecurityDescriptor,
+        file: &'a SecurityDescriptor,
+        token: &'a Token,
+    ) -> Self {
+        Self::from_checks(
+            AccessCheck::new(share, token),
+            AccessCheck::new(file, token),
+        )
+    }
+
+    /// Combine an access check against a share with one against a file on
+    /// it, for control over how each is checked
+    pub fn from_checks(share: AccessCheck<'a>, file: AccessCheck<'a>) -> Self {
+        Self { share, file }
+    }
+
+    /// Check whether the token is granted the `desired` rights over the
+    /// network
+    pub fn check(&self, desired: AccessRights) -> NetworkAccessResult {
+        let share = self.share.check(desired);
+        let file = self.file.check(desired);
+
+        NetworkAccessResult {
+            desired: share.desired(),
+            share,
+            file,
+        }
+    }
+
+    /// Find every right that the token is granted over the network
+    ///
+    /// Each layer is asked for its maximum allowed rights. The rights that
+    /// either one grants are treated as desired, so that
+    /// [`limited_by`](NetworkAccessResult::limited_by) reports the layer that
+    /// takes them away.
+    pub fn maximum_allowed(&self) -> NetworkAccessResult {
+        let share = self.share.maximum_allowed();
+        let file = self.file.maximum_allowed();
+
+        NetworkAccessResult {
+            desired: share.granted() | file.granted(),
+            share,
+            file,
+        }
+    }
+}
+
+/// The outcome of a [`NetworkAccessCheck`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NetworkAccessResult {
+    desired: AccessRights,
+    share: AccessCheckResult,
+    file: AccessCheckResult,
+}
+
+impl NetworkAccessResult {
+    /// Get the rights that were asked for, after generic rights are mapped
+    pub fn desired(&self) -> AccessRights {
+        self.desired
+    }
+
+    /// Get the rights that both the share and the file grant
+    pub fn granted(&self) -> AccessRights {
+        self.share.granted() & self.file.granted()
+    }
+
+    /// Check whether every desired right is granted
+    pub fn is_granted(&self) -> bool {
+        self.granted().contains(self.desired)
+    }
+
+    /// Get the result of the check against the share
+    pub fn share(&self) -> &AccessCheckResult {
+        &self.share
+    }
+
+    /// Get the result of the check against the file
+    pub fn file(&self) -> &AccessCheckResult {
+        &self.file
+    }
+
+    /// Get the highest share permission level that the share grants
+    pub fn share_rights(&self) -> Option<ShareRights> {
+        ShareRights::from_rights(self.share.granted())
+    }
+
+    /// Find the layer that kept desired rights from being granted, if any
+    pub fn limited_by(&self) -> Option<AccessLayer> {
+        self.explain(self.desired)
+    }
+
+    /// Find the layer that kept some of `rights` from being granted, if any
+    pub fn explain(&self, rights: AccessRights) -> Option<AccessLayer> {
+        let share = !self.share.granted().contains(rights);
+        let file = !self.file.granted().contains(rights);
+
+        match (share, file) {
+            (true, true) => Some(AccessLayer::Both),
+            (true, false) => Some(AccessLayer::Share),
+            (false, true) => Some(AccessLayer::File),
+            (false, false) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::constants::GroupAttributes;
+    use crate::{LocalBox, Sid};
+
+    fn sid(s: &str) -> LocalBox<Sid> {
+        s.parse().unwrap()
+    }
+
+    fn sd(sddl: &str) -> LocalBox<SecurityDescriptor> {
+        sddl.parse().unwrap()
+    }
+
+    #[test]
+    fn share_rights() {
+        assert_eq!(ShareRights::Change.rights().bits(), 0x1301bf);
+        assert_eq!(ShareRights::FullControl.rights().bits(), 0x1f01ff);
+        assert!(ShareRights::Change
+            .rights()
+            .contains(ShareRights::Read.rights()));
+
+        assert_eq!(
+            ShareRights::from_rights(AccessRights::FileAllAccess),
+            Some(ShareRights::FullControl)
+        );
+        assert_eq!(
+            ShareRights::from_rights(ShareRights::Change.rights() | AccessRights::WriteDac),
+            Some(ShareRights::Change)
+        );
+        assert_eq!(
+            ShareRights::from_rights(AccessRights::FileGenericRead),
+            None
+        );
+    }
+
+    #[test]
+    fn limiting_layer() {
+        let token = Token::new(sid("S-1-5-21-1-2-3-1001"))
+            .with_group(sid("WD"), GroupAttributes::Enabled)
+            .with_group(sid("BU"), GroupAttributes::Enabled)
+            .with_group(sid("NU"), GroupAttributes::Enabled);
+        let read = AccessRights::FileGenericRead;
+        let write = AccessRights::FileGenericWrite;
+
+        let full_share = sd("D:(A;;FA;;;WD)");
+        let read_share = sd("D:(A;;0x1200a9;;;WD)");
+        let no_share = sd("D:(A;;FA;;;BA)");
+        let change_file = sd("D:(A;;0x1301bf;;;BU)");
+        let read_file = sd("D:(A;;FR;;;BU)");
+
+        let check =
+            |share, file, desired| NetworkAccessCheck::new(share, file, &token).check(desired);
+
+        assert_eq!(check(&full_share, &change_file, write).limited_by(), None);
+        assert!(check(&full_share, &change_file, write).is_granted());
+        assert_eq!(
+            check(&read_share, &change_file, write).limited_by(),
+            Some(AccessLayer::Share)
+        );
+        assert_eq!(
+            check(&full_share, &read_file, write).limited_by(),
+            Some(AccessLayer::File)
+        );
+        assert_eq!(
+            check(&no_share, &read_file, write).limited_by(),
+            Some(AccessLayer::Both)
+        );
+        assert_eq!(check(&read_share, &read_file, read).limited_by(), None);
+
+        // Each right can be explained on its own
+        let result = check(&read_share, &read_file, read | AccessRights::Delete);
+        assert_eq!(result.granted(), read);
+        assert_eq!(
+            result.explain(AccessRights::Delete),
+            Some(AccessLayer::Both)
+        );
+        assert_eq!(result.explain(AccessRights::ReadControl), None);
+
+        // Deny ACEs on the share for the network logon SID
+        let share = sd("D:(D;;FW;;;NU)(A;;FA;;;WD)");
+        let result = check(&share, &change_file, write);
+        assert_eq!(result.limited_by(), Some(AccessLayer::Share));
+        assert_eq!(
+            result.share().denied_by(),
+            Some(crate::access::Pass::Discretionary)
+        );
+    }
+
+    #[test]
+    fn maximum_allowed() {
+        let token = Token::new(sid("S-1-5-21-1-2-3-1001"))
+            .with_group(sid("WD"), GroupAttributes::Enabled)
+            .with_group(sid("BU"), GroupAttributes::Enabled);
+
+        let share = sd("D:(A;;0x1301bf;;;WD)");
+        let file = sd("D:(A;;FA;;;BU)");
+        let result = NetworkAccessCheck::new(&share, &file, &token).maximum_allowed();
+
+        assert_eq!(result.granted(), ShareRights::Change.rights());
+        assert_eq!(result.share_rights(), Some(ShareRights::Change));
+        assert_eq!(result.limited_by(), Some(AccessLayer::Share));
+        assert_eq!(
+            result.explain(AccessRights::WriteDac),
+            Some(AccessLayer::Share)
+        );
+
+        let share = sd("D:(A;;FA;;;WD)");
+        let file = sd("D:(A;;FR;;;BU)");
+        let result = NetworkAccessCheck::new(&share, &file, &token).maximum_allowed();
+        assert_eq!(result.granted(), AccessRights::FileGenericRead);
+        assert_eq!(result.limited_by(), Some(AccessLayer::File));
+    }
+}
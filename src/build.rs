@@ -0,0 +1,331 @@
+//! Pure-Rust construction of the binary ACE, ACL, and security descriptor
+//! formats.
+//!
+//! Everything built here is self-relative: ACLs and security descriptors
+//! refer to their parts by offset, so a single buffer can be copied anywhere.
+
+use crate::token::{ClaimAttribute, ClaimValues};
+use crate::winnt::*;
+
+/// Build an ACE
+///
+/// Object type GUIDs are only written for the object ACE types. `data` is
+/// appended after the SID, and the ACE is padded to a multiple of 4 bytes.
+pub(crate) fn ace(
+    ace_type: u8,
+    flags: u8,
+    mask: u32,
+    object_type: Option<[u8; 16]>,
+    inherited_object_type: Option<[u8; 16]>,
+    sid: &[u8],
+    data: &[u8],
+) -> Vec<u8> {
+    let mut body = mask.to_le_bytes().to_vec();
+
+    if is_object_ace_type(ace_type) {
+        let mut object_flags = 0;
+        if object_type.is_some() {
+            object_flags |= ACE_OBJECT_TYPE_PRESENT;
+        }
+        if inherited_object_type.is_some() {
+            object_flags |= ACE_INHERITED_OBJECT_TYPE_PRESENT;
+        }
+
+        body.extend_from_slice(&object_flags.to_le_bytes());
+        body.extend(object_type.iter().flatten());
+        body.extend(inherited_object_type.iter().flatten());
+    }
+
+    body.extend_from_slice(sid);
+    body.extend_from_slice(data);
+
+    while !body.len().is_multiple_of(4) {
+        body.push(0);
+    }
+
+    let size = (body.len() + 4) as u16;
+
+    let mut ace = vec![ace_type, flags];
+    ace.extend_from_slice(&size.to_le_bytes());
+    ace.extend(body);
+    ace
+}
+
+/// Check whether an ACE type has object type GUIDs
+pub(crate) fn is_object_ace_type(ace_type: u8) -> bool {
+    matches!(
+        ace_type,
+        ACCESS_ALLOWED_OBJECT_ACE_TYPE
+            | ACCESS_DENIED_OBJECT_ACE_TYPE
+            | SYSTEM_AUDIT_OBJECT_ACE_TYPE
+            | ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE
+            | ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE
+            | SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE
+    )
+}
+
+/// Check whether an ACE type is a callback type, which can carry a
+/// condition
+pub(crate) fn is_callback_ace_type(ace_type: u8) -> bool {
+    matches!(
+        ace_type,
+        ACCESS_ALLOWED_CALLBACK_ACE_TYPE
+            | ACCESS_DENIED_CALLBACK_ACE_TYPE
+            | SYSTEM_AUDIT_CALLBACK_ACE_TYPE
+            | ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE
+            | ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE
+            | SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE
+    )
+}
+
+/// Build an ACL from a list of ACEs
+///
+/// The revision is `ACL_REVISION_DS` if any object ACEs are present, and
+/// `ACL_REVISION` otherwise.
+pub(crate) fn acl<A: AsRef<[u8]>>(aces: &[A]) -> Vec<u8> {
+    let revision = if aces.iter().any(|ace| is_object_ace_type(ace.as_ref()[0])) {
+        ACL_REVISION_DS
+    } else {
+        ACL_REVISION
+    };
+
+    let size = 8 + aces.iter().map(|ace| ace.as_ref().len()).sum::<usize>();
+
+    let mut acl = vec![revision, 0];
+    acl.extend_from_slice(&(size as u16).to_le_bytes());
+    acl.extend_from_slice(&(aces.len() as u16).to_le_bytes());
+    acl.extend_from_slice(&[0, 0]);
+
+    for ace in aces {
+        acl.extend_from_slice(ace.as_ref());
+    }
+
+    acl
+}
+
+/// The parts of a self-relative security descriptor
+///
+/// The `SE_DACL_PRESENT` and `SE_SACL_PRESENT` bits of `control` decide
+/// whether each ACL is present. A present ACL of `None` is a null ACL.
+#[derive(Default)]
+pub(crate) struct SecurityDescriptorParts<'a> {
+    pub control: u16,
+    pub owner: Option<&'a [u8]>,
+    pub group: Option<&'a [u8]>,
+    pub sacl: Option<&'a [u8]>,
+    pub dacl: Option<&'a [u8]>,
+}
+
+/// Build a self-relative security descriptor
+///
+/// The parts are laid out in the same order Windows uses: SACL, DACL,
+/// owner, then group.
+pub(crate) fn security_descriptor(parts: &SecurityDescriptorParts) -> Vec<u8> {
+    let mut control = parts.control | SE_SELF_RELATIVE;
+    let mut body = Vec::new();
+    let header_size = 20;
+
+    let mut place = |part: Option<&[u8]>| -> u32 {
+        match part {
+            Some(bytes) => {
+                let offset = header_size + body.len();
+                body.extend_from_slice(bytes);
+                offset as u32
+            }
+            None => 0,
+        }
+    };
+
+    let sacl = if control & SE_SACL_PRESENT != 0 {
+        place(parts.sacl)
+    } else {
+        0
+    };
+    let dacl = if control & SE_DACL_PRESENT != 0 {
+        place(parts.dacl)
+    } else {
+        0
+    };
+    let owner = place(parts.owner);
+    let group = place(parts.group);
+
+    if sacl == 0 && control & SE_SACL_PRESENT == 0 {
+        control &= !(SE_SACL_PROTECTED | SE_SACL_AUTO_INHERITED | SE_SACL_AUTO_INHERIT_REQ);
+    }
+
+    let mut sd = vec![SECURITY_DESCRIPTOR_REVISION as u8, 0];
+    sd.extend_from_slice(&control.to_le_bytes());
+    for offset in &[owner, group, sacl, dacl] {
+        sd.extend_from_slice(&offset.to_le_bytes());
+    }
+    sd.extend(body);
+    sd
+}
+
+/// Build the binary form of a SID
+pub(crate) fn sid(id_auth: [u8; 6], sub_auths: &[u32]) -> Vec<u8> {
+    let mut sid = vec![SID_REVISION, sub_auths.len() as u8];
+    sid.extend_from_slice(&id_auth);
+    for sub_auth in sub_auths {
+        sid.extend_from_slice(&sub_auth.to_le_bytes());
+    }
+    sid
+}
+
+/// Build the self-relative form of a claim, as stored in resource
+/// attribute ACEs
+///
+/// This is a `CLAIM_SECURITY_ATTRIBUTE_RELATIVE_V1` structure. Every offset
+/// in it is from the start of the structure.
+pub(crate) fn claim_attribute(claim: &ClaimAttribute) -> Vec<u8> {
+    let wide = |s: &str| {
+        s.encode_utf16()
+            .chain(Some(0))
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect::<Vec<u8>>()
+    };
+    let counted = |bytes: &[u8]| {
+        let mut value = (bytes.len() as u32).to_le_bytes().to_vec();
+        value.extend_from_slice(bytes);
+        value
+    };
+
+    let (value_type, values): (u16, Vec<Vec<u8>>) = match claim.values() {
+        ClaimValues::Int64(v) => (
+            CLAIM_SECURITY_ATTRIBUTE_TYPE_INT64,
+            v.iter().map(|n| n.to_le_bytes().to_vec()).collect(),
+        ),
+        ClaimValues::Uint64(v) => (
+            CLAIM_SECURITY_ATTRIBUTE_TYPE_UINT64,
+            v.iter().map(|n| n.to_le_bytes().to_vec()).collect(),
+        ),
+        ClaimValues::Boolean(v) => (
+            CLAIM_SECURITY_ATTRIBUTE_TYPE_BOOLEAN,
+            v.iter()
+                .map(|b| (*b as u64).to_le_bytes().to_vec())
+                .collect(),
+        ),
+        ClaimValues::String(v) => (
+            CLAIM_SECURITY_ATTRIBUTE_TYPE_STRING,
+            v.iter().map(|s| wide(s)).collect(),
+        ),
+        ClaimValues::Fqbn(v) => (
+            CLAIM_SECURITY_ATTRIBUTE_TYPE_FQBN,
+            // The name follows the version and its own 4-byte offset
+            v.iter()
+                .map(|(version, name)| {
+                    let mut value = version.to_le_bytes().to_vec();
+                    value.extend_from_slice(&[0; 4]);
+                    value.extend(wide(name));
+                    value
+                })
+                .collect(),
+        ),
+        ClaimValues::Sid(v) => (
+            CLAIM_SECURITY_ATTRIBUTE_TYPE_SID,
+            v.iter().map(|sid| counted(&sid.to_bytes())).collect(),
+        ),
+        ClaimValues::OctetString(v) => (
+            CLAIM_SECURITY_ATTRIBUTE_TYPE_OCTET_STRING,
+            v.iter().map(|octets| counted(octets)).collect(),
+        ),
+    };
+
+    let header_size = 16 + 4 * values.len();
+    let mut body = wide(claim.name());
+    let mut offsets = Vec::new();
+
+    for mut value in values {
+        while !body.len().is_multiple_of(4) {
+            body.push(0);
+        }
+
+        let offset = (header_size + body.len()) as u32;
+        if value_type == CLAIM_SECURITY_ATTRIBUTE_TYPE_FQBN {
+            value[8..12].copy_from_slice(&(offset + 12).to_le_bytes());
+        }
+
+        offsets.push(offset);
+        body.extend(value);
+    }
+
+    let mut attribute = (header_size as u32).to_le_bytes().to_vec();
+    attribute.extend_from_slice(&value_type.to_le_bytes());
+    attribute.extend_from_slice(&[0, 0]);
+    attribute.extend_from_slice(&claim.flags().bits().to_le_bytes());
+    attribute.extend_from_slice(&(offsets.len() as u32).to_le_bytes());
+    for offset in offsets {
+        attribute.extend_from_slice(&offset.to_le_bytes());
+    }
+    attribute.extend(body);
+    attribute
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ace_layout() {
+        let world = sid([0, 0, 0, 0, 0, 1], &[0]);
+
+        let simple = ace(
+            ACCESS_ALLOWED_ACE_TYPE,
+            0x3,
+            0x1F01FF,
+            None,
+            None,
+            &world,
+            &[],
+        );
+        assert_eq!(
+            simple,
+            vec![0, 3, 20, 0, 0xFF, 0x01, 0x1F, 0, 1, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0]
+        );
+
+        let object = ace(
+            ACCESS_ALLOWED_OBJECT_ACE_TYPE,
+            0,
+            1,
+            None,
+            Some([7; 16]),
+            &world,
+            &[],
+        );
+        assert_eq!(object.len(), 4 + 4 + 4 + 16 + 12);
+        assert_eq!(&object[8..12], &[2, 0, 0, 0]);
+        assert_eq!(&object[12..28], &[7; 16]);
+
+        let padded = ace(
+            ACCESS_ALLOWED_CALLBACK_ACE_TYPE,
+            0,
+            1,
+            None,
+            None,
+            &world,
+            b"artx\x01",
+        );
+        assert_eq!(padded.len(), 28);
+        assert_eq!(&padded[2..4], &[28, 0]);
+    }
+
+    #[test]
+    fn acl_revision() {
+        let world = sid([0, 0, 0, 0, 0, 1], &[0]);
+        let simple = ace(ACCESS_ALLOWED_ACE_TYPE, 0, 1, None, None, &world, &[]);
+        let object = ace(
+            ACCESS_ALLOWED_OBJECT_ACE_TYPE,
+            0,
+            1,
+            None,
+            None,
+            &world,
+            &[],
+        );
+
+        assert_eq!(acl::<Vec<u8>>(&[])[..], [ACL_REVISION, 0, 8, 0, 0, 0, 0, 0]);
+        assert_eq!(acl(&[&simple])[0], ACL_REVISION);
+        assert_eq!(acl(&[&simple, &object])[0], ACL_REVISION_DS);
+        assert_eq!(acl(&[&simple, &object])[4], 2);
+    }
+}
@@ -3,9 +3,8 @@
 #![allow(non_upper_case_globals)]
 #![allow(missing_docs)]
 
-use winapi::um::accctrl::*;
-use winapi::um::minwinbase::*;
-use winapi::um::winnt::*;
+use crate::winnt::*;
+use std::fmt;
 
 /// Create an enum from a list of constants. Generated enums get a method
 /// `from_raw` that allows them to be converted from a value.
@@ -36,9 +35,103 @@ macro_rules! constant_enum {
                 }
             }
         }
+
+        #[cfg(feature = "serde")]
+        impl ::serde::Serialize for $name {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(match self {
+                    $( $name::$item => stringify!($item), )*
+                })
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> ::serde::Deserialize<'de> for $name {
+            fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let name = <std::borrow::Cow<str>>::deserialize(deserializer)?;
+                match &*name {
+                    $( stringify!($item) => Ok($name::$item), )*
+                    _ => Err(::serde::de::Error::unknown_variant(&name, &[$( stringify!($item) ),*])),
+                }
+            }
+        }
+
+        #[cfg(feature = "schemars")]
+        impl ::schemars::JsonSchema for $name {
+            fn schema_name() -> std::borrow::Cow<'static, str> {
+                stringify!($name).into()
+            }
+
+            fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+                ::schemars::json_schema!({
+                    "type": "string",
+                    "enum": [$( stringify!($item) ),*],
+                })
+            }
+        }
     }
 }
 
+/// Implement serde support for a bitflags type.
+///
+/// `names` are written when serializing, in order; each is removed from the
+/// value once written, and any bits that are left over are written in hex.
+/// `aliases` are also accepted when deserializing. The second argument picks
+/// the default representation from [`crate::serde`]. With the `schemars`
+/// feature, the JSON Schema lists every name.
+macro_rules! serde_flags {
+    (
+        $name:ident, $repr:ident,
+        [ $( $flag:ident ),* $(,)? ],
+        [ $( $alias:ident ),* $(,)? ]
+    ) => {
+        #[cfg(feature = "serde")]
+        impl crate::serde::Flags for $name {
+            const NAMES: &'static [(&'static str, u32)] = &[
+                $( (stringify!($flag), $name::$flag.bits() as u32), )*
+            ];
+            const ALIASES: &'static [(&'static str, u32)] = &[
+                $( (stringify!($alias), $name::$alias.bits() as u32), )*
+            ];
+
+            fn to_u32(&self) -> u32 {
+                self.bits() as u32
+            }
+
+            fn from_u32(bits: u32) -> Option<Self> {
+                std::convert::TryFrom::try_from(bits)
+                    .ok()
+                    .and_then($name::from_bits)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl ::serde::Serialize for $name {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                crate::serde::$repr::serialize(self, serializer)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> ::serde::Deserialize<'de> for $name {
+            fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                crate::serde::$repr::deserialize(deserializer)
+            }
+        }
+
+        #[cfg(feature = "schemars")]
+        impl ::schemars::JsonSchema for $name {
+            fn schema_name() -> std::borrow::Cow<'static, str> {
+                stringify!($name).into()
+            }
+
+            fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+                crate::serde::schema::flags::<Self>()
+            }
+        }
+    };
+}
+
 constant_enum!(TrusteeForm; u32;
     msdn: "https://docs.microsoft.com/en-us/windows/win32/api/accctrl/ne-accctrl-trustee_form";
     TRUSTEE_IS_SID,
@@ -131,6 +224,21 @@ bitflags! {
     }
 }
 
+serde_flags!(
+    AceFlags,
+    names,
+    [
+        ObjectInherit,
+        ContainerInherit,
+        NoPropagateInherit,
+        InheritOnly,
+        Inherited,
+        SuccessfulAccess,
+        FailedAccess,
+    ],
+    []
+);
+
 bitflags! {
     /// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/secauthz/security-information).
     pub struct SecurityInformation: u32 {
@@ -149,6 +257,68 @@ bitflags! {
     }
 }
 
+serde_flags!(
+    SecurityInformation,
+    names,
+    [
+        Owner,
+        Group,
+        Dacl,
+        Sacl,
+        Label,
+        Attribute,
+        Scope,
+        Backup,
+        UnprotectedSacl,
+        UnprotectedDacl,
+        ProtectedSacl,
+        ProtectedDacl,
+    ],
+    []
+);
+
+bitflags! {
+    /// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-control).
+    pub struct SecurityDescriptorControl: u16 {
+        const OwnerDefaulted = SE_OWNER_DEFAULTED;
+        const GroupDefaulted = SE_GROUP_DEFAULTED;
+        const DaclPresent = SE_DACL_PRESENT;
+        const DaclDefaulted = SE_DACL_DEFAULTED;
+        const SaclPresent = SE_SACL_PRESENT;
+        const SaclDefaulted = SE_SACL_DEFAULTED;
+        const DaclAutoInheritReq = SE_DACL_AUTO_INHERIT_REQ;
+        const SaclAutoInheritReq = SE_SACL_AUTO_INHERIT_REQ;
+        const DaclAutoInherited = SE_DACL_AUTO_INHERITED;
+        const SaclAutoInherited = SE_SACL_AUTO_INHERITED;
+        const DaclProtected = SE_DACL_PROTECTED;
+        const SaclProtected = SE_SACL_PROTECTED;
+        const RmControlValid = SE_RM_CONTROL_VALID;
+        const SelfRelative = SE_SELF_RELATIVE;
+    }
+}
+
+serde_flags!(
+    SecurityDescriptorControl,
+    names,
+    [
+        OwnerDefaulted,
+        GroupDefaulted,
+        DaclPresent,
+        DaclDefaulted,
+        SaclPresent,
+        SaclDefaulted,
+        DaclAutoInheritReq,
+        SaclAutoInheritReq,
+        DaclAutoInherited,
+        SaclAutoInherited,
+        DaclProtected,
+        SaclProtected,
+        RmControlValid,
+        SelfRelative,
+    ],
+    []
+);
+
 bitflags! {
     /// Flags indicating the right to access a resource.
     ///
@@ -219,6 +389,435 @@ bitflags! {
     }
 }
 
+// The object-specific bits have no names that fit every type of object, so
+// masks are written in hex by default
+serde_flags!(
+    AccessRights,
+    hex,
+    [
+        Bit0,
+        Bit1,
+        Bit2,
+        Bit3,
+        Bit4,
+        Bit5,
+        Bit6,
+        Bit7,
+        Bit8,
+        Bit9,
+        Bit10,
+        Bit11,
+        Bit12,
+        Bit13,
+        Bit14,
+        Bit15,
+        Delete,
+        ReadControl,
+        WriteDac,
+        WriteOwner,
+        Synchronize,
+        AccessSystemSecurity,
+        GenericAll,
+        GenericExecute,
+        GenericWrite,
+        GenericRead,
+    ],
+    [
+        All,
+        StandardRightsRequired,
+        StandardRightsRead,
+        StandardRightsWrite,
+        StandardRightsExecute,
+        StartardRightsAll,
+        SpecificRightsAll,
+        FileAllAccess,
+        FileGenericRead,
+        FileGenericWrite,
+        FileGenericExecute,
+        KeyAllAccess,
+        KeyRead,
+        KeyWrite,
+        KeyExecute,
+        MandatoryLabelNoReadUp,
+        MandatoryLabelNoWriteUp,
+        MandatoryLabelNoExecuteUp,
+    ]
+);
+
+/// Create a bitflags type for the access rights of one type of object.
+///
+/// Every bit of an access mask gets a name, using the object-specific names
+/// given here for the low bits and `BitN` for any that are left over, so that
+/// conversions to and from [`AccessRights`] are lossless. `Display` uses the
+/// same names as `Debug`.
+macro_rules! typed_rights {
+    (
+        $(#[$outer:meta])*
+        $name:ident { $( const $flag:ident = $value:expr; )* }
+        all: $all:expr;
+    ) => {
+        bitflags! {
+            $(#[$outer])*
+            pub struct $name: u32 {
+                // Bits 15-0: Object-specific access rights
+                $( const $flag = $value; )*
+
+                // Bits 23-16: Standard access rights
+                const Delete = DELETE;
+                const ReadControl = READ_CONTROL;
+                const WriteDac = WRITE_DAC;
+                const WriteOwner = WRITE_OWNER;
+                const Synchronize = SYNCHRONIZE;
+                const Bit21 = 1 << 21;
+                const Bit22 = 1 << 22;
+                const Bit23 = 1 << 23;
+
+                // Bits 27-24
+                const AccessSystemSecurity = ACCESS_SYSTEM_SECURITY;
+                const MaximumAllowed = MAXIMUM_ALLOWED;
+                const Bit26 = 1 << 26;
+                const Bit27 = 1 << 27;
+
+                // Bits 31-28: Generic rights
+                const GenericAll = GENERIC_ALL;
+                const GenericExecute = GENERIC_EXECUTE;
+                const GenericWrite = GENERIC_WRITE;
+                const GenericRead = GENERIC_READ;
+            }
+        }
+
+        impl $name {
+            /// Every standard and object-specific right for this type of object
+            pub const AllAccess: Self = Self::from_bits_truncate($all);
+        }
+
+        impl From<AccessRights> for $name {
+            fn from(rights: AccessRights) -> Self {
+                Self::from_bits_truncate(rights.bits())
+            }
+        }
+
+        impl From<$name> for AccessRights {
+            fn from(rights: $name) -> Self {
+                AccessRights::from_bits_truncate(rights.bits())
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Debug::fmt(self, f)
+            }
+        }
+
+        serde_flags!($name, names, [
+            $( $flag, )*
+            Delete, ReadControl, WriteDac, WriteOwner, Synchronize, Bit21, Bit22, Bit23,
+            AccessSystemSecurity, MaximumAllowed, Bit26, Bit27,
+            GenericAll, GenericExecute, GenericWrite, GenericRead,
+        ], [AllAccess]);
+    };
+}
+
+typed_rights! {
+    /// Access rights for files and directories.
+    ///
+    /// Directories use the same bits under other names: `ReadData` lists the
+    /// directory, `WriteData` adds a file, `AppendData` adds a subdirectory,
+    /// and `Execute` traverses it.
+    ///
+    /// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/fileio/file-access-rights-constants)
+    /// for details.
+    FileRights {
+        const ReadData = FILE_READ_DATA;
+        const WriteData = FILE_WRITE_DATA;
+        const AppendData = FILE_APPEND_DATA;
+        const ReadEa = FILE_READ_EA;
+        const WriteEa = FILE_WRITE_EA;
+        const Execute = FILE_EXECUTE;
+        const DeleteChild = FILE_DELETE_CHILD;
+        const ReadAttributes = FILE_READ_ATTRIBUTES;
+        const WriteAttributes = FILE_WRITE_ATTRIBUTES;
+        const Bit9 = 1 << 9;
+        const Bit10 = 1 << 10;
+        const Bit11 = 1 << 11;
+        const Bit12 = 1 << 12;
+        const Bit13 = 1 << 13;
+        const Bit14 = 1 << 14;
+        const Bit15 = 1 << 15;
+    }
+    all: FILE_ALL_ACCESS;
+}
+
+typed_rights! {
+    /// Access rights for registry keys.
+    ///
+    /// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/sysinfo/registry-key-security-and-access-rights)
+    /// for details.
+    KeyRights {
+        const QueryValue = KEY_QUERY_VALUE;
+        const SetValue = KEY_SET_VALUE;
+        const CreateSubKey = KEY_CREATE_SUB_KEY;
+        const EnumerateSubKeys = KEY_ENUMERATE_SUB_KEYS;
+        const Notify = KEY_NOTIFY;
+        const CreateLink = KEY_CREATE_LINK;
+        const Bit6 = 1 << 6;
+        const Bit7 = 1 << 7;
+        const Wow64Key64 = KEY_WOW64_64KEY;
+        const Wow64Key32 = KEY_WOW64_32KEY;
+        const Bit10 = 1 << 10;
+        const Bit11 = 1 << 11;
+        const Bit12 = 1 << 12;
+        const Bit13 = 1 << 13;
+        const Bit14 = 1 << 14;
+        const Bit15 = 1 << 15;
+    }
+    all: KEY_ALL_ACCESS;
+}
+
+typed_rights! {
+    /// Access rights for services.
+    ///
+    /// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/services/service-security-and-access-rights)
+    /// for details.
+    ServiceRights {
+        const QueryConfig = SERVICE_QUERY_CONFIG;
+        const ChangeConfig = SERVICE_CHANGE_CONFIG;
+        const QueryStatus = SERVICE_QUERY_STATUS;
+        const EnumerateDependents = SERVICE_ENUMERATE_DEPENDENTS;
+        const Start = SERVICE_START;
+        const Stop = SERVICE_STOP;
+        const PauseContinue = SERVICE_PAUSE_CONTINUE;
+        const Interrogate = SERVICE_INTERROGATE;
+        const UserDefinedControl = SERVICE_USER_DEFINED_CONTROL;
+        const Bit9 = 1 << 9;
+        const Bit10 = 1 << 10;
+        const Bit11 = 1 << 11;
+        const Bit12 = 1 << 12;
+        const Bit13 = 1 << 13;
+        const Bit14 = 1 << 14;
+        const Bit15 = 1 << 15;
+    }
+    all: SERVICE_ALL_ACCESS;
+}
+
+typed_rights! {
+    /// Access rights for processes.
+    ///
+    /// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/procthread/process-security-and-access-rights)
+    /// for details.
+    ProcessRights {
+        const Terminate = PROCESS_TERMINATE;
+        const CreateThread = PROCESS_CREATE_THREAD;
+        const SetSessionId = PROCESS_SET_SESSIONID;
+        const VmOperation = PROCESS_VM_OPERATION;
+        const VmRead = PROCESS_VM_READ;
+        const VmWrite = PROCESS_VM_WRITE;
+        const DupHandle = PROCESS_DUP_HANDLE;
+        const CreateProcess = PROCESS_CREATE_PROCESS;
+        const SetQuota = PROCESS_SET_QUOTA;
+        const SetInformation = PROCESS_SET_INFORMATION;
+        const QueryInformation = PROCESS_QUERY_INFORMATION;
+        const SuspendResume = PROCESS_SUSPEND_RESUME;
+        const QueryLimitedInformation = PROCESS_QUERY_LIMITED_INFORMATION;
+        const SetLimitedInformation = PROCESS_SET_LIMITED_INFORMATION;
+        const Bit14 = 1 << 14;
+        const Bit15 = 1 << 15;
+    }
+    all: PROCESS_ALL_ACCESS;
+}
+
+typed_rights! {
+    /// Access rights for threads.
+    ///
+    /// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/procthread/thread-security-and-access-rights)
+    /// for details.
+    ThreadRights {
+        const Terminate = THREAD_TERMINATE;
+        const SuspendResume = THREAD_SUSPEND_RESUME;
+        const Bit2 = 1 << 2;
+        const GetContext = THREAD_GET_CONTEXT;
+        const SetContext = THREAD_SET_CONTEXT;
+        const SetInformation = THREAD_SET_INFORMATION;
+        const QueryInformation = THREAD_QUERY_INFORMATION;
+        const SetThreadToken = THREAD_SET_THREAD_TOKEN;
+        const Impersonate = THREAD_IMPERSONATE;
+        const DirectImpersonation = THREAD_DIRECT_IMPERSONATION;
+        const SetLimitedInformation = THREAD_SET_LIMITED_INFORMATION;
+        const QueryLimitedInformation = THREAD_QUERY_LIMITED_INFORMATION;
+        const Resume = THREAD_RESUME;
+        const Bit13 = 1 << 13;
+        const Bit14 = 1 << 14;
+        const Bit15 = 1 << 15;
+    }
+    all: THREAD_ALL_ACCESS;
+}
+
+typed_rights! {
+    /// Access rights for access tokens.
+    ///
+    /// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/secauthz/access-rights-for-access-token-objects)
+    /// for details.
+    TokenRights {
+        const AssignPrimary = TOKEN_ASSIGN_PRIMARY;
+        const Duplicate = TOKEN_DUPLICATE;
+        const Impersonate = TOKEN_IMPERSONATE;
+        const Query = TOKEN_QUERY;
+        const QuerySource = TOKEN_QUERY_SOURCE;
+        const AdjustPrivileges = TOKEN_ADJUST_PRIVILEGES;
+        const AdjustGroups = TOKEN_ADJUST_GROUPS;
+        const AdjustDefault = TOKEN_ADJUST_DEFAULT;
+        const AdjustSessionId = TOKEN_ADJUST_SESSIONID;
+        const Bit9 = 1 << 9;
+        const Bit10 = 1 << 10;
+        const Bit11 = 1 << 11;
+        const Bit12 = 1 << 12;
+        const Bit13 = 1 << 13;
+        const Bit14 = 1 << 14;
+        const Bit15 = 1 << 15;
+    }
+    all: TOKEN_ALL_ACCESS;
+}
+
+typed_rights! {
+    /// Access rights for named pipes.
+    ///
+    /// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/ipc/named-pipe-security-and-access-rights)
+    /// for details.
+    PipeRights {
+        const ReadData = FILE_READ_DATA;
+        const WriteData = FILE_WRITE_DATA;
+        const CreatePipeInstance = FILE_CREATE_PIPE_INSTANCE;
+        const ReadEa = FILE_READ_EA;
+        const WriteEa = FILE_WRITE_EA;
+        const Bit5 = 1 << 5;
+        const Bit6 = 1 << 6;
+        const ReadAttributes = FILE_READ_ATTRIBUTES;
+        const WriteAttributes = FILE_WRITE_ATTRIBUTES;
+        const Bit9 = 1 << 9;
+        const Bit10 = 1 << 10;
+        const Bit11 = 1 << 11;
+        const Bit12 = 1 << 12;
+        const Bit13 = 1 << 13;
+        const Bit14 = 1 << 14;
+        const Bit15 = 1 << 15;
+    }
+    all: FILE_ALL_ACCESS;
+}
+
+typed_rights! {
+    /// Access rights for Active Directory objects.
+    ///
+    /// Object ACEs can limit `ReadProperty`, `WriteProperty`, `SelfWrite`,
+    /// `ControlAccess`, `CreateChild`, and `DeleteChild` to one property,
+    /// validated write, extended right, or child class.
+    ///
+    /// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/api/iads/ne-iads-ads_rights_enum)
+    /// for details.
+    DsRights {
+        const CreateChild = ACTRL_DS_CREATE_CHILD;
+        const DeleteChild = ACTRL_DS_DELETE_CHILD;
+        const ListChildren = ACTRL_DS_LIST;
+        const SelfWrite = ACTRL_DS_SELF;
+        const ReadProperty = ACTRL_DS_READ_PROP;
+        const WriteProperty = ACTRL_DS_WRITE_PROP;
+        const DeleteTree = ACTRL_DS_DELETE_TREE;
+        const ListObject = ACTRL_DS_LIST_OBJECT;
+        const ControlAccess = ACTRL_DS_CONTROL_ACCESS;
+        const Bit9 = 1 << 9;
+        const Bit10 = 1 << 10;
+        const Bit11 = 1 << 11;
+        const Bit12 = 1 << 12;
+        const Bit13 = 1 << 13;
+        const Bit14 = 1 << 14;
+        const Bit15 = 1 << 15;
+    }
+    all: STANDARD_RIGHTS_REQUIRED | 0x01FF;
+}
+
+bitflags! {
+    /// Attributes of a group SID in an access token.
+    ///
+    /// See the `Attributes` member of [`SID_AND_ATTRIBUTES` on MSDN](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-token_groups)
+    /// for details.
+    pub struct GroupAttributes: u32 {
+        const Mandatory = SE_GROUP_MANDATORY;
+        const EnabledByDefault = SE_GROUP_ENABLED_BY_DEFAULT;
+        const Enabled = SE_GROUP_ENABLED;
+        const Owner = SE_GROUP_OWNER;
+        const UseForDenyOnly = SE_GROUP_USE_FOR_DENY_ONLY;
+        const Integrity = SE_GROUP_INTEGRITY;
+        const IntegrityEnabled = SE_GROUP_INTEGRITY_ENABLED;
+        const LogonId = SE_GROUP_LOGON_ID;
+        const Resource = SE_GROUP_RESOURCE;
+    }
+}
+
+serde_flags!(
+    GroupAttributes,
+    names,
+    [
+        Mandatory,
+        EnabledByDefault,
+        Enabled,
+        Owner,
+        UseForDenyOnly,
+        Integrity,
+        IntegrityEnabled,
+        Resource,
+        LogonId,
+    ],
+    []
+);
+
+bitflags! {
+    /// Attributes of a privilege in an access token.
+    ///
+    /// See the `Attributes` member of [`TOKEN_PRIVILEGES` on MSDN](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-token_privileges)
+    /// for details.
+    pub struct PrivilegeAttributes: u32 {
+        const EnabledByDefault = SE_PRIVILEGE_ENABLED_BY_DEFAULT;
+        const Enabled = SE_PRIVILEGE_ENABLED;
+        const Removed = SE_PRIVILEGE_REMOVED;
+        const UsedForAccess = SE_PRIVILEGE_USED_FOR_ACCESS;
+    }
+}
+
+serde_flags!(
+    PrivilegeAttributes,
+    names,
+    [EnabledByDefault, Enabled, Removed, UsedForAccess,],
+    []
+);
+
+bitflags! {
+    /// Flags on a claim security attribute.
+    ///
+    /// See the `Flags` member of [`CLAIM_SECURITY_ATTRIBUTE_V1` on MSDN](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-claim_security_attribute_v1)
+    /// for details.
+    pub struct ClaimFlags: u32 {
+        const NonInheritable = CLAIM_SECURITY_ATTRIBUTE_NON_INHERITABLE;
+        const ValueCaseSensitive = CLAIM_SECURITY_ATTRIBUTE_VALUE_CASE_SENSITIVE;
+        const UseForDenyOnly = CLAIM_SECURITY_ATTRIBUTE_USE_FOR_DENY_ONLY;
+        const DisabledByDefault = CLAIM_SECURITY_ATTRIBUTE_DISABLED_BY_DEFAULT;
+        const Disabled = CLAIM_SECURITY_ATTRIBUTE_DISABLED;
+        const Mandatory = CLAIM_SECURITY_ATTRIBUTE_MANDATORY;
+    }
+}
+
+serde_flags!(
+    ClaimFlags,
+    names,
+    [
+        NonInheritable,
+        ValueCaseSensitive,
+        UseForDenyOnly,
+        DisabledByDefault,
+        Disabled,
+        Mandatory,
+    ],
+    []
+);
+
 bitflags! {
     /// Flags indicating the settings for a local allocation.
     ///
@@ -233,6 +832,13 @@ bitflags! {
     }
 }
 
+serde_flags!(
+    LocalAllocFlags,
+    names,
+    [Moveable, NoCompact, NoDiscard, ZeroInit, Discardable,],
+    [Fixed]
+);
+
 #[cfg(test)]
 mod test {
     const A: u8 = 5;
@@ -242,6 +848,56 @@ mod test {
 
     constant_enum!(TestEnum; u8; doc: "This is a test"; A, B, C);
 
+    #[test]
+    fn typed_rights() {
+        use super::*;
+
+        // Conversions keep every bit
+        for bit in 0..32 {
+            let rights = AccessRights::from_bits_truncate(1 << bit);
+            assert_eq!(AccessRights::from(FileRights::from(rights)), rights);
+            assert_eq!(AccessRights::from(KeyRights::from(rights)), rights);
+            assert_eq!(AccessRights::from(ServiceRights::from(rights)), rights);
+            assert_eq!(AccessRights::from(ProcessRights::from(rights)), rights);
+            assert_eq!(AccessRights::from(ThreadRights::from(rights)), rights);
+            assert_eq!(AccessRights::from(TokenRights::from(rights)), rights);
+            assert_eq!(AccessRights::from(PipeRights::from(rights)), rights);
+            assert_eq!(AccessRights::from(DsRights::from(rights)), rights);
+        }
+
+        // The same bit has a different name for each type
+        let bit = AccessRights::Bit4;
+        assert_eq!(format!("{}", FileRights::from(bit)), "WriteEa");
+        assert_eq!(format!("{:?}", ServiceRights::from(bit)), "Start");
+        assert_eq!(format!("{}", ProcessRights::from(bit)), "VmRead");
+        assert_eq!(format!("{}", DsRights::from(bit)), "ReadProperty");
+
+        assert_eq!(
+            format!("{}", DsRights::ControlAccess | DsRights::ReadControl),
+            "ControlAccess | ReadControl"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                ThreadRights::from(AccessRights::Bit2 | AccessRights::GenericRead)
+            ),
+            "Bit2 | GenericRead"
+        );
+
+        assert_eq!(
+            AccessRights::from(FileRights::AllAccess),
+            AccessRights::FileAllAccess
+        );
+        assert_eq!(
+            AccessRights::from(KeyRights::AllAccess),
+            AccessRights::KeyAllAccess
+        );
+        assert_eq!(ServiceRights::AllAccess.bits(), 0xf01ff);
+        assert_eq!(ProcessRights::AllAccess.bits(), 0x1fffff);
+        assert_eq!(TokenRights::AllAccess.bits(), 0xf01ff);
+        assert_eq!(DsRights::AllAccess.bits(), 0xf01ff);
+    }
+
     #[test]
     fn constant_enum_works() {
         let enum_a = TestEnum::A;
@@ -0,0 +1,256 @@
+use crate::constants::AceFlags;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io;
+
+/// The objects that an ACE applies to, as the Advanced Security dialog
+/// describes its inheritance flags
+///
+/// Each variant but [`ThisFolderOnly`](Self::ThisFolderOnly) has
+/// `this_container_only`, which is the dialog's "Only apply these
+/// permissions to objects and/or containers within this container" box. It
+/// stands for `NoPropagateInherit`: the ACE is inherited by direct children
+/// only.
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use windows_permissions::constants::AceFlags;
+/// use windows_permissions::explorer::AppliesTo;
+///
+/// let applies_to = AppliesTo::try_from(AceFlags::ContainerInherit | AceFlags::InheritOnly).unwrap();
+/// assert_eq!(applies_to, AppliesTo::SubfoldersOnly { this_container_only: false });
+/// assert_eq!(applies_to.to_string(), "Subfolders only");
+///
+/// let flags = AceFlags::from(AppliesTo::ThisFolderSubfoldersAndFiles { this_container_only: true });
+/// assert_eq!(
+///     flags,
+///     AceFlags::ObjectInherit | AceFlags::ContainerInherit | AceFlags::NoPropagateInherit
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppliesTo {
+    /// The object itself, with no inheritance
+    ThisFolderOnly,
+    /// The object, and the folders and files in it (`OI`, `CI`)
+    ThisFolderSubfoldersAndFiles {
+        /// Whether only direct children inherit the ACE (`NP`)
+        this_container_only: bool,
+    },
+    /// The object, and the folders in it (`CI`)
+    ThisFolderAndSubfolders {
+        /// Whether only direct children inherit the ACE (`NP`)
+        this_container_only: bool,
+    },
+    /// The object, and the files in it (`OI`)
+    ThisFolderAndFiles {
+        /// Whether only direct children inherit the ACE (`NP`)
+        this_container_only: bool,
+    },
+    /// The folders and files in the object, but not the object (`OI`, `CI`,
+    /// `IO`)
+    SubfoldersAndFilesOnly {
+        /// Whether only direct children inherit the ACE (`NP`)
+        this_container_only: bool,
+    },
+    /// The folders in the object, but not the object (`CI`, `IO`)
+    SubfoldersOnly {
+        /// Whether only direct children inherit the ACE (`NP`)
+        this_container_only: bool,
+    },
+    /// The files in the object, but not the object (`OI`, `IO`)
+    FilesOnly {
+        /// Whether only direct children inherit the ACE (`NP`)
+        this_container_only: bool,
+    },
+}
+
+impl AppliesTo {
+    /// The ACE flags that decide what an ACE applies to
+    pub const FLAGS: AceFlags = AceFlags::from_bits_truncate(
+        AceFlags::ObjectInherit.bits()
+            | AceFlags::ContainerInherit.bits()
+            | AceFlags::InheritOnly.bits()
+            | AceFlags::NoPropagateInherit.bits(),
+    );
+
+    /// Get the name the Advanced Security dialog shows, without the
+    /// "this container only" box
+    pub fn name(self) -> &'static str {
+        match self {
+            AppliesTo::ThisFolderOnly => "This folder only",
+            AppliesTo::ThisFolderSubfoldersAndFiles { .. } => "This folder, subfolders and files",
+            AppliesTo::ThisFolderAndSubfolders { .. } => "This folder and subfolders",
+            AppliesTo::ThisFolderAndFiles { .. } => "This folder and files",
+            AppliesTo::SubfoldersAndFilesOnly { .. } => "Subfolders and files only",
+            AppliesTo::SubfoldersOnly { .. } => "Subfolders only",
+            AppliesTo::FilesOnly { .. } => "Files only",
+        }
+    }
+
+    /// Check whether only direct children inherit the ACE
+    pub fn this_container_only(self) -> bool {
+        match self {
+            AppliesTo::ThisFolderOnly => false,
+            AppliesTo::ThisFolderSubfoldersAndFiles {
+                this_container_only,
+            }
+            | AppliesTo::ThisFolderAndSubfolders {
+                this_container_only,
+            }
+            | AppliesTo::ThisFolderAndFiles {
+                this_container_only,
+            }
+            | AppliesTo::SubfoldersAndFilesOnly {
+                this_container_only,
+            }
+            | AppliesTo::SubfoldersOnly {
+                this_container_only,
+            }
+            | AppliesTo::FilesOnly {
+                this_container_only,
+            } => this_container_only,
+        }
+    }
+}
+
+impl TryFrom<AceFlags> for AppliesTo {
+    type Error = io::Error;
+
+    /// Read the inheritance flags of an ACE
+    ///
+    /// Flags outside of [`AppliesTo::FLAGS`], like `Inherited`, are ignored.
+    /// `NoPropagateInherit` without anything to inherit has no effect, so it
+    /// reads as [`ThisFolderOnly`](Self::ThisFolderOnly). `InheritOnly`
+    /// without anything to inherit applies to nothing, which is an
+    /// `InvalidData` error.
+    fn try_from(flags: AceFlags) -> io::Result<Self> {
+        let this_container_only = flags.contains(AceFlags::NoPropagateInherit);
+        let object = flags.contains(AceFlags::ObjectInherit);
+        let container = flags.contains(AceFlags::ContainerInherit);
+        let inherit_only = flags.contains(AceFlags::InheritOnly);
+
+        let applies_to = match (object, container, inherit_only) {
+            (false, false, false) => AppliesTo::ThisFolderOnly,
+            (true, true, false) => AppliesTo::ThisFolderSubfoldersAndFiles {
+                this_container_only,
+            },
+            (false, true, false) => AppliesTo::ThisFolderAndSubfolders {
+                this_container_only,
+            },
+            (true, false, false) => AppliesTo::ThisFolderAndFiles {
+                this_container_only,
+            },
+            (true, true, true) => AppliesTo::SubfoldersAndFilesOnly {
+                this_container_only,
+            },
+            (false, true, true) => AppliesTo::SubfoldersOnly {
+                this_container_only,
+            },
+            (true, false, true) => AppliesTo::FilesOnly {
+                this_container_only,
+            },
+            (false, false, true) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Inherit-only flags apply to nothing: {:?}", flags),
+                ))
+            }
+        };
+
+        Ok(applies_to)
+    }
+}
+
+impl From<AppliesTo> for AceFlags {
+    fn from(applies_to: AppliesTo) -> Self {
+        let mut flags = match applies_to {
+            AppliesTo::ThisFolderOnly => AceFlags::empty(),
+            AppliesTo::ThisFolderSubfoldersAndFiles { .. } => {
+                AceFlags::ObjectInherit | AceFlags::ContainerInherit
+            }
+            AppliesTo::ThisFolderAndSubfolders { .. } => AceFlags::ContainerInherit,
+            AppliesTo::ThisFolderAndFiles { .. } => AceFlags::ObjectInherit,
+            AppliesTo::SubfoldersAndFilesOnly { .. } => {
+                AceFlags::ObjectInherit | AceFlags::ContainerInherit | AceFlags::InheritOnly
+            }
+            AppliesTo::SubfoldersOnly { .. } => AceFlags::ContainerInherit | AceFlags::InheritOnly,
+            AppliesTo::FilesOnly { .. } => AceFlags::ObjectInherit | AceFlags::InheritOnly,
+        };
+
+        if applies_to.this_container_only() {
+            flags |= AceFlags::NoPropagateInherit;
+        }
+
+        flags
+    }
+}
+
+impl fmt::Display for AppliesTo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())?;
+
+        if self.this_container_only() {
+            f.write_str(" (this container only)")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{LocalBox, SecurityDescriptor};
+
+    #[test]
+    fn lossless() {
+        for bits in 0..=AppliesTo::FLAGS.bits() {
+            let flags = AceFlags::from_bits_truncate(bits) & AppliesTo::FLAGS;
+            let inheritable =
+                flags.intersects(AceFlags::ObjectInherit | AceFlags::ContainerInherit);
+            match AppliesTo::try_from(flags) {
+                Ok(AppliesTo::ThisFolderOnly) => {
+                    assert!(!inheritable && !flags.contains(AceFlags::InheritOnly))
+                }
+                Ok(applies_to) => assert_eq!(AceFlags::from(applies_to), flags),
+                Err(err) => {
+                    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+                    assert!(flags.contains(AceFlags::InheritOnly) && !inheritable);
+                }
+            }
+        }
+
+        let applies_to =
+            AppliesTo::try_from(AceFlags::ObjectInherit | AceFlags::Inherited).unwrap();
+        assert_eq!(
+            applies_to,
+            AppliesTo::ThisFolderAndFiles {
+                this_container_only: false
+            }
+        );
+    }
+
+    #[test]
+    fn no_propagate_alone() {
+        let sd: LocalBox<SecurityDescriptor> = "D:(A;NP;FA;;;BU)".parse().unwrap();
+        let ace = sd.dacl().unwrap().get_ace(0).unwrap();
+        assert_eq!(
+            AppliesTo::try_from(ace.flags()).unwrap(),
+            AppliesTo::ThisFolderOnly
+        );
+
+        assert!(AppliesTo::try_from(AceFlags::InheritOnly | AceFlags::NoPropagateInherit).is_err());
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(AppliesTo::ThisFolderOnly.to_string(), "This folder only");
+        assert_eq!(
+            AppliesTo::SubfoldersAndFilesOnly {
+                this_container_only: true
+            }
+            .to_string(),
+            "Subfolders and files only (this container only)"
+        );
+    }
+}
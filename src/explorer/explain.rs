@@ -0,0 +1,529 @@
+use super::{AppliesTo, ObjectKind, Permissions};
+use crate::constants::{AccessRights, AceFlags, AceType, SecurityDescriptorControl};
+use crate::sddl::SID_ALIASES;
+use crate::token::ClaimValues;
+use crate::winnt::{
+    MAXIMUM_ALLOWED, SYSTEM_MANDATORY_LABEL_NO_EXECUTE_UP, SYSTEM_MANDATORY_LABEL_NO_READ_UP,
+    SYSTEM_MANDATORY_LABEL_NO_WRITE_UP,
+};
+use crate::{Ace, Acl, SecurityDescriptor, Sid};
+use std::convert::TryFrom;
+use std::fmt::{self, Write};
+
+/// The names of the SDDL SID aliases
+const ALIAS_NAMES: &[(&str, &str)] = &[
+    ("AA", "Access Control Assistance Operators"),
+    ("AC", "All Application Packages"),
+    ("AN", "Anonymous Logon"),
+    ("AO", "Account Operators"),
+    ("AS", "Authentication Authority Asserted Identity"),
+    ("AU", "Authenticated Users"),
+    ("BA", "Built-in Administrators"),
+    ("BG", "Built-in Guests"),
+    ("BO", "Backup Operators"),
+    ("BU", "Built-in Users"),
+    ("CD", "Certificate Service DCOM Access"),
+    ("CG", "Creator Group"),
+    ("CO", "Creator Owner"),
+    ("CY", "Cryptographic Operators"),
+    ("ED", "Enterprise Domain Controllers"),
+    ("ER", "Event Log Readers"),
+    ("ES", "RDS Endpoint Servers"),
+    ("HA", "Hyper-V Administrators"),
+    ("HI", "High Mandatory Level"),
+    ("IS", "IIS Users"),
+    ("IU", "Interactive Users"),
+    ("LS", "Local Service"),
+    ("LU", "Performance Log Users"),
+    ("LW", "Low Mandatory Level"),
+    ("ME", "Medium Mandatory Level"),
+    ("MP", "Medium Plus Mandatory Level"),
+    ("MS", "RDS Management Servers"),
+    ("MU", "Performance Monitor Users"),
+    ("NO", "Network Configuration Operators"),
+    ("NS", "Network Service"),
+    ("NU", "Network Logon Users"),
+    ("OW", "Owner Rights"),
+    ("PO", "Printer Operators"),
+    ("PS", "Principal Self"),
+    ("PU", "Power Users"),
+    ("RA", "RDS Remote Access Servers"),
+    ("RC", "Restricted Code"),
+    ("RD", "Remote Desktop Users"),
+    ("RE", "Replicator"),
+    ("RM", "Remote Management Users"),
+    ("RU", "Pre-Windows 2000 Compatible Access"),
+    ("SI", "System Mandatory Level"),
+    ("SO", "Server Operators"),
+    ("SS", "Service Asserted Identity"),
+    ("SU", "Service Logon Users"),
+    ("SY", "Local System"),
+    ("UD", "User-Mode Drivers"),
+    ("WD", "Everyone"),
+    ("WR", "Write Restricted Code"),
+];
+
+/// The names of well-known accounts in a domain, by RID, with their SDDL
+/// aliases
+const DOMAIN_RID_NAMES: &[(u32, &str, &str)] = &[
+    (500, "LA", "Administrator"),
+    (501, "LG", "Guest"),
+    (512, "DA", "Domain Admins"),
+    (513, "DU", "Domain Users"),
+    (514, "DG", "Domain Guests"),
+    (515, "DD", "Domain Computers"),
+    (516, "DC", "Domain Controllers"),
+    (517, "CA", "Cert Publishers"),
+    (518, "SA", "Schema Admins"),
+    (519, "EA", "Enterprise Admins"),
+    (520, "PA", "Group Policy Creator Owners"),
+    (553, "RS", "RAS and IAS Servers"),
+];
+
+/// The generic and special rights, which are named on their own instead of
+/// as part of a permission
+const OTHER_RIGHTS: &[(AccessRights, &str)] = &[
+    (AccessRights::GenericAll, "Generic all"),
+    (AccessRights::GenericRead, "Generic read"),
+    (AccessRights::GenericWrite, "Generic write"),
+    (AccessRights::GenericExecute, "Generic execute"),
+    (AccessRights::AccessSystemSecurity, "Access system security"),
+    (
+        AccessRights::from_bits_truncate(MAXIMUM_ALLOWED),
+        "Maximum allowed",
+    ),
+];
+
+/// The policies of a mandatory label
+const LABEL_POLICIES: &[(u32, &str)] = &[
+    (SYSTEM_MANDATORY_LABEL_NO_WRITE_UP, "No write up"),
+    (SYSTEM_MANDATORY_LABEL_NO_READ_UP, "No read up"),
+    (SYSTEM_MANDATORY_LABEL_NO_EXECUTE_UP, "No execute up"),
+];
+
+impl SecurityDescriptor {
+    /// Describe the security descriptor in plain English
+    ///
+    /// Each part of the security descriptor gets a section, with its
+    /// control flags explained. Each ACE is listed with its type, its
+    /// trustee, its rights as permissions on an object of the given kind,
+    /// and what it applies to. SID aliases are expanded to their names.
+    ///
+    /// ```
+    /// use windows_permissions::explorer::ObjectKind;
+    /// use windows_permissions::{LocalBox, SecurityDescriptor};
+    ///
+    /// let sd: LocalBox<SecurityDescriptor> =
+    ///     "O:BAD:PAI(A;OICI;FA;;;SY)(A;OICIIO;GA;;;CO)".parse().unwrap();
+    ///
+    /// assert_eq!(
+    ///     sd.explain(ObjectKind::Folder),
+    ///     "\
+    /// Owner: Built-in Administrators (BA, S-1-5-32-544)
+    /// Group: not set
+    /// Discretionary ACL (who is allowed or denied access):
+    ///   Protected: entries are not inherited from the parent object
+    ///   Auto-inherited: inheritable entries were propagated from the parent object
+    ///   1. Allow Local System (SY, S-1-5-18)
+    ///      Rights: Full control (0x1f01ff)
+    ///      Applies to: This folder, subfolders and files
+    ///   2. Allow Creator Owner (CO, S-1-3-0)
+    ///      Rights: Generic all (0x10000000)
+    ///      Applies to: Subfolders and files only
+    /// System ACL (auditing and labels): not present
+    /// "
+    /// );
+    /// ```
+    pub fn explain(&self, kind: ObjectKind) -> String {
+        let mut out = String::new();
+        write_explanation(&mut out, self, kind).expect("Failed to write to a String");
+        out
+    }
+}
+
+/// Write the explanation of a security descriptor
+fn write_explanation(out: &mut String, sd: &SecurityDescriptor, kind: ObjectKind) -> fmt::Result {
+    let control = sd.control();
+
+    for (part, sid, defaulted) in &[
+        (
+            "Owner",
+            sd.owner(),
+            SecurityDescriptorControl::OwnerDefaulted,
+        ),
+        (
+            "Group",
+            sd.group(),
+            SecurityDescriptorControl::GroupDefaulted,
+        ),
+    ] {
+        match sid {
+            Some(sid) if control.contains(*defaulted) => {
+                writeln!(out, "{}: {} (set by default)", part, describe_sid(sid))?
+            }
+            Some(sid) => writeln!(out, "{}: {}", part, describe_sid(sid))?,
+            None => writeln!(out, "{}: not set", part)?,
+        }
+    }
+
+    write_acl(out, true, sd.non_null_dacl(), control, kind)?;
+    write_acl(out, false, sd.non_null_sacl(), control, kind)
+}
+
+/// Write the explanation of the DACL or SACL, and the control flags that
+/// apply to it
+fn write_acl(
+    out: &mut String,
+    is_dacl: bool,
+    acl: Option<&Acl>,
+    control: SecurityDescriptorControl,
+    kind: ObjectKind,
+) -> fmt::Result {
+    use SecurityDescriptorControl as C;
+
+    let (title, present, flags) = if is_dacl {
+        (
+            "Discretionary ACL (who is allowed or denied access)",
+            C::DaclPresent,
+            [
+                C::DaclProtected,
+                C::DaclAutoInherited,
+                C::DaclAutoInheritReq,
+                C::DaclDefaulted,
+            ],
+        )
+    } else {
+        (
+            "System ACL (auditing and labels)",
+            C::SaclPresent,
+            [
+                C::SaclProtected,
+                C::SaclAutoInherited,
+                C::SaclAutoInheritReq,
+                C::SaclDefaulted,
+            ],
+        )
+    };
+
+    let acl = match acl {
+        Some(acl) if control.contains(present) => acl,
+        _ => {
+            let state = if control.contains(present) {
+                "null"
+            } else {
+                "not present"
+            };
+            if is_dacl {
+                return writeln!(out, "{}: {}, so everyone has full access", title, state);
+            } else {
+                return writeln!(out, "{}: {}", title, state);
+            }
+        }
+    };
+
+    writeln!(out, "{}:", title)?;
+
+    let meanings = [
+        "Protected: entries are not inherited from the parent object",
+        "Auto-inherited: inheritable entries were propagated from the parent object",
+        "Auto-inherit requested: inheritable entries should be propagated to child objects",
+        "Defaulted: set by a default mechanism instead of by the creator",
+    ];
+    for (flag, meaning) in flags.iter().zip(&meanings) {
+        if control.contains(*flag) {
+            writeln!(out, "  {}", meaning)?;
+        }
+    }
+
+    if acl.len() == 0 && is_dacl {
+        writeln!(
+            out,
+            "  No entries, so nobody is allowed access, apart from the owner's right to read and change permissions"
+        )?;
+    } else if acl.len() == 0 {
+        writeln!(out, "  No entries")?;
+    }
+
+    for (index, ace) in acl.aces().enumerate() {
+        write_ace(out, index + 1, ace, kind)?;
+    }
+
+    Ok(())
+}
+
+/// Write the explanation of an ACE, as a numbered item
+fn write_ace(out: &mut String, number: usize, ace: &Ace, kind: ObjectKind) -> fmt::Result {
+    let ace_type = match ace.ace_type() {
+        AceType::ACCESS_ALLOWED_ACE_TYPE => "Allow",
+        AceType::ACCESS_DENIED_ACE_TYPE => "Deny",
+        AceType::SYSTEM_AUDIT_ACE_TYPE => "Audit",
+        AceType::ACCESS_ALLOWED_OBJECT_ACE_TYPE => "Allow (object)",
+        AceType::ACCESS_DENIED_OBJECT_ACE_TYPE => "Deny (object)",
+        AceType::SYSTEM_AUDIT_OBJECT_ACE_TYPE => "Audit (object)",
+        AceType::ACCESS_ALLOWED_CALLBACK_ACE_TYPE => "Allow (conditional)",
+        AceType::ACCESS_DENIED_CALLBACK_ACE_TYPE => "Deny (conditional)",
+        AceType::SYSTEM_AUDIT_CALLBACK_ACE_TYPE => "Audit (conditional)",
+        AceType::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE => "Allow (conditional object)",
+        AceType::ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE => "Deny (conditional object)",
+        AceType::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE => "Audit (conditional object)",
+        AceType::SYSTEM_MANDATORY_LABEL_ACE_TYPE => "Mandatory label",
+        AceType::SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE => "Resource attribute",
+        AceType::SYSTEM_SCOPED_POLICY_ID_ACE_TYPE => "Central access policy",
+    };
+
+    match ace.ace_type() {
+        AceType::SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE => {
+            writeln!(out, "  {}. {}", number, ace_type)?;
+            match ace.resource_attribute() {
+                Some(Ok(attribute)) => writeln!(
+                    out,
+                    "     {} = {}",
+                    attribute.name(),
+                    describe_values(attribute.values())
+                )?,
+                _ => writeln!(out, "     Malformed attribute")?,
+            }
+        }
+        AceType::SYSTEM_MANDATORY_LABEL_ACE_TYPE => {
+            writeln!(
+                out,
+                "  {}. {} {}",
+                number,
+                ace_type,
+                describe_sid(ace.raw_sid())
+            )?;
+            let policies: Vec<&str> = LABEL_POLICIES
+                .iter()
+                .filter(|(bit, _)| ace.mask().bits() & bit != 0)
+                .map(|(_, name)| *name)
+                .collect();
+            if policies.is_empty() {
+                writeln!(out, "     Policy: none")?;
+            } else {
+                writeln!(out, "     Policy: {}", policies.join(", "))?;
+            }
+        }
+        AceType::SYSTEM_SCOPED_POLICY_ID_ACE_TYPE => {
+            writeln!(out, "  {}. {} {}", number, ace_type, ace.raw_sid())?;
+        }
+        _ => {
+            writeln!(
+                out,
+                "  {}. {} {}",
+                number,
+                ace_type,
+                describe_sid(ace.raw_sid())
+            )?;
+            writeln!(out, "     Rights: {}", describe_rights(ace.mask(), kind))?;
+        }
+    }
+
+    if let Some(guid) = ace.object_type() {
+        writeln!(out, "     Object type: {}", guid)?;
+    }
+
+    if let Some(guid) = ace.inherited_object_type() {
+        writeln!(out, "     Inherited by objects of type: {}", guid)?;
+    }
+
+    match ace.condition() {
+        Some(Ok(condition)) => writeln!(out, "     Condition: {}", condition)?,
+        Some(Err(_)) => writeln!(out, "     Condition: malformed")?,
+        None => (),
+    }
+
+    let flags = ace.flags();
+    match (
+        flags.contains(AceFlags::SuccessfulAccess),
+        flags.contains(AceFlags::FailedAccess),
+    ) {
+        (true, true) => writeln!(out, "     Audits: successful and failed access")?,
+        (true, false) => writeln!(out, "     Audits: successful access")?,
+        (false, true) => writeln!(out, "     Audits: failed access")?,
+        (false, false) => (),
+    }
+
+    match AppliesTo::try_from(flags) {
+        Ok(applies_to) => writeln!(out, "     Applies to: {}", applies_to)?,
+        // Only `InheritOnly` without `ObjectInherit` or `ContainerInherit`
+        // fails to map
+        Err(_) => writeln!(
+            out,
+            "     Applies to: nothing, since it is inherit-only but not inheritable"
+        )?,
+    }
+
+    if flags.contains(AceFlags::Inherited) {
+        writeln!(out, "     Inherited from a parent object")?;
+    }
+
+    Ok(())
+}
+
+/// Describe a SID by name, with its alias and SID string
+fn describe_sid(sid: &Sid) -> String {
+    let string = sid.to_string();
+
+    let alias = SID_ALIASES
+        .iter()
+        .find(|(_, full)| *full == string)
+        .and_then(|(alias, _)| ALIAS_NAMES.iter().find(|(name, _)| name == alias));
+    if let Some((alias, name)) = alias {
+        return format!("{} ({}, {})", name, alias, string);
+    }
+
+    // Well-known accounts of a domain, S-1-5-21-X-Y-Z-RID
+    let is_domain_account = *sid.id_authority() == [0, 0, 0, 0, 0, 5]
+        && sid.sub_authority_count() == 5
+        && sid.sub_authority(0) == Some(21);
+    if is_domain_account {
+        let rid = sid.sub_authority(4).unwrap_or_default();
+        if let Some((_, alias, name)) = DOMAIN_RID_NAMES.iter().find(|(known, _, _)| *known == rid)
+        {
+            let domain = &string[..string.len() - rid.to_string().len() - 1];
+            return format!("{} of domain {} ({}, {})", name, domain, alias, string);
+        }
+    }
+
+    string
+}
+
+/// Describe an access mask as permissions, followed by generic and special
+/// rights
+fn describe_rights(mask: AccessRights, kind: ObjectKind) -> String {
+    let mut rest = mask;
+    let mut names = vec![];
+
+    for (rights, name) in OTHER_RIGHTS {
+        if rest.contains(*rights) {
+            names.push(name.to_string());
+            rest.remove(*rights);
+        }
+    }
+
+    if !rest.is_empty() || names.is_empty() {
+        names.insert(0, Permissions::new(rest, kind).to_string());
+    }
+
+    format!("{} ({:#x})", names.join(", "), mask.bits())
+}
+
+/// Describe the values of a resource attribute
+fn describe_values(values: &ClaimValues) -> String {
+    let values: Vec<String> = match values {
+        ClaimValues::Int64(v) => v.iter().map(|n| n.to_string()).collect(),
+        ClaimValues::Uint64(v) => v.iter().map(|n| n.to_string()).collect(),
+        ClaimValues::String(v) => v.iter().map(|s| format!("{:?}", s)).collect(),
+        ClaimValues::Fqbn(v) => v
+            .iter()
+            .map(|(version, name)| format!("{:?} version {}", name, version))
+            .collect(),
+        ClaimValues::Sid(v) => v.iter().map(|sid| describe_sid(sid)).collect(),
+        ClaimValues::Boolean(v) => v.iter().map(|b| b.to_string()).collect(),
+        ClaimValues::OctetString(v) => v
+            .iter()
+            .map(|octets| octets.iter().map(|b| format!("{:02x}", b)).collect())
+            .collect(),
+    };
+
+    values.join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::LocalBox;
+
+    fn explain(sddl: &str, kind: ObjectKind) -> String {
+        let sd: LocalBox<SecurityDescriptor> = sddl.parse().unwrap();
+        sd.explain(kind)
+    }
+
+    #[test]
+    fn aliases_have_names() {
+        for (alias, _) in SID_ALIASES {
+            assert!(
+                ALIAS_NAMES.iter().any(|(name, _)| name == alias),
+                "{} has no name",
+                alias
+            );
+        }
+    }
+
+    #[test]
+    fn acls() {
+        assert_eq!(
+            explain(
+                "O:S-1-5-21-1-2-3-512G:SYD:NO_ACCESS_CONTROL",
+                ObjectKind::File
+            ),
+            "\
+Owner: Domain Admins of domain S-1-5-21-1-2-3 (DA, S-1-5-21-1-2-3-512)
+Group: Local System (SY, S-1-5-18)
+Discretionary ACL (who is allowed or denied access): null, so everyone has full access
+System ACL (auditing and labels): not present
+"
+        );
+
+        assert_eq!(
+            explain("D:AR", ObjectKind::File),
+            "\
+Owner: not set
+Group: not set
+Discretionary ACL (who is allowed or denied access):
+  Auto-inherit requested: inheritable entries should be propagated to child objects
+  No entries, so nobody is allowed access, apart from the owner's right to read and change permissions
+System ACL (auditing and labels): not present
+"
+        );
+    }
+
+    #[test]
+    fn aces() {
+        let explained = explain(
+            "D:(D;ID;0x1200a9;;;S-1-5-21-1-2-3-1001)(XA;;FR;;;WD;(Member_of {SID(BA)}))\
+             S:(AU;SAFA;FA;;;WD)(ML;;NWNR;;;HI)\
+             (RA;;;;;WD;(\"Project\",TS,0x0,\"Apollo\",\"Gemini\"))",
+            ObjectKind::File,
+        );
+
+        assert_eq!(
+            explained,
+            "\
+Owner: not set
+Group: not set
+Discretionary ACL (who is allowed or denied access):
+  1. Deny S-1-5-21-1-2-3-1001
+     Rights: Read & execute (0x1200a9)
+     Applies to: This folder only
+     Inherited from a parent object
+  2. Allow (conditional) Everyone (WD, S-1-1-0)
+     Rights: Read (0x120089)
+     Condition: (Member_of {SID(BA)})
+     Applies to: This folder only
+System ACL (auditing and labels):
+  1. Audit Everyone (WD, S-1-1-0)
+     Rights: Full control (0x1f01ff)
+     Audits: successful and failed access
+     Applies to: This folder only
+  2. Mandatory label High Mandatory Level (HI, S-1-16-12288)
+     Policy: No write up, No read up
+     Applies to: This folder only
+  3. Resource attribute
+     Project = \"Apollo\", \"Gemini\"
+     Applies to: This folder only
+"
+        );
+
+        assert!(explain("D:(A;;GRGWWD;;;BU)", ObjectKind::Folder)
+            .contains("Rights: Change permissions, Generic read, Generic write (0xc0040000)"));
+        assert!(explain("D:(A;IO;FA;;;BU)", ObjectKind::Folder)
+            .contains("Applies to: nothing, since it is inherit-only but not inheritable"));
+        assert!(explain("D:(A;IONP;FA;;;BU)", ObjectKind::Folder)
+            .contains("Applies to: nothing, since it is inherit-only but not inheritable"));
+
+        let no_propagate = explain("D:(A;NP;FA;;;BU)", ObjectKind::Folder);
+        assert!(no_propagate.contains("Applies to: This folder only"));
+        assert!(!no_propagate.contains("Applies to: nothing"));
+    }
+}
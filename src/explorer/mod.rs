@@ -0,0 +1,29 @@
+//! The permission names used by the Windows Security dialog
+//!
+//! Explorer and the registry editor do not show access masks. They show a
+//! few basic permissions, like "Modify" or "Read & execute", each standing for
+//! a fixed set of rights, plus "Special permissions" for anything else. This
+//! module converts between access masks and those names, describes
+//! inheritance flags the way the Advanced Security dialog does, and lists an
+//! ACL's entries in the dialog's table. For a longer description of a whole
+//! security descriptor, see [`SecurityDescriptor::explain`](crate::SecurityDescriptor::explain).
+//!
+//! ```
+//! use windows_permissions::constants::AccessRights;
+//! use windows_permissions::explorer::{ObjectKind, Permissions};
+//!
+//! let modify = Permissions::new(AccessRights::from_bits_truncate(0x1301bf), ObjectKind::File);
+//! assert_eq!(modify.to_string(), "Modify");
+//!
+//! let parsed = Permissions::parse("Read, Delete", ObjectKind::File).unwrap();
+//! assert_eq!(parsed.mask(), AccessRights::FileGenericRead | AccessRights::Delete);
+//! ```
+
+mod applies_to;
+mod explain;
+mod permissions;
+mod summary;
+
+pub use applies_to::AppliesTo;
+pub use permissions::{BasicPermission, ObjectKind, Permissions};
+pub use summary::{AccessType, PermissionEntry, PermissionSummary};
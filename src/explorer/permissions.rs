@@ -0,0 +1,459 @@
+use crate::access::GenericMapping;
+use crate::constants::{AccessRights, FileRights, KeyRights};
+use std::fmt;
+use std::io;
+
+/// The kinds of objects whose permissions Explorer names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObjectKind {
+    /// A file
+    File,
+    /// A folder, which also has "List folder contents"
+    Folder,
+    /// A registry key, which only has "Full control" and "Read"
+    RegistryKey,
+}
+
+impl ObjectKind {
+    fn mapping(self) -> &'static GenericMapping {
+        match self {
+            ObjectKind::File => &GenericMapping::FILE,
+            ObjectKind::Folder => &GenericMapping::DIRECTORY,
+            ObjectKind::RegistryKey => &GenericMapping::KEY,
+        }
+    }
+
+    fn advanced(self) -> &'static [(u32, &'static str)] {
+        match self {
+            ObjectKind::File | ObjectKind::Folder => FILE_ADVANCED,
+            ObjectKind::RegistryKey => KEY_ADVANCED,
+        }
+    }
+}
+
+/// The advanced permissions of files and folders, in the order Explorer
+/// lists them
+const FILE_ADVANCED: &[(u32, &str)] = &[
+    (FileRights::Execute.bits(), "Traverse folder / execute file"),
+    (FileRights::ReadData.bits(), "List folder / read data"),
+    (FileRights::ReadAttributes.bits(), "Read attributes"),
+    (FileRights::ReadEa.bits(), "Read extended attributes"),
+    (FileRights::WriteData.bits(), "Create files / write data"),
+    (
+        FileRights::AppendData.bits(),
+        "Create folders / append data",
+    ),
+    (FileRights::WriteAttributes.bits(), "Write attributes"),
+    (FileRights::WriteEa.bits(), "Write extended attributes"),
+    (
+        FileRights::DeleteChild.bits(),
+        "Delete subfolders and files",
+    ),
+    (FileRights::Delete.bits(), "Delete"),
+    (FileRights::ReadControl.bits(), "Read permissions"),
+    (FileRights::WriteDac.bits(), "Change permissions"),
+    (FileRights::WriteOwner.bits(), "Take ownership"),
+];
+
+/// The advanced permissions of registry keys, in the order the registry
+/// editor lists them
+const KEY_ADVANCED: &[(u32, &str)] = &[
+    (KeyRights::QueryValue.bits(), "Query Value"),
+    (KeyRights::SetValue.bits(), "Set Value"),
+    (KeyRights::CreateSubKey.bits(), "Create Subkey"),
+    (KeyRights::EnumerateSubKeys.bits(), "Enumerate Subkeys"),
+    (KeyRights::Notify.bits(), "Notify"),
+    (KeyRights::CreateLink.bits(), "Create Link"),
+    (KeyRights::Delete.bits(), "Delete"),
+    (KeyRights::WriteDac.bits(), "Write DAC"),
+    (KeyRights::WriteOwner.bits(), "Write Owner"),
+    (KeyRights::ReadControl.bits(), "Read Control"),
+];
+
+/// The basic permissions shown on the Security tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BasicPermission {
+    /// Every right (`0x1f01ff` for files, `0xf003f` for keys)
+    FullControl,
+    /// Read, write, execute and delete (`0x1301bf`)
+    Modify,
+    /// Read and execute (`0x1200a9`)
+    ReadAndExecute,
+    /// Read and execute, for folders only (`0x1200a9`)
+    ListFolderContents,
+    /// Read (`0x120089` for files, `0x20019` for keys)
+    Read,
+    /// Write (`0x100116`)
+    Write,
+}
+
+impl BasicPermission {
+    /// Every basic permission, in the order Explorer lists them
+    pub const ALL: [BasicPermission; 6] = [
+        BasicPermission::FullControl,
+        BasicPermission::Modify,
+        BasicPermission::ReadAndExecute,
+        BasicPermission::ListFolderContents,
+        BasicPermission::Read,
+        BasicPermission::Write,
+    ];
+
+    /// Get the name Explorer shows for the permission
+    pub fn name(self) -> &'static str {
+        match self {
+            BasicPermission::FullControl => "Full control",
+            BasicPermission::Modify => "Modify",
+            BasicPermission::ReadAndExecute => "Read & execute",
+            BasicPermission::ListFolderContents => "List folder contents",
+            BasicPermission::Read => "Read",
+            BasicPermission::Write => "Write",
+        }
+    }
+
+    /// Get the rights that the permission stands for on an object of the
+    /// given kind
+    ///
+    /// Returns `None` if objects of that kind do not show the permission.
+    ///
+    /// ```
+    /// use windows_permissions::constants::AccessRights;
+    /// use windows_permissions::explorer::{BasicPermission, ObjectKind};
+    ///
+    /// assert_eq!(
+    ///     BasicPermission::Read.rights(ObjectKind::File),
+    ///     Some(AccessRights::FileGenericRead)
+    /// );
+    /// assert_eq!(
+    ///     BasicPermission::Read.rights(ObjectKind::RegistryKey),
+    ///     Some(AccessRights::KeyRead)
+    /// );
+    /// assert_eq!(BasicPermission::Modify.rights(ObjectKind::RegistryKey), None);
+    /// ```
+    pub fn rights(self, kind: ObjectKind) -> Option<AccessRights> {
+        let read_execute = AccessRights::FileGenericRead | AccessRights::FileGenericExecute;
+
+        match (self, kind) {
+            (BasicPermission::FullControl, ObjectKind::RegistryKey) => {
+                Some(AccessRights::KeyAllAccess)
+            }
+            (BasicPermission::Read, ObjectKind::RegistryKey) => Some(AccessRights::KeyRead),
+            (_, ObjectKind::RegistryKey) => None,
+            (BasicPermission::FullControl, _) => Some(AccessRights::FileAllAccess),
+            (BasicPermission::Modify, _) => {
+                Some(read_execute | AccessRights::FileGenericWrite | AccessRights::Delete)
+            }
+            (BasicPermission::ReadAndExecute, _) => Some(read_execute),
+            (BasicPermission::ListFolderContents, ObjectKind::Folder) => Some(read_execute),
+            (BasicPermission::ListFolderContents, _) => None,
+            (BasicPermission::Read, _) => Some(AccessRights::FileGenericRead),
+            // Explorer's Write leaves out the read control in FILE_GENERIC_WRITE
+            (BasicPermission::Write, _) => {
+                Some(AccessRights::FileGenericWrite - AccessRights::ReadControl)
+            }
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|permission| permission.name().eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for BasicPermission {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// An access mask described the way the Windows Security dialog shows it
+///
+/// The mask is split into the basic permissions whose rights it contains,
+/// and the special permissions left over. Explorer ticks "Special
+/// permissions" when there are any, and lists them by name on the Advanced
+/// page.
+///
+/// When displayed, only the basic permissions not implied by another one
+/// are named, followed by the names of the special permissions. Rights that
+/// have no name are shown in hex. An empty mask is shown as `None`.
+///
+/// ```
+/// use windows_permissions::explorer::{BasicPermission, ObjectKind, Permissions};
+/// use windows_permissions::{LocalBox, SecurityDescriptor};
+///
+/// let sd: LocalBox<SecurityDescriptor> = "D:(A;;0x1201ff;;;BU)".parse().unwrap();
+/// let ace = sd.dacl().unwrap().get_ace(0).unwrap();
+///
+/// let permissions = Permissions::new(ace.mask(), ObjectKind::Folder);
+/// assert!(permissions.basic().contains(&BasicPermission::ReadAndExecute));
+/// assert!(permissions.basic().contains(&BasicPermission::Write));
+/// assert!(permissions.has_special());
+/// assert_eq!(
+///     permissions.to_string(),
+///     "Read & execute, Write, Delete subfolders and files"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Permissions {
+    mask: AccessRights,
+    kind: ObjectKind,
+}
+
+impl Permissions {
+    /// Describe `mask` as permissions on an object of the given kind
+    ///
+    /// Generic rights are mapped to the object's specific rights first, as
+    /// Explorer does.
+    pub fn new(mask: AccessRights, kind: ObjectKind) -> Self {
+        Self {
+            mask: kind.mapping().map(mask),
+            kind,
+        }
+    }
+
+    /// Parse a list of permission names separated by commas
+    ///
+    /// Each name may be a basic permission, an advanced permission of the
+    /// given kind of object, or a hex mask like `0x100000`. Names are not
+    /// case sensitive, and `None` stands for no rights.
+    ///
+    /// ```
+    /// use windows_permissions::constants::AccessRights;
+    /// use windows_permissions::explorer::{ObjectKind, Permissions};
+    ///
+    /// let permissions = Permissions::parse("read, write dac", ObjectKind::RegistryKey).unwrap();
+    /// assert_eq!(
+    ///     permissions.mask(),
+    ///     AccessRights::KeyRead | AccessRights::WriteDac
+    /// );
+    /// assert_eq!(permissions.to_string(), "Read, Write DAC");
+    ///
+    /// assert!(Permissions::parse("Modify", ObjectKind::RegistryKey).is_err());
+    /// ```
+    pub fn parse(s: &str, kind: ObjectKind) -> io::Result<Self> {
+        let invalid = |reason: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid permissions ({}): {:?}", reason, s),
+            )
+        };
+
+        if s.trim().eq_ignore_ascii_case("none") {
+            return Ok(Self::new(AccessRights::empty(), kind));
+        }
+
+        let mut mask = AccessRights::empty();
+
+        for name in s.split(',').map(str::trim) {
+            if let Some(permission) = BasicPermission::from_name(name) {
+                mask |= permission
+                    .rights(kind)
+                    .ok_or_else(|| invalid("basic permission not shown for this object"))?;
+            } else if let Some((bits, _)) = kind
+                .advanced()
+                .iter()
+                .find(|(_, advanced)| advanced.eq_ignore_ascii_case(name))
+            {
+                mask |= AccessRights::from_bits_truncate(*bits);
+            } else if let Some(hex) = name.strip_prefix("0x").or_else(|| name.strip_prefix("0X")) {
+                let bits = u32::from_str_radix(hex, 16).map_err(|_| invalid("bad hex mask"))?;
+                mask |= AccessRights::from_bits_truncate(bits);
+            } else {
+                return Err(invalid("unknown permission name"));
+            }
+        }
+
+        Ok(Self::new(mask, kind))
+    }
+
+    /// Get the access mask, with generic rights mapped
+    pub fn mask(&self) -> AccessRights {
+        self.mask
+    }
+
+    /// Get the kind of object the permissions are for
+    pub fn kind(&self) -> ObjectKind {
+        self.kind
+    }
+
+    /// Get every basic permission whose box Explorer ticks
+    pub fn basic(&self) -> Vec<BasicPermission> {
+        BasicPermission::ALL
+            .iter()
+            .copied()
+            .filter(|permission| {
+                permission
+                    .rights(self.kind)
+                    .is_some_and(|rights| self.mask.contains(rights))
+            })
+            .collect()
+    }
+
+    /// Get the rights that are not covered by any basic permission
+    pub fn special(&self) -> AccessRights {
+        let covered = self
+            .basic()
+            .iter()
+            .filter_map(|permission| permission.rights(self.kind))
+            .fold(AccessRights::empty(), |covered, rights| covered | rights);
+
+        self.mask - covered
+    }
+
+    /// Check whether Explorer ticks "Special permissions"
+    pub fn has_special(&self) -> bool {
+        !self.special().is_empty()
+    }
+
+    /// Get the names of the advanced permissions in [`special`](Self::special),
+    /// and the rights in it that have no name
+    pub fn special_names(&self) -> (Vec<&'static str>, AccessRights) {
+        let mut rest = self.special();
+        let names = self
+            .kind
+            .advanced()
+            .iter()
+            .filter(|(bits, _)| rest.bits() & bits == *bits)
+            .map(|(_, name)| *name)
+            .collect();
+
+        for (bits, _) in self.kind.advanced() {
+            rest.remove(AccessRights::from_bits_truncate(*bits));
+        }
+
+        (names, rest)
+    }
+}
+
+impl fmt::Display for Permissions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Name a basic permission only if no earlier one already covers it
+        let mut names = vec![];
+        let mut covered: Vec<AccessRights> = vec![];
+        for permission in self.basic() {
+            let rights = permission
+                .rights(self.kind)
+                .unwrap_or_else(AccessRights::empty);
+            if !covered.iter().any(|other| other.contains(rights)) {
+                names.push(permission.name().to_string());
+            }
+            covered.push(rights);
+        }
+
+        let (special, rest) = self.special_names();
+        names.extend(special.iter().map(|name| name.to_string()));
+        if !rest.is_empty() {
+            names.push(format!("{:#x}", rest.bits()));
+        }
+
+        if names.is_empty() {
+            f.write_str("None")
+        } else {
+            f.write_str(&names.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rights(bits: u32) -> AccessRights {
+        AccessRights::from_bits_truncate(bits)
+    }
+
+    #[test]
+    fn basic_permissions() {
+        let describe = |bits, kind| Permissions::new(rights(bits), kind).to_string();
+
+        assert_eq!(describe(0x1f01ff, ObjectKind::File), "Full control");
+        assert_eq!(describe(0x1301bf, ObjectKind::File), "Modify");
+        assert_eq!(describe(0x1200a9, ObjectKind::File), "Read & execute");
+        assert_eq!(describe(0x120089, ObjectKind::File), "Read");
+        assert_eq!(describe(0x100116, ObjectKind::File), "Write");
+        assert_eq!(
+            describe(0x1201bf, ObjectKind::File),
+            "Read & execute, Write"
+        );
+        assert_eq!(describe(0x1200a9, ObjectKind::Folder), "Read & execute");
+        assert_eq!(describe(0x10000000, ObjectKind::Folder), "Full control");
+        assert_eq!(describe(0xf003f, ObjectKind::RegistryKey), "Full control");
+        assert_eq!(describe(0x20019, ObjectKind::RegistryKey), "Read");
+        assert_eq!(describe(0, ObjectKind::File), "None");
+
+        let folder = Permissions::new(rights(0x1301bf), ObjectKind::Folder);
+        assert_eq!(
+            folder.basic(),
+            vec![
+                BasicPermission::Modify,
+                BasicPermission::ReadAndExecute,
+                BasicPermission::ListFolderContents,
+                BasicPermission::Read,
+                BasicPermission::Write,
+            ]
+        );
+        assert!(!folder.has_special());
+    }
+
+    #[test]
+    fn special_permissions() {
+        let file = Permissions::new(rights(0x1200a9) | AccessRights::WriteDac, ObjectKind::File);
+        assert_eq!(file.special(), AccessRights::WriteDac);
+        assert_eq!(file.to_string(), "Read & execute, Change permissions");
+
+        let delete = Permissions::new(AccessRights::Delete, ObjectKind::File);
+        assert!(delete.basic().is_empty());
+        assert_eq!(delete.to_string(), "Delete");
+
+        let odd = Permissions::new(rights(0x100001 | 0x200), ObjectKind::File);
+        assert_eq!(
+            odd.special_names(),
+            (vec!["List folder / read data"], rights(0x100200))
+        );
+        assert_eq!(odd.to_string(), "List folder / read data, 0x100200");
+
+        let key = Permissions::new(rights(0x20019 | 0x2), ObjectKind::RegistryKey);
+        assert_eq!(key.to_string(), "Read, Set Value");
+    }
+
+    #[test]
+    fn parse() {
+        for (bits, kind) in &[
+            (0x1f01ff, ObjectKind::File),
+            (0x1201bf, ObjectKind::Folder),
+            (0x1200a9 | 0xc0000, ObjectKind::File),
+            (0x100001 | 0x200, ObjectKind::File),
+            (0x20019 | 0x2, ObjectKind::RegistryKey),
+            (0, ObjectKind::File),
+        ] {
+            let permissions = Permissions::new(rights(*bits), *kind);
+            let parsed = Permissions::parse(&permissions.to_string(), *kind).unwrap();
+            assert_eq!(parsed, permissions);
+        }
+
+        assert_eq!(
+            Permissions::parse("FULL CONTROL", ObjectKind::File)
+                .unwrap()
+                .mask(),
+            AccessRights::FileAllAccess
+        );
+        assert_eq!(
+            Permissions::parse("List folder contents, Take ownership", ObjectKind::Folder)
+                .unwrap()
+                .mask(),
+            rights(0x1200a9) | AccessRights::WriteOwner
+        );
+
+        for (s, kind) in &[
+            ("List folder contents", ObjectKind::File),
+            ("Write", ObjectKind::RegistryKey),
+            ("Query Value", ObjectKind::File),
+            ("Read,", ObjectKind::File),
+            ("0xzz", ObjectKind::File),
+        ] {
+            let err = Permissions::parse(s, *kind).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+    }
+}
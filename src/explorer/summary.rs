@@ -0,0 +1,238 @@
+use super::{AppliesTo, ObjectKind, Permissions};
+use crate::constants::{AceFlags, AceType};
+use crate::icacls::SidResolver;
+use crate::{Acl, Sid};
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Whether a permission entry allows or denies access
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessType {
+    /// The entry allows access
+    Allow,
+    /// The entry denies access
+    Deny,
+}
+
+impl fmt::Display for AccessType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            AccessType::Allow => "Allow",
+            AccessType::Deny => "Deny",
+        })
+    }
+}
+
+/// A row of the Advanced Security dialog's permission entries
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PermissionEntry<'a> {
+    access_type: AccessType,
+    principal: String,
+    sid: &'a Sid,
+    access: Permissions,
+    inherited: bool,
+    applies_to: Option<AppliesTo>,
+}
+
+impl<'a> PermissionEntry<'a> {
+    /// Get whether the entry allows or denies access
+    pub fn access_type(&self) -> AccessType {
+        self.access_type
+    }
+
+    /// Get the name of the principal, or its SID string if it has no name
+    pub fn principal(&self) -> &str {
+        &self.principal
+    }
+
+    /// Get the SID of the principal
+    pub fn sid(&self) -> &'a Sid {
+        self.sid
+    }
+
+    /// Get the permissions that the entry allows or denies
+    pub fn access(&self) -> &Permissions {
+        &self.access
+    }
+
+    /// Check whether the entry was inherited from a parent object
+    pub fn is_inherited(&self) -> bool {
+        self.inherited
+    }
+
+    /// Get the objects the entry applies to, or `None` if its inheritance
+    /// flags make it apply to nothing
+    pub fn applies_to(&self) -> Option<AppliesTo> {
+        self.applies_to
+    }
+}
+
+/// The permission entries of an ACL, as the Advanced Security dialog lists
+/// them
+///
+/// There is one entry per allow or deny ACE, including callback ACEs. Other
+/// ACE types are left out. When displayed, the entries are laid out in a
+/// table with the dialog's columns.
+///
+/// The dialog looks up the object each inherited ACE came from; since only
+/// the ACL is known here, inherited entries are shown as inherited from
+/// "Parent Object", as the dialog does when the lookup fails.
+///
+/// ```
+/// use windows_permissions::explorer::{AccessType, ObjectKind, PermissionSummary};
+/// use windows_permissions::icacls::WellKnownNames;
+/// use windows_permissions::{LocalBox, SecurityDescriptor};
+///
+/// let sd: LocalBox<SecurityDescriptor> =
+///     "D:(D;CI;0x100116;;;WD)(A;OICIID;FA;;;SY)(A;OICIIOID;0x1301bf;;;CO)".parse().unwrap();
+/// let summary = PermissionSummary::new(sd.dacl().unwrap(), ObjectKind::Folder, &WellKnownNames);
+///
+/// assert_eq!(summary.entries()[0].access_type(), AccessType::Deny);
+/// assert_eq!(summary.entries()[1].principal(), "NT AUTHORITY\\SYSTEM");
+///
+/// assert_eq!(
+///     summary.to_string(),
+///     "\
+/// Type   Principal            Access        Inherited from  Applies to
+/// Deny   Everyone             Write         None            This folder and subfolders
+/// Allow  NT AUTHORITY\\SYSTEM  Full control  Parent Object   This folder, subfolders and files
+/// Allow  CREATOR OWNER        Modify        Parent Object   Subfolders and files only
+/// "
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PermissionSummary<'a> {
+    entries: Vec<PermissionEntry<'a>>,
+}
+
+impl<'a> PermissionSummary<'a> {
+    /// Summarize the allow and deny ACEs of an ACL on an object of the given
+    /// kind, naming principals with `resolver`
+    pub fn new(acl: &'a Acl, kind: ObjectKind, resolver: &dyn SidResolver) -> Self {
+        let entries =
+            acl.aces()
+                .filter_map(|ace| {
+                    let access_type = match ace.ace_type() {
+                        AceType::ACCESS_ALLOWED_ACE_TYPE
+                        | AceType::ACCESS_ALLOWED_CALLBACK_ACE_TYPE => AccessType::Allow,
+                        AceType::ACCESS_DENIED_ACE_TYPE
+                        | AceType::ACCESS_DENIED_CALLBACK_ACE_TYPE => AccessType::Deny,
+                        _ => return None,
+                    };
+                    let sid = ace.sid()?;
+
+                    Some(PermissionEntry {
+                        access_type,
+                        principal: resolver.name(sid).unwrap_or_else(|| sid.to_string()),
+                        sid,
+                        access: Permissions::new(ace.mask(), kind),
+                        inherited: ace.flags().contains(AceFlags::Inherited),
+                        applies_to: AppliesTo::try_from(ace.flags()).ok(),
+                    })
+                })
+                .collect();
+
+        Self { entries }
+    }
+
+    /// Get the permission entries, in ACL order
+    pub fn entries(&self) -> &[PermissionEntry<'a>] {
+        &self.entries
+    }
+}
+
+impl fmt::Display for PermissionSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let header = [
+            "Type",
+            "Principal",
+            "Access",
+            "Inherited from",
+            "Applies to",
+        ];
+
+        let mut rows = vec![header
+            .iter()
+            .map(|cell| cell.to_string())
+            .collect::<Vec<_>>()];
+        for entry in &self.entries {
+            rows.push(vec![
+                entry.access_type.to_string(),
+                entry.principal.clone(),
+                entry.access.to_string(),
+                if entry.inherited {
+                    "Parent Object".to_string()
+                } else {
+                    "None".to_string()
+                },
+                entry.applies_to.map_or_else(
+                    || "Nothing".to_string(),
+                    |applies_to| applies_to.to_string(),
+                ),
+            ]);
+        }
+
+        let widths: Vec<usize> = (0..header.len())
+            .map(|column| {
+                rows.iter()
+                    .map(|row| row[column].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        for row in &rows {
+            let mut line = String::new();
+            for (column, cell) in row.iter().enumerate() {
+                if column + 1 == row.len() {
+                    line.push_str(cell);
+                } else {
+                    line.push_str(&format!("{:width$}  ", cell, width = widths[column]));
+                }
+            }
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::icacls::RawSids;
+    use crate::{LocalBox, SecurityDescriptor};
+
+    #[test]
+    fn entries() {
+        let sd: LocalBox<SecurityDescriptor> = "D:(A;;0x1200a9;;;S-1-5-21-1-2-3-1001)\
+             (A;OICINPID;0x20019;;;BU)(A;OIIO;FA;;;WD)S:(AU;SA;FA;;;WD)"
+            .parse()
+            .unwrap();
+        let summary = PermissionSummary::new(sd.dacl().unwrap(), ObjectKind::RegistryKey, &RawSids);
+        let entries = summary.entries();
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].principal(), "S-1-5-21-1-2-3-1001");
+        assert_eq!(
+            entries[0].access().to_string(),
+            "Query Value, Enumerate Subkeys, Create Link, Read Control, 0x100080"
+        );
+        assert!(!entries[0].is_inherited());
+        assert_eq!(entries[0].applies_to(), Some(AppliesTo::ThisFolderOnly));
+
+        assert_eq!(entries[1].sid().to_string(), "S-1-5-32-545");
+        assert_eq!(entries[1].access().to_string(), "Read");
+        assert!(entries[1].is_inherited());
+        assert_eq!(
+            entries[1].applies_to(),
+            Some(AppliesTo::ThisFolderSubfoldersAndFiles {
+                this_container_only: true
+            })
+        );
+
+        // Audit ACEs are left out
+        let summary = PermissionSummary::new(sd.sacl().unwrap(), ObjectKind::File, &RawSids);
+        assert!(summary.entries().is_empty());
+    }
+}
@@ -0,0 +1,737 @@
+//! Rendering and parsing the ACL syntax of `icacls`
+//!
+//! `icacls` shows each ACE of a DACL as an entry: the principal, a colon,
+//! then a run of parenthesized groups. The groups hold `DENY` for deny ACEs,
+//! the inheritance flags, and finally the rights.
+//!
+//! ```text
+//! C:\data NT AUTHORITY\SYSTEM:(OI)(CI)(F)
+//!         BUILTIN\Users:(I)(RX)
+//!         Everyone:(DENY)(W)
+//! ```
+//!
+//! The inheritance flags are `I` (inherited), `OI` (object inherit), `CI`
+//! (container inherit), `IO` (inherit only) and `NP` (no propagate). The
+//! rights are a comma-separated list of simple rights (`N`, `F`, `M`, `RX`,
+//! `R`, `W`, `D`) and advanced rights (`DE`, `RC`, `WDAC`, `WO`, `S`, `AS`,
+//! `MA`, `GR`, `GW`, `GE`, `GA`, `RD`, `WD`, `AD`, `REA`, `WEA`, `X`, `DC`,
+//! `RA`, `WA`). Rights without an abbreviation are written in hex.
+//!
+//! Principals are named by a [`SidResolver`]. Those it cannot name are
+//! written as SID strings, which are always accepted when parsing.
+//!
+//! The files written by `icacls /save` can be read and written with
+//! [`read_save_file`] and [`write_save_file`].
+//!
+//! See [MSDN](https://docs.microsoft.com/en-us/windows-server/administration/windows-commands/icacls)
+//! for details.
+
+use crate::build::{self, SecurityDescriptorParts};
+use crate::constants::{AccessRights, AceFlags, AceType};
+use crate::winnt::{
+    ACCESS_ALLOWED_ACE_TYPE, ACCESS_DENIED_ACE_TYPE, MAXIMUM_ALLOWED, SE_DACL_PRESENT,
+};
+use crate::{Ace, Acl, LocalBox, SecurityDescriptor, Sid};
+use std::io;
+
+mod save;
+
+pub use save::{read_save_file, write_save_file};
+
+/// The inheritance flags, in the order `icacls` shows them
+const FLAGS: &[(&str, AceFlags)] = &[
+    ("I", AceFlags::Inherited),
+    ("OI", AceFlags::ObjectInherit),
+    ("CI", AceFlags::ContainerInherit),
+    ("IO", AceFlags::InheritOnly),
+    ("NP", AceFlags::NoPropagateInherit),
+];
+
+/// The simple rights, largest first
+const SIMPLE_RIGHTS: &[(&str, u32)] = &[
+    ("F", 0x1f01ff),
+    ("M", 0x1301bf),
+    ("RX", 0x1200a9),
+    ("R", 0x120089),
+    ("W", 0x100116),
+    ("D", 0x110000),
+];
+
+/// The advanced rights, in the order `icacls` lists them
+const ADVANCED_RIGHTS: &[(&str, AccessRights)] = &[
+    ("DE", AccessRights::Delete),
+    ("RC", AccessRights::ReadControl),
+    ("WDAC", AccessRights::WriteDac),
+    ("WO", AccessRights::WriteOwner),
+    ("S", AccessRights::Synchronize),
+    ("AS", AccessRights::AccessSystemSecurity),
+    ("MA", AccessRights::from_bits_truncate(MAXIMUM_ALLOWED)),
+    ("GR", AccessRights::GenericRead),
+    ("GW", AccessRights::GenericWrite),
+    ("GE", AccessRights::GenericExecute),
+    ("GA", AccessRights::GenericAll),
+    ("RD", AccessRights::Bit0),
+    ("WD", AccessRights::Bit1),
+    ("AD", AccessRights::Bit2),
+    ("REA", AccessRights::Bit3),
+    ("WEA", AccessRights::Bit4),
+    ("X", AccessRights::Bit5),
+    ("DC", AccessRights::Bit6),
+    ("RA", AccessRights::Bit7),
+    ("WA", AccessRights::Bit8),
+];
+
+/// The names of well-known accounts, as `icacls` shows them on an English
+/// system
+const WELL_KNOWN_NAMES: &[(&str, &str)] = &[
+    ("S-1-1-0", "Everyone"),
+    ("S-1-3-0", "CREATOR OWNER"),
+    ("S-1-3-1", "CREATOR GROUP"),
+    ("S-1-3-4", "OWNER RIGHTS"),
+    ("S-1-5-2", "NT AUTHORITY\\NETWORK"),
+    ("S-1-5-4", "NT AUTHORITY\\INTERACTIVE"),
+    ("S-1-5-6", "NT AUTHORITY\\SERVICE"),
+    ("S-1-5-7", "NT AUTHORITY\\ANONYMOUS LOGON"),
+    ("S-1-5-11", "NT AUTHORITY\\Authenticated Users"),
+    ("S-1-5-18", "NT AUTHORITY\\SYSTEM"),
+    ("S-1-5-19", "NT AUTHORITY\\LOCAL SERVICE"),
+    ("S-1-5-20", "NT AUTHORITY\\NETWORK SERVICE"),
+    ("S-1-5-32-544", "BUILTIN\\Administrators"),
+    ("S-1-5-32-545", "BUILTIN\\Users"),
+    ("S-1-5-32-546", "BUILTIN\\Guests"),
+    ("S-1-5-32-547", "BUILTIN\\Power Users"),
+    ("S-1-5-32-551", "BUILTIN\\Backup Operators"),
+    ("S-1-5-32-555", "BUILTIN\\Remote Desktop Users"),
+    (
+        "S-1-5-80-956008885-3418522649-1831038044-1853292631-2271478464",
+        "NT SERVICE\\TrustedInstaller",
+    ),
+    (
+        "S-1-15-2-1",
+        "APPLICATION PACKAGE AUTHORITY\\ALL APPLICATION PACKAGES",
+    ),
+    (
+        "S-1-15-2-2",
+        "APPLICATION PACKAGE AUTHORITY\\ALL RESTRICTED APPLICATION PACKAGES",
+    ),
+];
+
+/// Converts between SIDs and the account names shown for them
+pub trait SidResolver {
+    /// Get the name of the account with the given SID, if it is known
+    fn name(&self, sid: &Sid) -> Option<String>;
+
+    /// Get the SID of the account with the given name, if it is known
+    fn sid(&self, name: &str) -> Option<LocalBox<Sid>>;
+}
+
+/// A [`SidResolver`] that knows no names, so that every principal is shown
+/// as a SID string
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RawSids;
+
+impl SidResolver for RawSids {
+    fn name(&self, _sid: &Sid) -> Option<String> {
+        None
+    }
+
+    fn sid(&self, _name: &str) -> Option<LocalBox<Sid>> {
+        None
+    }
+}
+
+/// A [`SidResolver`] that knows the English names of well-known accounts,
+/// like `BUILTIN\Administrators` and `Everyone`, on any platform
+///
+/// Names may be given with or without their domain, in any case.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WellKnownNames;
+
+impl SidResolver for WellKnownNames {
+    fn name(&self, sid: &Sid) -> Option<String> {
+        let sid = sid.to_string();
+
+        WELL_KNOWN_NAMES
+            .iter()
+            .find(|(known, _)| *known == sid)
+            .map(|(_, name)| name.to_string())
+    }
+
+    fn sid(&self, name: &str) -> Option<LocalBox<Sid>> {
+        WELL_KNOWN_NAMES
+            .iter()
+            .find(|(_, known)| {
+                let account = known.rsplit('\\').next().unwrap_or(known);
+                known.eq_ignore_ascii_case(name) || account.eq_ignore_ascii_case(name)
+            })
+            .map(|(sid, _)| sid.parse().expect("Invalid well-known SID"))
+    }
+}
+
+/// A [`SidResolver`] that asks Windows, with `LookupAccountSid` and
+/// `LookupAccountName`
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AccountLookup;
+
+#[cfg(target_os = "windows")]
+impl SidResolver for AccountLookup {
+    fn name(&self, sid: &Sid) -> Option<String> {
+        let (name, domain) = crate::wrappers::LookupAccountSid(sid).ok()?;
+
+        if domain.is_empty() {
+            Some(name.to_string_lossy().into_owned())
+        } else {
+            Some(format!(
+                "{}\\{}",
+                domain.to_string_lossy(),
+                name.to_string_lossy()
+            ))
+        }
+    }
+
+    fn sid(&self, name: &str) -> Option<LocalBox<Sid>> {
+        let (sid, _, _) =
+            crate::wrappers::LookupAccountName(Option::<&std::ffi::OsStr>::None, name).ok()?;
+        crate::wrappers::CopySid(&sid).ok()
+    }
+}
+
+/// Write an access mask as an `icacls` rights list, without parentheses
+///
+/// The simple rights are used where the mask contains them, followed by the
+/// advanced rights for whatever is left.
+///
+/// ```
+/// use windows_permissions::constants::AccessRights;
+/// use windows_permissions::icacls::format_rights;
+///
+/// assert_eq!(format_rights(AccessRights::FileAllAccess), "F");
+/// assert_eq!(format_rights(AccessRights::from_bits_truncate(0x1201bf)), "RX,W");
+/// assert_eq!(format_rights(AccessRights::GenericRead | AccessRights::GenericExecute), "GR,GE");
+/// assert_eq!(format_rights(AccessRights::empty()), "N");
+/// ```
+pub fn format_rights(mask: AccessRights) -> String {
+    if mask.is_empty() {
+        return "N".to_string();
+    }
+
+    let mut names = vec![];
+    let mut covered = AccessRights::empty();
+
+    for (name, bits) in SIMPLE_RIGHTS {
+        let rights = AccessRights::from_bits_truncate(*bits);
+        if mask.contains(rights) && !covered.contains(rights) {
+            names.push(name.to_string());
+            covered |= rights;
+        }
+    }
+
+    let mut rest = mask - covered;
+    for (name, rights) in ADVANCED_RIGHTS {
+        if rest.contains(*rights) {
+            names.push(name.to_string());
+            rest.remove(*rights);
+        }
+    }
+
+    if !rest.is_empty() {
+        names.push(format!("{:#x}", rest.bits()));
+    }
+
+    names.join(",")
+}
+
+/// Parse an `icacls` rights list, without parentheses
+///
+/// Abbreviations are not case sensitive.
+///
+/// ```
+/// use windows_permissions::constants::AccessRights;
+/// use windows_permissions::icacls::parse_rights;
+///
+/// assert_eq!(parse_rights("rx,wdac").unwrap().bits(), 0x1200a9 | 0x40000);
+/// assert!(parse_rights("RWX").is_err());
+/// ```
+pub fn parse_rights(s: &str) -> io::Result<AccessRights> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid icacls rights: {:?}", s),
+        )
+    };
+
+    let mut mask = AccessRights::empty();
+
+    for name in s.split(',').map(str::trim) {
+        if name.eq_ignore_ascii_case("N") {
+            continue;
+        }
+
+        if let Some((_, bits)) = SIMPLE_RIGHTS
+            .iter()
+            .find(|(simple, _)| simple.eq_ignore_ascii_case(name))
+        {
+            mask |= AccessRights::from_bits_truncate(*bits);
+        } else if let Some((_, rights)) = ADVANCED_RIGHTS
+            .iter()
+            .find(|(advanced, _)| advanced.eq_ignore_ascii_case(name))
+        {
+            mask |= *rights;
+        } else if let Some(hex) = name.strip_prefix("0x").or_else(|| name.strip_prefix("0X")) {
+            let bits = u32::from_str_radix(hex, 16).map_err(|_| invalid())?;
+            mask |= AccessRights::from_bits_truncate(bits);
+        } else {
+            return Err(invalid());
+        }
+    }
+
+    Ok(mask)
+}
+
+impl Ace {
+    /// Write the ACE as an `icacls` entry, like `BUILTIN\Users:(OI)(CI)(RX)`
+    ///
+    /// Only plain allow and deny ACEs can be written; other ACE types are an
+    /// `InvalidData` error.
+    ///
+    /// ```
+    /// use windows_permissions::icacls::{RawSids, WellKnownNames};
+    /// use windows_permissions::{LocalBox, SecurityDescriptor};
+    ///
+    /// let sd: LocalBox<SecurityDescriptor> =
+    ///     "D:(D;;0x100116;;;WD)(A;OICI;FA;;;S-1-5-21-1-2-3-1001)".parse().unwrap();
+    /// let dacl = sd.dacl().unwrap();
+    ///
+    /// let deny = dacl.get_ace(0).unwrap();
+    /// assert_eq!(deny.to_icacls(&WellKnownNames).unwrap(), "Everyone:(DENY)(W)");
+    /// assert_eq!(deny.to_icacls(&RawSids).unwrap(), "S-1-1-0:(DENY)(W)");
+    ///
+    /// let allow = dacl.get_ace(1).unwrap();
+    /// assert_eq!(
+    ///     allow.to_icacls(&WellKnownNames).unwrap(),
+    ///     "S-1-5-21-1-2-3-1001:(OI)(CI)(F)"
+    /// );
+    /// ```
+    pub fn to_icacls(&self, resolver: &dyn SidResolver) -> io::Result<String> {
+        let deny = match self.ace_type() {
+            AceType::ACCESS_ALLOWED_ACE_TYPE => false,
+            AceType::ACCESS_DENIED_ACE_TYPE => true,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("ACE cannot be written for icacls: {:?}", self),
+                ))
+            }
+        };
+
+        let sid = self.raw_sid();
+        let mut entry = resolver.name(sid).unwrap_or_else(|| sid.to_string());
+        entry.push(':');
+
+        if deny {
+            entry.push_str("(DENY)");
+        }
+
+        for (name, flag) in FLAGS {
+            if self.flags().contains(*flag) {
+                entry.push_str(&format!("({})", name));
+            }
+        }
+
+        entry.push_str(&format!("({})", format_rights(self.mask())));
+        Ok(entry)
+    }
+
+    /// Parse an `icacls` entry, like `BUILTIN\Users:(OI)(CI)(RX)`
+    ///
+    /// The principal may be a SID string, optionally prefixed with `*` as on
+    /// the `icacls` command line, or a name known to `resolver`.
+    ///
+    /// ```
+    /// use windows_permissions::constants::{AccessRights, AceFlags, AceType};
+    /// use windows_permissions::icacls::WellKnownNames;
+    /// use windows_permissions::Ace;
+    ///
+    /// let ace = Ace::from_icacls("BUILTIN\\Users:(OI)(CI)(RX)", &WellKnownNames).unwrap();
+    ///
+    /// assert_eq!(ace.ace_type(), AceType::ACCESS_ALLOWED_ACE_TYPE);
+    /// assert_eq!(ace.flags(), AceFlags::ObjectInherit | AceFlags::ContainerInherit);
+    /// assert_eq!(ace.mask().bits(), 0x1200a9);
+    /// assert_eq!(ace.sid().unwrap().to_string(), "S-1-5-32-545");
+    /// ```
+    pub fn from_icacls(entry: &str, resolver: &dyn SidResolver) -> io::Result<LocalBox<Ace>> {
+        let bytes = parse_entry(entry, resolver)?;
+        unsafe { LocalBox::from_bytes(&bytes) }
+    }
+}
+
+impl Acl {
+    /// Write each ACE as an `icacls` entry, one per line
+    ///
+    /// See [`Ace::to_icacls`].
+    pub fn to_icacls(&self, resolver: &dyn SidResolver) -> io::Result<String> {
+        let entries = self
+            .aces()
+            .map(|ace| ace.to_icacls(resolver))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        Ok(entries.join("\n"))
+    }
+
+    /// Parse `icacls` entries, one per line, into an ACL
+    ///
+    /// Blank lines and surrounding whitespace are ignored. See
+    /// [`Ace::from_icacls`].
+    ///
+    /// ```
+    /// use windows_permissions::icacls::WellKnownNames;
+    /// use windows_permissions::Acl;
+    ///
+    /// let entries = "
+    ///     NT AUTHORITY\\SYSTEM:(OI)(CI)(F)
+    ///     Everyone:(DENY)(W)
+    /// ";
+    ///
+    /// let acl = Acl::from_icacls(entries, &WellKnownNames).unwrap();
+    /// assert_eq!(acl.len(), 2);
+    /// assert_eq!(
+    ///     acl.to_icacls(&WellKnownNames).unwrap(),
+    ///     "NT AUTHORITY\\SYSTEM:(OI)(CI)(F)\nEveryone:(DENY)(W)"
+    /// );
+    /// ```
+    pub fn from_icacls(entries: &str, resolver: &dyn SidResolver) -> io::Result<LocalBox<Acl>> {
+        let aces = entries
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| parse_entry(line, resolver))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        unsafe { LocalBox::from_bytes(&build::acl(&aces)) }
+    }
+}
+
+impl SecurityDescriptor {
+    /// Write the DACL the way `icacls` shows a file
+    ///
+    /// The first entry follows the path, and the others are lined up under
+    /// it. A security descriptor without a DACL is shown with no entries.
+    ///
+    /// ```
+    /// use windows_permissions::icacls::WellKnownNames;
+    /// use windows_permissions::{LocalBox, SecurityDescriptor};
+    ///
+    /// let sd: LocalBox<SecurityDescriptor> = "D:(A;OICI;FA;;;SY)(A;ID;0x1200a9;;;BU)".parse().unwrap();
+    ///
+    /// assert_eq!(
+    ///     sd.to_icacls("C:\\data", &WellKnownNames).unwrap(),
+    ///     "C:\\data NT AUTHORITY\\SYSTEM:(OI)(CI)(F)\n        BUILTIN\\Users:(I)(RX)"
+    /// );
+    /// ```
+    pub fn to_icacls(&self, path: &str, resolver: &dyn SidResolver) -> io::Result<String> {
+        let entries = match self.non_null_dacl() {
+            Some(dacl) => dacl
+                .aces()
+                .map(|ace| ace.to_icacls(resolver))
+                .collect::<io::Result<Vec<_>>>()?,
+            None => vec![],
+        };
+
+        let indent = " ".repeat(path.chars().count() + 1);
+        let mut output = path.to_string();
+
+        for (index, entry) in entries.iter().enumerate() {
+            if index == 0 {
+                output.push(' ');
+            } else {
+                output.push('\n');
+                output.push_str(&indent);
+            }
+            output.push_str(entry);
+        }
+
+        Ok(output)
+    }
+}
+
+/// Parse the output of `icacls <path>` into a security descriptor for each
+/// file
+///
+/// Each file starts on an unindented line, with its path followed by its
+/// first entry, and continues with indented entries. Since both paths and
+/// names may contain spaces, the path is taken to end at the first space
+/// after which a valid entry follows. Lines without an entry, like the
+/// closing summary, are skipped, so files with an empty DACL are left out.
+///
+/// The security descriptors have only a DACL.
+///
+/// ```
+/// use windows_permissions::icacls::{self, WellKnownNames};
+///
+/// let output = "\
+/// C:\\My Files BUILTIN\\Administrators:(I)(OI)(CI)(F)
+///             NT AUTHORITY\\Authenticated Users:(I)(M)
+///
+/// Successfully processed 1 files; Failed processing 0 files
+/// ";
+///
+/// let files = icacls::parse_output(output, &WellKnownNames).unwrap();
+/// assert_eq!(files.len(), 1);
+///
+/// let (path, sd) = &files[0];
+/// assert_eq!(path, "C:\\My Files");
+/// assert_eq!(
+///     sd.as_sddl().unwrap(),
+///     "D:(A;OICIID;FA;;;BA)(A;ID;0x1301bf;;;AU)"
+/// );
+/// ```
+pub fn parse_output(
+    output: &str,
+    resolver: &dyn SidResolver,
+) -> io::Result<Vec<(String, LocalBox<SecurityDescriptor>)>> {
+    let mut files: Vec<(String, Vec<Vec<u8>>)> = vec![];
+
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if line.starts_with(char::is_whitespace) {
+            let (_, aces) = files.last_mut().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Invalid icacls output (entry before any path): {:?}", line),
+                )
+            })?;
+            aces.push(parse_entry(line, resolver)?);
+        } else if line.contains(":(") {
+            let first = line
+                .match_indices(' ')
+                .find_map(|(index, _)| {
+                    parse_entry(&line[index + 1..], resolver)
+                        .ok()
+                        .map(|ace| (line[..index].to_string(), ace))
+                })
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("Invalid icacls output (no path and entry): {:?}", line),
+                    )
+                })?;
+
+            files.push((first.0, vec![first.1]));
+        }
+    }
+
+    files
+        .into_iter()
+        .map(|(path, aces)| {
+            let dacl = build::acl(&aces);
+            let sd = build::security_descriptor(&SecurityDescriptorParts {
+                control: SE_DACL_PRESENT,
+                dacl: Some(&dacl),
+                ..Default::default()
+            });
+
+            Ok((path, unsafe { LocalBox::from_bytes(&sd) }?))
+        })
+        .collect()
+}
+
+/// Parse an `icacls` entry into the bytes of an ACE
+fn parse_entry(entry: &str, resolver: &dyn SidResolver) -> io::Result<Vec<u8>> {
+    let invalid = |reason: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid icacls entry ({}): {:?}", reason, entry),
+        )
+    };
+
+    let trimmed = entry.trim();
+    let split = trimmed
+        .find(":(")
+        .ok_or_else(|| invalid("missing rights"))?;
+    let (name, mut groups) = (&trimmed[..split], &trimmed[split + 1..]);
+
+    let sid_string = name.strip_prefix('*').unwrap_or(name);
+    let sid = if sid_string.starts_with("S-") || sid_string.starts_with("s-") {
+        sid_string.parse::<LocalBox<Sid>>()?
+    } else {
+        resolver
+            .sid(name)
+            .ok_or_else(|| invalid("unknown principal"))?
+    };
+
+    let mut ace_type = ACCESS_ALLOWED_ACE_TYPE;
+    let mut flags = AceFlags::empty();
+    let mut mask = None;
+
+    while !groups.is_empty() {
+        let group = groups
+            .strip_prefix('(')
+            .ok_or_else(|| invalid("expected '('"))?;
+        let end = group.find(')').ok_or_else(|| invalid("missing ')'"))?;
+        groups = &group[end + 1..];
+        let group = &group[..end];
+
+        if group.eq_ignore_ascii_case("DENY") {
+            ace_type = ACCESS_DENIED_ACE_TYPE;
+        } else if let Some((_, flag)) = FLAGS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(group))
+        {
+            flags |= *flag;
+        } else if mask.is_none() {
+            mask = Some(parse_rights(group).map_err(|_| invalid("bad rights"))?);
+        } else {
+            return Err(invalid("more than one list of rights"));
+        }
+    }
+
+    let mask = mask.ok_or_else(|| invalid("missing rights"))?;
+
+    Ok(build::ace(
+        ace_type,
+        flags.bits(),
+        mask.bits(),
+        None,
+        None,
+        &sid.to_bytes(),
+        &[],
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rights() {
+        for (bits, rights) in &[
+            (0x1f01ff, "F"),
+            (0x1301bf, "M"),
+            (0x1200a9, "RX"),
+            (0x120089, "R"),
+            (0x100116, "W"),
+            (0x110000, "D"),
+            (0x1201bf, "RX,W"),
+            (0x1300a9, "RX,D"),
+            (0x1200a9 | 0x40000, "RX,WDAC"),
+            (0x100001, "S,RD"),
+            (0x10000, "DE"),
+            (0xa0000000, "GR,GE"),
+            (0x120089 | 0x8000, "R,0x8000"),
+            (0, "N"),
+        ] {
+            let mask = AccessRights::from_bits_truncate(*bits);
+            assert_eq!(format_rights(mask), *rights);
+            assert_eq!(parse_rights(rights).unwrap(), mask);
+        }
+
+        assert_eq!(parse_rights("r, W").unwrap().bits(), 0x120089 | 0x100116);
+        for rights in &["", "Q", "RX,", "0xg"] {
+            let err = parse_rights(rights).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+    }
+
+    #[test]
+    fn entries() {
+        for (sddl, entry) in &[
+            ("D:(A;;FA;;;SY)", "NT AUTHORITY\\SYSTEM:(F)"),
+            ("D:(A;OICIIO;GA;;;CO)", "CREATOR OWNER:(OI)(CI)(IO)(GA)"),
+            (
+                "D:(A;OICINP;0x1200a9;;;BU)",
+                "BUILTIN\\Users:(OI)(CI)(NP)(RX)",
+            ),
+            (
+                "D:(D;OICIID;0x100116;;;WD)",
+                "Everyone:(DENY)(I)(OI)(CI)(W)",
+            ),
+            (
+                "D:(A;;0x1301bf;;;S-1-5-21-1-2-3-1001)",
+                "S-1-5-21-1-2-3-1001:(M)",
+            ),
+        ] {
+            let sd: LocalBox<SecurityDescriptor> = sddl.parse().unwrap();
+            let ace = sd.dacl().unwrap().get_ace(0).unwrap();
+            assert_eq!(ace.to_icacls(&WellKnownNames).unwrap(), *entry);
+
+            let parsed = Ace::from_icacls(entry, &WellKnownNames).unwrap();
+            assert_eq!(parsed.as_bytes(), ace.as_bytes());
+        }
+
+        // Names without their domain, SIDs on the command line, and any order
+        let ace = Ace::from_icacls("*S-1-5-32-544:(ci)(deny)(oi)(rx,wdac)", &RawSids).unwrap();
+        assert_eq!(ace.ace_type(), AceType::ACCESS_DENIED_ACE_TYPE);
+        assert_eq!(
+            ace.flags(),
+            AceFlags::ObjectInherit | AceFlags::ContainerInherit
+        );
+        assert_eq!(ace.mask().bits(), 0x1200a9 | 0x40000);
+        let ace = Ace::from_icacls("administrators:(F)", &WellKnownNames).unwrap();
+        assert_eq!(ace.sid().unwrap().to_string(), "S-1-5-32-544");
+
+        for entry in &[
+            "Everyone",
+            "Everyone:F",
+            "Nobody:(F)",
+            "Administrators:(F)",
+            "Everyone:(F)(R)",
+            "Everyone:(OI)",
+            "Everyone:(F",
+            "Everyone:(F)x",
+        ] {
+            let resolver: &dyn SidResolver = if entry.starts_with("Administrators") {
+                &RawSids
+            } else {
+                &WellKnownNames
+            };
+            let err = Ace::from_icacls(entry, resolver).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+
+        let sd: LocalBox<SecurityDescriptor> = "S:(AU;SA;FA;;;WD)".parse().unwrap();
+        let err = sd
+            .sacl()
+            .unwrap()
+            .get_ace(0)
+            .unwrap()
+            .to_icacls(&RawSids)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn output() {
+        let sd: LocalBox<SecurityDescriptor> =
+            "D:(A;OICI;FA;;;SY)(D;;0x100116;;;WD)(A;;0x1200a9;;;S-1-5-21-1-2-3-1001)"
+                .parse()
+                .unwrap();
+        let rendered = sd.to_icacls("C:\\a b", &WellKnownNames).unwrap();
+        assert_eq!(
+            rendered,
+            "C:\\a b NT AUTHORITY\\SYSTEM:(OI)(CI)(F)\n       Everyone:(DENY)(W)\n       S-1-5-21-1-2-3-1001:(RX)"
+        );
+
+        let output = format!(
+            "{}\nD:\\x BUILTIN\\Users:(RX)\n\nSuccessfully processed 2 files; Failed processing 0 files\n",
+            rendered
+        );
+        let files = parse_output(&output, &WellKnownNames).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].0, "C:\\a b");
+        assert_eq!(
+            files[0].1.dacl().unwrap().as_bytes(),
+            sd.dacl().unwrap().as_bytes()
+        );
+        assert_eq!(files[1].0, "D:\\x");
+        assert_eq!(files[1].1.dacl().unwrap().len(), 1);
+
+        let empty: LocalBox<SecurityDescriptor> = "D:".parse().unwrap();
+        assert_eq!(empty.to_icacls("C:\\", &RawSids).unwrap(), "C:\\");
+
+        let err = parse_output("  Everyone:(F)", &WellKnownNames).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,225 @@
+//! Reading and writing the files of `icacls /save` and `icacls /restore`
+//!
+//! A save file is UTF-16LE text without a byte order mark. Each file or
+//! directory takes two lines, each ended by `\r\n`: its path, relative to
+//! the directory that was saved, then its security descriptor in SDDL.
+//!
+//! ```text
+//! report.docx
+//! D:AI(A;ID;FA;;;SY)(A;ID;FA;;;BA)(A;ID;0x1200a9;;;BU)
+//! ```
+
+use crate::constants::SecurityInformation;
+use crate::{wrappers, LocalBox, SecurityDescriptor};
+use std::borrow::Borrow;
+use std::io::{self, Read, Write};
+
+/// The parts of a security descriptor that `icacls /save` writes
+///
+/// The owner and group are left out.
+const SAVED_INFORMATION: SecurityInformation = SecurityInformation::from_bits_truncate(
+    SecurityInformation::Dacl.bits()
+        | SecurityInformation::Sacl.bits()
+        | SecurityInformation::Label.bits(),
+);
+
+fn invalid(reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid icacls save file ({})", reason),
+    )
+}
+
+/// Read the records of an `icacls /save` file
+///
+/// Each record is a path, relative to the directory that was saved, and its
+/// security descriptor. A leading byte order mark and lines ended by `\n`
+/// alone are accepted, as some editors write them.
+///
+/// ```
+/// use windows_permissions::icacls::read_save_file;
+///
+/// let text = "notes.txt\r\nD:AI(A;ID;FA;;;SY)(A;ID;0x1200a9;;;BU)\r\n";
+/// let bytes: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+///
+/// let records = read_save_file(&bytes[..]).unwrap();
+/// assert_eq!(records.len(), 1);
+///
+/// let (path, sd) = &records[0];
+/// assert_eq!(path, "notes.txt");
+/// assert_eq!(sd.dacl().unwrap().len(), 2);
+/// ```
+pub fn read_save_file<R: Read>(
+    mut reader: R,
+) -> io::Result<Vec<(String, LocalBox<SecurityDescriptor>)>> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+
+    if !bytes.len().is_multiple_of(2) {
+        return Err(invalid("odd number of bytes"));
+    }
+
+    let units: Vec<u16> = bytes
+        .chunks(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .collect();
+    let text = String::from_utf16(&units).map_err(|_| invalid("not UTF-16"))?;
+    let text = text.strip_prefix('\u{feff}').unwrap_or(&text);
+
+    let mut lines: Vec<&str> = text
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    if !lines.len().is_multiple_of(2) {
+        return Err(invalid("path without a security descriptor"));
+    }
+
+    lines
+        .chunks(2)
+        .map(|record| {
+            let sd = record[1].parse().map_err(|err: io::Error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "Invalid icacls save file (bad SDDL for {:?}): {}",
+                        record[0], err
+                    ),
+                )
+            })?;
+
+            Ok((record[0].to_string(), sd))
+        })
+        .collect()
+}
+
+/// Write records in the format of an `icacls /save` file, so that
+/// `icacls /restore` can apply them
+///
+/// Each path should be relative to the directory the file will be restored
+/// into. As `icacls` does, only the DACL, SACL and mandatory label are
+/// written.
+///
+/// ```
+/// use windows_permissions::icacls::{read_save_file, write_save_file};
+/// use windows_permissions::{LocalBox, SecurityDescriptor};
+///
+/// let sd: LocalBox<SecurityDescriptor> = "O:BAD:PAI(A;OICI;FA;;;SY)".parse().unwrap();
+///
+/// let mut bytes = vec![];
+/// write_save_file(&mut bytes, &[("data\\logs", sd)]).unwrap();
+///
+/// let text = "data\\logs\r\nD:PAI(A;OICI;FA;;;SY)\r\n";
+/// let expected: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+/// assert_eq!(bytes, expected);
+///
+/// let records = read_save_file(&bytes[..]).unwrap();
+/// assert_eq!(records[0].0, "data\\logs");
+/// ```
+pub fn write_save_file<W, P, S>(mut writer: W, records: &[(P, S)]) -> io::Result<()>
+where
+    W: Write,
+    P: AsRef<str>,
+    S: Borrow<SecurityDescriptor>,
+{
+    let mut text = String::new();
+
+    for (path, sd) in records {
+        let path = path.as_ref();
+        if path.contains(['\r', '\n']) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid path for icacls save file: {:?}", path),
+            ));
+        }
+
+        let sddl = wrappers::ConvertSecurityDescriptorToStringSecurityDescriptor(
+            sd.borrow(),
+            SAVED_INFORMATION,
+        )?;
+
+        text.push_str(path);
+        text.push_str("\r\n");
+        text.push_str(&sddl.to_string_lossy());
+        text.push_str("\r\n");
+    }
+
+    let bytes: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    writer.write_all(&bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn utf16(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let records: Vec<(String, LocalBox<SecurityDescriptor>)> = vec![
+            (
+                "a.txt".to_string(),
+                "D:AI(A;ID;FA;;;SY)(A;ID;0x1200a9;;;BU)".parse().unwrap(),
+            ),
+            (
+                "sub\\ünïcödé".to_string(),
+                "D:PAI(D;;0x100116;;;WD)(A;OICI;FA;;;BA)S:(ML;;NW;;;HI)"
+                    .parse()
+                    .unwrap(),
+            ),
+        ];
+
+        let mut bytes = vec![];
+        write_save_file(&mut bytes, &records).unwrap();
+        assert_eq!(
+            bytes,
+            utf16(
+                "a.txt\r\nD:AI(A;ID;FA;;;SY)(A;ID;0x1200a9;;;BU)\r\n\
+                 sub\\ünïcödé\r\nD:PAI(D;;0x100116;;;WD)(A;OICI;FA;;;BA)S:(ML;;NW;;;HI)\r\n"
+            )
+        );
+
+        let read = read_save_file(&bytes[..]).unwrap();
+        assert_eq!(read.len(), 2);
+        for ((path, sd), (read_path, read_sd)) in records.iter().zip(&read) {
+            assert_eq!(path, read_path);
+            assert_eq!(sd.as_sddl().unwrap(), read_sd.as_sddl().unwrap());
+        }
+
+        let mut rewritten = vec![];
+        write_save_file(&mut rewritten, &read).unwrap();
+        assert_eq!(rewritten, bytes);
+
+        assert!(read_save_file(&[][..]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn lenient_reading() {
+        let mut bytes = utf16("\u{feff}a\nD:(A;;FA;;;SY)\n\r\n");
+        assert_eq!(read_save_file(&bytes[..]).unwrap().len(), 1);
+
+        bytes.push(0);
+        let err = read_save_file(&bytes[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn errors() {
+        for text in &["a\r\n", "a\r\nD:(X;;FA;;;SY)\r\n", "a\r\nnot sddl\r\n"] {
+            let err = read_save_file(&utf16(text)[..]).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+
+        let err = read_save_file(&[0x00, 0xd8][..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let sd: LocalBox<SecurityDescriptor> = "D:".parse().unwrap();
+        let err = write_save_file(vec![], &[("a\r\nb", sd)]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -11,6 +11,22 @@
 //! - ACL (Access Control List)
 //! - ACE (Access Control Entry)
 //! - SD (Security Descriptor)
+//! - Constants for well-known SIDs (see [`structures::well_known`])
+//! - Validated, zero-copy views over untrusted bytes (see [`SecurityDescriptorRef`])
+//! - Access tokens (see [`token`])
+//! - Access checks, evaluated in pure Rust (see [`access`])
+//! - The permission names shown by the Windows Security dialog (see [`explorer`])
+//! - The ACL syntax of `icacls` (see [`icacls`])
+//!
+//! ## Features
+//!
+//! - `serde`: Serialize and deserialize SIDs, ACLs, ACEs, security
+//!   descriptors and the types in [`constants`] (see `serde`)
+//! - `schemars`: JSON Schemas for the serialized forms (see `serde::schema`)
+//!
+//! SIDs and security descriptors can be checked and built at compile time
+//! with the `sid!` and `sddl!` macros of the `windows-permissions-macros`
+//! crate.
 //!
 //! There are two kinds of abstractions:
 //!
@@ -35,23 +51,36 @@
 extern crate bitflags;
 extern crate winapi;
 
-#[cfg(target_os = "windows")]
+pub mod access;
 pub mod constants;
-#[cfg(target_os = "windows")]
+pub mod explorer;
+pub mod icacls;
 pub mod localheap;
-#[cfg(target_os = "windows")]
+pub mod privilege;
+#[cfg(feature = "serde")]
+pub mod serde;
 pub mod structures;
+pub mod token;
 #[cfg(target_os = "windows")]
 pub mod utilities;
-#[cfg(target_os = "windows")]
 pub mod wrappers;
 
+#[doc(hidden)]
+pub mod __private;
+mod build;
+mod sddl;
 #[cfg(target_os = "windows")]
 mod windows_secure;
+mod winnt;
 
-#[cfg(target_os = "windows")]
 pub use localheap::LocalBox;
+pub use privilege::Privilege;
 #[cfg(target_os = "windows")]
-pub use structures::{Ace, Acl, SecurityDescriptor, Sid, Trustee};
+pub use structures::Trustee;
+pub use structures::{
+    Ace, AceRef, Acl, AclRef, Condition, Guid, SecurityDescriptor, SecurityDescriptorRef, Sid,
+    SidRef,
+};
+pub use token::Token;
 #[cfg(target_os = "windows")]
 pub use windows_secure::WindowsSecure;
@@ -1,5 +1,6 @@
 //! A specialized [`Box`] variation for items stored on the local heap.
 
+#[cfg(target_os = "windows")]
 use crate::constants::LocalAllocFlags;
 use std::borrow::{Borrow, BorrowMut};
 use std::cmp::PartialEq;
@@ -7,7 +8,7 @@ use std::fmt;
 use std::hash::Hash;
 use std::io;
 use std::ops::{Deref, DerefMut};
-use std::ptr::{null_mut, NonNull};
+use std::ptr::NonNull;
 
 /// A smart pointer to an object on the local heap.
 ///
@@ -40,6 +41,9 @@ use std::ptr::{null_mut, NonNull};
 ///
 /// For details, see [MSDN](https://docs.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-localalloc#parameters).
 ///
+/// On platforms other than Windows, there is no local heap. The C heap
+/// (`malloc`, `calloc`, and `free`) is used in its place.
+///
 /// # Exotically-sized types
 ///
 /// This struct has not been tested with exotically-sized types. Use with
@@ -54,8 +58,9 @@ impl<T> LocalBox<T> {
     /// # Safety
     ///
     /// - The `NonNull` pointer *must* have been allocated with
-    /// a Windows API call. When the resulting `NonNull<T>` is dropped, it
-    /// will be dropped with `LocalFree`
+    ///   a Windows API call (or, on other platforms, with `malloc`). When
+    ///   the resulting `NonNull<T>` is dropped, it will be dropped with
+    ///   `LocalFree` (or `free`)
     /// - The buffer pointed to by the pointer must be a valid `T`
     pub unsafe fn from_raw(ptr: NonNull<T>) -> Self {
         // Future maintainers:
@@ -96,18 +101,38 @@ impl<T> LocalBox<T> {
     ///
     /// Additionally, `size` should be large enough to contain a `T`.
     pub unsafe fn try_allocate(zeroed: bool, size: usize) -> io::Result<Self> {
-        let flags = match zeroed {
-            true => LocalAllocFlags::Fixed | LocalAllocFlags::ZeroInit,
-            false => LocalAllocFlags::Fixed,
+        #[cfg(target_os = "windows")]
+        let ptr = {
+            let flags = match zeroed {
+                true => LocalAllocFlags::Fixed | LocalAllocFlags::ZeroInit,
+                false => LocalAllocFlags::Fixed,
+            };
+
+            winapi::um::winbase::LocalAlloc(flags.bits(), size)
         };
 
-        let ptr = winapi::um::winbase::LocalAlloc(flags.bits(), size);
+        #[cfg(not(target_os = "windows"))]
+        let ptr = match zeroed {
+            true => libc::calloc(1, size),
+            false => libc::malloc(size),
+        };
 
         Ok(Self {
             ptr: NonNull::new(ptr as *mut _).ok_or_else(io::Error::last_os_error)?,
         })
     }
 
+    /// Allocate a copy of a buffer
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must be a valid representation of a `T`.
+    pub(crate) unsafe fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let local_box = Self::try_allocate(false, bytes.len())?;
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), local_box.as_ptr() as *mut u8, bytes.len());
+        Ok(local_box)
+    }
+
     /// Get a pointer to the underlying data structure
     ///
     /// Use this when interacting with FFI libraries that want pointers.
@@ -118,14 +143,22 @@ impl<T> LocalBox<T> {
 
 impl<T> Drop for LocalBox<T> {
     fn drop(&mut self) {
-        let result = unsafe { winapi::um::winbase::LocalFree(self.as_ptr() as *mut _) };
-        debug_assert_eq!(result, null_mut());
+        #[cfg(target_os = "windows")]
+        {
+            let result = unsafe { winapi::um::winbase::LocalFree(self.as_ptr() as *mut _) };
+            debug_assert_eq!(result, std::ptr::null_mut());
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        unsafe {
+            libc::free(self.as_ptr() as *mut _)
+        };
     }
 }
 
 impl<T> AsRef<T> for LocalBox<T> {
     fn as_ref(&self) -> &T {
-        &*self
+        self
     }
 }
 
@@ -196,5 +229,7 @@ where
 // the system may arbitrarily create threads that accesses that heap. Hence, it
 // is safe to assume that LocalAlloc/LocalFree are serialized, and so LocalBox
 // are safe to share across threads.
+//
+// On other platforms, malloc/free are required to be thread-safe.
 unsafe impl<U: Send> Send for LocalBox<U> {}
 unsafe impl<U: Sync> Sync for LocalBox<U> {}
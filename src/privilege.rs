@@ -0,0 +1,355 @@
+//! The privileges an access token can hold.
+//!
+//! [Privileges](https://docs.microsoft.com/en-us/windows/win32/secauthz/privilege-constants)
+//! let an account perform system-wide operations. A few of them also bypass
+//! or supplement the access checks against a security descriptor, such as
+//! `SeBackupPrivilege` and `SeTakeOwnershipPrivilege`.
+//!
+//! ```
+//! use windows_permissions::Privilege;
+//! use windows_permissions::constants::SecurityInformation;
+//!
+//! let privilege: Privilege = "SeSecurityPrivilege".parse().unwrap();
+//! assert_eq!(privilege, Privilege::Security);
+//! assert_eq!(privilege.to_string(), "SeSecurityPrivilege");
+//! assert_eq!(privilege.description(), "Manage auditing and security log");
+//!
+//! // Reading a SACL takes SeSecurityPrivilege (or SeBackupPrivilege with
+//! // backup intent)
+//! assert_eq!(
+//!     Privilege::that_can_read(SecurityInformation::Sacl),
+//!     vec![Privilege::Security, Privilege::Backup],
+//! );
+//! ```
+
+use crate::constants::{AccessRights, SecurityInformation};
+use crate::token::Luid;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+macro_rules! privileges {
+    ( $( $(#[$meta:meta])* $variant:ident = $luid:expr, $name:expr, $constant:expr, $description:expr; )* ) => {
+        /// A privilege, as named by one of the `SE_*_NAME` constants.
+        ///
+        /// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/secauthz/privilege-constants)
+        /// for details on each privilege.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum Privilege {
+            $(
+                $(#[$meta])*
+                #[doc = ""]
+                #[doc = concat!("`", $name, "` (`", $constant, "`): ", $description)]
+                $variant,
+            )*
+        }
+
+        impl Privilege {
+            /// Every privilege, in order of their standard LUIDs
+            pub const ALL: &'static [Privilege] = &[ $( Privilege::$variant ),* ];
+
+            /// Get the name of the privilege, such as `SeBackupPrivilege`
+            pub fn name(self) -> &'static str {
+                match self {
+                    $( Privilege::$variant => $name, )*
+                }
+            }
+
+            /// Get the name of the `winnt.h` constant for the privilege, such
+            /// as `SE_BACKUP_NAME`
+            pub fn constant_name(self) -> &'static str {
+                match self {
+                    $( Privilege::$variant => $constant, )*
+                }
+            }
+
+            /// Get the description of the privilege, as shown by the Local
+            /// Security Policy editor and `whoami /priv`
+            pub fn description(self) -> &'static str {
+                match self {
+                    $( Privilege::$variant => $description, )*
+                }
+            }
+
+            /// Get the standard LUID of the privilege
+            ///
+            /// These are the `SE_*_PRIVILEGE` values from `ntseapi.h`, which
+            /// are the same on every Windows machine.
+            pub fn luid(self) -> Luid {
+                match self {
+                    $( Privilege::$variant => Luid::from($luid as u64), )*
+                }
+            }
+        }
+    };
+}
+
+privileges! {
+    CreateToken = 2, "SeCreateTokenPrivilege", "SE_CREATE_TOKEN_NAME",
+        "Create a token object";
+    AssignPrimaryToken = 3, "SeAssignPrimaryTokenPrivilege", "SE_ASSIGNPRIMARYTOKEN_NAME",
+        "Replace a process level token";
+    LockMemory = 4, "SeLockMemoryPrivilege", "SE_LOCK_MEMORY_NAME",
+        "Lock pages in memory";
+    IncreaseQuota = 5, "SeIncreaseQuotaPrivilege", "SE_INCREASE_QUOTA_NAME",
+        "Adjust memory quotas for a process";
+    /// This privilege is obsolete and shares its LUID with `MachineAccount`.
+    UnsolicitedInput = 6, "SeUnsolicitedInputPrivilege", "SE_UNSOLICITED_INPUT_NAME",
+        "Read unsolicited input from a terminal device";
+    MachineAccount = 6, "SeMachineAccountPrivilege", "SE_MACHINE_ACCOUNT_NAME",
+        "Add workstations to domain";
+    Tcb = 7, "SeTcbPrivilege", "SE_TCB_NAME",
+        "Act as part of the operating system";
+    Security = 8, "SeSecurityPrivilege", "SE_SECURITY_NAME",
+        "Manage auditing and security log";
+    TakeOwnership = 9, "SeTakeOwnershipPrivilege", "SE_TAKE_OWNERSHIP_NAME",
+        "Take ownership of files or other objects";
+    LoadDriver = 10, "SeLoadDriverPrivilege", "SE_LOAD_DRIVER_NAME",
+        "Load and unload device drivers";
+    SystemProfile = 11, "SeSystemProfilePrivilege", "SE_SYSTEM_PROFILE_NAME",
+        "Profile system performance";
+    Systemtime = 12, "SeSystemtimePrivilege", "SE_SYSTEMTIME_NAME",
+        "Change the system time";
+    ProfileSingleProcess = 13, "SeProfileSingleProcessPrivilege", "SE_PROF_SINGLE_PROCESS_NAME",
+        "Profile single process";
+    IncreaseBasePriority = 14, "SeIncreaseBasePriorityPrivilege", "SE_INC_BASE_PRIORITY_NAME",
+        "Increase scheduling priority";
+    CreatePagefile = 15, "SeCreatePagefilePrivilege", "SE_CREATE_PAGEFILE_NAME",
+        "Create a pagefile";
+    CreatePermanent = 16, "SeCreatePermanentPrivilege", "SE_CREATE_PERMANENT_NAME",
+        "Create permanent shared objects";
+    Backup = 17, "SeBackupPrivilege", "SE_BACKUP_NAME",
+        "Back up files and directories";
+    Restore = 18, "SeRestorePrivilege", "SE_RESTORE_NAME",
+        "Restore files and directories";
+    Shutdown = 19, "SeShutdownPrivilege", "SE_SHUTDOWN_NAME",
+        "Shut down the system";
+    Debug = 20, "SeDebugPrivilege", "SE_DEBUG_NAME",
+        "Debug programs";
+    Audit = 21, "SeAuditPrivilege", "SE_AUDIT_NAME",
+        "Generate security audits";
+    SystemEnvironment = 22, "SeSystemEnvironmentPrivilege", "SE_SYSTEM_ENVIRONMENT_NAME",
+        "Modify firmware environment values";
+    ChangeNotify = 23, "SeChangeNotifyPrivilege", "SE_CHANGE_NOTIFY_NAME",
+        "Bypass traverse checking";
+    RemoteShutdown = 24, "SeRemoteShutdownPrivilege", "SE_REMOTE_SHUTDOWN_NAME",
+        "Force shutdown from a remote system";
+    Undock = 25, "SeUndockPrivilege", "SE_UNDOCK_NAME",
+        "Remove computer from docking station";
+    SyncAgent = 26, "SeSyncAgentPrivilege", "SE_SYNC_AGENT_NAME",
+        "Synchronize directory service data";
+    EnableDelegation = 27, "SeEnableDelegationPrivilege", "SE_ENABLE_DELEGATION_NAME",
+        "Enable computer and user accounts to be trusted for delegation";
+    ManageVolume = 28, "SeManageVolumePrivilege", "SE_MANAGE_VOLUME_NAME",
+        "Perform volume maintenance tasks";
+    Impersonate = 29, "SeImpersonatePrivilege", "SE_IMPERSONATE_NAME",
+        "Impersonate a client after authentication";
+    CreateGlobal = 30, "SeCreateGlobalPrivilege", "SE_CREATE_GLOBAL_NAME",
+        "Create global objects";
+    TrustedCredManAccess = 31, "SeTrustedCredManAccessPrivilege", "SE_TRUSTED_CREDMAN_ACCESS_NAME",
+        "Access Credential Manager as a trusted caller";
+    Relabel = 32, "SeRelabelPrivilege", "SE_RELABEL_NAME",
+        "Modify an object label";
+    IncreaseWorkingSet = 33, "SeIncreaseWorkingSetPrivilege", "SE_INC_WORKING_SET_NAME",
+        "Increase a process working set";
+    TimeZone = 34, "SeTimeZonePrivilege", "SE_TIME_ZONE_NAME",
+        "Change the time zone";
+    CreateSymbolicLink = 35, "SeCreateSymbolicLinkPrivilege", "SE_CREATE_SYMBOLIC_LINK_NAME",
+        "Create symbolic links";
+    DelegateSessionUserImpersonate = 36, "SeDelegateSessionUserImpersonatePrivilege",
+        "SE_DELEGATE_SESSION_USER_IMPERSONATE_NAME",
+        "Obtain an impersonation token for another user in the same session";
+}
+
+impl Privilege {
+    /// Look up a privilege by its standard LUID
+    ///
+    /// `SeUnsolicitedInputPrivilege` shares a LUID with
+    /// `SeMachineAccountPrivilege`, so it is never returned.
+    pub fn from_luid(luid: Luid) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .filter(|&p| p != Privilege::UnsolicitedInput)
+            .find(|p| p.luid() == luid)
+    }
+
+    /// Get the parts of a security descriptor this privilege allows reading,
+    /// regardless of the DACL
+    ///
+    /// `SeBackupPrivilege` only applies when the object is opened with
+    /// backup intent (such as `FILE_FLAG_BACKUP_SEMANTICS`).
+    pub fn can_read(self) -> SecurityInformation {
+        match self {
+            Privilege::Security => SecurityInformation::Sacl,
+            Privilege::Backup => all_parts(),
+            _ => SecurityInformation::empty(),
+        }
+    }
+
+    /// Get the parts of a security descriptor this privilege allows writing,
+    /// regardless of the DACL
+    ///
+    /// `SeTakeOwnershipPrivilege` only allows setting the owner to the
+    /// caller's own user or an owner group. `SeRestorePrivilege` allows
+    /// any owner, but only applies when the object is opened with backup
+    /// intent. `SeRelabelPrivilege` allows raising a label above the
+    /// caller's own integrity level.
+    pub fn can_write(self) -> SecurityInformation {
+        match self {
+            Privilege::Security => SecurityInformation::Sacl,
+            Privilege::TakeOwnership => SecurityInformation::Owner,
+            Privilege::Restore => all_parts(),
+            Privilege::Relabel => SecurityInformation::Label,
+            _ => SecurityInformation::empty(),
+        }
+    }
+
+    /// Get the access rights this privilege grants on any securable object,
+    /// regardless of the DACL
+    ///
+    /// `ACCESS_SYSTEM_SECURITY` can never be granted by a DACL, so it
+    /// always requires one of these privileges.
+    pub fn security_rights(self) -> AccessRights {
+        match self {
+            Privilege::Security => AccessRights::AccessSystemSecurity,
+            Privilege::TakeOwnership => AccessRights::WriteOwner,
+            Privilege::Backup => AccessRights::ReadControl | AccessRights::AccessSystemSecurity,
+            Privilege::Restore => {
+                AccessRights::WriteDac
+                    | AccessRights::WriteOwner
+                    | AccessRights::AccessSystemSecurity
+            }
+            _ => AccessRights::empty(),
+        }
+    }
+
+    /// Get the privileges that allow reading any of `info`, regardless of the
+    /// DACL
+    pub fn that_can_read(info: SecurityInformation) -> Vec<Privilege> {
+        Self::ALL
+            .iter()
+            .copied()
+            .filter(|p| p.can_read().intersects(info))
+            .collect()
+    }
+
+    /// Get the privileges that allow writing any of `info`, regardless of the
+    /// DACL
+    pub fn that_can_write(info: SecurityInformation) -> Vec<Privilege> {
+        Self::ALL
+            .iter()
+            .copied()
+            .filter(|p| p.can_write().intersects(info))
+            .collect()
+    }
+}
+
+fn all_parts() -> SecurityInformation {
+    SecurityInformation::Owner
+        | SecurityInformation::Group
+        | SecurityInformation::Dacl
+        | SecurityInformation::Sacl
+        | SecurityInformation::Label
+        | SecurityInformation::Attribute
+        | SecurityInformation::Scope
+        | SecurityInformation::Backup
+}
+
+impl From<Privilege> for Luid {
+    fn from(privilege: Privilege) -> Self {
+        privilege.luid()
+    }
+}
+
+impl fmt::Display for Privilege {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.name())
+    }
+}
+
+impl FromStr for Privilege {
+    type Err = io::Error;
+
+    /// Parse a privilege from its name (`SeBackupPrivilege`) or its
+    /// constant name (`SE_BACKUP_NAME`), ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|p| p.name().eq_ignore_ascii_case(s) || p.constant_name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Unknown privilege: {:?}", s),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for &privilege in Privilege::ALL {
+            assert_eq!(privilege.name().parse::<Privilege>().unwrap(), privilege);
+            assert_eq!(
+                privilege.constant_name().parse::<Privilege>().unwrap(),
+                privilege
+            );
+            assert_eq!(
+                privilege
+                    .to_string()
+                    .to_lowercase()
+                    .parse::<Privilege>()
+                    .unwrap(),
+                privilege
+            );
+        }
+
+        assert!("SeMadeUpPrivilege".parse::<Privilege>().is_err());
+        assert!("".parse::<Privilege>().is_err());
+    }
+
+    #[test]
+    fn luids_are_standard() {
+        assert_eq!(Privilege::CreateToken.luid(), Luid::from(2));
+        assert_eq!(Privilege::Security.luid(), Luid::from(8));
+        assert_eq!(Privilege::Backup.luid(), Luid::from(17));
+        assert_eq!(
+            Privilege::DelegateSessionUserImpersonate.luid(),
+            Luid::from(36)
+        );
+
+        for &privilege in Privilege::ALL {
+            let expected = match privilege {
+                Privilege::UnsolicitedInput => Privilege::MachineAccount,
+                other => other,
+            };
+            assert_eq!(Privilege::from_luid(privilege.luid()), Some(expected));
+        }
+
+        assert_eq!(Privilege::from_luid(Luid::from(0)), None);
+        assert_eq!(Privilege::from_luid(Luid::from(37)), None);
+    }
+
+    #[test]
+    fn security_descriptor_operations() {
+        assert_eq!(
+            Privilege::that_can_read(SecurityInformation::Sacl),
+            vec![Privilege::Security, Privilege::Backup]
+        );
+        assert_eq!(
+            Privilege::that_can_write(SecurityInformation::Owner),
+            vec![Privilege::TakeOwnership, Privilege::Restore]
+        );
+        assert_eq!(
+            Privilege::that_can_write(SecurityInformation::Label),
+            vec![Privilege::Restore, Privilege::Relabel]
+        );
+        assert!(Privilege::that_can_read(SecurityInformation::empty()).is_empty());
+        assert!(Privilege::Shutdown.security_rights().is_empty());
+    }
+}
@@ -0,0 +1,1112 @@
+//! Pure-Rust support for the Security Descriptor Definition Language (SDDL).
+//!
+//! On Windows, SDDL is handled by the WinAPI. This module implements the
+//! same formats so that the data structures in this crate can be parsed and
+//! printed on any platform.
+//!
+//! See [MSDN](https://docs.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-string-format)
+//! for the format.
+
+use crate::build::{self, SecurityDescriptorParts};
+use crate::constants::ClaimFlags;
+#[cfg(not(target_os = "windows"))]
+use crate::constants::SecurityInformation;
+use crate::token::{ClaimAttribute, ClaimValues};
+use crate::winnt::*;
+#[cfg(not(target_os = "windows"))]
+use crate::{Ace, Acl, Guid, SecurityDescriptor};
+use crate::{Condition, Sid};
+use std::io;
+
+/// SID aliases that can be used in place of a full SID string
+///
+/// These are the aliases from `sddl.h` that do not depend on the domain or
+/// machine that the SDDL is evaluated on.
+pub(crate) const SID_ALIASES: &[(&str, &str)] = &[
+    ("AA", "S-1-5-32-579"),
+    ("AC", "S-1-15-2-1"),
+    ("AN", "S-1-5-7"),
+    ("AO", "S-1-5-32-548"),
+    ("AS", "S-1-18-1"),
+    ("AU", "S-1-5-11"),
+    ("BA", "S-1-5-32-544"),
+    ("BG", "S-1-5-32-546"),
+    ("BO", "S-1-5-32-551"),
+    ("BU", "S-1-5-32-545"),
+    ("CD", "S-1-5-32-574"),
+    ("CG", "S-1-3-1"),
+    ("CO", "S-1-3-0"),
+    ("CY", "S-1-5-32-569"),
+    ("ED", "S-1-5-9"),
+    ("ER", "S-1-5-32-573"),
+    ("ES", "S-1-5-32-576"),
+    ("HA", "S-1-5-32-578"),
+    ("HI", "S-1-16-12288"),
+    ("IS", "S-1-5-32-568"),
+    ("IU", "S-1-5-4"),
+    ("LS", "S-1-5-19"),
+    ("LU", "S-1-5-32-559"),
+    ("LW", "S-1-16-4096"),
+    ("ME", "S-1-16-8192"),
+    ("MP", "S-1-16-8448"),
+    ("MS", "S-1-5-32-577"),
+    ("MU", "S-1-5-32-558"),
+    ("NO", "S-1-5-32-556"),
+    ("NS", "S-1-5-20"),
+    ("NU", "S-1-5-2"),
+    ("OW", "S-1-3-4"),
+    ("PO", "S-1-5-32-550"),
+    ("PS", "S-1-5-10"),
+    ("PU", "S-1-5-32-547"),
+    ("RA", "S-1-5-32-575"),
+    ("RC", "S-1-5-12"),
+    ("RD", "S-1-5-32-555"),
+    ("RE", "S-1-5-32-552"),
+    ("RM", "S-1-5-32-580"),
+    ("RU", "S-1-5-32-554"),
+    ("SI", "S-1-16-16384"),
+    ("SO", "S-1-5-32-549"),
+    ("SS", "S-1-18-2"),
+    ("SU", "S-1-5-6"),
+    ("SY", "S-1-5-18"),
+    ("UD", "S-1-5-84-0-0-0-0-0"),
+    ("WD", "S-1-1-0"),
+    ("WR", "S-1-5-33"),
+];
+
+/// SID aliases that are relative to the current domain or machine
+///
+/// These cannot be resolved without asking Windows.
+pub(crate) const DOMAIN_SID_ALIASES: &[&str] = &[
+    "AP", "CA", "CN", "DA", "DC", "DD", "DG", "DU", "EA", "EK", "KA", "LA", "LG", "PA", "RO", "RS",
+    "SA",
+];
+
+/// ACE types, as used in ACE strings
+const ACE_TYPES: &[(&str, u8)] = &[
+    ("A", ACCESS_ALLOWED_ACE_TYPE),
+    ("D", ACCESS_DENIED_ACE_TYPE),
+    ("OA", ACCESS_ALLOWED_OBJECT_ACE_TYPE),
+    ("OD", ACCESS_DENIED_OBJECT_ACE_TYPE),
+    ("AU", SYSTEM_AUDIT_ACE_TYPE),
+    ("OU", SYSTEM_AUDIT_OBJECT_ACE_TYPE),
+    ("ML", SYSTEM_MANDATORY_LABEL_ACE_TYPE),
+    ("XA", ACCESS_ALLOWED_CALLBACK_ACE_TYPE),
+    ("XD", ACCESS_DENIED_CALLBACK_ACE_TYPE),
+    ("ZA", ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE),
+    ("XU", SYSTEM_AUDIT_CALLBACK_ACE_TYPE),
+    ("RA", SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE),
+    ("SP", SYSTEM_SCOPED_POLICY_ID_ACE_TYPE),
+];
+
+/// ACE flags, in the order Windows prints them
+const ACE_FLAGS: &[(&str, u8)] = &[
+    ("OI", OBJECT_INHERIT_ACE),
+    ("CI", CONTAINER_INHERIT_ACE),
+    ("NP", NO_PROPAGATE_INHERIT_ACE),
+    ("IO", INHERIT_ONLY_ACE),
+    ("ID", INHERITED_ACE),
+    ("SA", SUCCESSFUL_ACCESS_ACE_FLAG),
+    ("FA", FAILED_ACCESS_ACE_FLAG),
+];
+
+/// Access rights that stand for a single bit, in the order Windows prints
+/// them
+const SINGLE_RIGHTS: &[(&str, u32)] = &[
+    ("CC", 0x1),
+    ("DC", 0x2),
+    ("LC", 0x4),
+    ("SW", 0x8),
+    ("RP", 0x10),
+    ("WP", 0x20),
+    ("DT", 0x40),
+    ("LO", 0x80),
+    ("CR", 0x100),
+    ("SD", DELETE),
+    ("RC", READ_CONTROL),
+    ("WD", WRITE_DAC),
+    ("WO", WRITE_OWNER),
+    ("GA", GENERIC_ALL),
+    ("GX", GENERIC_EXECUTE),
+    ("GW", GENERIC_WRITE),
+    ("GR", GENERIC_READ),
+];
+
+/// Access rights that stand for several bits
+///
+/// Windows only prints these when they match the entire mask.
+const AGGREGATE_RIGHTS: &[(&str, u32)] = &[
+    ("FA", FILE_ALL_ACCESS),
+    ("FR", FILE_GENERIC_READ),
+    ("FW", FILE_GENERIC_WRITE),
+    ("FX", FILE_GENERIC_EXECUTE),
+    ("KA", KEY_ALL_ACCESS),
+    ("KR", KEY_READ),
+    ("KW", KEY_WRITE),
+    ("KX", KEY_EXECUTE),
+];
+
+/// Mandatory label policy rights, used in place of the others in `ML` ACEs
+const LABEL_RIGHTS: &[(&str, u32)] = &[
+    ("NW", SYSTEM_MANDATORY_LABEL_NO_WRITE_UP),
+    ("NR", SYSTEM_MANDATORY_LABEL_NO_READ_UP),
+    ("NX", SYSTEM_MANDATORY_LABEL_NO_EXECUTE_UP),
+];
+
+fn invalid_sid(s: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("Invalid SID string: {:?}", s),
+    )
+}
+
+fn parse_number(s: &str) -> Option<u64> {
+    if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        u64::from_str_radix(hex, 16).ok()
+    } else if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Parse a string SID or SID alias into its ID authority and sub-authorities
+///
+/// Aliases that depend on the current domain or machine return an error.
+pub(crate) fn parse_sid(s: &str) -> io::Result<([u8; 6], Vec<u32>)> {
+    if let Some((_, full)) = SID_ALIASES.iter().find(|(alias, _)| *alias == s) {
+        return parse_sid(full);
+    }
+
+    if DOMAIN_SID_ALIASES.contains(&s) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "SID alias {:?} requires a domain and is only supported on Windows",
+                s
+            ),
+        ));
+    }
+
+    let mut parts = s.split('-');
+
+    match parts.next() {
+        Some("S") | Some("s") => (),
+        _ => return Err(invalid_sid(s)),
+    }
+
+    if parts.next() != Some("1") {
+        return Err(invalid_sid(s));
+    }
+
+    let id_auth = parts
+        .next()
+        .and_then(parse_number)
+        .filter(|&n| n < 1 << 48)
+        .ok_or_else(|| invalid_sid(s))?;
+
+    let mut id_auth_bytes = [0u8; 6];
+    id_auth_bytes.copy_from_slice(&id_auth.to_be_bytes()[2..]);
+
+    let sub_auths = parts
+        .map(|part| {
+            parse_number(part)
+                .filter(|&n| n <= u32::MAX as u64)
+                .map(|n| n as u32)
+                .ok_or_else(|| invalid_sid(s))
+        })
+        .collect::<io::Result<Vec<u32>>>()?;
+
+    if sub_auths.is_empty() || sub_auths.len() > 15 {
+        return Err(invalid_sid(s));
+    }
+
+    Ok((id_auth_bytes, sub_auths))
+}
+
+/// Format a SID in the standard `S-1-...` form
+pub(crate) fn format_sid(sid: &Sid) -> String {
+    format_sid_parts(sid.id_authority(), sid.sub_authorities())
+}
+
+/// Format a SID string from the ID authority and sub-authorities
+pub(crate) fn format_sid_parts(
+    id_auth: &[u8; 6],
+    sub_auths: impl IntoIterator<Item = u32>,
+) -> String {
+    let mut string = if id_auth[0] == 0 && id_auth[1] == 0 {
+        format!("S-1-{}", Sid::id_auth_to_number(*id_auth))
+    } else {
+        format!(
+            "S-1-0x{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
+            id_auth[0], id_auth[1], id_auth[2], id_auth[3], id_auth[4], id_auth[5]
+        )
+    };
+
+    for sub_auth in sub_auths {
+        string.push_str(&format!("-{}", sub_auth));
+    }
+
+    string
+}
+
+/// Format a SID using its alias if it has one
+pub(crate) fn format_sid_or_alias(sid: &Sid) -> String {
+    let string = format_sid(sid);
+
+    SID_ALIASES
+        .iter()
+        .find(|(_, full)| *full == string)
+        .map(|(alias, _)| alias.to_string())
+        .unwrap_or(string)
+}
+
+fn invalid_sd(s: &str, reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("Invalid security descriptor string ({}): {:?}", reason, s),
+    )
+}
+
+/// Split `s` at each top-level occurrence of `separator`, ignoring anything
+/// in parentheses or quotes
+fn split_top_level(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut quoted = false;
+    let mut start = 0;
+
+    for (index, c) in s.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth = depth.saturating_sub(1),
+            c if c == separator && depth == 0 && !quoted => {
+                parts.push(&s[start..index]);
+                start = index + c.len_utf8();
+            }
+            _ => (),
+        }
+    }
+
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Split a security descriptor string into its `O:`, `G:`, `D:`, and `S:`
+/// components
+fn split_components(s: &str) -> io::Result<Vec<(char, &str)>> {
+    let bytes = s.as_bytes();
+    let mut starts = Vec::new();
+    let mut depth = 0usize;
+    let mut quoted = false;
+
+    for (index, &b) in bytes.iter().enumerate() {
+        match b {
+            b'"' => quoted = !quoted,
+            b'(' if !quoted => depth += 1,
+            b')' if !quoted => depth = depth.saturating_sub(1),
+            b'O' | b'G' | b'D' | b'S'
+                if depth == 0 && !quoted && bytes.get(index + 1) == Some(&b':') =>
+            {
+                starts.push(index)
+            }
+            _ => (),
+        }
+    }
+
+    if starts.first().map_or(!s.is_empty(), |&first| first != 0) {
+        return Err(invalid_sd(s, "expected a component"));
+    }
+
+    Ok(starts
+        .iter()
+        .enumerate()
+        .map(|(n, &start)| {
+            let end = starts.get(n + 1).copied().unwrap_or(s.len());
+            (bytes[start] as char, &s[start + 2..end])
+        })
+        .collect())
+}
+
+fn sid_bytes(s: &str) -> io::Result<Vec<u8>> {
+    let (id_auth, sub_auths) = parse_sid(s)?;
+    Ok(build::sid(id_auth, &sub_auths))
+}
+
+fn parse_rights(s: &str, whole: &str) -> io::Result<u32> {
+    if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        return u32::from_str_radix(hex, 16).map_err(|_| invalid_sd(whole, "bad access mask"));
+    }
+
+    if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+        return s.parse().map_err(|_| invalid_sd(whole, "bad access mask"));
+    }
+
+    if !s.len().is_multiple_of(2) || !s.is_ascii() {
+        return Err(invalid_sd(whole, "bad access rights"));
+    }
+
+    let mut mask = 0;
+
+    for index in (0..s.len()).step_by(2) {
+        let code = s[index..index + 2].to_ascii_uppercase();
+        mask |= SINGLE_RIGHTS
+            .iter()
+            .chain(AGGREGATE_RIGHTS)
+            .chain(LABEL_RIGHTS)
+            .find(|(name, _)| *name == code)
+            .map(|(_, value)| *value)
+            .ok_or_else(|| invalid_sd(whole, "unknown access right"))?;
+    }
+
+    Ok(mask)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn format_rights(mask: u32, ace_type: u8) -> String {
+    if mask == 0 {
+        return String::new();
+    }
+
+    let singles = if ace_type == SYSTEM_MANDATORY_LABEL_ACE_TYPE {
+        LABEL_RIGHTS
+    } else {
+        if let Some((name, _)) = AGGREGATE_RIGHTS.iter().find(|(_, value)| *value == mask) {
+            return name.to_string();
+        }
+        SINGLE_RIGHTS
+    };
+
+    let mut string = String::new();
+    let mut remaining = mask;
+
+    for (name, value) in singles {
+        if mask & value != 0 {
+            string.push_str(name);
+            remaining &= !value;
+        }
+    }
+
+    if remaining == 0 {
+        string
+    } else {
+        format!("{:#x}", mask)
+    }
+}
+
+pub(crate) fn parse_guid(s: &str) -> Option<[u8; 16]> {
+    let parts: Vec<&str> = s.split('-').collect();
+    let lengths: Vec<usize> = parts.iter().map(|p| p.len()).collect();
+
+    if lengths != [8, 4, 4, 4, 12] || !s.bytes().all(|b| b == b'-' || b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let data1 = u32::from_str_radix(parts[0], 16).ok()?;
+    let data2 = u16::from_str_radix(parts[1], 16).ok()?;
+    let data3 = u16::from_str_radix(parts[2], 16).ok()?;
+    let data4 = format!("{}{}", parts[3], parts[4]);
+
+    let mut guid = [0u8; 16];
+    guid[0..4].copy_from_slice(&data1.to_le_bytes());
+    guid[4..6].copy_from_slice(&data2.to_le_bytes());
+    guid[6..8].copy_from_slice(&data3.to_le_bytes());
+    for index in 0..8 {
+        guid[8 + index] = u8::from_str_radix(&data4[index * 2..index * 2 + 2], 16).ok()?;
+    }
+
+    Some(guid)
+}
+
+pub(crate) fn format_guid(guid: &[u8; 16]) -> String {
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+        u32::from_le_bytes([guid[0], guid[1], guid[2], guid[3]]),
+        u16::from_le_bytes([guid[4], guid[5]]),
+        u16::from_le_bytes([guid[6], guid[7]]),
+        guid[8],
+        guid[9],
+        guid[10],
+        guid[11],
+        guid[12],
+        guid[13],
+        guid[14],
+        guid[15]
+    )
+}
+
+fn parse_ace(s: &str, whole: &str) -> io::Result<Vec<u8>> {
+    let fields = split_top_level(s, ';');
+
+    if fields.len() < 6 || fields.len() > 7 {
+        return Err(invalid_sd(whole, "wrong number of ACE fields"));
+    }
+
+    let mut ace_type = ACE_TYPES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(fields[0]))
+        .map(|(_, value)| *value)
+        .ok_or_else(|| invalid_sd(whole, "unknown ACE type"))?;
+
+    let mut flags = 0;
+    let flag_string = fields[1];
+    if !flag_string.len().is_multiple_of(2) || !flag_string.is_ascii() {
+        return Err(invalid_sd(whole, "bad ACE flags"));
+    }
+    for index in (0..flag_string.len()).step_by(2) {
+        let code = &flag_string[index..index + 2];
+        flags |= ACE_FLAGS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(code))
+            .map(|(_, value)| *value)
+            .ok_or_else(|| invalid_sd(whole, "unknown ACE flag"))?;
+    }
+
+    let mask = parse_rights(fields[2], whole)?;
+
+    let guid = |field: &str| -> io::Result<Option<[u8; 16]>> {
+        if field.is_empty() {
+            Ok(None)
+        } else {
+            parse_guid(field)
+                .map(Some)
+                .ok_or_else(|| invalid_sd(whole, "bad GUID"))
+        }
+    };
+    let object_type = guid(fields[3])?;
+    let inherited_object_type = guid(fields[4])?;
+
+    if build::is_object_ace_type(ace_type) {
+        // Like Windows, use the plain ACE type when there are no GUIDs
+        if object_type.is_none() && inherited_object_type.is_none() {
+            ace_type = match ace_type {
+                ACCESS_ALLOWED_OBJECT_ACE_TYPE => ACCESS_ALLOWED_ACE_TYPE,
+                ACCESS_DENIED_OBJECT_ACE_TYPE => ACCESS_DENIED_ACE_TYPE,
+                SYSTEM_AUDIT_OBJECT_ACE_TYPE => SYSTEM_AUDIT_ACE_TYPE,
+                ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE => ACCESS_ALLOWED_CALLBACK_ACE_TYPE,
+                other => other,
+            };
+        }
+    } else if object_type.is_some() || inherited_object_type.is_some() {
+        return Err(invalid_sd(whole, "GUIDs on a non-object ACE"));
+    }
+
+    let sid = sid_bytes(fields[5])?;
+
+    let data = match fields.get(6) {
+        Some(field) if build::is_callback_ace_type(ace_type) => field
+            .parse::<Condition>()
+            .map_err(|_| invalid_sd(whole, "bad conditional expression"))?
+            .to_bytes(),
+        Some(field) if ace_type == SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE => {
+            build::claim_attribute(&parse_resource_attribute(field, whole)?)
+        }
+        Some(_) => return Err(invalid_sd(whole, "extra ACE field")),
+        None => Vec::new(),
+    };
+
+    Ok(build::ace(
+        ace_type,
+        flags,
+        mask,
+        object_type,
+        inherited_object_type,
+        &sid,
+        &data,
+    ))
+}
+
+/// Parse the attribute of a resource attribute ACE, such as
+/// `("Secrecy",TU,0x0,3)`
+fn parse_resource_attribute(s: &str, whole: &str) -> io::Result<ClaimAttribute> {
+    let bad = || invalid_sd(whole, "bad resource attribute");
+
+    let inner = s
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .ok_or_else(bad)?;
+    let fields = split_top_level(inner, ',');
+    if fields.len() < 3 {
+        return Err(bad());
+    }
+
+    let string = |field: &str| {
+        field
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .map(str::to_string)
+            .ok_or_else(bad)
+    };
+    let integer = |field: &str| {
+        let (negative, digits) = match field.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, field),
+        };
+        parse_number(digits).map(|n| (negative, n)).ok_or_else(bad)
+    };
+
+    let name = string(fields[0])?;
+    let flags = integer(fields[2])
+        .ok()
+        .filter(|&(negative, n)| !negative && n <= u32::MAX as u64)
+        .map(|(_, n)| ClaimFlags::from_bits_truncate(n as u32))
+        .ok_or_else(bad)?;
+    let values = &fields[3..];
+
+    let values = match fields[1] {
+        "TI" => ClaimValues::Int64(
+            values
+                .iter()
+                .map(|field| match integer(field)? {
+                    (true, n) => 0i64.checked_sub_unsigned(n).ok_or_else(bad),
+                    (false, n) if n <= i64::MAX as u64 => Ok(n as i64),
+                    _ => Err(bad()),
+                })
+                .collect::<io::Result<_>>()?,
+        ),
+        "TU" => ClaimValues::Uint64(
+            values
+                .iter()
+                .map(|field| match integer(field)? {
+                    (false, n) => Ok(n),
+                    (true, _) => Err(bad()),
+                })
+                .collect::<io::Result<_>>()?,
+        ),
+        "TS" => ClaimValues::String(
+            values
+                .iter()
+                .map(|field| string(field))
+                .collect::<io::Result<_>>()?,
+        ),
+        "TD" => ClaimValues::Sid(
+            values
+                .iter()
+                .map(|field| {
+                    let sid = field
+                        .strip_prefix("SID(")
+                        .and_then(|s| s.strip_suffix(')'))
+                        .unwrap_or(field);
+                    let (id_auth, sub_auths) = parse_sid(sid)?;
+                    Sid::new(id_auth, &sub_auths)
+                })
+                .collect::<io::Result<_>>()?,
+        ),
+        "TX" => ClaimValues::OctetString(
+            values
+                .iter()
+                .map(|field| {
+                    let hex = field.strip_prefix('#').unwrap_or(field);
+                    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+                        return Err(bad());
+                    }
+                    (0..hex.len())
+                        .step_by(2)
+                        .map(|index| {
+                            u8::from_str_radix(&hex[index..index + 2], 16).map_err(|_| bad())
+                        })
+                        .collect()
+                })
+                .collect::<io::Result<_>>()?,
+        ),
+        "TB" => ClaimValues::Boolean(
+            values
+                .iter()
+                .map(|field| match *field {
+                    "0" => Ok(false),
+                    "1" => Ok(true),
+                    _ => Err(bad()),
+                })
+                .collect::<io::Result<_>>()?,
+        ),
+        _ => return Err(bad()),
+    };
+
+    Ok(ClaimAttribute::new(name, flags, values))
+}
+
+#[cfg(not(target_os = "windows"))]
+/// Format the attribute of a resource attribute ACE
+fn format_resource_attribute(attribute: &ClaimAttribute) -> io::Result<String> {
+    let (type_name, values): (&str, Vec<String>) = match attribute.values() {
+        ClaimValues::Int64(v) => ("TI", v.iter().map(|n| n.to_string()).collect()),
+        ClaimValues::Uint64(v) => ("TU", v.iter().map(|n| n.to_string()).collect()),
+        ClaimValues::String(v) => ("TS", v.iter().map(|s| format!("\"{}\"", s)).collect()),
+        ClaimValues::Sid(v) => ("TD", v.iter().map(|sid| format_sid_or_alias(sid)).collect()),
+        ClaimValues::OctetString(v) => (
+            "TX",
+            v.iter()
+                .map(|octets| {
+                    octets
+                        .iter()
+                        .map(|b| format!("{:02x}", b))
+                        .collect::<String>()
+                })
+                .map(|hex| format!("#{}", hex))
+                .collect(),
+        ),
+        ClaimValues::Boolean(v) => ("TB", v.iter().map(|b| (*b as u8).to_string()).collect()),
+        ClaimValues::Fqbn(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "FQBN resource attributes have no SDDL representation",
+            ))
+        }
+    };
+
+    let mut string = format!(
+        "(\"{}\",{},{:#x}",
+        attribute.name(),
+        type_name,
+        attribute.flags().bits()
+    );
+    for value in values {
+        string.push(',');
+        string.push_str(&value);
+    }
+    string.push(')');
+
+    Ok(string)
+}
+
+/// Parse the flags and ACEs of a `D:` or `S:` component
+///
+/// Returns the control flags, and the ACL (or `None` for a null ACL).
+fn parse_acl(s: &str, whole: &str, dacl: bool) -> io::Result<(u16, Option<Vec<u8>>)> {
+    let (flag_string, aces) = match s.find('(') {
+        Some(index) => s.split_at(index),
+        None => (s, ""),
+    };
+
+    let (protected, auto_inherited, auto_inherit_req) = if dacl {
+        (
+            SE_DACL_PROTECTED,
+            SE_DACL_AUTO_INHERITED,
+            SE_DACL_AUTO_INHERIT_REQ,
+        )
+    } else {
+        (
+            SE_SACL_PROTECTED,
+            SE_SACL_AUTO_INHERITED,
+            SE_SACL_AUTO_INHERIT_REQ,
+        )
+    };
+
+    let mut control = if dacl {
+        SE_DACL_PRESENT
+    } else {
+        SE_SACL_PRESENT
+    };
+    let mut null = false;
+    let mut rest = flag_string;
+
+    while !rest.is_empty() {
+        if let Some(r) = rest.strip_prefix("NO_ACCESS_CONTROL") {
+            null = true;
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix("AI") {
+            control |= auto_inherited;
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix("AR") {
+            control |= auto_inherit_req;
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix('P') {
+            control |= protected;
+            rest = r;
+        } else {
+            return Err(invalid_sd(whole, "unknown ACL flag"));
+        }
+    }
+
+    let mut ace_list = Vec::new();
+    let mut rest = aces;
+
+    while !rest.is_empty() {
+        if !rest.starts_with('(') {
+            return Err(invalid_sd(whole, "expected an ACE"));
+        }
+
+        let mut depth = 0usize;
+        let mut quoted = false;
+        let mut end = None;
+
+        for (index, c) in rest.char_indices() {
+            match c {
+                '"' => quoted = !quoted,
+                '(' if !quoted => depth += 1,
+                ')' if !quoted => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(index);
+                        break;
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let end = end.ok_or_else(|| invalid_sd(whole, "unterminated ACE"))?;
+        ace_list.push(parse_ace(&rest[1..end], whole)?);
+        rest = &rest[end + 1..];
+    }
+
+    if null {
+        if !ace_list.is_empty() {
+            return Err(invalid_sd(whole, "ACEs in a null ACL"));
+        }
+        Ok((control, None))
+    } else {
+        Ok((control, Some(build::acl(&ace_list))))
+    }
+}
+
+/// Parse a security descriptor string into a self-relative security
+/// descriptor
+pub(crate) fn parse_sd(s: &str) -> io::Result<Vec<u8>> {
+    let mut owner = None;
+    let mut group = None;
+    let mut dacl = None;
+    let mut sacl = None;
+    let mut control = 0;
+
+    for (kind, value) in split_components(s)? {
+        let slot_taken = match kind {
+            'O' => owner.replace(sid_bytes(value)?).is_some(),
+            'G' => group.replace(sid_bytes(value)?).is_some(),
+            'D' => {
+                let (acl_control, acl) = parse_acl(value, s, true)?;
+                control |= acl_control;
+                dacl.replace(acl).is_some()
+            }
+            'S' => {
+                let (acl_control, acl) = parse_acl(value, s, false)?;
+                control |= acl_control;
+                sacl.replace(acl).is_some()
+            }
+            _ => unreachable!("split_components only returns known components"),
+        };
+
+        if slot_taken {
+            return Err(invalid_sd(s, "repeated component"));
+        }
+    }
+
+    Ok(build::security_descriptor(&SecurityDescriptorParts {
+        control,
+        owner: owner.as_deref(),
+        group: group.as_deref(),
+        sacl: sacl.as_ref().and_then(|acl| acl.as_deref()),
+        dacl: dacl.as_ref().and_then(|acl| acl.as_deref()),
+    }))
+}
+
+#[cfg(not(target_os = "windows"))]
+/// Format an ACE as an ACE string
+pub(crate) fn format_ace(ace: &Ace) -> io::Result<String> {
+    let ace_type = ace.ace_type() as u8;
+
+    let type_name = ACE_TYPES
+        .iter()
+        .find(|(_, value)| *value == ace_type)
+        .map(|(name, _)| *name)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("ACE type {:#x} has no SDDL representation", ace_type),
+            )
+        })?;
+
+    let data = if let Some(condition) = ace.condition() {
+        format!(";{}", condition?)
+    } else if let Some(attribute) = ace.resource_attribute() {
+        format!(";{}", format_resource_attribute(&attribute?)?)
+    } else if ace.application_data().iter().all(|&b| b == 0) {
+        String::new()
+    } else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "ACE has application data with no SDDL representation",
+        ));
+    };
+
+    let flags = ACE_FLAGS
+        .iter()
+        .filter(|(_, value)| ace.flags().bits() & value != 0)
+        .map(|(name, _)| *name)
+        .collect::<String>();
+
+    let guid = |g: Option<Guid>| g.map(|g| g.to_string()).unwrap_or_default();
+
+    Ok(format!(
+        "({};{};{};{};{};{}{})",
+        type_name,
+        flags,
+        format_rights(ace.mask().bits(), ace_type),
+        guid(ace.object_type()),
+        guid(ace.inherited_object_type()),
+        format_sid_or_alias(ace.raw_sid()),
+        data,
+    ))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn format_acl<'a, I: Iterator<Item = &'a Ace>>(aces: I) -> io::Result<String> {
+    aces.map(format_ace).collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+/// Format a security descriptor as a security descriptor string
+///
+/// Only the parts selected by `info` are included. If `info` does not
+/// include the SACL, the label, attribute, and scope parts select just those
+/// ACEs from it.
+pub(crate) fn format_sd(sd: &SecurityDescriptor, info: SecurityInformation) -> io::Result<String> {
+    let (control, _) = crate::wrappers::GetSecurityDescriptorControl(sd)?;
+    let control = control.bits();
+    let mut string = String::new();
+
+    if info.contains(SecurityInformation::Owner) {
+        if let Some(owner) = sd.owner() {
+            string.push_str("O:");
+            string.push_str(&format_sid_or_alias(owner));
+        }
+    }
+
+    if info.contains(SecurityInformation::Group) {
+        if let Some(group) = sd.group() {
+            string.push_str("G:");
+            string.push_str(&format_sid_or_alias(group));
+        }
+    }
+
+    let acl_flags = |protected, auto_inherit_req, auto_inherited| {
+        let mut flags = String::new();
+        if control & protected != 0 {
+            flags.push('P');
+        }
+        if control & auto_inherit_req != 0 {
+            flags.push_str("AR");
+        }
+        if control & auto_inherited != 0 {
+            flags.push_str("AI");
+        }
+        flags
+    };
+
+    let acl_string = |acl: Option<&Acl>, filter: &dyn Fn(&Ace) -> bool| -> io::Result<String> {
+        match acl {
+            Some(acl) => format_acl(acl.aces().filter(|ace| filter(ace))),
+            None => Ok("NO_ACCESS_CONTROL".to_string()),
+        }
+    };
+
+    if info.contains(SecurityInformation::Dacl) && control & SE_DACL_PRESENT != 0 {
+        string.push_str("D:");
+        string.push_str(&acl_flags(
+            SE_DACL_PROTECTED,
+            SE_DACL_AUTO_INHERIT_REQ,
+            SE_DACL_AUTO_INHERITED,
+        ));
+        string.push_str(&acl_string(sd.non_null_dacl(), &|_| true)?);
+    }
+
+    let sacl_parts =
+        SecurityInformation::Label | SecurityInformation::Attribute | SecurityInformation::Scope;
+
+    if info.intersects(SecurityInformation::Sacl | sacl_parts) && control & SE_SACL_PRESENT != 0 {
+        let whole_sacl = info.contains(SecurityInformation::Sacl);
+        let wanted = |ace: &Ace| {
+            whole_sacl
+                || match ace.ace_type() as u8 {
+                    SYSTEM_MANDATORY_LABEL_ACE_TYPE => info.contains(SecurityInformation::Label),
+                    SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE => {
+                        info.contains(SecurityInformation::Attribute)
+                    }
+                    SYSTEM_SCOPED_POLICY_ID_ACE_TYPE => info.contains(SecurityInformation::Scope),
+                    _ => false,
+                }
+        };
+
+        let aces = acl_string(sd.non_null_sacl(), &wanted)?;
+
+        if whole_sacl || !aces.is_empty() {
+            string.push_str("S:");
+            if whole_sacl {
+                string.push_str(&acl_flags(
+                    SE_SACL_PROTECTED,
+                    SE_SACL_AUTO_INHERIT_REQ,
+                    SE_SACL_AUTO_INHERITED,
+                ));
+            }
+            string.push_str(&aces);
+        }
+    }
+
+    Ok(string)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_sids() {
+        assert_eq!(
+            parse_sid("S-1-5-18").unwrap(),
+            ([0, 0, 0, 0, 0, 5], vec![18])
+        );
+        assert_eq!(parse_sid("SY").unwrap(), ([0, 0, 0, 0, 0, 5], vec![18]));
+        assert_eq!(
+            parse_sid("S-1-0x123456789ABC-1-0x10").unwrap(),
+            ([0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC], vec![1, 16])
+        );
+
+        for bad in &[
+            "",
+            "S",
+            "S-1",
+            "S-1-5",
+            "S-2-5-18",
+            "S-1-5-",
+            "S-1-x-1",
+            "S-1-5-4294967296",
+            "XX",
+            "DA",
+        ] {
+            assert!(parse_sid(bad).is_err(), "{:?} should not parse", bad);
+        }
+    }
+
+    #[test]
+    fn guid_round_trip() {
+        let string = "c434c045-9b91-4504-a2a0-aea9e781ec69";
+        let guid = parse_guid(string).unwrap();
+
+        assert_eq!(&guid[..4], &[0x45, 0xC0, 0x34, 0xC4]);
+        assert_eq!(format_guid(&guid), string);
+
+        for bad in &["", "c434c045", "c434c045-9b91-4504-a2a0-aea9e781ec6g"] {
+            assert!(parse_guid(bad).is_none(), "{:?} should not parse", bad);
+        }
+    }
+
+    #[test]
+    fn rights_round_trip() {
+        let cases = [
+            ("", 0),
+            ("FA", FILE_ALL_ACCESS),
+            ("KR", KEY_READ),
+            ("CCDCLCSWRPWPDTLOCRSDRCWDWO", 0x000F_01FF),
+            (
+                "SDGXGWGR",
+                DELETE | GENERIC_EXECUTE | GENERIC_WRITE | GENERIC_READ,
+            ),
+            ("0x1200a9", 0x0012_00A9),
+        ];
+
+        for (string, mask) in &cases {
+            assert_eq!(parse_rights(string, string).unwrap(), *mask);
+            #[cfg(not(target_os = "windows"))]
+            assert_eq!(format_rights(*mask, ACCESS_ALLOWED_ACE_TYPE), *string);
+        }
+
+        assert_eq!(parse_rights("0x10", "").unwrap(), 0x10);
+        assert_eq!(parse_rights("16", "").unwrap(), 16);
+        assert_eq!(parse_rights("fa", "").unwrap(), FILE_ALL_ACCESS);
+        #[cfg(not(target_os = "windows"))]
+        assert_eq!(format_rights(0x3, SYSTEM_MANDATORY_LABEL_ACE_TYPE), "NWNR");
+        assert!(parse_rights("XX", "").is_err());
+        assert!(parse_rights("F", "").is_err());
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn sd_round_trip() {
+        let cases = [
+            "",
+            "O:SYG:BA",
+            "O:S-1-5-21-1-2-3-500D:",
+            "D:NO_ACCESS_CONTROL",
+            "D:PAI(A;OICIID;FA;;;SY)(D;;WDWO;;;WD)",
+            "D:(OA;CI;RPWP;c434c045-9b91-4504-a2a0-aea9e781ec69;;AU)",
+            "D:(OD;;CR;;c434c045-9b91-4504-a2a0-aea9e781ec69;S-1-5-21-1-2-3-1001)",
+            "S:ARAI(AU;SAFA;FA;;;WD)(ML;;NW;;;HI)",
+            "O:BAG:SYD:S:",
+            "D:(XA;;FA;;;WD;(Member_of {SID(BA)}))",
+            "D:(XD;OICI;FW;;;AU;((@Resource.Secrecy > 2) && (!(Exists @User.Clearance))))",
+            "D:(ZA;;RP;c434c045-9b91-4504-a2a0-aea9e781ec69;;AU;(@Device.Managed == 1))",
+            "S:(XU;SA;FA;;;WD;(@User.Dept Any_of {\"HR\", \"IT\"}))",
+            "S:(RA;CI;;;;WD;(\"Secrecy\",TU,0x0,3))",
+            "S:(RA;;;;;WD;(\"Project\",TS,0x2,\"Alpha\",\"Beta\"))",
+            "S:(RA;;;;;WD;(\"Level\",TI,0x0,-5,7))",
+            "S:(RA;;;;;WD;(\"Owners\",TD,0x0,BA,S-1-5-21-1-2-3-1001))",
+            "S:(RA;;;;;WD;(\"Key\",TX,0x0,#00ff))",
+            "S:(RA;;;;;WD;(\"Flag\",TB,0x0,1))",
+        ];
+
+        for case in &cases {
+            let bytes = parse_sd(case).unwrap();
+            let sd = unsafe { &*(bytes.as_ptr() as *const SecurityDescriptor) };
+            assert_eq!(format_sd(sd, SecurityInformation::all()).unwrap(), *case);
+        }
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn sd_normalizes() {
+        let cases = [
+            ("G:SYO:BA", "O:BAG:SY"),
+            ("D:(OA;;GA;;;S-1-1-0)", "D:(A;;GA;;;WD)"),
+            ("D:(A;CIOI;0x1f01ff;;;s-1-5-18)", "D:(A;OICI;FA;;;SY)"),
+            (
+                "S:(ML;;NWNR;;;LW)(AU;FA;GA;;;WD)",
+                "S:(ML;;NWNR;;;LW)(AU;FA;GA;;;WD)",
+            ),
+        ];
+
+        for (input, output) in &cases {
+            let bytes = parse_sd(input).unwrap();
+            let sd = unsafe { &*(bytes.as_ptr() as *const SecurityDescriptor) };
+            assert_eq!(format_sd(sd, SecurityInformation::all()).unwrap(), *output);
+        }
+
+        let bytes = parse_sd("O:BAS:(ML;;NW;;;LW)(AU;FA;GA;;;WD)").unwrap();
+        let sd = unsafe { &*(bytes.as_ptr() as *const SecurityDescriptor) };
+        assert_eq!(
+            format_sd(sd, SecurityInformation::Label).unwrap(),
+            "S:(ML;;NW;;;LW)"
+        );
+        assert_eq!(format_sd(sd, SecurityInformation::Dacl).unwrap(), "");
+    }
+
+    #[test]
+    fn sd_errors() {
+        for bad in &[
+            "X:",
+            "O:",
+            "O:SYO:SY",
+            "D:(A;;FA;;;)",
+            "D:(A;;FA;;;SY",
+            "D:(Q;;FA;;;SY)",
+            "D:(A;ZZ;FA;;;SY)",
+            "D:(A;;FA;c434c045-9b91-4504-a2a0-aea9e781ec69;;SY)",
+            "D:Q(A;;FA;;;SY)",
+            "D:NO_ACCESS_CONTROL(A;;FA;;;SY)",
+            "D:(XA;;FA;;;WD;(Member_of))",
+            "D:(A;;FA;;;WD;(Member_of {SID(BA)}))",
+            "S:(RA;;;;;WD;(\"Secrecy\",TQ,0x0,3))",
+            "S:(RA;;;;;WD;(\"Secrecy\",TU,0x0,-3))",
+            "junk",
+        ] {
+            assert!(parse_sd(bad).is_err(), "{:?} should not parse", bad);
+        }
+    }
+
+    #[test]
+    fn aliases_are_valid() {
+        for (alias, full) in SID_ALIASES {
+            assert!(parse_sid(full).is_ok(), "{} has an invalid SID", alias);
+        }
+    }
+}
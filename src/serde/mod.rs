@@ -0,0 +1,325 @@
+//! Serialization with [serde](https://serde.rs), enabled by the `serde`
+//! feature
+//!
+//! Every representation works on all platforms, without calling Windows.
+//!
+//! | Type | Representation |
+//! |------|----------------|
+//! | [`Sid`](crate::Sid) | A SID string, like `"S-1-5-32-544"`. SDDL aliases like `"BA"` are also accepted. |
+//! | [`Guid`](crate::Guid) | A GUID string, like `"bf967aba-0de6-11d0-a285-00aa003049e2"` |
+//! | [`Condition`](crate::Condition) | A conditional expression in SDDL, like `"(Member_of {SID(BA)})"` |
+//! | [`AccessRights`](crate::constants::AccessRights) | A mask in [`hex`], like `"0x1f01ff"` |
+//! | Other bitflags in [`constants`](crate::constants) | An array of flag [`names`], like `["ObjectInherit", "ContainerInherit"]` |
+//! | Enums in [`constants`](crate::constants) | The name of the constant, like `"ACCESS_ALLOWED_ACE_TYPE"` |
+//! | [`Ace`](crate::Ace) | An object, described below |
+//! | [`Acl`](crate::Acl) | An array of ACEs |
+//! | [`SecurityDescriptor`](crate::SecurityDescriptor) | An object, described below, or an [`sddl`] string |
+//! | [`Token`](crate::Token) | An object with the same fields as the token, described below |
+//! | [`Luid`](crate::token::Luid) | An integer |
+//!
+//! Bitflags are read from either representation, so masks can be given as
+//! names, hex, or an array of both. Unknown names are an error. In formats
+//! that are not human-readable, bitflags are plain integers.
+//!
+//! An ACE has the fields `type`, `flags`, `mask` and `sid`, then
+//! `object_type` and `inherited_object_type` for object ACEs, and either a
+//! `condition` for callback ACEs or the hex `application_data` for any
+//! other data after the SID. Fields that do not apply are left out. When
+//! reading, `flags` defaults to empty and the `sid` of a resource attribute
+//! ACE defaults to Everyone.
+//!
+//! A security descriptor has the fields `control`, `owner`, `group`,
+//! `dacl` and `sacl`, all of which may be left out. A present ACL sets its
+//! `DaclPresent` or `SaclPresent` control flag; a present flag without an
+//! ACL is a null ACL. `SelfRelative` is left out, since every security
+//! descriptor built by this crate is self-relative.
+//!
+//! A token has a `user`, then `groups`, `privileges`, `owner`,
+//! `primary_group`, `integrity_level`, `restricting_sids`,
+//! `write_restricted`, `app_container_sid`,
+//! `less_privileged_app_container`, `capabilities`, `device_groups`,
+//! `user_claims` and `device_claims`. Fields that are empty or `false` are
+//! left out, and default to that when reading. A SID with attributes is an
+//! object with `sid` and `attributes`, a privilege has `luid` and
+//! `attributes`, and a claim has `name`, `flags` and `values`, where the
+//! values are an object whose only key is the value type, like
+//! `{"Uint64": [3]}`.
+//!
+//! With the `schemars` feature, the `schema` module describes these
+//! representations in JSON Schema.
+//!
+//! The representation of a field can be chosen with `#[serde(with)]`:
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use windows_permissions::constants::FileRights;
+//! use windows_permissions::{LocalBox, SecurityDescriptor, Sid};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Share {
+//!     owner: LocalBox<Sid>,
+//!     read_rights: FileRights,
+//!     #[serde(with = "windows_permissions::serde::hex")]
+//!     write_rights: FileRights,
+//!     #[serde(with = "windows_permissions::serde::sddl")]
+//!     permissions: LocalBox<SecurityDescriptor>,
+//! }
+//!
+//! let share: Share = serde_json::from_str(r#"{
+//!     "owner": "BA",
+//!     "read_rights": ["ReadData", "ReadControl", "0x20"],
+//!     "write_rights": ["WriteData", "AppendData"],
+//!     "permissions": "D:P(A;;FA;;;SY)"
+//! }"#).unwrap();
+//!
+//! assert_eq!(share.read_rights, FileRights::ReadData | FileRights::Execute | FileRights::ReadControl);
+//! assert_eq!(
+//!     serde_json::to_string(&share).unwrap(),
+//!     r#"{"owner":"S-1-5-32-544","read_rights":["ReadData","Execute","ReadControl"],"write_rights":"0x6","permissions":"D:P(A;;FA;;;SY)"}"#
+//! );
+//! ```
+
+use ::serde::de::{self, Deserializer, SeqAccess, Unexpected, Visitor};
+use ::serde::Deserialize;
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::marker::PhantomData;
+
+#[cfg(feature = "schemars")]
+pub mod schema;
+mod structures;
+
+/// A bitflags type from [`constants`](crate::constants)
+pub trait Flags: Sized {
+    /// The names written when serializing, in order
+    const NAMES: &'static [(&'static str, u32)];
+
+    /// Other names that are accepted when deserializing
+    const ALIASES: &'static [(&'static str, u32)];
+
+    /// Get the bits of the flags
+    fn to_u32(&self) -> u32;
+
+    /// Get flags from bits, or `None` if any are unknown
+    fn from_u32(bits: u32) -> Option<Self>;
+}
+
+fn parse_flag<F: Flags, E: de::Error>(s: &str) -> Result<u32, E> {
+    if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        return u32::from_str_radix(hex, 16)
+            .map_err(|_| E::invalid_value(Unexpected::Str(s), &"a mask in hex"));
+    }
+
+    F::NAMES
+        .iter()
+        .chain(F::ALIASES)
+        .find(|(name, _)| *name == s)
+        .map(|&(_, bits)| bits)
+        .ok_or_else(|| E::custom(format!("unknown flag `{}`", s)))
+}
+
+fn flags_from_u32<F: Flags, E: de::Error>(bits: u32) -> Result<F, E> {
+    F::from_u32(bits).ok_or_else(|| E::custom(format!("unknown bits in {:#x}", bits)))
+}
+
+struct FlagsVisitor<F>(PhantomData<F>);
+
+impl<'de, F: Flags> Visitor<'de> for FlagsVisitor<F> {
+    type Value = F;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of flag names, or a mask in hex")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<F, E> {
+        let bits = u32::try_from(value)
+            .map_err(|_| E::invalid_value(Unexpected::Unsigned(value), &self))?;
+        flags_from_u32(bits)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<F, E> {
+        flags_from_u32(parse_flag::<F, E>(value)?)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<F, A::Error> {
+        let mut bits = 0;
+        while let Some(name) = seq.next_element::<Cow<str>>()? {
+            bits |= parse_flag::<F, A::Error>(&name)?;
+        }
+        flags_from_u32(bits)
+    }
+}
+
+fn deserialize_flags<'de, F: Flags, D: Deserializer<'de>>(deserializer: D) -> Result<F, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(FlagsVisitor(PhantomData))
+    } else {
+        flags_from_u32(u32::deserialize(deserializer)?)
+    }
+}
+
+/// Bitflags as an array of flag names
+///
+/// Bits without a name are written in hex as the last element, like
+/// `["ReadControl", "0x4"]`. Either representation is accepted when
+/// deserializing.
+pub mod names {
+    use super::Flags;
+    use ::serde::ser::{SerializeSeq, Serializer};
+    use ::serde::Deserializer;
+
+    /// Serialize bitflags as an array of flag names
+    pub fn serialize<F: Flags, S: Serializer>(flags: &F, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bits = flags.to_u32();
+        if !serializer.is_human_readable() {
+            return serializer.serialize_u32(bits);
+        }
+
+        let mut seq = serializer.serialize_seq(None)?;
+        for &(name, flag) in F::NAMES {
+            if flag != 0 && bits & flag == flag {
+                seq.serialize_element(name)?;
+                bits &= !flag;
+            }
+        }
+        if bits != 0 {
+            seq.serialize_element(&format!("{:#x}", bits))?;
+        }
+        seq.end()
+    }
+
+    /// Deserialize bitflags from an array of flag names or hex masks, or a
+    /// single name or mask
+    pub fn deserialize<'de, F: Flags, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<F, D::Error> {
+        super::deserialize_flags(deserializer)
+    }
+}
+
+/// Bitflags as a mask in hex, like `"0x1f01ff"`
+///
+/// Either representation is accepted when deserializing.
+pub mod hex {
+    use super::Flags;
+    use ::serde::{Deserializer, Serializer};
+
+    /// Serialize bitflags as a mask in hex
+    pub fn serialize<F: Flags, S: Serializer>(flags: &F, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(&format_args!("{:#x}", flags.to_u32()))
+        } else {
+            serializer.serialize_u32(flags.to_u32())
+        }
+    }
+
+    /// Deserialize bitflags from a hex mask, or an array of flag names or
+    /// hex masks
+    pub fn deserialize<'de, F: Flags, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<F, D::Error> {
+        super::deserialize_flags(deserializer)
+    }
+}
+
+/// A security descriptor as an SDDL string
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use windows_permissions::{LocalBox, SecurityDescriptor};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Config {
+///     #[serde(with = "windows_permissions::serde::sddl")]
+///     sd: LocalBox<SecurityDescriptor>,
+/// }
+///
+/// let config: Config = serde_json::from_str(r#"{"sd": "O:BAD:(A;;FA;;;SY)"}"#).unwrap();
+/// assert_eq!(config.sd.dacl().unwrap().len(), 1);
+/// ```
+pub mod sddl {
+    use crate::{LocalBox, SecurityDescriptor};
+    use ::serde::de::{self, Deserialize, Deserializer};
+    use ::serde::ser::{self, Serializer};
+    use std::borrow::{Borrow, Cow};
+
+    /// Serialize a security descriptor as an SDDL string
+    pub fn serialize<T, S>(sd: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Borrow<SecurityDescriptor>,
+        S: Serializer,
+    {
+        let sddl = sd.borrow().as_sddl().map_err(ser::Error::custom)?;
+        serializer.serialize_str(&sddl.to_string_lossy())
+    }
+
+    /// Deserialize a security descriptor from an SDDL string
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<LocalBox<SecurityDescriptor>, D::Error> {
+        let sddl = Cow::<str>::deserialize(deserializer)?;
+        sddl.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::constants::{AccessRights, AceFlags, AceType, KeyRights, SecurityInformation};
+
+    #[test]
+    fn flags() {
+        let flags = AceFlags::ObjectInherit | AceFlags::Inherited;
+        assert_eq!(
+            serde_json::to_string(&flags).unwrap(),
+            r#"["ObjectInherit","Inherited"]"#
+        );
+        assert_eq!(serde_json::to_string(&AceFlags::empty()).unwrap(), "[]");
+
+        let rights = KeyRights::QueryValue | KeyRights::from_bits_truncate(1 << 6);
+        assert_eq!(
+            serde_json::to_string(&rights).unwrap(),
+            r#"["QueryValue","Bit6"]"#
+        );
+        let rights = AccessRights::from_bits_truncate(0x0200_0001);
+        assert_eq!(serde_json::to_string(&rights).unwrap(), r#""0x2000001""#);
+
+        let mut json = vec![];
+        super::names::serialize(&rights, &mut serde_json::Serializer::new(&mut json)).unwrap();
+        assert_eq!(json, br#"["Bit0","0x2000000"]"#);
+
+        for json in &[
+            r#"["Owner", "Dacl"]"#,
+            r#"["Owner", "0x4"]"#,
+            r#""0x5""#,
+            "5",
+        ] {
+            let info: SecurityInformation = serde_json::from_str(json).unwrap();
+            assert_eq!(info, SecurityInformation::Owner | SecurityInformation::Dacl);
+        }
+
+        for json in &[
+            r#"["owner"]"#,
+            r#""0xz""#,
+            "4294967296",
+            r#"["0x20"]"#,
+            r#""0x100""#,
+        ] {
+            assert!(serde_json::from_str::<AceFlags>(json).is_err(), "{}", json);
+        }
+
+        let rights: KeyRights = serde_json::from_str(r#"["AllAccess"]"#).unwrap();
+        assert_eq!(rights, KeyRights::AllAccess);
+    }
+
+    #[test]
+    fn enums() {
+        let ace_type = AceType::SYSTEM_MANDATORY_LABEL_ACE_TYPE;
+        let json = serde_json::to_string(&ace_type).unwrap();
+        assert_eq!(json, r#""SYSTEM_MANDATORY_LABEL_ACE_TYPE""#);
+        assert_eq!(serde_json::from_str::<AceType>(&json).unwrap(), ace_type);
+
+        let err = serde_json::from_str::<AceType>(r#""ACCESS_ALLOWED""#).unwrap_err();
+        assert!(err.to_string().contains("ACCESS_ALLOWED_ACE_TYPE"));
+    }
+}
@@ -0,0 +1,285 @@
+//! JSON Schemas for the serialized forms, enabled by the `schemars` feature
+//!
+//! Every type that can be serialized implements
+//! [`JsonSchema`]. The schemas of bitflags and enums
+//! from [`constants`](crate::constants) list every name they accept, so a
+//! misspelled right or ACE type fails validation.
+//!
+//! ```
+//! use windows_permissions::SecurityDescriptor;
+//!
+//! let schema = serde_json::to_value(schemars::schema_for!(SecurityDescriptor)).unwrap();
+//! let defs = &schema["$defs"];
+//!
+//! assert!(defs["AceType"]["enum"]
+//!     .as_array()
+//!     .unwrap()
+//!     .contains(&"ACCESS_ALLOWED_ACE_TYPE".into()));
+//! assert_eq!(defs["Ace"]["properties"]["mask"]["$ref"], "#/$defs/AccessRights");
+//! ```
+//!
+//! A security descriptor that is serialized with [`sddl`](super::sddl) can
+//! use the schema of [`sddl()`] instead, by adding
+//! `#[schemars(schema_with = "windows_permissions::serde::schema::sddl")]`
+//! to the field.
+
+use super::Flags;
+use crate::constants::{AccessRights, AceFlags, AceType, SecurityDescriptorControl};
+use crate::sddl::SID_ALIASES;
+use crate::{Ace, Acl, Condition, Guid, LocalBox, SecurityDescriptor, Sid};
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use std::borrow::Cow;
+
+const HEX_MASK: &str = "^0[xX][0-9A-Fa-f]{1,8}$";
+
+/// The schema of a bitflags type that accepts the names of `F`
+pub(crate) fn flags<F: Flags>() -> Schema {
+    let names: Vec<&str> = F::NAMES
+        .iter()
+        .chain(F::ALIASES)
+        .map(|&(name, _)| name)
+        .collect();
+
+    json_schema!({
+        "description": "An array of flag names or masks in hex, or a single name, mask in hex, or integer",
+        "anyOf": [
+            {
+                "type": "array",
+                "items": {
+                    "anyOf": [
+                        { "type": "string", "enum": names.clone() },
+                        { "type": "string", "pattern": HEX_MASK },
+                    ],
+                },
+            },
+            { "type": "string", "enum": names },
+            { "type": "string", "pattern": HEX_MASK },
+            { "type": "integer", "minimum": 0, "maximum": u32::MAX },
+        ],
+    })
+}
+
+/// The schema of a security descriptor serialized as an SDDL string
+///
+/// Use it with `#[schemars(schema_with)]` on fields that use
+/// [`sddl`](super::sddl).
+pub fn sddl(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "type": "string",
+        "description": "A security descriptor in SDDL, like O:BAD:P(A;;FA;;;SY)",
+    })
+}
+
+impl<T: JsonSchema> JsonSchema for LocalBox<T> {
+    fn inline_schema() -> bool {
+        T::inline_schema()
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        T::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        T::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        T::json_schema(generator)
+    }
+}
+
+impl JsonSchema for Sid {
+    fn schema_name() -> Cow<'static, str> {
+        "Sid".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let number = "(0[xX][0-9A-Fa-f]+|[0-9]+)";
+        let aliases: Vec<&str> = SID_ALIASES.iter().map(|&(alias, _)| alias).collect();
+
+        json_schema!({
+            "description": "A SID string, like S-1-5-32-544, or an SDDL alias, like BA",
+            "anyOf": [
+                {
+                    "type": "string",
+                    "pattern": format!("^[Ss]-1-{0}(-{0}){{1,15}}$", number),
+                },
+                { "type": "string", "enum": aliases },
+            ],
+        })
+    }
+}
+
+impl JsonSchema for Guid {
+    fn schema_name() -> Cow<'static, str> {
+        "Guid".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let guid = "[0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{12}";
+
+        json_schema!({
+            "type": "string",
+            "description": "A GUID, like bf967aba-0de6-11d0-a285-00aa003049e2",
+            "pattern": format!("^({0}|\\{{{0}\\}})$", guid),
+        })
+    }
+}
+
+impl JsonSchema for Condition {
+    fn schema_name() -> Cow<'static, str> {
+        "Condition".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "description": "A conditional expression in SDDL, like (Member_of {SID(BA)})",
+        })
+    }
+}
+
+impl JsonSchema for Ace {
+    fn schema_name() -> Cow<'static, str> {
+        "Ace".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "object",
+            "properties": {
+                "type": generator.subschema_for::<AceType>(),
+                "flags": generator.subschema_for::<AceFlags>(),
+                "mask": generator.subschema_for::<AccessRights>(),
+                "sid": generator.subschema_for::<Sid>(),
+                "object_type": generator.subschema_for::<Guid>(),
+                "inherited_object_type": generator.subschema_for::<Guid>(),
+                "condition": generator.subschema_for::<Condition>(),
+                "application_data": {
+                    "type": "string",
+                    "description": "The data after the SID, in hex",
+                    "pattern": "^([0-9A-Fa-f]{2})*$",
+                },
+            },
+            "required": ["type", "mask"],
+            "additionalProperties": false,
+        })
+    }
+}
+
+impl JsonSchema for Acl {
+    fn schema_name() -> Cow<'static, str> {
+        "Acl".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "array",
+            "items": generator.subschema_for::<Ace>(),
+        })
+    }
+}
+
+impl JsonSchema for SecurityDescriptor {
+    fn schema_name() -> Cow<'static, str> {
+        "SecurityDescriptor".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "object",
+            "properties": {
+                "control": generator.subschema_for::<SecurityDescriptorControl>(),
+                "owner": generator.subschema_for::<Sid>(),
+                "group": generator.subschema_for::<Sid>(),
+                "dacl": generator.subschema_for::<Acl>(),
+                "sacl": generator.subschema_for::<Acl>(),
+            },
+            "additionalProperties": false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use jsonschema::JSONSchema;
+    use serde_json::{json, Value};
+
+    fn validator<T: JsonSchema>() -> JSONSchema {
+        let schema = serde_json::to_value(schemars::schema_for!(T)).unwrap();
+        JSONSchema::compile(&schema).unwrap()
+    }
+
+    #[test]
+    fn serialized_forms_are_valid() {
+        let validator = validator::<SecurityDescriptor>();
+
+        for sddl in &[
+            "O:BAG:SYD:PAI(A;OICI;FA;;;SY)(D;ID;0x100116;;;WD)S:(ML;;NW;;;HI)",
+            "D:(OA;CI;RP;bf967a86-0de6-11d0-a285-00aa003049e2;;AU)",
+            "D:(XA;;FA;;;WD;(Member_of {SID(BA)}))",
+            r#"S:(RA;;;;;WD;("Secrecy",TU,0x0,3))"#,
+            "D:NO_ACCESS_CONTROL",
+        ] {
+            let sd: LocalBox<SecurityDescriptor> = sddl.parse().unwrap();
+            let value = serde_json::to_value(&sd).unwrap();
+            assert!(validator.is_valid(&value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn token_is_valid() {
+        use crate::constants::{ClaimFlags, GroupAttributes, PrivilegeAttributes};
+        use crate::token::{ClaimAttribute, ClaimValues};
+        use crate::{Privilege, Token};
+
+        let validator = validator::<Token>();
+        let token = Token::new("S-1-5-21-1-2-3-1001".parse().unwrap())
+            .with_group("BU".parse().unwrap(), GroupAttributes::Enabled)
+            .with_privilege(Privilege::Backup, PrivilegeAttributes::Enabled)
+            .with_user_claim(ClaimAttribute::new(
+                "Clearance",
+                ClaimFlags::empty(),
+                ClaimValues::Uint64(vec![3]),
+            ));
+
+        let value = serde_json::to_value(&token).unwrap();
+        assert!(validator.is_valid(&value), "{}", value);
+        assert!(!validator.is_valid(&json!({ "user": { "sid": "BU" }, "group": [] })));
+        assert!(!validator
+            .is_valid(&json!({ "user": { "sid": "BU" }, "privileges": [{ "luid": "17" }] })));
+    }
+
+    #[test]
+    fn misspellings_are_invalid() {
+        let validator = validator::<Ace>();
+        let ace = |field: &str, value: Value| {
+            let mut ace = json!({
+                "type": "ACCESS_ALLOWED_ACE_TYPE",
+                "flags": ["ObjectInherit", "0x2"],
+                "mask": ["FileAllAccess"],
+                "sid": "BA",
+            });
+            ace[field] = value;
+            ace
+        };
+
+        assert!(validator.is_valid(&ace("sid", json!("S-1-5-21-1-2-3-500"))));
+        assert!(validator.is_valid(&ace("mask", json!("0x1f01ff"))));
+        assert!(validator.is_valid(&ace("flags", json!(3))));
+
+        for invalid in &[
+            ace("type", json!("ACCESS_ALLOWED")),
+            ace("mask", json!(["FileAllAcess"])),
+            ace("mask", json!("0x1f01fg")),
+            ace("flags", json!(["ObjectInheritance"])),
+            ace("sid", json!("S-1-5")),
+            ace("sid", json!("Administrators")),
+            ace("object_type", json!("bf967a86-0de6-11d0-a285")),
+            ace("acess_mask", json!("0x1")),
+        ] {
+            assert!(!validator.is_valid(invalid), "{}", invalid);
+        }
+    }
+}
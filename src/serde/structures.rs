@@ -0,0 +1,412 @@
+use crate::build::{self, SecurityDescriptorParts};
+use crate::constants::{AccessRights, AceFlags, AceType, SecurityDescriptorControl};
+use crate::{Ace, Acl, Condition, Guid, LocalBox, SecurityDescriptor, Sid};
+use ::serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::io;
+
+impl<T: Serialize> Serialize for LocalBox<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+/// Deserialize a string and parse it with `FromStr`
+fn parse<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: std::str::FromStr<Err = io::Error>,
+    D: Deserializer<'de>,
+{
+    Cow::<str>::deserialize(deserializer)?
+        .parse()
+        .map_err(de::Error::custom)
+}
+
+impl Serialize for Sid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LocalBox<Sid> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        parse(deserializer)
+    }
+}
+
+impl Serialize for Guid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Guid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        parse(deserializer)
+    }
+}
+
+impl Serialize for Condition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Condition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        parse(deserializer)
+    }
+}
+
+/// The fields of an ACE
+///
+/// `S` is `&Sid` when serializing and `LocalBox<Sid>` when deserializing.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields, bound(deserialize = "S: Deserialize<'de>"))]
+struct AceRepr<S> {
+    #[serde(rename = "type")]
+    ace_type: AceType,
+    #[serde(default = "AceFlags::empty")]
+    flags: AceFlags,
+    mask: AccessRights,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sid: Option<S>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    object_type: Option<Guid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inherited_object_type: Option<Guid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    condition: Option<Condition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    application_data: Option<String>,
+}
+
+impl<'a> From<&'a Ace> for AceRepr<&'a Sid> {
+    fn from(ace: &'a Ace) -> Self {
+        let data = ace.application_data();
+        let (condition, application_data) = match ace.condition() {
+            Some(Ok(condition)) => (Some(condition), None),
+            _ if data.is_empty() => (None, None),
+            _ => (
+                None,
+                Some(data.iter().map(|b| format!("{:02x}", b)).collect()),
+            ),
+        };
+
+        AceRepr {
+            ace_type: ace.ace_type(),
+            flags: ace.flags(),
+            mask: ace.mask(),
+            sid: ace.sid(),
+            object_type: ace.object_type(),
+            inherited_object_type: ace.inherited_object_type(),
+            condition,
+            application_data,
+        }
+    }
+}
+
+impl AceRepr<LocalBox<Sid>> {
+    /// Build the binary form of the ACE
+    fn to_bytes<E: de::Error>(&self) -> Result<Vec<u8>, E> {
+        let ace_type = self.ace_type as u8;
+
+        if !build::is_object_ace_type(ace_type)
+            && (self.object_type.is_some() || self.inherited_object_type.is_some())
+        {
+            return Err(E::custom(format!(
+                "{:?} cannot have an object type",
+                self.ace_type
+            )));
+        }
+
+        if !build::is_callback_ace_type(ace_type) && self.condition.is_some() {
+            return Err(E::custom(format!(
+                "{:?} cannot have a condition",
+                self.ace_type
+            )));
+        }
+
+        let sid = match (&self.sid, self.ace_type) {
+            (Some(sid), _) => sid.to_bytes(),
+            (None, AceType::SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE) => {
+                build::sid([0, 0, 0, 0, 0, 1], &[0])
+            }
+            (None, _) => return Err(E::missing_field("sid")),
+        };
+
+        let data = match (&self.condition, &self.application_data) {
+            (Some(_), Some(_)) => {
+                return Err(E::custom(
+                    "an ACE cannot have both a condition and application data",
+                ))
+            }
+            (Some(condition), None) => condition.to_bytes(),
+            (None, Some(hex)) => parse_hex(hex)
+                .ok_or_else(|| E::invalid_value(de::Unexpected::Str(hex), &"bytes in hex"))?,
+            (None, None) => vec![],
+        };
+
+        let ace = build::ace(
+            ace_type,
+            self.flags.bits(),
+            self.mask.bits(),
+            self.object_type.map(Guid::to_bytes),
+            self.inherited_object_type.map(Guid::to_bytes),
+            &sid,
+            &data,
+        );
+
+        if ace.len() > u16::MAX as usize {
+            return Err(E::custom("ACE is too large"));
+        }
+
+        Ok(ace)
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+impl Serialize for Ace {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        AceRepr::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LocalBox<Ace> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = AceRepr::<LocalBox<Sid>>::deserialize(deserializer)?.to_bytes()?;
+        unsafe { LocalBox::from_bytes(&bytes) }.map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Acl {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.aces().map(AceRepr::from))
+    }
+}
+
+/// Build the binary form of an ACL from the fields of its ACEs
+fn acl_bytes<E: de::Error>(aces: &[AceRepr<LocalBox<Sid>>]) -> Result<Vec<u8>, E> {
+    let aces = aces
+        .iter()
+        .map(AceRepr::to_bytes)
+        .collect::<Result<Vec<_>, E>>()?;
+    let acl = build::acl(&aces);
+
+    if acl.len() > u16::MAX as usize {
+        return Err(E::custom("ACL is too large"));
+    }
+
+    Ok(acl)
+}
+
+impl<'de> Deserialize<'de> for LocalBox<Acl> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let aces = Vec::<AceRepr<LocalBox<Sid>>>::deserialize(deserializer)?;
+        let bytes = acl_bytes(&aces)?;
+        unsafe { LocalBox::from_bytes(&bytes) }.map_err(de::Error::custom)
+    }
+}
+
+/// The fields of a security descriptor
+///
+/// `S` and `A` are `&Sid` and `&Acl` when serializing, and `LocalBox<Sid>`
+/// and `Vec<AceRepr<LocalBox<Sid>>>` when deserializing.
+#[derive(Serialize, Deserialize)]
+#[serde(
+    deny_unknown_fields,
+    bound(deserialize = "S: Deserialize<'de>, A: Deserialize<'de>")
+)]
+struct SecurityDescriptorRepr<S, A> {
+    #[serde(default = "SecurityDescriptorControl::empty")]
+    control: SecurityDescriptorControl,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    owner: Option<S>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<S>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dacl: Option<A>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sacl: Option<A>,
+}
+
+impl Serialize for SecurityDescriptor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let repr = SecurityDescriptorRepr {
+            control: self.control() - SecurityDescriptorControl::SelfRelative,
+            owner: self.owner(),
+            group: self.group(),
+            dacl: self.non_null_dacl(),
+            sacl: self.non_null_sacl(),
+        };
+
+        repr.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LocalBox<SecurityDescriptor> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr =
+            SecurityDescriptorRepr::<LocalBox<Sid>, Vec<AceRepr<LocalBox<Sid>>>>::deserialize(
+                deserializer,
+            )?;
+
+        let mut control = repr.control;
+        if repr.dacl.is_some() {
+            control |= SecurityDescriptorControl::DaclPresent;
+        }
+        if repr.sacl.is_some() {
+            control |= SecurityDescriptorControl::SaclPresent;
+        }
+
+        let owner = repr.owner.as_ref().map(|sid| sid.to_bytes());
+        let group = repr.group.as_ref().map(|sid| sid.to_bytes());
+        let dacl = repr.dacl.as_deref().map(acl_bytes).transpose()?;
+        let sacl = repr.sacl.as_deref().map(acl_bytes).transpose()?;
+
+        let bytes = build::security_descriptor(&SecurityDescriptorParts {
+            control: control.bits(),
+            owner: owner.as_deref(),
+            group: group.as_deref(),
+            sacl: sacl.as_deref(),
+            dacl: dacl.as_deref(),
+        });
+        unsafe { LocalBox::from_bytes(&bytes) }.map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static SDDL_CASES: &[&str] = &[
+        "",
+        "O:BAG:SY",
+        "D:NO_ACCESS_CONTROL",
+        "D:PAI(A;OICI;FA;;;SY)(D;ID;0x100116;;;WD)S:(ML;;NW;;;HI)(AU;SAFA;FA;;;WD)",
+        "D:(OA;CI;RP;bf967a86-0de6-11d0-a285-00aa003049e2;bf967aba-0de6-11d0-a285-00aa003049e2;AU)",
+        "D:(XA;;FA;;;WD;(Member_of {SID(BA)}))",
+        r#"S:(RA;;;;;WD;("Secrecy",TU,0x0,3))"#,
+    ];
+
+    #[test]
+    fn round_trip() {
+        for sddl in SDDL_CASES {
+            let sd: LocalBox<SecurityDescriptor> = sddl.parse().unwrap();
+
+            let json = serde_json::to_string(&sd).unwrap();
+            let from_json: LocalBox<SecurityDescriptor> = serde_json::from_str(&json).unwrap();
+            assert_eq!(
+                from_json.as_sddl().unwrap(),
+                sd.as_sddl().unwrap(),
+                "{}",
+                json
+            );
+
+            let yaml = serde_yaml::to_string(&sd).unwrap();
+            let from_yaml: LocalBox<SecurityDescriptor> = serde_yaml::from_str(&yaml).unwrap();
+            assert_eq!(
+                from_yaml.as_sddl().unwrap(),
+                sd.as_sddl().unwrap(),
+                "{}",
+                yaml
+            );
+
+            for acl in sd.non_null_dacl().into_iter().chain(sd.non_null_sacl()) {
+                let json = serde_json::to_string(acl).unwrap();
+                let from_json: LocalBox<Acl> = serde_json::from_str(&json).unwrap();
+                assert_eq!(from_json.as_bytes(), acl.as_bytes());
+
+                for ace in acl.aces() {
+                    let json = serde_json::to_string(ace).unwrap();
+                    let from_json: LocalBox<Ace> = serde_json::from_str(&json).unwrap();
+                    assert_eq!(from_json.as_bytes(), ace.as_bytes());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn representation() {
+        let sd: LocalBox<SecurityDescriptor> =
+            "O:BAD:P(A;OICI;FA;;;SY)(XD;;FX;;;WD;(@User.clearance < 3))"
+                .parse()
+                .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&sd).unwrap(),
+            serde_json::json!({
+                "control": ["DaclPresent", "DaclProtected"],
+                "owner": "S-1-5-32-544",
+                "dacl": [
+                    {
+                        "type": "ACCESS_ALLOWED_ACE_TYPE",
+                        "flags": ["ObjectInherit", "ContainerInherit"],
+                        "mask": "0x1f01ff",
+                        "sid": "S-1-5-18",
+                    },
+                    {
+                        "type": "ACCESS_DENIED_CALLBACK_ACE_TYPE",
+                        "flags": [],
+                        "mask": "0x1200a0",
+                        "sid": "S-1-1-0",
+                        "condition": "(@User.clearance < 3)",
+                    },
+                ],
+            })
+        );
+
+        // Names, hex, aliases and defaults are accepted when reading
+        let read: LocalBox<SecurityDescriptor> = serde_json::from_value(serde_json::json!({
+            "owner": "BA",
+            "dacl": [{
+                "type": "ACCESS_ALLOWED_ACE_TYPE",
+                "flags": "0x3",
+                "mask": ["FileAllAccess"],
+                "sid": "SY",
+            }],
+        }))
+        .unwrap();
+        assert_eq!(read.as_sddl().unwrap(), "O:BAD:(A;OICI;FA;;;SY)");
+    }
+
+    #[test]
+    fn errors() {
+        let errors = [
+            // Misspelled names
+            r#"{"type": "ACCESS_ALLOWED", "mask": "0x1", "sid": "SY"}"#,
+            r#"{"type": "ACCESS_ALLOWED_ACE_TYPE", "mask": ["FileAllAcess"], "sid": "SY"}"#,
+            r#"{"type": "ACCESS_ALLOWED_ACE_TYPE", "mask": "0x1", "sid": "SY", "flag": []}"#,
+            // Bad values
+            r#"{"type": "ACCESS_ALLOWED_ACE_TYPE", "mask": "0x1", "sid": "S-1-X"}"#,
+            r#"{"type": "ACCESS_ALLOWED_ACE_TYPE", "flags": "0x20", "mask": "0x1", "sid": "SY"}"#,
+            r#"{"type": "ACCESS_ALLOWED_ACE_TYPE", "mask": "0x1", "sid": "SY", "application_data": "0"}"#,
+            // Fields that do not fit the ACE type
+            r#"{"type": "ACCESS_ALLOWED_ACE_TYPE", "mask": "0x1"}"#,
+            r#"{"type": "ACCESS_ALLOWED_ACE_TYPE", "mask": "0x1", "sid": "SY",
+                "object_type": "bf967aba-0de6-11d0-a285-00aa003049e2"}"#,
+            r#"{"type": "ACCESS_ALLOWED_CALLBACK_ACE_TYPE", "mask": "0x1", "sid": "SY",
+                "condition": "(Member_of {SID(BA)})", "application_data": "00"}"#,
+            r#"{"type": "ACCESS_ALLOWED_ACE_TYPE", "mask": "0x1", "sid": "SY",
+                "condition": "(Member_of {SID(BA)})"}"#,
+        ];
+
+        for json in &errors {
+            assert!(
+                serde_json::from_str::<LocalBox<Ace>>(json).is_err(),
+                "{}",
+                json
+            );
+        }
+    }
+}
@@ -1,9 +1,18 @@
-use crate::constants::{AccessRights, AceFlags, AceType};
-use crate::Sid;
+use crate::constants::{AccessRights, AceFlags, AceType, ClaimFlags};
+use crate::structures::condition::SIGNATURE;
+use crate::token::{ClaimAttribute, ClaimValues};
+use crate::winnt::{
+    ACE_HEADER, ACE_INHERITED_OBJECT_TYPE_PRESENT, ACE_OBJECT_TYPE_PRESENT,
+    CLAIM_SECURITY_ATTRIBUTE_TYPE_BOOLEAN, CLAIM_SECURITY_ATTRIBUTE_TYPE_FQBN,
+    CLAIM_SECURITY_ATTRIBUTE_TYPE_INT64, CLAIM_SECURITY_ATTRIBUTE_TYPE_OCTET_STRING,
+    CLAIM_SECURITY_ATTRIBUTE_TYPE_SID, CLAIM_SECURITY_ATTRIBUTE_TYPE_STRING,
+    CLAIM_SECURITY_ATTRIBUTE_TYPE_UINT64,
+};
+use crate::{Condition, Guid, Sid};
 use std::fmt;
+use std::io;
 use std::mem;
 use std::ptr::NonNull;
-use winapi::um::winnt::ACE_HEADER;
 
 /// An access control list.
 ///
@@ -44,109 +53,200 @@ impl Ace {
         AceFlags::from_bits_truncate(self.header.AceFlags)
     }
 
-    /// Get the access mask if it is available for this ACE type
-    pub fn mask(&self) -> AccessRights {
-        use winapi::um::winnt::*;
-
-        macro_rules! mask_mapping {
-            ($slf:ident ; $($t:ident => $b:ty),*) => {{
-                match $slf.ace_type() {
-                    $(
-                    AceType::$t => AccessRights::from_bits_truncate(
-                        (*(&$slf.header as *const _ as *mut $b)).Mask,
-                    ),
-                    )*
-                }
-            }}
+    /// Get the raw bytes of the ACE, including the header
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        unsafe {
+            std::slice::from_raw_parts(
+                &self.header as *const ACE_HEADER as *const u8,
+                self.header.AceSize as usize,
+            )
         }
+    }
 
-        unsafe {
-            mask_mapping! {self;
-                ACCESS_ALLOWED_ACE_TYPE => ACCESS_ALLOWED_ACE,
-                ACCESS_ALLOWED_CALLBACK_ACE_TYPE => ACCESS_ALLOWED_CALLBACK_ACE,
-                ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE => ACCESS_ALLOWED_CALLBACK_OBJECT_ACE,
-                ACCESS_ALLOWED_OBJECT_ACE_TYPE => ACCESS_ALLOWED_OBJECT_ACE,
-                ACCESS_DENIED_ACE_TYPE => ACCESS_DENIED_ACE,
-                ACCESS_DENIED_CALLBACK_ACE_TYPE => ACCESS_DENIED_ACE,
-                ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE => ACCESS_DENIED_CALLBACK_OBJECT_ACE,
-                ACCESS_DENIED_OBJECT_ACE_TYPE => ACCESS_DENIED_OBJECT_ACE,
-                SYSTEM_AUDIT_ACE_TYPE => SYSTEM_AUDIT_ACE,
-                SYSTEM_AUDIT_CALLBACK_ACE_TYPE => SYSTEM_AUDIT_CALLBACK_ACE,
-                SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE => SYSTEM_AUDIT_CALLBACK_ACE,
-                SYSTEM_AUDIT_OBJECT_ACE_TYPE => SYSTEM_AUDIT_OBJECT_ACE,
-                SYSTEM_MANDATORY_LABEL_ACE_TYPE => SYSTEM_MANDATORY_LABEL_ACE,
-                SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE => SYSTEM_RESOURCE_ATTRIBUTE_ACE,
-                SYSTEM_SCOPED_POLICY_ID_ACE_TYPE => SYSTEM_SCOPED_POLICY_ID_ACE
-            }
+    /// Read a little-endian `u32` from the ACE body
+    fn read_u32(&self, offset: usize) -> u32 {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(&self.as_bytes()[offset..offset + 4]);
+        u32::from_le_bytes(bytes)
+    }
+
+    /// Check whether this is one of the object ACE types, which have object
+    /// type GUIDs between the mask and the SID
+    pub(crate) fn is_object_ace(&self) -> bool {
+        matches!(
+            self.ace_type(),
+            AceType::ACCESS_ALLOWED_OBJECT_ACE_TYPE
+                | AceType::ACCESS_DENIED_OBJECT_ACE_TYPE
+                | AceType::SYSTEM_AUDIT_OBJECT_ACE_TYPE
+                | AceType::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE
+                | AceType::ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE
+                | AceType::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE
+        )
+    }
+
+    /// Get the offset of a GUID in an object ACE, if the flag is set
+    fn guid_offset(&self, flag: u32) -> Option<usize> {
+        if !self.is_object_ace() {
+            return None;
+        }
+
+        let flags = self.read_u32(8);
+        if flags & flag == 0 {
+            return None;
+        }
+
+        // The object type comes first if both are present
+        if flag == ACE_INHERITED_OBJECT_TYPE_PRESENT && flags & ACE_OBJECT_TYPE_PRESENT != 0 {
+            Some(28)
+        } else {
+            Some(12)
+        }
+    }
+
+    fn read_guid(&self, offset: usize) -> Guid {
+        let mut guid = [0u8; 16];
+        guid.copy_from_slice(&self.as_bytes()[offset..offset + 16]);
+        Guid::from_bytes(guid)
+    }
+
+    /// Get the object type of an object ACE, if present
+    ///
+    /// This is the property, property set, or object class that the ACE
+    /// applies to. ACEs without one apply to the whole object.
+    ///
+    /// ```
+    /// use windows_permissions::{Guid, LocalBox, SecurityDescriptor};
+    ///
+    /// let sd: LocalBox<SecurityDescriptor> =
+    ///     "D:(OA;;RP;bf967a86-0de6-11d0-a285-00aa003049e2;;AU)(A;;RC;;;AU)".parse().unwrap();
+    /// let dacl = sd.dacl().unwrap();
+    ///
+    /// assert_eq!(
+    ///     dacl.get_ace(0).unwrap().object_type(),
+    ///     Some("bf967a86-0de6-11d0-a285-00aa003049e2".parse::<Guid>().unwrap())
+    /// );
+    /// assert_eq!(dacl.get_ace(1).unwrap().object_type(), None);
+    /// ```
+    pub fn object_type(&self) -> Option<Guid> {
+        self.guid_offset(ACE_OBJECT_TYPE_PRESENT)
+            .map(|offset| self.read_guid(offset))
+    }
+
+    /// Get the inherited object type of an object ACE, if present
+    ///
+    /// This is the type of child object that can inherit the ACE.
+    pub fn inherited_object_type(&self) -> Option<Guid> {
+        self.guid_offset(ACE_INHERITED_OBJECT_TYPE_PRESENT)
+            .map(|offset| self.read_guid(offset))
+    }
+
+    /// Get the offset of the SID within the ACE
+    fn sid_offset(&self) -> usize {
+        if self.is_object_ace() {
+            let flags = self.read_u32(8);
+            let guids = (flags & ACE_OBJECT_TYPE_PRESENT != 0) as usize
+                + (flags & ACE_INHERITED_OBJECT_TYPE_PRESENT != 0) as usize;
+            12 + 16 * guids
+        } else {
+            8
         }
     }
 
+    /// Get the data following the SID, such as the condition of a callback
+    /// ACE or the attribute of a resource attribute ACE
+    pub(crate) fn application_data(&self) -> &[u8] {
+        let sid_len = crate::wrappers::GetSidLengthRequired(self.raw_sid().sub_authority_count());
+        &self.as_bytes()[self.sid_offset() + sid_len..]
+    }
+
+    /// Get the SID stored in the ACE, even for ACE types where it has no
+    /// meaning
+    pub(crate) fn raw_sid(&self) -> &Sid {
+        let offset = self.sid_offset();
+        unsafe { &*(self.as_bytes()[offset..].as_ptr() as *const Sid) }
+    }
+
+    /// Get the access mask if it is available for this ACE type
+    ///
+    /// Every ACE type stores its mask directly after the header.
+    pub fn mask(&self) -> AccessRights {
+        AccessRights::from_bits_truncate(self.read_u32(4))
+    }
+
     /// Get the SID if it is available for this ACE type
+    ///
+    /// The SID follows the mask, or for object ACE types, the object type
+    /// GUIDs that are present.
     pub fn sid(&self) -> Option<&Sid> {
-        use winapi::um::winnt::*;
-
-        macro_rules! get_sid {
-            ($slf:ident ; $ace_type:ty ; $sid_field:ident ) => {
-                Some(
-                    &*(&(*(&$slf.header as *const ACE_HEADER as *const $ace_type)).$sid_field
-                        as *const _ as *const Sid),
-                )
-            };
-            ($slf:ident ; $ace_type:ty) => {
-                get_sid!($slf ; $ace_type ; SidStart)
-            };
-            ($slf:ident ; $ace_type:ty ; $field_none:ident , $field_one:ident, $field_both:ident) => {{
-                let flags = (*(&$slf.header as *const ACE_HEADER as *const $ace_type)).Flags;
-                let obj_pres = flags & winapi::um::winnt::ACE_OBJECT_TYPE_PRESENT != 0;
-                let inh_pres = flags & winapi::um::winnt::ACE_INHERITED_OBJECT_TYPE_PRESENT != 0;
-                match (obj_pres, inh_pres) {
-                    (false, false) => get_sid!($slf ; $ace_type ; $field_none),
-                    (true,  false) => get_sid!($slf ; $ace_type ; $field_one),
-                    (false, true ) => get_sid!($slf ; $ace_type ; $field_one),
-                    (true,  true ) => get_sid!($slf ; $ace_type ; $field_both),
-                }}
-            };
+        match self.ace_type() {
+            // The SID of a resource attribute ACE is always Everyone, and
+            // carries no meaning
+            AceType::SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE => None,
+            _ => Some(self.raw_sid()),
         }
+    }
 
-        unsafe {
-            match self.ace_type() {
-                AceType::ACCESS_ALLOWED_ACE_TYPE => get_sid!(self; ACCESS_ALLOWED_ACE),
-                AceType::ACCESS_ALLOWED_CALLBACK_ACE_TYPE => {
-                    get_sid!(self; ACCESS_ALLOWED_CALLBACK_ACE)
-                }
-                AceType::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE => {
-                    get_sid!(self; ACCESS_ALLOWED_CALLBACK_OBJECT_ACE;
-                    ObjectType, InheritedObjectType, SidStart)
-                }
-                AceType::ACCESS_ALLOWED_OBJECT_ACE_TYPE => {
-                    get_sid!(self; ACCESS_ALLOWED_OBJECT_ACE;
-                    ObjectType, InheritedObjectType, SidStart)
-                }
-                AceType::ACCESS_DENIED_ACE_TYPE => get_sid!(self; ACCESS_DENIED_ACE),
-                AceType::ACCESS_DENIED_CALLBACK_ACE_TYPE => get_sid!(self; ACCESS_DENIED_ACE),
-                AceType::ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE => {
-                    get_sid!(self; ACCESS_DENIED_CALLBACK_OBJECT_ACE;
-                    ObjectType, InheritedObjectType, SidStart)
-                }
-                AceType::ACCESS_DENIED_OBJECT_ACE_TYPE => get_sid!(self; ACCESS_DENIED_OBJECT_ACE;
-                    ObjectType, InheritedObjectType, SidStart),
-                AceType::SYSTEM_AUDIT_ACE_TYPE => get_sid!(self; SYSTEM_AUDIT_ACE),
-                AceType::SYSTEM_AUDIT_CALLBACK_ACE_TYPE => {
-                    get_sid!(self; SYSTEM_AUDIT_CALLBACK_ACE)
-                }
-                AceType::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE => {
-                    get_sid!(self; SYSTEM_AUDIT_CALLBACK_ACE; SidStart)
-                }
-                AceType::SYSTEM_AUDIT_OBJECT_ACE_TYPE => get_sid!(self; SYSTEM_AUDIT_OBJECT_ACE;
-                    ObjectType, InheritedObjectType, SidStart),
-                AceType::SYSTEM_MANDATORY_LABEL_ACE_TYPE => {
-                    get_sid!(self; SYSTEM_MANDATORY_LABEL_ACE)
-                }
-                AceType::SYSTEM_SCOPED_POLICY_ID_ACE_TYPE => {
-                    get_sid!(self; SYSTEM_SCOPED_POLICY_ID_ACE)
-                }
-                AceType::SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE => None, // TODO: Resource attributes are more complex
-            }
+    /// Check whether this is one of the callback ACE types, which can carry
+    /// a condition
+    pub(crate) fn is_callback_ace(&self) -> bool {
+        matches!(
+            self.ace_type(),
+            AceType::ACCESS_ALLOWED_CALLBACK_ACE_TYPE
+                | AceType::ACCESS_DENIED_CALLBACK_ACE_TYPE
+                | AceType::SYSTEM_AUDIT_CALLBACK_ACE_TYPE
+                | AceType::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE
+                | AceType::ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE
+                | AceType::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE
+        )
+    }
+
+    /// Get the condition of a callback ACE
+    ///
+    /// Returns `None` for other ACE types, and for callback ACEs whose
+    /// application data is not a conditional expression.
+    ///
+    /// ```
+    /// use windows_permissions::{Condition, LocalBox, SecurityDescriptor};
+    ///
+    /// let sd: LocalBox<SecurityDescriptor> =
+    ///     "D:(XA;;FA;;;WD;(Member_of {SID(BA)}))(A;;FR;;;WD)".parse().unwrap();
+    /// let dacl = sd.dacl().unwrap();
+    ///
+    /// let condition = dacl.get_ace(0).unwrap().condition().unwrap().unwrap();
+    /// assert_eq!(condition, "(Member_of {SID(BA)})".parse::<Condition>().unwrap());
+    /// assert!(dacl.get_ace(1).unwrap().condition().is_none());
+    /// ```
+    pub fn condition(&self) -> Option<io::Result<Condition>> {
+        let data = self.application_data();
+
+        if self.is_callback_ace() && data.starts_with(SIGNATURE) {
+            Some(Condition::from_bytes(data))
+        } else {
+            None
+        }
+    }
+
+    /// Get the attribute of a resource attribute ACE
+    ///
+    /// Returns `None` for other ACE types.
+    ///
+    /// ```
+    /// use windows_permissions::{LocalBox, SecurityDescriptor};
+    /// use windows_permissions::token::ClaimValues;
+    ///
+    /// let sd: LocalBox<SecurityDescriptor> =
+    ///     r#"S:(RA;;;;;WD;("Secrecy",TU,0x0,3))"#.parse().unwrap();
+    /// let ace = sd.sacl().unwrap().get_ace(0).unwrap();
+    ///
+    /// let attribute = ace.resource_attribute().unwrap().unwrap();
+    /// assert_eq!(attribute.name(), "Secrecy");
+    /// assert_eq!(attribute.values(), &ClaimValues::Uint64(vec![3]));
+    /// ```
+    pub fn resource_attribute(&self) -> Option<io::Result<ClaimAttribute>> {
+        if self.ace_type() == AceType::SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE {
+            Some(read_claim_attribute(self.application_data()))
+        } else {
+            None
         }
     }
 }
@@ -160,10 +260,102 @@ impl fmt::Debug for Ace {
     }
 }
 
+/// Read the self-relative form of a claim, a
+/// `CLAIM_SECURITY_ATTRIBUTE_RELATIVE_V1` structure
+pub(crate) fn read_claim_attribute(data: &[u8]) -> io::Result<ClaimAttribute> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "Invalid resource attribute");
+
+    let bytes_at = |offset: usize, len: usize| {
+        offset
+            .checked_add(len)
+            .and_then(|end| data.get(offset..end))
+            .ok_or_else(invalid)
+    };
+    let u32_at = |offset: usize| -> io::Result<u32> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(bytes_at(offset, 4)?);
+        Ok(u32::from_le_bytes(bytes))
+    };
+    let u64_at = |offset: usize| -> io::Result<u64> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(bytes_at(offset, 8)?);
+        Ok(u64::from_le_bytes(bytes))
+    };
+    let string_at = |offset: usize| -> io::Result<String> {
+        let units = data
+            .get(offset..)
+            .ok_or_else(invalid)?
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .take_while(|&unit| unit != 0)
+            .collect::<Vec<_>>();
+        String::from_utf16(&units).map_err(|_| invalid())
+    };
+    let counted_at =
+        |offset: usize| -> io::Result<&[u8]> { bytes_at(offset + 4, u32_at(offset)? as usize) };
+
+    let name = string_at(u32_at(0)? as usize)?;
+    let value_type = u32_at(4)? as u16;
+    let flags = ClaimFlags::from_bits_truncate(u32_at(8)?);
+    let offsets = (0..u32_at(12)? as usize)
+        .map(|index| u32_at(16 + 4 * index).map(|offset| offset as usize))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let values = match value_type {
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_INT64 => ClaimValues::Int64(
+            offsets
+                .iter()
+                .map(|&offset| u64_at(offset).map(|n| n as i64))
+                .collect::<io::Result<_>>()?,
+        ),
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_UINT64 => ClaimValues::Uint64(
+            offsets
+                .iter()
+                .map(|&offset| u64_at(offset))
+                .collect::<io::Result<_>>()?,
+        ),
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_BOOLEAN => ClaimValues::Boolean(
+            offsets
+                .iter()
+                .map(|&offset| u64_at(offset).map(|n| n != 0))
+                .collect::<io::Result<_>>()?,
+        ),
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_STRING => ClaimValues::String(
+            offsets
+                .iter()
+                .map(|&offset| string_at(offset))
+                .collect::<io::Result<_>>()?,
+        ),
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_FQBN => ClaimValues::Fqbn(
+            offsets
+                .iter()
+                .map(|&offset| Ok((u64_at(offset)?, string_at(u32_at(offset + 8)? as usize)?)))
+                .collect::<io::Result<_>>()?,
+        ),
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_SID => ClaimValues::Sid(
+            offsets
+                .iter()
+                .map(|&offset| Sid::from_bytes(counted_at(offset)?).ok_or_else(invalid))
+                .collect::<io::Result<_>>()?,
+        ),
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_OCTET_STRING => ClaimValues::OctetString(
+            offsets
+                .iter()
+                .map(|&offset| counted_at(offset).map(|octets| octets.to_vec()))
+                .collect::<io::Result<_>>()?,
+        ),
+        _ => return Err(invalid()),
+    };
+
+    Ok(ClaimAttribute::new(name, flags, values))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{wrappers, LocalBox, SecurityDescriptor};
+    #[cfg(target_os = "windows")]
+    use crate::wrappers;
+    use crate::{LocalBox, SecurityDescriptor};
 
     enum DaclSacl {
         Dacl,
@@ -173,6 +365,7 @@ mod test {
     use DaclSacl::{Dacl, Sacl};
 
     #[test]
+    #[cfg(target_os = "windows")]
     fn mandatory_label() {
         let access_rights = [
             ("NR", AccessRights::MandatoryLabelNoReadUp),
@@ -204,6 +397,38 @@ mod test {
         }
     }
 
+    #[test]
+    fn mandatory_label_portable() {
+        let access_rights = [
+            ("NR", AccessRights::MandatoryLabelNoReadUp),
+            ("NW", AccessRights::MandatoryLabelNoWriteUp),
+            ("NX", AccessRights::MandatoryLabelNoExecuteUp),
+        ];
+
+        let test_setups = [
+            ("(ML;;", ";;;LW)", "S-1-16-4096"),
+            ("(ML;;", ";;;ME)", "S-1-16-8192"),
+            ("(ML;;", ";;;HI)", "S-1-16-12288"),
+        ];
+
+        for (sddl1, sddl2, sid_type) in test_setups.iter() {
+            for (mask_sddl, mask_value) in access_rights.iter() {
+                let sd: LocalBox<SecurityDescriptor> = format!("S:{}{}{}", sddl1, mask_sddl, sddl2)
+                    .parse()
+                    .unwrap();
+
+                let ace = sd.sacl().unwrap().get_ace(0).unwrap();
+
+                assert_eq!(ace.ace_type(), AceType::SYSTEM_MANDATORY_LABEL_ACE_TYPE);
+                assert_eq!(ace.mask(), *mask_value);
+                assert_eq!(
+                    ace.sid().unwrap(),
+                    &*sid_type.parse::<LocalBox<Sid>>().unwrap()
+                );
+            }
+        }
+    }
+
     #[test]
     fn resource_attribute() {
         // These are weird enough that they get their own tests
@@ -213,6 +438,34 @@ mod test {
 
         assert_eq!(ace.mask(), AccessRights::empty());
         assert_eq!(ace.sid(), None);
+
+        let attribute = ace.resource_attribute().unwrap().unwrap();
+        assert_eq!(attribute.name(), "Secrecy");
+        assert_eq!(attribute.flags(), ClaimFlags::empty());
+        assert_eq!(attribute.values(), &ClaimValues::Uint64(vec![3]));
+    }
+
+    #[test]
+    fn claim_attribute_round_trip() {
+        let sid = |s: &str| s.parse::<LocalBox<Sid>>().unwrap();
+        let values = [
+            ClaimValues::Int64(vec![-1, 0, i64::MAX]),
+            ClaimValues::Uint64(vec![u64::MAX]),
+            ClaimValues::String(vec!["HBI".to_string(), "".to_string()]),
+            ClaimValues::Fqbn(vec![(7, "O=Contoso".to_string())]),
+            ClaimValues::Sid(vec![sid("BA"), sid("S-1-5-21-1-2-3-1001")]),
+            ClaimValues::Boolean(vec![true, false]),
+            ClaimValues::OctetString(vec![vec![1, 2, 3], vec![]]),
+            ClaimValues::Int64(vec![]),
+        ];
+
+        for values in values.iter() {
+            let claim = ClaimAttribute::new("Name", ClaimFlags::ValueCaseSensitive, values.clone());
+            let bytes = crate::build::claim_attribute(&claim);
+            assert_eq!(read_claim_attribute(&bytes).unwrap(), claim);
+        }
+
+        assert!(read_claim_attribute(&[0; 8]).is_err());
     }
 
     #[test]
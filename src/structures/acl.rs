@@ -1,8 +1,11 @@
-use crate::{constants, wrappers, Ace, Trustee};
+use crate::winnt::ACL;
+#[cfg(target_os = "windows")]
+use crate::Trustee;
+use crate::{constants, wrappers, Ace};
 use std::fmt;
+// Only `effective_rights` uses this outside the tests
+#[cfg_attr(not(target_os = "windows"), allow(unused_imports))]
 use std::io;
-use winapi::shared::winerror::ERROR_INVALID_PARAMETER;
-use winapi::um::winnt::ACL;
 
 /// An entry in an access control list (ACL).
 #[repr(C)]
@@ -15,6 +18,16 @@ impl Acl {
         unsafe { &*(self as *const _ as *const _) }
     }
 
+    /// Get the raw bytes of the ACL, including the header
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        unsafe {
+            std::slice::from_raw_parts(
+                self as *const Acl as *const u8,
+                self.internal_type_reference().AclSize as usize,
+            )
+        }
+    }
+
     /// Determine what rights the given `Trustee` has under this ACL
     ///
     /// ```
@@ -35,6 +48,7 @@ impl Acl {
     /// assert_eq!(acl.effective_rights(&trustee1).unwrap(), AccessRights::FileAllAccess);
     /// assert_eq!(acl.effective_rights(&trustee2).unwrap(), AccessRights::FileGenericRead);
     /// ```
+    #[cfg(target_os = "windows")]
     pub fn effective_rights(&self, trustee: &Trustee) -> io::Result<constants::AccessRights> {
         wrappers::GetEffectiveRightsFromAcl(self, trustee)
     }
@@ -82,14 +96,27 @@ impl Acl {
     /// assert!(acl.get_ace(2).is_none());
     /// ```
     pub fn get_ace(&self, index: u32) -> Option<&Ace> {
-        match wrappers::GetAce(self, index) {
-            Ok(ace) => Some(ace),
-            Err(ref e) if e.raw_os_error() == Some(ERROR_INVALID_PARAMETER as i32) => None,
-            other_err => {
-                other_err.expect("GetAce returned error on valid Ace");
-                unreachable!() // Because other_err will always fail the expect
-            }
+        if index >= self.len() {
+            return None;
         }
+
+        Some(wrappers::GetAce(self, index).expect("GetAce returned error on valid Ace"))
+    }
+
+    /// Iterate over the ACEs in this ACL
+    ///
+    /// ```
+    /// use windows_permissions::{LocalBox, SecurityDescriptor};
+    /// use windows_permissions::constants::AceType::*;
+    ///
+    /// let sd = "D:(A;;GA;;;SY)(D;;GW;;;WD)"
+    ///     .parse::<LocalBox<SecurityDescriptor>>().unwrap();
+    ///
+    /// let types: Vec<_> = sd.dacl().unwrap().aces().map(|ace| ace.ace_type()).collect();
+    /// assert_eq!(types, vec![ACCESS_ALLOWED_ACE_TYPE, ACCESS_DENIED_ACE_TYPE]);
+    /// ```
+    pub fn aces(&self) -> impl Iterator<Item = &Ace> {
+        (0..self.len()).filter_map(move |index| self.get_ace(index))
     }
 
     /// Get the ACL's revision level
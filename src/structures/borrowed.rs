@@ -0,0 +1,811 @@
+//! Borrowed views over untrusted binary security structures
+//!
+//! [`Sid`], [`Acl`], [`Ace`] and [`SecurityDescriptor`] trust the memory
+//! they point to. The views here are made from a byte slice instead, and
+//! check every size, count and offset when they are created, so bytes from
+//! the network or a disk image can be read safely. After that, reading from
+//! a view never goes out of bounds. Nothing is copied, the bytes need no
+//! particular alignment, and no Windows APIs are used.
+//!
+//! [`Sid`]: crate::Sid
+//! [`Acl`]: crate::Acl
+//! [`Ace`]: crate::Ace
+//! [`SecurityDescriptor`]: crate::SecurityDescriptor
+
+use crate::constants::{AccessRights, AceFlags, AceType, AclRevision, SecurityDescriptorControl};
+use crate::structures::ace::read_claim_attribute;
+use crate::structures::condition::SIGNATURE;
+use crate::token::ClaimAttribute;
+use crate::winnt::{
+    ACE_INHERITED_OBJECT_TYPE_PRESENT, ACE_OBJECT_TYPE_PRESENT, SECURITY_DESCRIPTOR_REVISION,
+    SE_DACL_PRESENT, SE_SACL_PRESENT, SE_SELF_RELATIVE,
+};
+use crate::{build, sddl};
+use crate::{Ace, Acl, Condition, Guid, LocalBox, SecurityDescriptor, Sid};
+use std::convert::TryFrom;
+use std::fmt;
+use std::io;
+
+fn invalid_data(reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid security structure ({})", reason),
+    )
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let bytes = bytes.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// A borrowed, validated view of a SID in its binary form
+///
+/// See [`Sid`] for the owned equivalent.
+///
+/// ```
+/// use windows_permissions::structures::well_known;
+/// use windows_permissions::SidRef;
+///
+/// let bytes = [1, 2, 0, 0, 0, 0, 0, 5, 32, 0, 0, 0, 32, 2, 0, 0];
+/// let sid = SidRef::from_bytes(&bytes).unwrap();
+///
+/// assert_eq!(sid.to_string(), "S-1-5-32-544");
+/// assert_eq!(sid.sub_authorities().collect::<Vec<_>>(), vec![32, 544]);
+/// assert_eq!(sid, *well_known::ADMINISTRATORS);
+///
+/// assert!(SidRef::from_bytes(&bytes[..15]).is_err());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SidRef<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> SidRef<'a> {
+    /// Check that `bytes` are exactly one SID, and view them
+    pub fn from_bytes(bytes: &'a [u8]) -> io::Result<Self> {
+        let sid = Self::read_prefix(bytes)?;
+
+        if sid.bytes.len() != bytes.len() {
+            return Err(invalid_data("trailing bytes after SID"));
+        }
+
+        Ok(sid)
+    }
+
+    /// View the SID at the start of `bytes`, which may be followed by other
+    /// data
+    fn read_prefix(bytes: &'a [u8]) -> io::Result<Self> {
+        let (revision, count) = match bytes {
+            [revision, count, ..] => (*revision, *count as usize),
+            _ => return Err(invalid_data("SID is truncated")),
+        };
+
+        if revision != 1 {
+            return Err(invalid_data("unknown SID revision"));
+        }
+        if count > 15 {
+            return Err(invalid_data("SID has more than 15 sub-authorities"));
+        }
+
+        bytes
+            .get(..8 + 4 * count)
+            .map(|bytes| Self { bytes })
+            .ok_or_else(|| invalid_data("SID is truncated"))
+    }
+
+    /// Get the number of sub-authorities in the SID
+    pub fn sub_authority_count(&self) -> u8 {
+        self.bytes[1]
+    }
+
+    /// Get the ID authority of the SID
+    pub fn id_authority(&self) -> &'a [u8; 6] {
+        <&[u8; 6]>::try_from(&self.bytes[2..8]).expect("SID was checked to have a header")
+    }
+
+    /// Get a sub-authority of the SID if it is available
+    ///
+    /// Returns `None` if the SID has too few sub-authorities.
+    pub fn sub_authority(&self, index: u8) -> Option<u32> {
+        read_u32(self.bytes, 8 + 4 * index as usize)
+    }
+
+    /// Iterate over the sub-authorities in the SID
+    pub fn sub_authorities(&self) -> impl ExactSizeIterator<Item = u32> + 'a {
+        self.bytes[8..].chunks_exact(4).map(|sub_auth| {
+            u32::from_le_bytes([sub_auth[0], sub_auth[1], sub_auth[2], sub_auth[3]])
+        })
+    }
+
+    /// Get the binary form of the SID
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Copy the SID into a [`Sid`]
+    pub fn to_sid(&self) -> io::Result<LocalBox<Sid>> {
+        unsafe { LocalBox::from_bytes(self.bytes) }
+    }
+}
+
+impl fmt::Debug for SidRef<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_map()
+            .entry(&"string_sid", &self.to_string())
+            .entry(&"id_auth", &self.id_authority())
+            .entry(&"sub_auth_count", &self.sub_authority_count())
+            .entry(&"sub_auths", &self.sub_authorities().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl fmt::Display for SidRef<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(&sddl::format_sid_parts(
+            self.id_authority(),
+            self.sub_authorities(),
+        ))
+    }
+}
+
+impl PartialEq<Sid> for SidRef<'_> {
+    fn eq(&self, other: &Sid) -> bool {
+        self.id_authority() == other.id_authority()
+            && self.sub_authority_count() == other.sub_authority_count()
+            && (0..self.sub_authority_count())
+                .all(|index| self.sub_authority(index) == other.sub_authority(index))
+    }
+}
+
+impl PartialEq<SidRef<'_>> for Sid {
+    fn eq(&self, other: &SidRef) -> bool {
+        other == self
+    }
+}
+
+/// A borrowed, validated view of an ACE in its binary form
+///
+/// See [`Ace`] for the owned equivalent. The ACE type must be one of the
+/// types in [`AceType`], and the ACE flags must all be known.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct AceRef<'a> {
+    bytes: &'a [u8],
+    sid_offset: usize,
+    sid: SidRef<'a>,
+}
+
+impl<'a> AceRef<'a> {
+    /// Check that `bytes` are exactly one ACE, and view them
+    pub fn from_bytes(bytes: &'a [u8]) -> io::Result<Self> {
+        let ace = Self::read_prefix(bytes)?;
+
+        if ace.bytes.len() != bytes.len() {
+            return Err(invalid_data("trailing bytes after ACE"));
+        }
+
+        Ok(ace)
+    }
+
+    /// View the ACE at the start of `bytes`, which may be followed by other
+    /// data
+    fn read_prefix(bytes: &'a [u8]) -> io::Result<Self> {
+        let truncated = || invalid_data("ACE is truncated");
+
+        let size = read_u16(bytes, 2).ok_or_else(truncated)? as usize;
+        let bytes = bytes.get(..size).ok_or_else(truncated)?;
+
+        if size < 8 {
+            return Err(invalid_data("ACE is smaller than its header and mask"));
+        }
+        if !size.is_multiple_of(4) {
+            return Err(invalid_data("ACE size is not a multiple of 4"));
+        }
+        if AceType::from_raw(bytes[0]).is_none() {
+            return Err(invalid_data("unknown ACE type"));
+        }
+        if AceFlags::from_bits(bytes[1]).is_none() {
+            return Err(invalid_data("unknown ACE flags"));
+        }
+
+        let sid_offset = if build::is_object_ace_type(bytes[0]) {
+            let flags = read_u32(bytes, 8).ok_or_else(truncated)?;
+            if flags & !(ACE_OBJECT_TYPE_PRESENT | ACE_INHERITED_OBJECT_TYPE_PRESENT) != 0 {
+                return Err(invalid_data("unknown object ACE flags"));
+            }
+            12 + 16 * flags.count_ones() as usize
+        } else {
+            8
+        };
+
+        let sid = SidRef::read_prefix(bytes.get(sid_offset..).ok_or_else(truncated)?)?;
+
+        Ok(Self {
+            bytes,
+            sid_offset,
+            sid,
+        })
+    }
+
+    /// Determine the type of ACE
+    pub fn ace_type(&self) -> AceType {
+        AceType::from_raw(self.bytes[0]).expect("ACE type was checked")
+    }
+
+    /// Get the option flags set on the ACE
+    pub fn flags(&self) -> AceFlags {
+        AceFlags::from_bits_truncate(self.bytes[1])
+    }
+
+    /// Get the access mask
+    pub fn mask(&self) -> AccessRights {
+        AccessRights::from_bits_truncate(read_u32(self.bytes, 4).expect("ACE has a mask"))
+    }
+
+    /// Get a GUID from an object ACE, if the flag is set
+    fn guid(&self, flag: u32) -> Option<Guid> {
+        if !build::is_object_ace_type(self.bytes[0]) {
+            return None;
+        }
+
+        let flags = read_u32(self.bytes, 8).expect("Object ACE has flags");
+        if flags & flag == 0 {
+            return None;
+        }
+
+        // The object type comes first if both are present
+        let offset =
+            if flag == ACE_INHERITED_OBJECT_TYPE_PRESENT && flags & ACE_OBJECT_TYPE_PRESENT != 0 {
+                28
+            } else {
+                12
+            };
+
+        let guid = <[u8; 16]>::try_from(&self.bytes[offset..offset + 16])
+            .expect("GUIDs were checked to fit before the SID");
+        Some(Guid::from_bytes(guid))
+    }
+
+    /// Get the object type of an object ACE, if present
+    pub fn object_type(&self) -> Option<Guid> {
+        self.guid(ACE_OBJECT_TYPE_PRESENT)
+    }
+
+    /// Get the inherited object type of an object ACE, if present
+    pub fn inherited_object_type(&self) -> Option<Guid> {
+        self.guid(ACE_INHERITED_OBJECT_TYPE_PRESENT)
+    }
+
+    /// Get the SID if it is available for this ACE type
+    ///
+    /// As with [`Ace::sid`], the SID of a resource attribute ACE is not
+    /// returned.
+    pub fn sid(&self) -> Option<SidRef<'a>> {
+        match self.ace_type() {
+            AceType::SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE => None,
+            _ => Some(self.sid),
+        }
+    }
+
+    /// Get the data following the SID, such as the condition of a callback
+    /// ACE or the attribute of a resource attribute ACE
+    ///
+    /// This includes the padding at the end of the ACE.
+    pub fn application_data(&self) -> &'a [u8] {
+        &self.bytes[self.sid_offset + self.sid.as_bytes().len()..]
+    }
+
+    /// Get the condition of a callback ACE
+    ///
+    /// Returns `None` for other ACE types, and for callback ACEs whose
+    /// application data is not a conditional expression.
+    pub fn condition(&self) -> Option<io::Result<Condition>> {
+        let data = self.application_data();
+        let callback = matches!(
+            self.ace_type(),
+            AceType::ACCESS_ALLOWED_CALLBACK_ACE_TYPE
+                | AceType::ACCESS_DENIED_CALLBACK_ACE_TYPE
+                | AceType::SYSTEM_AUDIT_CALLBACK_ACE_TYPE
+                | AceType::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE
+                | AceType::ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE
+                | AceType::SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE
+        );
+
+        if callback && data.starts_with(SIGNATURE) {
+            Some(Condition::from_bytes(data))
+        } else {
+            None
+        }
+    }
+
+    /// Get the attribute of a resource attribute ACE
+    ///
+    /// Returns `None` for other ACE types.
+    pub fn resource_attribute(&self) -> Option<io::Result<ClaimAttribute>> {
+        if self.ace_type() == AceType::SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE {
+            Some(read_claim_attribute(self.application_data()))
+        } else {
+            None
+        }
+    }
+
+    /// Get the binary form of the ACE, including the header
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Copy the ACE into an [`Ace`]
+    pub fn to_ace(&self) -> io::Result<LocalBox<Ace>> {
+        unsafe { LocalBox::from_bytes(self.bytes) }
+    }
+}
+
+impl fmt::Debug for AceRef<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let mut map = fmt.debug_map();
+        map.entry(&"ace_type", &self.ace_type());
+        map.entry(&"flags", &self.flags());
+        map.entry(&"mask", &self.mask());
+        map.entry(&"sid", &self.sid().map(|sid| sid.to_string()));
+        map.finish()
+    }
+}
+
+/// A borrowed, validated view of an ACL in its binary form
+///
+/// See [`Acl`] for the owned equivalent. Every ACE in the ACL is checked
+/// when the view is created.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct AclRef<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> AclRef<'a> {
+    /// Check that `bytes` are exactly one ACL, and view them
+    pub fn from_bytes(bytes: &'a [u8]) -> io::Result<Self> {
+        let acl = Self::read_prefix(bytes)?;
+
+        if acl.bytes.len() != bytes.len() {
+            return Err(invalid_data("trailing bytes after ACL"));
+        }
+
+        Ok(acl)
+    }
+
+    /// View the ACL at the start of `bytes`, which may be followed by other
+    /// data
+    fn read_prefix(bytes: &'a [u8]) -> io::Result<Self> {
+        let truncated = || invalid_data("ACL is truncated");
+
+        let size = read_u16(bytes, 2).ok_or_else(truncated)? as usize;
+        let count = read_u16(bytes, 4).ok_or_else(truncated)?;
+        let bytes = bytes.get(..size).ok_or_else(truncated)?;
+
+        if size < 8 {
+            return Err(invalid_data("ACL is smaller than its header"));
+        }
+        if !size.is_multiple_of(4) {
+            return Err(invalid_data("ACL size is not a multiple of 4"));
+        }
+        if AclRevision::from_raw(bytes[0]).is_none() {
+            return Err(invalid_data("unknown ACL revision"));
+        }
+
+        // Every ACE must fit within the ACL; anything after the last one is
+        // free space
+        let mut rest = &bytes[8..];
+        for _ in 0..count {
+            let ace = AceRef::read_prefix(rest)?;
+            rest = &rest[ace.bytes.len()..];
+        }
+
+        Ok(Self { bytes })
+    }
+
+    /// Get the ACL's revision level
+    pub fn revision_level(&self) -> AclRevision {
+        AclRevision::from_raw(self.bytes[0]).expect("ACL revision was checked")
+    }
+
+    /// Determine the number of ACEs in this ACL
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u32 {
+        read_u16(self.bytes, 4).expect("ACL has a header") as u32
+    }
+
+    /// Get an ACE by index
+    ///
+    /// Returns `None` if there are too few ACEs to satisfy the request.
+    pub fn get_ace(&self, index: u32) -> Option<AceRef<'a>> {
+        self.aces().nth(index as usize)
+    }
+
+    /// Iterate over the ACEs in this ACL
+    pub fn aces(&self) -> impl Iterator<Item = AceRef<'a>> + 'a {
+        let mut rest = &self.bytes[8..];
+
+        (0..self.len()).map(move |_| {
+            let ace = AceRef::read_prefix(rest).expect("ACEs were checked");
+            rest = &rest[ace.bytes.len()..];
+            ace
+        })
+    }
+
+    /// Get the binary form of the ACL, including the header
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Copy the ACL into an [`Acl`]
+    pub fn to_acl(&self) -> io::Result<LocalBox<Acl>> {
+        unsafe { LocalBox::from_bytes(self.bytes) }
+    }
+}
+
+impl fmt::Debug for AclRef<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list().entries(self.aces()).finish()
+    }
+}
+
+/// A borrowed, validated view of a self-relative security descriptor
+///
+/// See [`SecurityDescriptor`] for the owned equivalent. The owner, group
+/// and ACLs are checked when the view is created. Bytes after them are
+/// allowed, since a security descriptor does not record its own size.
+///
+/// ```
+/// use windows_permissions::constants::{AccessRights, AceType};
+/// use windows_permissions::structures::well_known;
+/// use windows_permissions::SecurityDescriptorRef;
+///
+/// // O:SYD:(A;;FA;;;BU)
+/// let bytes = [
+///     1, 0, 0x04, 0x80, 52, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20, 0, 0, 0,
+///     // DACL
+///     2, 0, 32, 0, 1, 0, 0, 0,
+///     0, 0, 24, 0, 0xff, 0x01, 0x1f, 0x00,
+///     1, 2, 0, 0, 0, 0, 0, 5, 32, 0, 0, 0, 33, 2, 0, 0,
+///     // Owner
+///     1, 1, 0, 0, 0, 0, 0, 5, 18, 0, 0, 0,
+/// ];
+///
+/// let sd = SecurityDescriptorRef::from_bytes(&bytes).unwrap();
+/// assert_eq!(sd.owner().unwrap(), *well_known::LOCAL_SYSTEM);
+/// assert!(sd.group().is_none());
+///
+/// for ace in sd.dacl().unwrap().aces() {
+///     assert_eq!(ace.ace_type(), AceType::ACCESS_ALLOWED_ACE_TYPE);
+///     assert_eq!(ace.mask(), AccessRights::FileAllAccess);
+///     assert_eq!(ace.sid().unwrap(), *well_known::USERS);
+/// }
+///
+/// // Any truncation is caught
+/// assert!(SecurityDescriptorRef::from_bytes(&bytes[..60]).is_err());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SecurityDescriptorRef<'a> {
+    bytes: &'a [u8],
+    owner: Option<SidRef<'a>>,
+    group: Option<SidRef<'a>>,
+    sacl: Option<AclRef<'a>>,
+    dacl: Option<AclRef<'a>>,
+}
+
+impl<'a> SecurityDescriptorRef<'a> {
+    /// Check that `bytes` start with a self-relative security descriptor,
+    /// and view them
+    pub fn from_bytes(bytes: &'a [u8]) -> io::Result<Self> {
+        let header = |offset| {
+            read_u32(bytes, offset).ok_or_else(|| invalid_data("security descriptor is truncated"))
+        };
+
+        let [owner, group, sacl, dacl] = [header(4)?, header(8)?, header(12)?, header(16)?];
+        let control = read_u16(bytes, 2).expect("Header was checked");
+
+        if bytes[0] as u32 != SECURITY_DESCRIPTOR_REVISION {
+            return Err(invalid_data("unknown security descriptor revision"));
+        }
+        if control & SE_SELF_RELATIVE == 0 {
+            return Err(invalid_data("security descriptor is not self-relative"));
+        }
+
+        // A zero offset means the part is absent
+        let part = |offset: u32| -> io::Result<Option<&'a [u8]>> {
+            match offset as usize {
+                0 => Ok(None),
+                offset if offset < 20 => Err(invalid_data("offset points into the header")),
+                offset if !offset.is_multiple_of(4) => {
+                    Err(invalid_data("offset is not a multiple of 4"))
+                }
+                offset => bytes
+                    .get(offset..)
+                    .filter(|part| !part.is_empty())
+                    .map(Some)
+                    .ok_or_else(|| invalid_data("offset is out of bounds")),
+            }
+        };
+        let acl = |present: u16, offset: u32| -> io::Result<Option<AclRef<'a>>> {
+            match part(offset)? {
+                Some(part) if control & present != 0 => AclRef::read_prefix(part).map(Some),
+                _ => Ok(None),
+            }
+        };
+
+        Ok(Self {
+            bytes,
+            owner: part(owner)?.map(SidRef::read_prefix).transpose()?,
+            group: part(group)?.map(SidRef::read_prefix).transpose()?,
+            sacl: acl(SE_SACL_PRESENT, sacl)?,
+            dacl: acl(SE_DACL_PRESENT, dacl)?,
+        })
+    }
+
+    /// Get the control flags
+    pub fn control(&self) -> SecurityDescriptorControl {
+        SecurityDescriptorControl::from_bits_truncate(
+            read_u16(self.bytes, 2).expect("Header was checked"),
+        )
+    }
+
+    /// Get the owner SID if it exists
+    pub fn owner(&self) -> Option<SidRef<'a>> {
+        self.owner
+    }
+
+    /// Get the group SID if it exists
+    pub fn group(&self) -> Option<SidRef<'a>> {
+        self.group
+    }
+
+    /// Get the DACL if it exists
+    ///
+    /// A present but null DACL is returned as `None`; the `DaclPresent`
+    /// control flag tells it apart from a missing one.
+    pub fn dacl(&self) -> Option<AclRef<'a>> {
+        self.dacl
+    }
+
+    /// Get the SACL if it exists
+    ///
+    /// A present but null SACL is returned as `None`; the `SaclPresent`
+    /// control flag tells it apart from a missing one.
+    pub fn sacl(&self) -> Option<AclRef<'a>> {
+        self.sacl
+    }
+
+    /// Get the bytes the view was created from
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Copy the security descriptor into a [`SecurityDescriptor`]
+    pub fn to_security_descriptor(&self) -> io::Result<LocalBox<SecurityDescriptor>> {
+        unsafe { LocalBox::from_bytes(self.bytes) }
+    }
+}
+
+impl fmt::Debug for SecurityDescriptorRef<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_map()
+            .entry(&"control", &self.control())
+            .entry(&"owner", &self.owner())
+            .entry(&"group", &self.group())
+            .entry(&"dacl", &self.dacl())
+            .entry(&"sacl", &self.sacl())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SDDL: &[&str] = &[
+        "O:BAG:SYD:PAI(A;OICI;FA;;;SY)(D;ID;0x100116;;;WD)S:(ML;;NW;;;HI)",
+        "O:S-1-5-21-1-2-3-500D:(OA;CI;RP;bf967a86-0de6-11d0-a285-00aa003049e2;;AU)",
+        "D:(OD;;WP;bf967a86-0de6-11d0-a285-00aa003049e2;bf967aba-0de6-11d0-a285-00aa003049e2;BU)",
+        "D:(XA;;FA;;;WD;(Member_of {SID(BA)}))",
+        r#"S:(RA;;;;;WD;("Secrecy",TU,0x0,3))"#,
+        "D:NO_ACCESS_CONTROL",
+        "G:S-1-5-21-1-2-3-4-5-6-7-8-9-10-11-12-13-14",
+        "D:",
+    ];
+
+    fn check(view: &SecurityDescriptorRef, sd: &SecurityDescriptor) {
+        assert_eq!(view.control(), sd.control());
+        assert_eq!(view.owner().is_some(), sd.owner().is_some());
+        assert_eq!(view.group().is_some(), sd.group().is_some());
+        if let (Some(lhs), Some(rhs)) = (view.owner(), sd.owner()) {
+            assert_eq!(lhs, *rhs);
+            assert_eq!(lhs.to_string(), rhs.to_string());
+        }
+        if let (Some(lhs), Some(rhs)) = (view.group(), sd.group()) {
+            assert_eq!(lhs, *rhs);
+        }
+
+        for (view_acl, acl) in &[
+            (view.dacl(), sd.non_null_dacl()),
+            (view.sacl(), sd.non_null_sacl()),
+        ] {
+            assert_eq!(view_acl.is_some(), acl.is_some());
+            if let (Some(view_acl), Some(acl)) = (view_acl, acl) {
+                assert_eq!(view_acl.as_bytes(), acl.as_bytes());
+                assert_eq!(view_acl.revision_level(), acl.revision_level());
+                assert_eq!(view_acl.len(), acl.len());
+                assert_eq!(view_acl.aces().count(), acl.len() as usize);
+
+                for (view_ace, ace) in view_acl.aces().zip(acl.aces()) {
+                    assert_eq!(view_ace.as_bytes(), ace.as_bytes());
+                    assert_eq!(view_ace.ace_type(), ace.ace_type());
+                    assert_eq!(view_ace.flags(), ace.flags());
+                    assert_eq!(view_ace.mask(), ace.mask());
+                    assert_eq!(view_ace.object_type(), ace.object_type());
+                    assert_eq!(
+                        view_ace.inherited_object_type(),
+                        ace.inherited_object_type()
+                    );
+                    assert_eq!(view_ace.application_data(), ace.application_data());
+                    assert_eq!(
+                        view_ace.condition().map(Result::unwrap),
+                        ace.condition().map(Result::unwrap)
+                    );
+                    assert_eq!(
+                        view_ace.resource_attribute().map(Result::unwrap),
+                        ace.resource_attribute().map(Result::unwrap)
+                    );
+                    match (view_ace.sid(), ace.sid()) {
+                        (Some(lhs), Some(rhs)) => assert_eq!(lhs, *rhs),
+                        (None, None) => (),
+                        sids => panic!("SIDs differ: {:?}", sids),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn matches_owned_structures() {
+        for sddl in SDDL {
+            let bytes = sddl::parse_sd(sddl).unwrap();
+            let sd: LocalBox<SecurityDescriptor> = sddl.parse().unwrap();
+            let view = SecurityDescriptorRef::from_bytes(&bytes).unwrap();
+
+            check(&view, &sd);
+
+            let copy = view.to_security_descriptor().unwrap();
+            assert_eq!(copy.as_sddl().unwrap(), sd.as_sddl().unwrap());
+        }
+    }
+
+    #[test]
+    fn unaligned_bytes() {
+        for sddl in SDDL {
+            let bytes = sddl::parse_sd(sddl).unwrap();
+            let sd: LocalBox<SecurityDescriptor> = sddl.parse().unwrap();
+
+            let mut shifted = vec![0xAA; 3];
+            shifted.extend_from_slice(&bytes);
+
+            let view = SecurityDescriptorRef::from_bytes(&shifted[3..]).unwrap();
+            check(&view, &sd);
+        }
+    }
+
+    #[test]
+    fn standalone_views() {
+        let bytes = sddl::parse_sd("O:BAD:(A;;FA;;;SY)(D;;FW;;;WD)").unwrap();
+        let view = SecurityDescriptorRef::from_bytes(&bytes).unwrap();
+
+        let owner = view.owner().unwrap();
+        assert_eq!(SidRef::from_bytes(owner.as_bytes()).unwrap(), owner);
+        assert_eq!(*owner.to_sid().unwrap(), owner);
+        assert!(SidRef::from_bytes(&owner.as_bytes()[..12]).is_err());
+        assert!(SidRef::from_bytes(&[owner.as_bytes(), &[0]].concat()).is_err());
+
+        let dacl = view.dacl().unwrap();
+        assert_eq!(AclRef::from_bytes(dacl.as_bytes()).unwrap(), dacl);
+        assert_eq!(dacl.to_acl().unwrap().len(), 2);
+
+        let ace = dacl.get_ace(1).unwrap();
+        assert_eq!(AceRef::from_bytes(ace.as_bytes()).unwrap(), ace);
+        assert_eq!(ace.to_ace().unwrap().mask(), ace.mask());
+        assert!(dacl.get_ace(2).is_none());
+        assert!(AceRef::from_bytes(&dacl.as_bytes()[8..]).is_err());
+    }
+
+    #[test]
+    fn invalid_structures() {
+        let sid = |bytes: &[u8]| SidRef::from_bytes(bytes).is_err();
+        assert!(sid(&[]));
+        assert!(sid(&[1, 1, 0, 0, 0, 0, 0, 5, 18, 0, 0]));
+        assert!(sid(&[2, 1, 0, 0, 0, 0, 0, 5, 18, 0, 0, 0]));
+        assert!(sid(&[1, 0, 0, 0, 0, 0, 0, 5, 0, 0, 0, 0]));
+        assert!(sid(&[[1, 16, 0, 0, 0, 0, 0, 5].as_ref(), &[0; 64]].concat()));
+
+        let everyone = [1, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+        let ace = |header: [u8; 4], body: &[u8]| {
+            AceRef::from_bytes(&[header.as_ref(), body, &everyone].concat()).is_err()
+        };
+        assert!(!ace([0, 0, 20, 0], &[0; 4]));
+        assert!(ace([0, 0, 16, 0], &[0; 4]));
+        assert!(ace([0, 0, 0, 0], &[0; 4]));
+        assert!(ace([0, 0, 24, 0], &[0; 4]));
+        assert!(ace([0, 0, 22, 0], &[0; 6]));
+        assert!(ace([3, 0, 20, 0], &[0; 4]));
+        assert!(ace([0, 0x20, 20, 0], &[0; 4]));
+        assert!(!ace([5, 0, 24, 0], &[0; 8]));
+        assert!(ace([5, 0, 24, 0], &[0, 0, 0, 0, 1, 0, 0, 0]));
+        assert!(ace([5, 0, 24, 0], &[0, 0, 0, 0, 4, 0, 0, 0]));
+
+        let acl = |bytes: &[u8]| AclRef::from_bytes(bytes).is_err();
+        assert!(!acl(&[2, 0, 8, 0, 0, 0, 0, 0]));
+        assert!(acl(&[2, 0, 8, 0, 1, 0, 0, 0]));
+        assert!(acl(&[2, 0, 4, 0, 0, 0, 0, 0]));
+        assert!(acl(&[3, 0, 8, 0, 0, 0, 0, 0]));
+        assert!(acl(&[2, 0, 12, 0, 0, 0, 0, 0]));
+
+        let header = |control: u16, owner: u32| {
+            let mut sd = vec![1, 0];
+            sd.extend_from_slice(&control.to_le_bytes());
+            sd.extend_from_slice(&owner.to_le_bytes());
+            sd.extend_from_slice(&[0; 12]);
+            sd.extend_from_slice(&everyone);
+            SecurityDescriptorRef::from_bytes(&sd).is_err()
+        };
+        assert!(!header(0x8000, 20));
+        assert!(!header(0x8000, 0));
+        assert!(header(0x0000, 20));
+        assert!(header(0x8000, 8));
+        assert!(header(0x8000, 22));
+        assert!(header(0x8000, 32));
+        assert!(header(0x8000, u32::MAX));
+    }
+
+    #[test]
+    fn truncated_descriptors() {
+        for sddl in SDDL {
+            let bytes = sddl::parse_sd(sddl).unwrap();
+
+            // The last part always ends at the end of the buffer
+            for len in 0..bytes.len() {
+                assert!(
+                    SecurityDescriptorRef::from_bytes(&bytes[..len]).is_err(),
+                    "{} truncated to {}",
+                    sddl,
+                    len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn corrupted_descriptors() {
+        // None of these may panic, and whatever is accepted must be readable
+        for sddl in SDDL {
+            let bytes = sddl::parse_sd(sddl).unwrap();
+
+            for index in 0..bytes.len() {
+                for &value in &[0x00, 0x01, 0x05, 0x10, 0x80, 0xFF] {
+                    let mut corrupted = bytes.clone();
+                    corrupted[index] = value;
+
+                    if let Ok(view) = SecurityDescriptorRef::from_bytes(&corrupted) {
+                        let _ = format!("{:?}", view);
+                        let _ = view.to_security_descriptor().unwrap().as_sddl();
+                        for acl in view.dacl().iter().chain(view.sacl().iter()) {
+                            for ace in acl.aces() {
+                                let _ = (ace.object_type(), ace.inherited_object_type());
+                                let _ = (ace.condition(), ace.resource_attribute());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
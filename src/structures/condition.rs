@@ -0,0 +1,851 @@
+use crate::{sddl, LocalBox, Sid};
+use std::convert::TryFrom;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+/// The signature at the start of the binary form of a condition
+pub(crate) const SIGNATURE: &[u8; 4] = b"artx";
+
+// Literal tokens
+const INT8: u8 = 0x01;
+const INT64: u8 = 0x04;
+const UNICODE_STRING: u8 = 0x10;
+const OCTET_STRING: u8 = 0x18;
+const COMPOSITE: u8 = 0x50;
+const SID: u8 = 0x51;
+
+// Attribute tokens
+const LOCAL_ATTRIBUTE: u8 = 0xf8;
+const USER_ATTRIBUTE: u8 = 0xf9;
+const RESOURCE_ATTRIBUTE: u8 = 0xfa;
+const DEVICE_ATTRIBUTE: u8 = 0xfb;
+
+// Operator tokens
+pub(crate) const EQUALS: u8 = 0x80;
+pub(crate) const NOT_EQUALS: u8 = 0x81;
+pub(crate) const LESS_THAN: u8 = 0x82;
+pub(crate) const LESS_THAN_OR_EQUAL: u8 = 0x83;
+pub(crate) const GREATER_THAN: u8 = 0x84;
+pub(crate) const GREATER_THAN_OR_EQUAL: u8 = 0x85;
+pub(crate) const CONTAINS: u8 = 0x86;
+pub(crate) const EXISTS: u8 = 0x87;
+pub(crate) const ANY_OF: u8 = 0x88;
+pub(crate) const MEMBER_OF: u8 = 0x89;
+pub(crate) const DEVICE_MEMBER_OF: u8 = 0x8a;
+pub(crate) const MEMBER_OF_ANY: u8 = 0x8b;
+pub(crate) const DEVICE_MEMBER_OF_ANY: u8 = 0x8c;
+pub(crate) const NOT_EXISTS: u8 = 0x8d;
+pub(crate) const NOT_CONTAINS: u8 = 0x8e;
+pub(crate) const NOT_ANY_OF: u8 = 0x8f;
+pub(crate) const NOT_MEMBER_OF: u8 = 0x90;
+pub(crate) const NOT_DEVICE_MEMBER_OF: u8 = 0x91;
+pub(crate) const NOT_MEMBER_OF_ANY: u8 = 0x92;
+pub(crate) const NOT_DEVICE_MEMBER_OF_ANY: u8 = 0x93;
+pub(crate) const AND: u8 = 0xa0;
+pub(crate) const OR: u8 = 0xa1;
+pub(crate) const NOT: u8 = 0xa2;
+
+/// Operators that take two operands, with their SDDL names
+const BINARY_OPERATORS: &[(u8, &str)] = &[
+    (EQUALS, "=="),
+    (NOT_EQUALS, "!="),
+    (LESS_THAN, "<"),
+    (LESS_THAN_OR_EQUAL, "<="),
+    (GREATER_THAN, ">"),
+    (GREATER_THAN_OR_EQUAL, ">="),
+    (CONTAINS, "Contains"),
+    (ANY_OF, "Any_of"),
+    (NOT_CONTAINS, "Not_Contains"),
+    (NOT_ANY_OF, "Not_Any_of"),
+    (AND, "&&"),
+    (OR, "||"),
+];
+
+/// Operators that take one operand, with their SDDL names
+const UNARY_OPERATORS: &[(u8, &str)] = &[
+    (EXISTS, "Exists"),
+    (NOT_EXISTS, "Not_Exists"),
+    (MEMBER_OF, "Member_of"),
+    (DEVICE_MEMBER_OF, "Device_Member_of"),
+    (MEMBER_OF_ANY, "Member_of_Any"),
+    (DEVICE_MEMBER_OF_ANY, "Device_Member_of_Any"),
+    (NOT_MEMBER_OF, "Not_Member_of"),
+    (NOT_DEVICE_MEMBER_OF, "Not_Device_Member_of"),
+    (NOT_MEMBER_OF_ANY, "Not_Member_of_Any"),
+    (NOT_DEVICE_MEMBER_OF_ANY, "Not_Device_Member_of_Any"),
+    (NOT, "!"),
+];
+
+/// Where the values of an attribute come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum AttributeSource {
+    /// An attribute supplied by the application, written without a prefix
+    Local,
+    /// A user claim, written `@User.`
+    User,
+    /// A resource attribute of the object, written `@Resource.`
+    Resource,
+    /// A device claim, written `@Device.`
+    Device,
+}
+
+/// Attribute tokens and their SDDL prefixes
+const ATTRIBUTE_SOURCES: &[(AttributeSource, u8, &str)] = &[
+    (AttributeSource::Local, LOCAL_ATTRIBUTE, ""),
+    (AttributeSource::User, USER_ATTRIBUTE, "@User."),
+    (AttributeSource::Resource, RESOURCE_ATTRIBUTE, "@Resource."),
+    (AttributeSource::Device, DEVICE_ATTRIBUTE, "@Device."),
+];
+
+/// How an integer literal was written, which is kept so it can be written
+/// the same way again
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct IntegerStyle {
+    /// `1` for `+`, `2` for `-`, and `3` for no sign
+    sign: u8,
+    /// `1` for octal, `2` for decimal, and `3` for hexadecimal
+    base: u8,
+}
+
+/// A literal value in a condition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Literal {
+    Integer(i64, IntegerStyle),
+    String(String),
+    Octets(Vec<u8>),
+    Sid(LocalBox<Sid>),
+    Composite(Vec<Literal>),
+}
+
+/// A node in the syntax tree of a condition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Node {
+    Literal(Literal),
+    Attribute(AttributeSource, String),
+    Unary(u8, Box<Node>),
+    Binary(u8, Box<Node>, Box<Node>),
+}
+
+/// A conditional expression, as carried by callback ACEs such as
+/// `ACCESS_ALLOWED_CALLBACK_ACE_TYPE`.
+///
+/// Conditions can be parsed from and written as their SDDL form, and
+/// converted to and from the binary form stored in ACEs. See
+/// [MSDN](https://docs.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-definition-language-for-conditional-aces-)
+/// for the SDDL syntax.
+///
+/// ```
+/// use windows_permissions::Condition;
+///
+/// let condition: Condition = r#"(Member_of {SID(BA)}) && (@User.Title == "PM")"#
+///     .parse()
+///     .unwrap();
+///
+/// assert_eq!(
+///     condition.to_string(),
+///     r#"((Member_of {SID(BA)}) && (@User.Title == "PM"))"#
+/// );
+/// assert_eq!(Condition::from_bytes(&condition.to_bytes()).unwrap(), condition);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    root: Node,
+}
+
+impl Condition {
+    /// Read a condition from its binary form, which starts with the
+    /// signature `artx`
+    ///
+    /// Trailing zero bytes, which pad the ACE to a multiple of 4 bytes, are
+    /// ignored.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let tokens = bytes
+            .strip_prefix(SIGNATURE)
+            .ok_or_else(|| invalid_data("missing signature"))?;
+
+        let mut stack = Vec::new();
+        let mut position = 0;
+
+        while position < tokens.len() {
+            let token = tokens[position];
+
+            if token == 0 {
+                if tokens[position..].iter().all(|&b| b == 0) {
+                    break;
+                }
+                return Err(invalid_data("padding in the middle"));
+            }
+
+            position += 1;
+
+            let node = if let Some(source) = attribute_source(token) {
+                let name = read_string(tokens, &mut position)?;
+                Node::Attribute(source, name)
+            } else if is_operator(token, BINARY_OPERATORS) {
+                let right = stack.pop();
+                let left = stack.pop();
+                match (left, right) {
+                    (Some(left), Some(right)) => {
+                        Node::Binary(token, Box::new(left), Box::new(right))
+                    }
+                    _ => return Err(invalid_data("missing operand")),
+                }
+            } else if is_operator(token, UNARY_OPERATORS) {
+                let operand = stack.pop().ok_or_else(|| invalid_data("missing operand"))?;
+                Node::Unary(token, Box::new(operand))
+            } else {
+                Node::Literal(read_literal(tokens, &mut position, token)?)
+            };
+
+            stack.push(node);
+        }
+
+        match (stack.pop(), stack.is_empty()) {
+            (Some(root), true) => Ok(Self { root }),
+            (None, _) => Err(invalid_data("empty condition")),
+            (Some(_), false) => Err(invalid_data("unused operands")),
+        }
+    }
+
+    /// Get the binary form of the condition, starting with the signature
+    /// `artx`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = SIGNATURE.to_vec();
+        write_node(&self.root, &mut bytes);
+        bytes
+    }
+
+    /// Get the root of the syntax tree
+    pub(crate) fn root(&self) -> &Node {
+        &self.root
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.root {
+            Node::Unary(..) | Node::Binary(..) => fmt.write_str(&format_node(&self.root)),
+            _ => write!(fmt, "({})", format_node(&self.root)),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = io::Error;
+
+    /// Parse the SDDL form of a condition, with or without the parentheses
+    /// around it
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid conditional expression ({}): {:?}", reason, s),
+            )
+        };
+
+        let mut parser = Parser {
+            tokens: tokenize(s).map_err(invalid)?,
+            position: 0,
+        };
+
+        let root = parser.parse_or().map_err(invalid)?;
+        if parser.position != parser.tokens.len() {
+            return Err(invalid("unexpected text after the expression"));
+        }
+
+        Ok(Self { root })
+    }
+}
+
+fn invalid_data(reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid conditional expression ({})", reason),
+    )
+}
+
+fn is_operator(token: u8, operators: &[(u8, &str)]) -> bool {
+    operators.iter().any(|(op, _)| *op == token)
+}
+
+fn operator_name(token: u8) -> &'static str {
+    BINARY_OPERATORS
+        .iter()
+        .chain(UNARY_OPERATORS)
+        .find(|(op, _)| *op == token)
+        .map(|(_, name)| *name)
+        .expect("Unknown operator in syntax tree")
+}
+
+fn attribute_source(token: u8) -> Option<AttributeSource> {
+    ATTRIBUTE_SOURCES
+        .iter()
+        .find(|(_, t, _)| *t == token)
+        .map(|(source, _, _)| *source)
+}
+
+fn read_bytes<'a>(tokens: &'a [u8], position: &mut usize, len: usize) -> io::Result<&'a [u8]> {
+    let bytes = tokens
+        .get(*position..*position + len)
+        .ok_or_else(|| invalid_data("truncated token"))?;
+    *position += len;
+    Ok(bytes)
+}
+
+fn read_length<'a>(tokens: &'a [u8], position: &mut usize) -> io::Result<&'a [u8]> {
+    let mut len = [0u8; 4];
+    len.copy_from_slice(read_bytes(tokens, position, 4)?);
+    read_bytes(tokens, position, u32::from_le_bytes(len) as usize)
+}
+
+fn read_string(tokens: &[u8], position: &mut usize) -> io::Result<String> {
+    let bytes = read_length(tokens, position)?;
+    if !bytes.len().is_multiple_of(2) {
+        return Err(invalid_data("odd string length"));
+    }
+
+    let units = bytes
+        .chunks(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect::<Vec<_>>();
+    String::from_utf16(&units).map_err(|_| invalid_data("bad string"))
+}
+
+fn read_literal(tokens: &[u8], position: &mut usize, token: u8) -> io::Result<Literal> {
+    match token {
+        INT8..=INT64 => {
+            let bytes = read_bytes(tokens, position, 10)?;
+            let mut value = [0u8; 8];
+            value.copy_from_slice(&bytes[..8]);
+
+            let style = IntegerStyle {
+                sign: bytes[8],
+                base: bytes[9],
+            };
+            if !(1..=3).contains(&style.sign) || !(1..=3).contains(&style.base) {
+                return Err(invalid_data("bad integer"));
+            }
+
+            Ok(Literal::Integer(i64::from_le_bytes(value), style))
+        }
+        UNICODE_STRING => read_string(tokens, position).map(Literal::String),
+        OCTET_STRING => read_length(tokens, position).map(|bytes| Literal::Octets(bytes.to_vec())),
+        SID => Sid::from_bytes(read_length(tokens, position)?)
+            .map(Literal::Sid)
+            .ok_or_else(|| invalid_data("bad SID")),
+        COMPOSITE => {
+            let inner = read_length(tokens, position)?;
+            let mut inner_position = 0;
+            let mut elements = Vec::new();
+
+            while inner_position < inner.len() {
+                let token = inner[inner_position];
+                inner_position += 1;
+                elements.push(read_literal(inner, &mut inner_position, token)?);
+            }
+
+            Ok(Literal::Composite(elements))
+        }
+        other => Err(invalid_data(&format!("unknown token {:#x}", other))),
+    }
+}
+
+fn write_length(bytes: &[u8], out: &mut Vec<u8>) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn write_string(s: &str, out: &mut Vec<u8>) {
+    let bytes = s
+        .encode_utf16()
+        .flat_map(|unit| unit.to_le_bytes())
+        .collect::<Vec<_>>();
+    write_length(&bytes, out);
+}
+
+fn write_literal(literal: &Literal, out: &mut Vec<u8>) {
+    match literal {
+        Literal::Integer(value, style) => {
+            out.push(INT64);
+            out.extend_from_slice(&value.to_le_bytes());
+            out.push(style.sign);
+            out.push(style.base);
+        }
+        Literal::String(s) => {
+            out.push(UNICODE_STRING);
+            write_string(s, out);
+        }
+        Literal::Octets(octets) => {
+            out.push(OCTET_STRING);
+            write_length(octets, out);
+        }
+        Literal::Sid(sid) => {
+            out.push(SID);
+            write_length(&sid.to_bytes(), out);
+        }
+        Literal::Composite(elements) => {
+            let mut inner = Vec::new();
+            for element in elements {
+                write_literal(element, &mut inner);
+            }
+
+            out.push(COMPOSITE);
+            write_length(&inner, out);
+        }
+    }
+}
+
+/// Write a node in postfix order, as the binary form requires
+fn write_node(node: &Node, out: &mut Vec<u8>) {
+    match node {
+        Node::Literal(literal) => write_literal(literal, out),
+        Node::Attribute(source, name) => {
+            let (_, token, _) = ATTRIBUTE_SOURCES
+                .iter()
+                .find(|(s, _, _)| s == source)
+                .expect("Every source has a token");
+            out.push(*token);
+            write_string(name, out);
+        }
+        Node::Unary(op, operand) => {
+            write_node(operand, out);
+            out.push(*op);
+        }
+        Node::Binary(op, left, right) => {
+            write_node(left, out);
+            write_node(right, out);
+            out.push(*op);
+        }
+    }
+}
+
+fn format_literal(literal: &Literal) -> String {
+    match literal {
+        Literal::Integer(value, style) => {
+            let sign = match (style.sign, *value < 0) {
+                (_, true) => "-",
+                (1, false) => "+",
+                _ => "",
+            };
+            let magnitude = value.unsigned_abs();
+
+            match style.base {
+                1 if magnitude != 0 => format!("{}0{:o}", sign, magnitude),
+                3 => format!("{}0x{:x}", sign, magnitude),
+                _ => format!("{}{}", sign, magnitude),
+            }
+        }
+        Literal::String(s) => format!("\"{}\"", s),
+        Literal::Octets(octets) => {
+            let hex = octets
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>();
+            format!("#{}", hex)
+        }
+        Literal::Sid(sid) => format!("SID({})", sddl::format_sid_or_alias(sid)),
+        Literal::Composite(elements) => {
+            let elements = elements.iter().map(format_literal).collect::<Vec<_>>();
+            format!("{{{}}}", elements.join(", "))
+        }
+    }
+}
+
+/// Format a node, putting every operator in parentheses
+fn format_node(node: &Node) -> String {
+    match node {
+        Node::Literal(literal) => format_literal(literal),
+        Node::Attribute(source, name) => {
+            let (_, _, prefix) = ATTRIBUTE_SOURCES
+                .iter()
+                .find(|(s, _, _)| s == source)
+                .expect("Every source has a prefix");
+            format!("{}{}", prefix, name)
+        }
+        Node::Unary(NOT, operand) => match **operand {
+            Node::Unary(..) | Node::Binary(..) => format!("(!{})", format_node(operand)),
+            _ => format!("(!({}))", format_node(operand)),
+        },
+        Node::Unary(op, operand) => format!("({} {})", operator_name(*op), format_node(operand)),
+        Node::Binary(op, left, right) => format!(
+            "({} {} {})",
+            format_node(left),
+            operator_name(*op),
+            format_node(right)
+        ),
+    }
+}
+
+/// A token in the SDDL form of a condition
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    OpenBrace,
+    CloseBrace,
+    Comma,
+    Operator(u8),
+    Literal(Literal),
+    Attribute(AttributeSource, String),
+}
+
+/// Characters that can appear in attribute names
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || "_:/.$'\\%".contains(c)
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, &'static str> {
+    let mut tokens = Vec::new();
+    let mut rest = s;
+
+    loop {
+        rest = rest.trim_start();
+
+        let c = match rest.chars().next() {
+            Some(c) => c,
+            None => return Ok(tokens),
+        };
+
+        let symbol = [
+            ("&&", Token::Operator(AND)),
+            ("||", Token::Operator(OR)),
+            ("==", Token::Operator(EQUALS)),
+            ("!=", Token::Operator(NOT_EQUALS)),
+            ("<=", Token::Operator(LESS_THAN_OR_EQUAL)),
+            (">=", Token::Operator(GREATER_THAN_OR_EQUAL)),
+            ("<", Token::Operator(LESS_THAN)),
+            (">", Token::Operator(GREATER_THAN)),
+            ("!", Token::Operator(NOT)),
+            ("(", Token::Open),
+            (")", Token::Close),
+            ("{", Token::OpenBrace),
+            ("}", Token::CloseBrace),
+            (",", Token::Comma),
+        ]
+        .iter()
+        .find(|(text, _)| rest.starts_with(text));
+
+        if let Some((text, token)) = symbol {
+            tokens.push(token.clone());
+            rest = &rest[text.len()..];
+        } else if c == '"' {
+            let end = rest[1..].find('"').ok_or("unterminated string")?;
+            tokens.push(Token::Literal(Literal::String(
+                rest[1..end + 1].to_string(),
+            )));
+            rest = &rest[end + 2..];
+        } else if c == '#' {
+            let end = rest[1..]
+                .find(|c: char| !c.is_ascii_hexdigit())
+                .map_or(rest.len(), |end| end + 1);
+            let hex = &rest[1..end];
+            if !hex.len().is_multiple_of(2) {
+                return Err("odd number of digits in an octet string");
+            }
+
+            let octets = (0..hex.len())
+                .step_by(2)
+                .map(|index| u8::from_str_radix(&hex[index..index + 2], 16))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| "bad octet string")?;
+            tokens.push(Token::Literal(Literal::Octets(octets)));
+            rest = &rest[end..];
+        } else if c == '-' || c == '+' || c.is_ascii_digit() {
+            let end = rest[1..]
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .map_or(rest.len(), |end| end + 1);
+            tokens.push(Token::Literal(parse_integer(&rest[..end])?));
+            rest = &rest[end..];
+        } else if c == '@' {
+            let (source, prefix) = ATTRIBUTE_SOURCES[1..]
+                .iter()
+                .find(|(_, _, prefix)| {
+                    rest.get(..prefix.len())
+                        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+                })
+                .map(|(source, _, prefix)| (*source, *prefix))
+                .ok_or("unknown attribute prefix")?;
+
+            rest = &rest[prefix.len()..];
+            let end = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+            if end == 0 {
+                return Err("missing attribute name");
+            }
+
+            tokens.push(Token::Attribute(source, rest[..end].to_string()));
+            rest = &rest[end..];
+        } else if is_name_char(c) {
+            let end = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+            let word = &rest[..end];
+            rest = &rest[end..];
+
+            if word.eq_ignore_ascii_case("SID") && rest.trim_start().starts_with('(') {
+                let open = rest.find('(').expect("Just checked");
+                let close = rest.find(')').ok_or("unterminated SID")?;
+                let (id_auth, sub_auths) =
+                    sddl::parse_sid(rest[open + 1..close].trim()).map_err(|_| "bad SID")?;
+                let sid = Sid::new(id_auth, &sub_auths).map_err(|_| "bad SID")?;
+
+                tokens.push(Token::Literal(Literal::Sid(sid)));
+                rest = &rest[close + 1..];
+            } else if let Some((op, _)) = BINARY_OPERATORS
+                .iter()
+                .chain(UNARY_OPERATORS)
+                .find(|(_, name)| name.eq_ignore_ascii_case(word))
+            {
+                tokens.push(Token::Operator(*op));
+            } else {
+                tokens.push(Token::Attribute(AttributeSource::Local, word.to_string()));
+            }
+        } else {
+            return Err("unexpected character");
+        }
+    }
+}
+
+fn parse_integer(s: &str) -> Result<Literal, &'static str> {
+    let (sign, digits) = match s.as_bytes()[0] {
+        b'+' => (1, &s[1..]),
+        b'-' => (2, &s[1..]),
+        _ => (3, s),
+    };
+
+    let (base, radix, digits) = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        (3, 16, hex)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        (1, 8, &digits[1..])
+    } else {
+        (2, 10, digits)
+    };
+
+    let magnitude = u64::from_str_radix(digits, radix).map_err(|_| "bad integer")?;
+    let value = if sign == 2 {
+        0i64.checked_sub_unsigned(magnitude)
+    } else {
+        i64::try_from(magnitude).ok()
+    }
+    .ok_or("integer out of range")?;
+
+    Ok(Literal::Integer(value, IntegerStyle { sign, base }))
+}
+
+/// A recursive descent parser over the tokens of a condition
+///
+/// `||` binds least tightly, then `&&`, then `!`.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+type ParseResult<T> = Result<T, &'static str>;
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> ParseResult<Node> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Operator(OR)) {
+            self.position += 1;
+            left = Node::Binary(OR, Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> ParseResult<Node> {
+        let mut left = self.parse_unary()?;
+        while self.peek() == Some(&Token::Operator(AND)) {
+            self.position += 1;
+            left = Node::Binary(AND, Box::new(left), Box::new(self.parse_unary()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> ParseResult<Node> {
+        if self.peek() == Some(&Token::Operator(NOT)) {
+            self.position += 1;
+            return Ok(Node::Unary(NOT, Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> ParseResult<Node> {
+        match self.peek() {
+            Some(Token::Open) => {
+                self.position += 1;
+                let node = self.parse_or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(node),
+                    _ => Err("missing closing parenthesis"),
+                }
+            }
+            Some(Token::Operator(op)) if is_operator(*op, UNARY_OPERATORS) => {
+                let op = *op;
+                self.position += 1;
+                let operand = self.parse_operand()?;
+
+                if (op == EXISTS || op == NOT_EXISTS) && !matches!(operand, Node::Attribute(..)) {
+                    return Err("Exists needs an attribute");
+                }
+
+                Ok(Node::Unary(op, Box::new(operand)))
+            }
+            _ => {
+                let left = self.parse_operand()?;
+                match self.peek() {
+                    Some(Token::Operator(op))
+                        if is_operator(*op, BINARY_OPERATORS) && *op != AND && *op != OR =>
+                    {
+                        let op = *op;
+                        self.position += 1;
+                        let right = self.parse_operand()?;
+                        Ok(Node::Binary(op, Box::new(left), Box::new(right)))
+                    }
+                    _ => Ok(left),
+                }
+            }
+        }
+    }
+
+    fn parse_operand(&mut self) -> ParseResult<Node> {
+        match self.next() {
+            Some(Token::Attribute(source, name)) => Ok(Node::Attribute(source, name)),
+            Some(Token::Literal(literal)) => Ok(Node::Literal(literal)),
+            Some(Token::OpenBrace) => {
+                let mut elements = Vec::new();
+
+                if self.peek() == Some(&Token::CloseBrace) {
+                    self.position += 1;
+                    return Ok(Node::Literal(Literal::Composite(elements)));
+                }
+
+                loop {
+                    match self.next() {
+                        Some(Token::Literal(literal)) => elements.push(literal),
+                        _ => return Err("expected a value"),
+                    }
+
+                    match self.next() {
+                        Some(Token::Comma) => (),
+                        Some(Token::CloseBrace) => break,
+                        _ => return Err("missing closing brace"),
+                    }
+                }
+
+                Ok(Node::Literal(Literal::Composite(elements)))
+            }
+            _ => Err("expected an attribute or value"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let cases = [
+            "(Member_of {SID(BA)})",
+            "(Member_of {SID(BA), SID(S-1-5-21-1-2-3-1001)})",
+            "(Not_Device_Member_of_Any {SID(BU)})",
+            r#"((@User.Title == "PM") || (@User.Title == "Dev"))"#,
+            "(@Resource.Confidentiality <= @User.Clearance)",
+            "(@Device.Managed == 1)",
+            "(@User.Level > -0x10)",
+            "(@User.Level >= 017)",
+            r#"(@User.Project Any_of {"Alpha", "Beta"})"#,
+            r#"(@Resource.Projects Not_Contains {"Gamma"})"#,
+            "(Exists @User.smartcard)",
+            "(!(Member_of {SID(WD)}))",
+            "(!(@User.smartcard))",
+            "(@User.Key == #00ff10)",
+            "(TRUE)",
+        ];
+
+        for case in &cases {
+            let condition: Condition = case.parse().unwrap();
+            assert_eq!(condition.to_string(), *case);
+
+            let bytes = condition.to_bytes();
+            assert_eq!(&bytes[..4], b"artx");
+            assert_eq!(Condition::from_bytes(&bytes).unwrap(), condition);
+        }
+    }
+
+    #[test]
+    fn precedence() {
+        let parse = |s: &str| s.parse::<Condition>().unwrap().to_string();
+
+        assert_eq!(
+            parse("Member_of {SID(BA)} || Member_of {SID(BU)} && !Exists @User.x"),
+            "((Member_of {SID(BA)}) || ((Member_of {SID(BU)}) && (!(Exists @User.x))))"
+        );
+        assert_eq!(
+            parse("member_of SID(BA)"),
+            "(Member_of SID(BA))",
+            "Keywords are case-insensitive"
+        );
+    }
+
+    #[test]
+    fn binary_layout() {
+        let condition: Condition = "(@User.x == 1)".parse().unwrap();
+        let bytes = condition.to_bytes();
+
+        assert_eq!(bytes[4], USER_ATTRIBUTE);
+        assert_eq!(&bytes[5..9], &[2, 0, 0, 0]);
+        assert_eq!(&bytes[9..11], &[b'x', 0]);
+        assert_eq!(bytes[11], INT64);
+        assert_eq!(&bytes[12..22], &[1, 0, 0, 0, 0, 0, 0, 0, 3, 2]);
+        assert_eq!(bytes[22], EQUALS);
+        assert_eq!(bytes.len(), 23);
+
+        // Padding is allowed at the end
+        let mut padded = bytes.clone();
+        padded.push(0);
+        assert_eq!(Condition::from_bytes(&padded).unwrap(), condition);
+    }
+
+    #[test]
+    fn errors() {
+        for bad in &[
+            "",
+            "()",
+            "(@User.x ==)",
+            "(@User.x == 1",
+            "(Exists 1)",
+            r#"(@User.x == "a)"#,
+            "(@Nobody.x == 1)",
+            "(@User.x == {1, {2}})",
+            "(@User.x == 99999999999999999999)",
+            "(Member_of {SID(XX)})",
+            "(@User.x == 1) junk",
+        ] {
+            assert!(
+                bad.parse::<Condition>().is_err(),
+                "{:?} should not parse",
+                bad
+            );
+        }
+
+        for bad in [&b""[..], b"artx", b"xtra\x80", b"artx\x80", b"artx\x04\x01"] {
+            assert!(
+                Condition::from_bytes(bad).is_err(),
+                "{:?} should not parse",
+                bad
+            );
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::sddl;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+/// A GUID, as used for object types in object ACEs.
+///
+/// The bytes are stored in the same order as the Windows
+/// [`GUID`](https://docs.microsoft.com/en-us/windows/win32/api/guiddef/ns-guiddef-guid)
+/// structure, so the first three fields are little-endian.
+///
+/// ```
+/// use windows_permissions::Guid;
+///
+/// // The schemaIDGUID of the Active Directory `user` class
+/// let guid: Guid = "bf967aba-0de6-11d0-a285-00aa003049e2".parse().unwrap();
+///
+/// assert_eq!(guid.to_bytes()[0], 0xBA);
+/// assert_eq!(guid.to_string(), "bf967aba-0de6-11d0-a285-00aa003049e2");
+/// assert_eq!(guid, "{BF967ABA-0DE6-11D0-A285-00AA003049E2}".parse().unwrap());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Guid([u8; 16]);
+
+impl Guid {
+    /// Create a GUID from its in-memory representation
+    pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    /// Get the in-memory representation of the GUID
+    pub const fn to_bytes(self) -> [u8; 16] {
+        self.0
+    }
+}
+
+impl fmt::Display for Guid {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(&sddl::format_guid(&self.0))
+    }
+}
+
+impl fmt::Debug for Guid {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "Guid({})", self)
+    }
+}
+
+impl FromStr for Guid {
+    type Err = io::Error;
+
+    /// Parse a GUID in `8-4-4-4-12` hex format, with or without braces
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let inner = s
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .unwrap_or(s);
+
+        sddl::parse_guid(inner).map(Guid).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid GUID: {:?}", s),
+            )
+        })
+    }
+}
@@ -2,12 +2,21 @@
 
 mod ace;
 mod acl;
+mod borrowed;
+pub(crate) mod condition;
+mod guid;
 mod sd;
 mod sid;
+#[cfg(target_os = "windows")]
 mod trustee;
+pub mod well_known;
 
 pub use ace::Ace;
 pub use acl::Acl;
+pub use borrowed::{AceRef, AclRef, SecurityDescriptorRef, SidRef};
+pub use condition::Condition;
+pub use guid::Guid;
 pub use sd::SecurityDescriptor;
 pub use sid::Sid;
+#[cfg(target_os = "windows")]
 pub use trustee::{Trustee, TrusteeSubject};
@@ -1,4 +1,4 @@
-use crate::constants::SecurityInformation;
+use crate::constants::{SecurityDescriptorControl, SecurityInformation};
 use crate::{wrappers, Acl, LocalBox, Sid};
 use std::ffi::OsString;
 use std::fmt;
@@ -71,6 +71,24 @@ impl SecurityDescriptor {
             .expect("Valid SecurityDescriptor failed to get group")
     }
 
+    /// Get the control flags
+    ///
+    /// ```
+    /// use windows_permissions::constants::SecurityDescriptorControl;
+    /// use windows_permissions::{LocalBox, SecurityDescriptor};
+    ///
+    /// let sd: LocalBox<SecurityDescriptor> = "D:P(A;;FA;;;SY)".parse().unwrap();
+    ///
+    /// assert!(sd.control().contains(
+    ///     SecurityDescriptorControl::DaclPresent | SecurityDescriptorControl::DaclProtected
+    /// ));
+    /// ```
+    pub fn control(&self) -> SecurityDescriptorControl {
+        wrappers::GetSecurityDescriptorControl(self)
+            .expect("Valid SecurityDescriptor failed to get control")
+            .0
+    }
+
     /// Get the DACL if it exists
     pub fn dacl(&self) -> Option<&Acl> {
         wrappers::GetSecurityDescriptorDacl(self)
@@ -82,6 +100,26 @@ impl SecurityDescriptor {
         wrappers::GetSecurityDescriptorSacl(self)
             .expect("Valid SecurityDescriptor failed to get sacl")
     }
+
+    /// Get the DACL, or `None` if it is missing or null
+    ///
+    /// Unlike [`dacl`](Self::dacl), this does not panic on a null DACL. The
+    /// `DaclPresent` control flag tells the two apart.
+    pub(crate) fn non_null_dacl(&self) -> Option<&Acl> {
+        wrappers::security_descriptor_dacl(self)
+            .expect("Valid SecurityDescriptor failed to get dacl")
+            .1
+    }
+
+    /// Get the SACL, or `None` if it is missing or null
+    ///
+    /// Unlike [`sacl`](Self::sacl), this does not panic on a null SACL. The
+    /// `SaclPresent` control flag tells the two apart.
+    pub(crate) fn non_null_sacl(&self) -> Option<&Acl> {
+        wrappers::security_descriptor_sacl(self)
+            .expect("Valid SecurityDescriptor failed to get sacl")
+            .1
+    }
 }
 
 impl fmt::Debug for SecurityDescriptor {
@@ -166,6 +204,7 @@ mod test {
     }
 
     #[test]
+    #[cfg(target_os = "windows")]
     fn sddl_missing_acls() -> io::Result<()> {
         let sd: LocalBox<SecurityDescriptor> = "O:LAG:AO".parse()?;
         assert!(sd.dacl().is_none());
@@ -185,4 +224,25 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn sddl_missing_acls_portable() -> io::Result<()> {
+        let sd: LocalBox<SecurityDescriptor> = "O:BAG:AO".parse()?;
+        assert!(sd.dacl().is_none());
+        assert!(sd.sacl().is_none());
+
+        let sd: LocalBox<SecurityDescriptor> = "O:BAG:AOD:".parse()?;
+        assert!(sd.dacl().is_some());
+        assert!(sd.sacl().is_none());
+
+        let sd: LocalBox<SecurityDescriptor> = "O:BAG:AOS:".parse()?;
+        assert!(sd.dacl().is_none());
+        assert!(sd.sacl().is_some());
+
+        let sd: LocalBox<SecurityDescriptor> = "O:BAG:AOD:S:".parse()?;
+        assert!(sd.dacl().is_some());
+        assert!(sd.sacl().is_some());
+
+        Ok(())
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{wrappers, LocalBox};
+use crate::{build, wrappers, LocalBox};
 use std::fmt;
 use std::hash::Hash;
 use std::io;
@@ -51,6 +51,7 @@ impl Sid {
     ///
     /// assert_eq!(win_world_sid, another_sid);
     /// ```
+    #[cfg(target_os = "windows")]
     pub fn well_known_sid(well_known_sid_type: u32) -> io::Result<LocalBox<Sid>> {
         wrappers::CreateWellKnownSid(well_known_sid_type, None)
     }
@@ -133,6 +134,22 @@ impl Sid {
             | (id_auth[1] as u64) << 32
             | (id_auth[0] as u64) << 40
     }
+
+    /// Copy a SID out of its binary form, checking that it is well formed
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Option<LocalBox<Sid>> {
+        let count = *bytes.get(1)? as usize;
+
+        if bytes[0] != 1 || count > 15 || bytes.len() != 8 + 4 * count {
+            return None;
+        }
+
+        unsafe { LocalBox::from_bytes(bytes).ok() }
+    }
+
+    /// Get the binary form of the SID
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        build::sid(*self.id_authority(), &self.sub_authorities())
+    }
 }
 
 #[cfg(test)]
@@ -192,7 +209,7 @@ impl fmt::Display for Sid {
         write!(
             fmt,
             "{}",
-            wrappers::ConvertSidToStringSid(&self)
+            wrappers::ConvertSidToStringSid(self)
                 .expect("Passed a safe Sid to ConvertSidToStringSid but got an error")
                 .to_string_lossy()
         )
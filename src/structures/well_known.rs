@@ -0,0 +1,373 @@
+//! Constant SIDs for well-known principals
+//!
+//! Each constant is a `&'static Sid` laid out exactly like the SIDs inside
+//! ACEs and security descriptors, so it can be compared with [`Ace::sid`],
+//! [`SecurityDescriptor::owner`] and friends, or used to look up a
+//! `LocalBox<Sid>` key in a map, without allocating.
+//!
+//! The list follows
+//! [Well-known SIDs](https://docs.microsoft.com/en-us/windows/win32/secauthz/well-known-sids).
+//! SIDs that are relative to a domain or machine, like Domain Admins, are
+//! not included.
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use windows_permissions::structures::well_known;
+//! use windows_permissions::{wrappers, LocalBox, SecurityDescriptor, Sid};
+//!
+//! let sd: LocalBox<SecurityDescriptor> = "D:(A;;FA;;;SY)(A;;FA;;;BA)(A;;FR;;;BU)".parse().unwrap();
+//! let dacl = sd.dacl().unwrap();
+//!
+//! let describe = |sid: &Sid| match sid {
+//!     sid if sid == well_known::LOCAL_SYSTEM => "the system",
+//!     sid if sid == well_known::ADMINISTRATORS => "administrators",
+//!     _ => "someone else",
+//! };
+//! assert_eq!(describe(dacl.get_ace(0).unwrap().sid().unwrap()), "the system");
+//! assert_eq!(describe(dacl.get_ace(2).unwrap().sid().unwrap()), "someone else");
+//!
+//! let mut rights: HashMap<LocalBox<Sid>, u32> = HashMap::new();
+//! for ace in dacl.aces() {
+//!     let sid = wrappers::CopySid(ace.sid().unwrap()).unwrap();
+//!     rights.insert(sid, ace.mask().bits());
+//! }
+//! assert_eq!(rights.get(well_known::ADMINISTRATORS), Some(&0x1f01ff));
+//! assert_eq!(rights.get(well_known::GUESTS), None);
+//! ```
+//!
+//! [`Ace::sid`]: crate::Ace::sid
+//! [`SecurityDescriptor::owner`]: crate::SecurityDescriptor::owner
+
+use crate::__private::StaticSid;
+use crate::Sid;
+
+/// Lay out a SID with the given identifier authority and sub-authorities
+macro_rules! well_known {
+    ($authority:expr; $($sub_authority:expr),+) => {{
+        const SID: &StaticSid<{ [$(stringify!($sub_authority)),+].len() }> =
+            &StaticSid::new([0, 0, 0, 0, 0, $authority], [$($sub_authority),+]);
+        SID.as_sid()
+    }};
+}
+
+/// `S-1-0-0`: Nobody
+pub const NULL_SID: &Sid = well_known!(0; 0);
+
+/// `S-1-1-0` (`WD`): Everyone
+pub const EVERYONE: &Sid = well_known!(1; 0);
+
+/// `S-1-2-0`: Users who log on to terminals locally
+pub const LOCAL: &Sid = well_known!(2; 0);
+
+/// `S-1-2-1`: Users who log on to the physical console
+pub const CONSOLE_LOGON: &Sid = well_known!(2; 1);
+
+/// `S-1-3-0` (`CO`): Placeholder for the creator of an object in inheritable
+/// ACEs
+pub const CREATOR_OWNER: &Sid = well_known!(3; 0);
+
+/// `S-1-3-1` (`CG`): Placeholder for the primary group of the creator of an
+/// object in inheritable ACEs
+pub const CREATOR_GROUP: &Sid = well_known!(3; 1);
+
+/// `S-1-3-4` (`OW`): The current owner of an object
+pub const OWNER_RIGHTS: &Sid = well_known!(3; 4);
+
+/// `S-1-5-1`: Users who log on through a dial-up connection
+pub const DIALUP: &Sid = well_known!(5; 1);
+
+/// `S-1-5-2` (`NU`): Users who log on across a network
+pub const NETWORK: &Sid = well_known!(5; 2);
+
+/// `S-1-5-3`: Users who log on through a batch queue
+pub const BATCH: &Sid = well_known!(5; 3);
+
+/// `S-1-5-4` (`IU`): Users who log on interactively
+pub const INTERACTIVE: &Sid = well_known!(5; 4);
+
+/// `S-1-5-6` (`SU`): Accounts that log on as a service
+pub const SERVICE: &Sid = well_known!(5; 6);
+
+/// `S-1-5-7` (`AN`): Anonymous logons
+pub const ANONYMOUS: &Sid = well_known!(5; 7);
+
+/// `S-1-5-8`: Proxy
+pub const PROXY: &Sid = well_known!(5; 8);
+
+/// `S-1-5-9` (`ED`): Enterprise domain controllers
+pub const ENTERPRISE_DOMAIN_CONTROLLERS: &Sid = well_known!(5; 9);
+
+/// `S-1-5-10` (`PS`): Placeholder for the principal that an Active Directory
+/// object represents
+pub const PRINCIPAL_SELF: &Sid = well_known!(5; 10);
+
+/// `S-1-5-11` (`AU`): Authenticated users
+pub const AUTHENTICATED_USERS: &Sid = well_known!(5; 11);
+
+/// `S-1-5-12` (`RC`): Restricted code
+pub const RESTRICTED_CODE: &Sid = well_known!(5; 12);
+
+/// `S-1-5-13`: Users who log on to a Remote Desktop Session Host
+pub const TERMINAL_SERVER_USERS: &Sid = well_known!(5; 13);
+
+/// `S-1-5-14`: Users who log on through Remote Desktop
+pub const REMOTE_INTERACTIVE_LOGON: &Sid = well_known!(5; 14);
+
+/// `S-1-5-15`: Users from the same organization
+pub const THIS_ORGANIZATION: &Sid = well_known!(5; 15);
+
+/// `S-1-5-17`: The account used by IIS
+pub const IUSR: &Sid = well_known!(5; 17);
+
+/// `S-1-5-18` (`SY`): The LocalSystem account
+pub const LOCAL_SYSTEM: &Sid = well_known!(5; 18);
+
+/// `S-1-5-19` (`LS`): The LocalService account
+pub const LOCAL_SERVICE: &Sid = well_known!(5; 19);
+
+/// `S-1-5-20` (`NS`): The NetworkService account
+pub const NETWORK_SERVICE: &Sid = well_known!(5; 20);
+
+/// `S-1-5-33` (`WR`): Write-restricted code
+pub const WRITE_RESTRICTED_CODE: &Sid = well_known!(5; 33);
+
+/// `S-1-5-32-544` (`BA`): BUILTIN\Administrators
+pub const ADMINISTRATORS: &Sid = well_known!(5; 32, 544);
+
+/// `S-1-5-32-545` (`BU`): BUILTIN\Users
+pub const USERS: &Sid = well_known!(5; 32, 545);
+
+/// `S-1-5-32-546` (`BG`): BUILTIN\Guests
+pub const GUESTS: &Sid = well_known!(5; 32, 546);
+
+/// `S-1-5-32-547` (`PU`): BUILTIN\Power Users
+pub const POWER_USERS: &Sid = well_known!(5; 32, 547);
+
+/// `S-1-5-32-548` (`AO`): BUILTIN\Account Operators
+pub const ACCOUNT_OPERATORS: &Sid = well_known!(5; 32, 548);
+
+/// `S-1-5-32-549` (`SO`): BUILTIN\Server Operators
+pub const SERVER_OPERATORS: &Sid = well_known!(5; 32, 549);
+
+/// `S-1-5-32-550` (`PO`): BUILTIN\Print Operators
+pub const PRINT_OPERATORS: &Sid = well_known!(5; 32, 550);
+
+/// `S-1-5-32-551` (`BO`): BUILTIN\Backup Operators
+pub const BACKUP_OPERATORS: &Sid = well_known!(5; 32, 551);
+
+/// `S-1-5-32-552` (`RE`): BUILTIN\Replicator
+pub const REPLICATOR: &Sid = well_known!(5; 32, 552);
+
+/// `S-1-5-32-554` (`RU`): BUILTIN\Pre-Windows 2000 Compatible Access
+pub const PRE_WINDOWS_2000_COMPATIBLE_ACCESS: &Sid = well_known!(5; 32, 554);
+
+/// `S-1-5-32-555` (`RD`): BUILTIN\Remote Desktop Users
+pub const REMOTE_DESKTOP_USERS: &Sid = well_known!(5; 32, 555);
+
+/// `S-1-5-32-556` (`NO`): BUILTIN\Network Configuration Operators
+pub const NETWORK_CONFIGURATION_OPERATORS: &Sid = well_known!(5; 32, 556);
+
+/// `S-1-5-32-558` (`MU`): BUILTIN\Performance Monitor Users
+pub const PERFORMANCE_MONITOR_USERS: &Sid = well_known!(5; 32, 558);
+
+/// `S-1-5-32-559` (`LU`): BUILTIN\Performance Log Users
+pub const PERFORMANCE_LOG_USERS: &Sid = well_known!(5; 32, 559);
+
+/// `S-1-5-32-568` (`IS`): BUILTIN\IIS_IUSRS
+pub const IIS_USERS: &Sid = well_known!(5; 32, 568);
+
+/// `S-1-5-32-569` (`CY`): BUILTIN\Cryptographic Operators
+pub const CRYPTOGRAPHIC_OPERATORS: &Sid = well_known!(5; 32, 569);
+
+/// `S-1-5-32-573` (`ER`): BUILTIN\Event Log Readers
+pub const EVENT_LOG_READERS: &Sid = well_known!(5; 32, 573);
+
+/// `S-1-5-32-574` (`CD`): BUILTIN\Certificate Service DCOM Access
+pub const CERTIFICATE_SERVICE_DCOM_ACCESS: &Sid = well_known!(5; 32, 574);
+
+/// `S-1-5-32-575` (`RA`): BUILTIN\RDS Remote Access Servers
+pub const RDS_REMOTE_ACCESS_SERVERS: &Sid = well_known!(5; 32, 575);
+
+/// `S-1-5-32-576` (`ES`): BUILTIN\RDS Endpoint Servers
+pub const RDS_ENDPOINT_SERVERS: &Sid = well_known!(5; 32, 576);
+
+/// `S-1-5-32-577` (`MS`): BUILTIN\RDS Management Servers
+pub const RDS_MANAGEMENT_SERVERS: &Sid = well_known!(5; 32, 577);
+
+/// `S-1-5-32-578` (`HA`): BUILTIN\Hyper-V Administrators
+pub const HYPER_V_ADMINISTRATORS: &Sid = well_known!(5; 32, 578);
+
+/// `S-1-5-32-579` (`AA`): BUILTIN\Access Control Assistance Operators
+pub const ACCESS_CONTROL_ASSISTANCE_OPERATORS: &Sid = well_known!(5; 32, 579);
+
+/// `S-1-5-32-580` (`RM`): BUILTIN\Remote Management Users
+pub const REMOTE_MANAGEMENT_USERS: &Sid = well_known!(5; 32, 580);
+
+/// `S-1-5-80-956008885-3418522649-1831038044-1853292631-2271478464`: The
+/// TrustedInstaller service
+pub const TRUSTED_INSTALLER: &Sid =
+    well_known!(5; 80, 956008885, 3418522649, 1831038044, 1853292631, 2271478464);
+
+/// `S-1-5-84-0-0-0-0-0` (`UD`): User-mode drivers
+pub const USER_MODE_DRIVERS: &Sid = well_known!(5; 84, 0, 0, 0, 0, 0);
+
+/// `S-1-15-2-1` (`AC`): All application packages
+pub const ALL_APPLICATION_PACKAGES: &Sid = well_known!(15; 2, 1);
+
+/// `S-1-15-2-2`: All restricted application packages
+pub const ALL_RESTRICTED_APPLICATION_PACKAGES: &Sid = well_known!(15; 2, 2);
+
+/// `S-1-16-0`: Untrusted mandatory level
+pub const UNTRUSTED_MANDATORY_LEVEL: &Sid = well_known!(16; 0);
+
+/// `S-1-16-4096` (`LW`): Low mandatory level
+pub const LOW_MANDATORY_LEVEL: &Sid = well_known!(16; 4096);
+
+/// `S-1-16-8192` (`ME`): Medium mandatory level
+pub const MEDIUM_MANDATORY_LEVEL: &Sid = well_known!(16; 8192);
+
+/// `S-1-16-8448` (`MP`): Medium plus mandatory level
+pub const MEDIUM_PLUS_MANDATORY_LEVEL: &Sid = well_known!(16; 8448);
+
+/// `S-1-16-12288` (`HI`): High mandatory level
+pub const HIGH_MANDATORY_LEVEL: &Sid = well_known!(16; 12288);
+
+/// `S-1-16-16384` (`SI`): System mandatory level
+pub const SYSTEM_MANDATORY_LEVEL: &Sid = well_known!(16; 16384);
+
+/// `S-1-16-20480`: Protected process mandatory level
+pub const PROTECTED_PROCESS_MANDATORY_LEVEL: &Sid = well_known!(16; 20480);
+
+/// `S-1-18-1` (`AS`): Identity asserted by an authentication authority
+pub const AUTHENTICATION_AUTHORITY_ASSERTED_IDENTITY: &Sid = well_known!(18; 1);
+
+/// `S-1-18-2` (`SS`): Identity asserted by a service
+pub const SERVICE_ASSERTED_IDENTITY: &Sid = well_known!(18; 2);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sddl::SID_ALIASES;
+    use crate::{LocalBox, SecurityDescriptor};
+    use std::collections::HashSet;
+
+    const ALL: &[(&Sid, &str)] = &[
+        (NULL_SID, "S-1-0-0"),
+        (EVERYONE, "S-1-1-0"),
+        (LOCAL, "S-1-2-0"),
+        (CONSOLE_LOGON, "S-1-2-1"),
+        (CREATOR_OWNER, "S-1-3-0"),
+        (CREATOR_GROUP, "S-1-3-1"),
+        (OWNER_RIGHTS, "S-1-3-4"),
+        (DIALUP, "S-1-5-1"),
+        (NETWORK, "S-1-5-2"),
+        (BATCH, "S-1-5-3"),
+        (INTERACTIVE, "S-1-5-4"),
+        (SERVICE, "S-1-5-6"),
+        (ANONYMOUS, "S-1-5-7"),
+        (PROXY, "S-1-5-8"),
+        (ENTERPRISE_DOMAIN_CONTROLLERS, "S-1-5-9"),
+        (PRINCIPAL_SELF, "S-1-5-10"),
+        (AUTHENTICATED_USERS, "S-1-5-11"),
+        (RESTRICTED_CODE, "S-1-5-12"),
+        (TERMINAL_SERVER_USERS, "S-1-5-13"),
+        (REMOTE_INTERACTIVE_LOGON, "S-1-5-14"),
+        (THIS_ORGANIZATION, "S-1-5-15"),
+        (IUSR, "S-1-5-17"),
+        (LOCAL_SYSTEM, "S-1-5-18"),
+        (LOCAL_SERVICE, "S-1-5-19"),
+        (NETWORK_SERVICE, "S-1-5-20"),
+        (WRITE_RESTRICTED_CODE, "S-1-5-33"),
+        (ADMINISTRATORS, "S-1-5-32-544"),
+        (USERS, "S-1-5-32-545"),
+        (GUESTS, "S-1-5-32-546"),
+        (POWER_USERS, "S-1-5-32-547"),
+        (ACCOUNT_OPERATORS, "S-1-5-32-548"),
+        (SERVER_OPERATORS, "S-1-5-32-549"),
+        (PRINT_OPERATORS, "S-1-5-32-550"),
+        (BACKUP_OPERATORS, "S-1-5-32-551"),
+        (REPLICATOR, "S-1-5-32-552"),
+        (PRE_WINDOWS_2000_COMPATIBLE_ACCESS, "S-1-5-32-554"),
+        (REMOTE_DESKTOP_USERS, "S-1-5-32-555"),
+        (NETWORK_CONFIGURATION_OPERATORS, "S-1-5-32-556"),
+        (PERFORMANCE_MONITOR_USERS, "S-1-5-32-558"),
+        (PERFORMANCE_LOG_USERS, "S-1-5-32-559"),
+        (IIS_USERS, "S-1-5-32-568"),
+        (CRYPTOGRAPHIC_OPERATORS, "S-1-5-32-569"),
+        (EVENT_LOG_READERS, "S-1-5-32-573"),
+        (CERTIFICATE_SERVICE_DCOM_ACCESS, "S-1-5-32-574"),
+        (RDS_REMOTE_ACCESS_SERVERS, "S-1-5-32-575"),
+        (RDS_ENDPOINT_SERVERS, "S-1-5-32-576"),
+        (RDS_MANAGEMENT_SERVERS, "S-1-5-32-577"),
+        (HYPER_V_ADMINISTRATORS, "S-1-5-32-578"),
+        (ACCESS_CONTROL_ASSISTANCE_OPERATORS, "S-1-5-32-579"),
+        (REMOTE_MANAGEMENT_USERS, "S-1-5-32-580"),
+        (
+            TRUSTED_INSTALLER,
+            "S-1-5-80-956008885-3418522649-1831038044-1853292631-2271478464",
+        ),
+        (USER_MODE_DRIVERS, "S-1-5-84-0-0-0-0-0"),
+        (ALL_APPLICATION_PACKAGES, "S-1-15-2-1"),
+        (ALL_RESTRICTED_APPLICATION_PACKAGES, "S-1-15-2-2"),
+        (UNTRUSTED_MANDATORY_LEVEL, "S-1-16-0"),
+        (LOW_MANDATORY_LEVEL, "S-1-16-4096"),
+        (MEDIUM_MANDATORY_LEVEL, "S-1-16-8192"),
+        (MEDIUM_PLUS_MANDATORY_LEVEL, "S-1-16-8448"),
+        (HIGH_MANDATORY_LEVEL, "S-1-16-12288"),
+        (SYSTEM_MANDATORY_LEVEL, "S-1-16-16384"),
+        (PROTECTED_PROCESS_MANDATORY_LEVEL, "S-1-16-20480"),
+        (AUTHENTICATION_AUTHORITY_ASSERTED_IDENTITY, "S-1-18-1"),
+        (SERVICE_ASSERTED_IDENTITY, "S-1-18-2"),
+    ];
+
+    #[test]
+    fn constants_match_strings() {
+        for &(sid, string) in ALL {
+            let parsed: LocalBox<Sid> = string.parse().unwrap();
+            assert_eq!(sid, &*parsed, "{}", string);
+            assert_eq!(sid.to_bytes(), parsed.to_bytes(), "{}", string);
+        }
+    }
+
+    #[test]
+    fn every_alias_has_a_constant() {
+        for &(alias, string) in SID_ALIASES {
+            assert!(
+                ALL.iter().any(|&(_, known)| known == string),
+                "{} ({}) has no constant",
+                alias,
+                string
+            );
+        }
+    }
+
+    #[test]
+    fn lookups_with_owned_sids() {
+        let owned: HashSet<LocalBox<Sid>> = ALL
+            .iter()
+            .map(|&(_, string)| string.parse().unwrap())
+            .collect();
+
+        assert_eq!(owned.len(), ALL.len());
+        for &(sid, string) in ALL {
+            assert!(owned.contains(sid), "{}", string);
+        }
+    }
+
+    #[test]
+    fn compare_with_ace_sids() {
+        let sd: LocalBox<SecurityDescriptor> = "O:BAG:SYD:(A;;FA;;;CO)(A;;FR;;;AU)S:(ML;;NW;;;HI)"
+            .parse()
+            .unwrap();
+        let dacl = sd.dacl().unwrap();
+
+        assert_eq!(sd.owner(), Some(ADMINISTRATORS));
+        assert_eq!(sd.group(), Some(LOCAL_SYSTEM));
+        assert_eq!(dacl.get_ace(0).unwrap().sid(), Some(CREATOR_OWNER));
+        assert_eq!(dacl.get_ace(1).unwrap().sid(), Some(AUTHENTICATED_USERS));
+        assert_eq!(
+            sd.sacl().unwrap().get_ace(0).unwrap().sid(),
+            Some(HIGH_MANDATORY_LEVEL)
+        );
+    }
+}
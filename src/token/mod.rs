@@ -0,0 +1,805 @@
+//! A portable model of Windows access tokens.
+//!
+//! An [access token](https://docs.microsoft.com/en-us/windows/win32/secauthz/access-tokens)
+//! describes the security context of a process or thread: the user, the
+//! groups they belong to, the privileges they hold, and so on. Windows
+//! compares it against security descriptors to decide what the process may do.
+//!
+//! A [`Token`] is plain data. It can be built by hand on any platform, for
+//! example from SIDs parsed out of stored strings:
+//!
+//! ```
+//! use windows_permissions::{LocalBox, Sid, Token};
+//! use windows_permissions::constants::GroupAttributes;
+//!
+//! let user: LocalBox<Sid> = "S-1-5-21-1-2-3-1001".parse().unwrap();
+//! let users: LocalBox<Sid> = "BU".parse().unwrap();
+//!
+//! let token = Token::new(user.clone())
+//!     .with_group(users, GroupAttributes::Mandatory | GroupAttributes::Enabled);
+//!
+//! assert_eq!(token.user().sid(), &*user);
+//! assert_eq!(token.groups().len(), 1);
+//! assert_eq!(token.owner(), &*user);
+//! ```
+//!
+//! It can also be parsed from the output of `whoami /all` with
+//! [`Token::from_whoami`], which works on any platform. With the `serde`
+//! feature, it can be serialized and read back in any format that serde
+//! supports.
+//!
+//! On Windows, it can also be read from a live token with
+//! `Token::from_current_process`, `Token::from_current_thread`, or
+//! `Token::from_handle`.
+
+use crate::constants::{ClaimFlags, GroupAttributes, PrivilegeAttributes};
+use crate::{LocalBox, Privilege, Sid};
+
+mod whoami;
+
+/// The security attribute that marks less privileged AppContainer tokens
+const LPAC_CLAIM: &str = "WIN://NOALLAPPPKG";
+
+#[cfg(feature = "serde")]
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// A SID in a token, along with its `SE_GROUP_*` attributes.
+///
+/// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-sid_and_attributes)
+/// for details.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SidAndAttributes {
+    sid: LocalBox<Sid>,
+    #[cfg_attr(feature = "serde", serde(default = "GroupAttributes::empty"))]
+    attributes: GroupAttributes,
+}
+
+impl SidAndAttributes {
+    /// Create a new `SidAndAttributes`
+    pub fn new(sid: LocalBox<Sid>, attributes: GroupAttributes) -> Self {
+        Self { sid, attributes }
+    }
+
+    /// Get the SID
+    pub fn sid(&self) -> &Sid {
+        &self.sid
+    }
+
+    /// Get the attributes
+    pub fn attributes(&self) -> GroupAttributes {
+        self.attributes
+    }
+
+    /// Check whether the SID is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.attributes.contains(GroupAttributes::Enabled)
+    }
+
+    /// Check whether the SID may only be used to match access-denied ACEs
+    pub fn is_deny_only(&self) -> bool {
+        self.attributes.contains(GroupAttributes::UseForDenyOnly)
+    }
+}
+
+/// A locally unique identifier.
+///
+/// LUIDs are used to identify privileges and logon sessions. See
+/// [MSDN](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-luid)
+/// for details.
+///
+/// With the `serde` feature, a LUID is serialized as a single integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(from = "u64", into = "u64"))]
+pub struct Luid {
+    /// The high-order bits
+    pub high_part: i32,
+
+    /// The low-order bits
+    pub low_part: u32,
+}
+
+impl From<u64> for Luid {
+    fn from(value: u64) -> Self {
+        Self {
+            high_part: (value >> 32) as i32,
+            low_part: value as u32,
+        }
+    }
+}
+
+impl From<Luid> for u64 {
+    fn from(luid: Luid) -> Self {
+        (luid.high_part as u32 as u64) << 32 | luid.low_part as u64
+    }
+}
+
+/// A privilege in a token, along with its `SE_PRIVILEGE_*` attributes.
+///
+/// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-luid_and_attributes)
+/// for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LuidAndAttributes {
+    luid: Luid,
+    #[cfg_attr(feature = "serde", serde(default = "PrivilegeAttributes::empty"))]
+    attributes: PrivilegeAttributes,
+}
+
+impl LuidAndAttributes {
+    /// Create a new `LuidAndAttributes`
+    pub fn new(luid: Luid, attributes: PrivilegeAttributes) -> Self {
+        Self { luid, attributes }
+    }
+
+    /// Get the LUID identifying the privilege
+    pub fn luid(&self) -> Luid {
+        self.luid
+    }
+
+    /// Get the attributes
+    pub fn attributes(&self) -> PrivilegeAttributes {
+        self.attributes
+    }
+
+    /// Get the privilege, if the LUID is one of the standard values
+    pub fn privilege(&self) -> Option<Privilege> {
+        Privilege::from_luid(self.luid)
+    }
+
+    /// Check whether the privilege is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.attributes.contains(PrivilegeAttributes::Enabled)
+    }
+}
+
+/// The values of a claim security attribute.
+///
+/// Every value in a claim has the same type. See the `ValueType` member of
+/// [`CLAIM_SECURITY_ATTRIBUTE_V1` on MSDN](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-claim_security_attribute_v1)
+/// for details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ClaimValues {
+    /// `CLAIM_SECURITY_ATTRIBUTE_TYPE_INT64`
+    Int64(Vec<i64>),
+
+    /// `CLAIM_SECURITY_ATTRIBUTE_TYPE_UINT64`
+    Uint64(Vec<u64>),
+
+    /// `CLAIM_SECURITY_ATTRIBUTE_TYPE_STRING`
+    String(Vec<String>),
+
+    /// `CLAIM_SECURITY_ATTRIBUTE_TYPE_FQBN`, as (version, name) pairs
+    Fqbn(Vec<(u64, String)>),
+
+    /// `CLAIM_SECURITY_ATTRIBUTE_TYPE_SID`
+    Sid(Vec<LocalBox<Sid>>),
+
+    /// `CLAIM_SECURITY_ATTRIBUTE_TYPE_BOOLEAN`
+    Boolean(Vec<bool>),
+
+    /// `CLAIM_SECURITY_ATTRIBUTE_TYPE_OCTET_STRING`
+    OctetString(Vec<Vec<u8>>),
+}
+
+impl ClaimValues {
+    /// Get the number of values
+    pub fn len(&self) -> usize {
+        match self {
+            ClaimValues::Int64(v) => v.len(),
+            ClaimValues::Uint64(v) => v.len(),
+            ClaimValues::String(v) => v.len(),
+            ClaimValues::Fqbn(v) => v.len(),
+            ClaimValues::Sid(v) => v.len(),
+            ClaimValues::Boolean(v) => v.len(),
+            ClaimValues::OctetString(v) => v.len(),
+        }
+    }
+
+    /// Check whether there are no values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A claim security attribute, such as a user or device claim.
+///
+/// See [MSDN](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-claim_security_attribute_v1)
+/// for details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ClaimAttribute {
+    name: String,
+    #[cfg_attr(feature = "serde", serde(default = "ClaimFlags::empty"))]
+    flags: ClaimFlags,
+    values: ClaimValues,
+}
+
+impl ClaimAttribute {
+    /// Create a new claim
+    pub fn new<S: Into<String>>(name: S, flags: ClaimFlags, values: ClaimValues) -> Self {
+        Self {
+            name: name.into(),
+            flags,
+            values,
+        }
+    }
+
+    /// Get the name of the claim
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the flags set on the claim
+    pub fn flags(&self) -> ClaimFlags {
+        self.flags
+    }
+
+    /// Get the values of the claim
+    pub fn values(&self) -> &ClaimValues {
+        &self.values
+    }
+}
+
+/// An access token.
+///
+/// See the [module documentation](self) for an overview.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct Token {
+    user: SidAndAttributes,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    groups: Vec<SidAndAttributes>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    privileges: Vec<LuidAndAttributes>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    owner: Option<LocalBox<Sid>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    primary_group: Option<LocalBox<Sid>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    integrity_level: Option<LocalBox<Sid>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    restricting_sids: Vec<SidAndAttributes>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_false"))]
+    write_restricted: bool,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    app_container_sid: Option<LocalBox<Sid>>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_false"))]
+    less_privileged_app_container: bool,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    capabilities: Vec<SidAndAttributes>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    device_groups: Vec<SidAndAttributes>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    user_claims: Vec<ClaimAttribute>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    device_claims: Vec<ClaimAttribute>,
+}
+
+impl Token {
+    /// Create a token for a user, with nothing else in it
+    ///
+    /// The other parts of the token can be filled in with the `with_*`
+    /// methods.
+    pub fn new(user: LocalBox<Sid>) -> Self {
+        Self {
+            user: SidAndAttributes::new(user, GroupAttributes::empty()),
+            groups: Vec::new(),
+            privileges: Vec::new(),
+            owner: None,
+            primary_group: None,
+            integrity_level: None,
+            restricting_sids: Vec::new(),
+            write_restricted: false,
+            app_container_sid: None,
+            less_privileged_app_container: false,
+            capabilities: Vec::new(),
+            device_groups: Vec::new(),
+            user_claims: Vec::new(),
+            device_claims: Vec::new(),
+        }
+    }
+
+    /// Set the attributes on the user SID
+    ///
+    /// These are normally empty, but restricted tokens may mark the user
+    /// SID with `GroupAttributes::UseForDenyOnly`.
+    pub fn with_user_attributes(mut self, attributes: GroupAttributes) -> Self {
+        self.user.attributes = attributes;
+        self
+    }
+
+    /// Add a group SID
+    pub fn with_group(mut self, sid: LocalBox<Sid>, attributes: GroupAttributes) -> Self {
+        self.groups.push(SidAndAttributes::new(sid, attributes));
+        self
+    }
+
+    /// Add a privilege, given as a [`Privilege`] or a raw
+    /// LUID
+    pub fn with_privilege<L: Into<Luid>>(
+        mut self,
+        luid: L,
+        attributes: PrivilegeAttributes,
+    ) -> Self {
+        self.privileges
+            .push(LuidAndAttributes::new(luid.into(), attributes));
+        self
+    }
+
+    /// Set the default owner for objects created with this token
+    pub fn with_owner(mut self, owner: LocalBox<Sid>) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Set the default primary group for objects created with this token
+    pub fn with_primary_group(mut self, primary_group: LocalBox<Sid>) -> Self {
+        self.primary_group = Some(primary_group);
+        self
+    }
+
+    /// Set the mandatory integrity level, as a label SID such as `S-1-16-8192`
+    pub fn with_integrity_level(mut self, label: LocalBox<Sid>) -> Self {
+        self.integrity_level = Some(label);
+        self
+    }
+
+    /// Add a restricting SID, making this a restricted token
+    pub fn with_restricting_sid(mut self, sid: LocalBox<Sid>, attributes: GroupAttributes) -> Self {
+        self.restricting_sids
+            .push(SidAndAttributes::new(sid, attributes));
+        self
+    }
+
+    /// Set whether the restricting SIDs only apply to write access
+    ///
+    /// This is how tokens made with `CreateRestrictedToken` and
+    /// `WRITE_RESTRICTED` behave.
+    pub fn with_write_restricted(mut self, write_restricted: bool) -> Self {
+        self.write_restricted = write_restricted;
+        self
+    }
+
+    /// Set the AppContainer package SID, such as `S-1-15-2-...`, making this
+    /// an AppContainer token
+    pub fn with_app_container_sid(mut self, sid: LocalBox<Sid>) -> Self {
+        self.app_container_sid = Some(sid);
+        self
+    }
+
+    /// Set whether this is a less privileged AppContainer (LPAC) token
+    ///
+    /// LPAC tokens are not granted the access given to
+    /// `ALL APPLICATION PACKAGES`.
+    pub fn with_less_privileged_app_container(mut self, less_privileged: bool) -> Self {
+        self.less_privileged_app_container = less_privileged;
+        self
+    }
+
+    /// Add a capability SID
+    pub fn with_capability(mut self, sid: LocalBox<Sid>, attributes: GroupAttributes) -> Self {
+        self.capabilities
+            .push(SidAndAttributes::new(sid, attributes));
+        self
+    }
+
+    /// Add a device group SID, as used by `Device_Member_of` conditions
+    pub fn with_device_group(mut self, sid: LocalBox<Sid>, attributes: GroupAttributes) -> Self {
+        self.device_groups
+            .push(SidAndAttributes::new(sid, attributes));
+        self
+    }
+
+    /// Add a user claim
+    pub fn with_user_claim(mut self, claim: ClaimAttribute) -> Self {
+        self.user_claims.push(claim);
+        self
+    }
+
+    /// Add a device claim
+    pub fn with_device_claim(mut self, claim: ClaimAttribute) -> Self {
+        self.device_claims.push(claim);
+        self
+    }
+
+    /// Get the user SID and its attributes
+    pub fn user(&self) -> &SidAndAttributes {
+        &self.user
+    }
+
+    /// Get the group SIDs and their attributes
+    pub fn groups(&self) -> &[SidAndAttributes] {
+        &self.groups
+    }
+
+    /// Get the privileges and their attributes
+    pub fn privileges(&self) -> &[LuidAndAttributes] {
+        &self.privileges
+    }
+
+    /// Get the default owner for objects created with this token
+    ///
+    /// If no owner was set, this is the user SID, which matches the Windows
+    /// default.
+    pub fn owner(&self) -> &Sid {
+        self.owner.as_deref().unwrap_or_else(|| self.user.sid())
+    }
+
+    /// Get the default primary group for objects created with this token
+    pub fn primary_group(&self) -> Option<&Sid> {
+        self.primary_group.as_deref()
+    }
+
+    /// Get the mandatory integrity level label SID
+    ///
+    /// If no integrity level was set, this looks for a group with the
+    /// `GroupAttributes::Integrity` attribute.
+    pub fn integrity_level(&self) -> Option<&Sid> {
+        self.integrity_level.as_deref().or_else(|| {
+            self.groups
+                .iter()
+                .find(|g| g.attributes().contains(GroupAttributes::Integrity))
+                .map(|g| g.sid())
+        })
+    }
+
+    /// Get the restricting SIDs
+    pub fn restricting_sids(&self) -> &[SidAndAttributes] {
+        &self.restricting_sids
+    }
+
+    /// Check whether this is a restricted token
+    pub fn is_restricted(&self) -> bool {
+        !self.restricting_sids.is_empty()
+    }
+
+    /// Check whether the restricting SIDs only apply to write access
+    pub fn is_write_restricted(&self) -> bool {
+        self.write_restricted
+    }
+
+    /// Get the AppContainer package SID
+    pub fn app_container_sid(&self) -> Option<&Sid> {
+        self.app_container_sid.as_deref()
+    }
+
+    /// Check whether this is an AppContainer token
+    pub fn is_app_container(&self) -> bool {
+        self.app_container_sid.is_some()
+    }
+
+    /// Check whether this is a less privileged AppContainer (LPAC) token
+    ///
+    /// This is true if it was set with
+    /// [`with_less_privileged_app_container`](Self::with_less_privileged_app_container),
+    /// or if the token has a `WIN://NOALLAPPPKG` user claim, as LPAC tokens
+    /// do on Windows.
+    pub fn is_less_privileged_app_container(&self) -> bool {
+        self.is_app_container()
+            && (self.less_privileged_app_container
+                || self
+                    .user_claims
+                    .iter()
+                    .any(|claim| claim.name().eq_ignore_ascii_case(LPAC_CLAIM)))
+    }
+
+    /// Get the capability SIDs
+    pub fn capabilities(&self) -> &[SidAndAttributes] {
+        &self.capabilities
+    }
+
+    /// Get the device group SIDs
+    pub fn device_groups(&self) -> &[SidAndAttributes] {
+        &self.device_groups
+    }
+
+    /// Get the user claims
+    pub fn user_claims(&self) -> &[ClaimAttribute] {
+        &self.user_claims
+    }
+
+    /// Get the device claims
+    pub fn device_claims(&self) -> &[ClaimAttribute] {
+        &self.device_claims
+    }
+
+    /// Check whether the token holds a privilege, and it is enabled
+    pub fn has_enabled_privilege<L: Into<Luid>>(&self, luid: L) -> bool {
+        let luid = luid.into();
+        self.privileges
+            .iter()
+            .any(|p| p.luid() == luid && p.is_enabled())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use super::*;
+    use crate::wrappers;
+    use std::io;
+    use std::os::windows::io::AsRawHandle;
+    use winapi::um::processthreadsapi::{GetCurrentProcess, GetCurrentThread};
+    use winapi::um::winnt::*;
+
+    impl Token {
+        /// Read the token of the current process
+        pub fn from_current_process() -> io::Result<Self> {
+            let token =
+                wrappers::OpenProcessToken(unsafe { GetCurrentProcess() } as _, TOKEN_QUERY)?;
+            Self::from_handle(&token)
+        }
+
+        /// Read the impersonation token of the current thread
+        ///
+        /// If the thread is not impersonating, this fails with
+        /// `ERROR_NO_TOKEN`.
+        pub fn from_current_thread() -> io::Result<Self> {
+            let token =
+                wrappers::OpenThreadToken(unsafe { GetCurrentThread() } as _, TOKEN_QUERY, true)?;
+            Self::from_handle(&token)
+        }
+
+        /// Read a token from a handle
+        ///
+        /// The handle must have been opened with `TOKEN_QUERY` access.
+        pub fn from_handle<H: AsRawHandle>(token: &H) -> io::Result<Self> {
+            Ok(Self {
+                user: wrappers::GetTokenInformationUser(token)?,
+                groups: wrappers::GetTokenInformationGroups(token, TokenGroups)?,
+                privileges: wrappers::GetTokenInformationPrivileges(token)?,
+                owner: wrappers::GetTokenInformationSid(token, TokenOwner)?,
+                primary_group: wrappers::GetTokenInformationSid(token, TokenPrimaryGroup)?,
+                integrity_level: wrappers::GetTokenInformationSid(token, TokenIntegrityLevel)?,
+                restricting_sids: wrappers::GetTokenInformationGroups(token, TokenRestrictedSids)?,
+                write_restricted: wrappers::GetTokenInformationWriteRestricted(token)?,
+                app_container_sid: wrappers::GetTokenInformationSid(token, TokenAppContainerSid)?,
+                less_privileged_app_container:
+                    wrappers::GetTokenInformationLessPrivilegedAppContainer(token)?,
+                capabilities: wrappers::GetTokenInformationGroups(token, TokenCapabilities)?,
+                device_groups: wrappers::GetTokenInformationGroups(token, TokenDeviceGroups)?,
+                user_claims: wrappers::GetTokenInformationClaims(token, TokenUserClaimAttributes)?,
+                device_claims: wrappers::GetTokenInformationClaims(
+                    token,
+                    TokenDeviceClaimAttributes,
+                )?,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sid(s: &str) -> LocalBox<Sid> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn build_by_hand() {
+        let token = Token::new(sid("S-1-5-21-1-2-3-1001"))
+            .with_group(
+                sid("WD"),
+                GroupAttributes::Mandatory | GroupAttributes::Enabled,
+            )
+            .with_group(sid("BA"), GroupAttributes::UseForDenyOnly)
+            .with_privilege(Privilege::Backup, PrivilegeAttributes::Enabled)
+            .with_privilege(Luid::from(9), PrivilegeAttributes::empty())
+            .with_primary_group(sid("S-1-5-21-1-2-3-513"))
+            .with_restricting_sid(sid("RC"), GroupAttributes::Enabled)
+            .with_capability(sid("S-1-15-3-1"), GroupAttributes::Enabled)
+            .with_user_claim(ClaimAttribute::new(
+                "Clearance",
+                ClaimFlags::empty(),
+                ClaimValues::Uint64(vec![3]),
+            ));
+
+        assert_eq!(token.user().sid(), &*sid("S-1-5-21-1-2-3-1001"));
+        assert_eq!(token.groups().len(), 2);
+        assert!(token.groups()[0].is_enabled());
+        assert!(token.groups()[1].is_deny_only());
+        assert!(token.has_enabled_privilege(Privilege::Backup));
+        assert!(token.has_enabled_privilege(Luid::from(17)));
+        assert_eq!(
+            token.privileges()[1].privilege(),
+            Some(Privilege::TakeOwnership)
+        );
+        assert!(!token.has_enabled_privilege(Luid::from(9)));
+        assert!(!token.has_enabled_privilege(Luid::from(8)));
+        assert_eq!(token.owner(), token.user().sid());
+        assert_eq!(token.primary_group(), Some(&*sid("S-1-5-21-1-2-3-513")));
+        assert!(token.is_restricted());
+        assert_eq!(token.capabilities().len(), 1);
+        assert_eq!(token.user_claims()[0].name(), "Clearance");
+        assert_eq!(token.user_claims()[0].values().len(), 1);
+        assert!(token.device_claims().is_empty());
+    }
+
+    #[test]
+    fn integrity_level_falls_back_to_groups() {
+        let token = Token::new(sid("SY"));
+        assert_eq!(token.integrity_level(), None);
+
+        let token = token.with_group(
+            sid("ME"),
+            GroupAttributes::Integrity | GroupAttributes::IntegrityEnabled,
+        );
+        assert_eq!(token.integrity_level(), Some(&*sid("S-1-16-8192")));
+
+        let token = token.with_integrity_level(sid("HI"));
+        assert_eq!(token.integrity_level(), Some(&*sid("S-1-16-12288")));
+    }
+
+    #[test]
+    fn app_container() {
+        let token = Token::new(sid("S-1-5-21-1-2-3-1001"));
+        assert!(!token.is_app_container());
+        assert!(!token.is_less_privileged_app_container());
+
+        let package = sid("S-1-15-2-1-2-3-4-5-6-7");
+        let token = token.with_app_container_sid(package.clone());
+        assert_eq!(token.app_container_sid(), Some(&*package));
+        assert!(!token.is_less_privileged_app_container());
+
+        let lpac = token.clone().with_user_claim(ClaimAttribute::new(
+            "WIN://NOALLAPPPKG",
+            ClaimFlags::empty(),
+            ClaimValues::Uint64(vec![0]),
+        ));
+        assert!(lpac.is_less_privileged_app_container());
+        assert!(token
+            .with_less_privileged_app_container(true)
+            .is_less_privileged_app_container());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip() {
+        let token = Token::new(sid("S-1-5-21-1-2-3-1001"))
+            .with_group(
+                sid("WD"),
+                GroupAttributes::Mandatory | GroupAttributes::Enabled,
+            )
+            .with_privilege(Privilege::Backup, PrivilegeAttributes::Enabled)
+            .with_integrity_level(sid("ME"))
+            .with_write_restricted(true)
+            .with_user_claim(ClaimAttribute::new(
+                "Clearance",
+                ClaimFlags::ValueCaseSensitive,
+                ClaimValues::Fqbn(vec![(1, "Contoso".to_owned())]),
+            ))
+            .with_device_claim(ClaimAttribute::new(
+                "Managed",
+                ClaimFlags::empty(),
+                ClaimValues::Sid(vec![sid("SY")]),
+            ));
+
+        let json = serde_json::to_value(&token).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "user": { "sid": "S-1-5-21-1-2-3-1001", "attributes": [] },
+                "groups": [{ "sid": "S-1-1-0", "attributes": ["Mandatory", "Enabled"] }],
+                "privileges": [{ "luid": 17, "attributes": ["Enabled"] }],
+                "integrity_level": "S-1-16-8192",
+                "write_restricted": true,
+                "user_claims": [{
+                    "name": "Clearance",
+                    "flags": ["ValueCaseSensitive"],
+                    "values": { "Fqbn": [[1, "Contoso"]] },
+                }],
+                "device_claims": [{
+                    "name": "Managed",
+                    "flags": [],
+                    "values": { "Sid": ["S-1-5-18"] },
+                }],
+            })
+        );
+        assert_eq!(serde_json::from_value::<Token>(json).unwrap(), token);
+
+        let yaml = serde_yaml::to_string(&token).unwrap();
+        assert_eq!(serde_yaml::from_str::<Token>(&yaml).unwrap(), token);
+
+        // Attributes default to empty, and SDDL aliases are accepted
+        let read: Token = serde_json::from_value(serde_json::json!({
+            "user": { "sid": "SY" },
+            "privileges": [{ "luid": 17 }],
+        }))
+        .unwrap();
+        assert_eq!(
+            read,
+            Token::new(sid("SY")).with_privilege(Privilege::Backup, PrivilegeAttributes::empty())
+        );
+    }
+
+    #[test]
+    fn luid_round_trip() {
+        for value in &[0u64, 1, 17, 0xFFFF_FFFF, 0x1_0000_0000, u64::MAX] {
+            assert_eq!(u64::from(Luid::from(*value)), *value);
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn read_current_process() {
+        let token = Token::from_current_process().unwrap();
+
+        assert_eq!(
+            token.user().sid(),
+            &*crate::utilities::current_process_sid().unwrap()
+        );
+        assert!(token.integrity_level().is_some());
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn read_from_handle() {
+        use winapi::um::processthreadsapi::GetCurrentProcess;
+        use winapi::um::winnt::TOKEN_QUERY;
+
+        let handle =
+            crate::wrappers::OpenProcessToken(unsafe { GetCurrentProcess() } as _, TOKEN_QUERY)
+                .unwrap();
+        let token = Token::from_handle(&handle).unwrap();
+
+        assert_eq!(
+            token.user().sid(),
+            &*crate::utilities::current_process_sid().unwrap()
+        );
+        assert!(!token.groups().is_empty());
+        assert!(token.primary_group().is_some());
+        assert!(!token.is_app_container());
+        assert!(!token.is_less_privileged_app_container());
+        assert_eq!(
+            token.owner(),
+            &*crate::wrappers::GetTokenInformationSid(&handle, winapi::um::winnt::TokenOwner)
+                .unwrap()
+                .unwrap()
+        );
+    }
+}
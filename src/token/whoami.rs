@@ -0,0 +1,522 @@
+//! Parsing the output of `whoami`.
+//!
+//! `whoami` prints tables in one of two formats. The default (`/fo table`)
+//! lays out columns under a line of `=` runs, and `/fo csv` prints quoted,
+//! comma-separated rows. In both, the headers are localized, so rows are
+//! recognized by their shape instead:
+//!
+//! - The user table has two columns, the second of which is a SID
+//! - The group table has four columns, the third of which is a SID
+//! - The privilege table has three columns, the first of which is a
+//!   privilege name
+//!
+//! Anything else (section titles, headers, claims) is ignored.
+//!
+//! Group attributes and privilege states are localized too. The phrases
+//! printed by English, German, French and Spanish systems are recognized;
+//! any other phrase is ignored, so the attribute it describes is left unset.
+
+use super::{LuidAndAttributes, SidAndAttributes, Token};
+use crate::constants::{GroupAttributes, PrivilegeAttributes};
+use crate::{LocalBox, Privilege, Sid};
+use std::io;
+
+impl Token {
+    /// Parse the output of `whoami /all`
+    ///
+    /// Both the table and CSV formats are supported. The output must include
+    /// the user; to parse output that doesn't, such as `whoami /groups`, start
+    /// from [`Token::new`] and use [`Token::with_whoami`].
+    ///
+    /// ```
+    /// use windows_permissions::{Privilege, Token};
+    ///
+    /// let output = r#"
+    /// "User Name","SID"
+    /// "desktop\alice","S-1-5-21-1-2-3-1001"
+    ///
+    /// "Group Name","Type","SID","Attributes"
+    /// "Everyone","Well-known group","S-1-1-0","Mandatory group, Enabled by default, Enabled group"
+    /// "Mandatory Label\Medium Mandatory Level","Label","S-1-16-8192",""
+    ///
+    /// "Privilege Name","Description","State"
+    /// "SeChangeNotifyPrivilege","Bypass traverse checking","Enabled"
+    /// "SeShutdownPrivilege","Shut down the system","Disabled"
+    /// "#;
+    ///
+    /// let token = Token::from_whoami(output).unwrap();
+    ///
+    /// assert_eq!(token.user().sid().to_string(), "S-1-5-21-1-2-3-1001");
+    /// assert_eq!(token.groups().len(), 2);
+    /// assert_eq!(token.integrity_level().unwrap().to_string(), "S-1-16-8192");
+    /// assert!(token.has_enabled_privilege(Privilege::ChangeNotify));
+    /// assert!(!token.has_enabled_privilege(Privilege::Shutdown));
+    /// ```
+    pub fn from_whoami(output: &str) -> io::Result<Self> {
+        let rows = parse_rows(output)?;
+
+        let user = rows
+            .iter()
+            .find_map(|row| match row {
+                Row::User(sid) => Some(sid.clone()),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "whoami output does not include the user",
+                )
+            })?;
+
+        Ok(Token::new(user).with_rows(rows))
+    }
+
+    /// Add the groups, privileges, and integrity level from the output of
+    /// `whoami`
+    ///
+    /// This accepts the same formats as [`Token::from_whoami`], but the user
+    /// is optional. If it is present, it replaces the user in this token.
+    ///
+    /// ```
+    /// use windows_permissions::{LocalBox, Sid, Token};
+    ///
+    /// let output = r#"
+    /// "Group Name","Type","SID","Attributes"
+    /// "BUILTIN\Administrators","Alias","S-1-5-32-544","Group used for deny only"
+    /// "#;
+    ///
+    /// let user: LocalBox<Sid> = "S-1-5-21-1-2-3-1001".parse().unwrap();
+    /// let token = Token::new(user).with_whoami(output).unwrap();
+    ///
+    /// assert!(token.groups()[0].is_deny_only());
+    /// ```
+    pub fn with_whoami(self, output: &str) -> io::Result<Self> {
+        Ok(self.with_rows(parse_rows(output)?))
+    }
+
+    fn with_rows(mut self, rows: Vec<Row>) -> Self {
+        for row in rows {
+            match row {
+                Row::User(sid) => {
+                    self.user = SidAndAttributes::new(sid, self.user.attributes());
+                }
+                Row::Group(group) => {
+                    if group.attributes().contains(GroupAttributes::Integrity) {
+                        self.integrity_level = Some(group.sid.clone());
+                    }
+                    self.groups.push(group);
+                }
+                Row::Privilege(privilege) => self.privileges.push(privilege),
+            }
+        }
+
+        self
+    }
+}
+
+enum Row {
+    User(LocalBox<Sid>),
+    Group(SidAndAttributes),
+    Privilege(LuidAndAttributes),
+}
+
+fn parse_rows(output: &str) -> io::Result<Vec<Row>> {
+    let mut rows = Vec::new();
+    let mut columns: Option<Vec<(usize, usize)>> = None;
+
+    for line in output.lines() {
+        let line = line.trim_end();
+
+        if line.is_empty() {
+            columns = None;
+        } else if line.starts_with('"') {
+            if let Some(row) = parse_row(&split_csv(line)?) {
+                rows.push(row);
+            }
+        } else if let Some(spans) = separator_spans(line) {
+            columns = Some(spans);
+        } else if let Some(spans) = &columns {
+            if let Some(row) = parse_row(&split_columns(line, spans)) {
+                rows.push(row);
+            }
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Parse a line of `=` runs into the (start, end) character offsets of the
+/// columns below it
+fn separator_spans(line: &str) -> Option<Vec<(usize, usize)>> {
+    if !line.starts_with('=') || !line.chars().all(|c| c == '=' || c == ' ') {
+        return None;
+    }
+
+    let mut spans = Vec::new();
+    let mut start = None;
+
+    for (index, c) in line.chars().chain(Some(' ')).enumerate() {
+        match (c, start) {
+            ('=', None) => start = Some(index),
+            (' ', Some(s)) => {
+                spans.push((s, index));
+                start = None;
+            }
+            _ => (),
+        }
+    }
+
+    Some(spans)
+}
+
+/// Split a table row into columns; the last column runs to the end of line
+fn split_columns(line: &str, spans: &[(usize, usize)]) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+
+    spans
+        .iter()
+        .enumerate()
+        .map(|(index, &(start, end))| {
+            let end = if index + 1 == spans.len() {
+                chars.len()
+            } else {
+                end.min(chars.len())
+            };
+            let start = start.min(end);
+            chars[start..end]
+                .iter()
+                .collect::<String>()
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+/// Split a CSV row, handling quoted fields and doubled quotes
+fn split_csv(line: &str) -> io::Result<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        let mut field = String::new();
+
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("Unterminated quote in CSV row: {:?}", line),
+                        ))
+                    }
+                }
+            }
+        }
+
+        while let Some(&c) = chars.peek() {
+            if c == ',' {
+                break;
+            }
+            field.push(c);
+            chars.next();
+        }
+
+        fields.push(field);
+
+        if chars.next().is_none() {
+            return Ok(fields);
+        }
+    }
+}
+
+fn parse_sid(field: &str) -> Option<LocalBox<Sid>> {
+    if field.starts_with("S-1-") {
+        field.parse().ok()
+    } else {
+        None
+    }
+}
+
+fn parse_row(fields: &[String]) -> Option<Row> {
+    match fields {
+        [_name, sid] => parse_sid(sid).map(Row::User),
+        [_name, _kind, sid, attributes] => parse_sid(sid).map(|sid| {
+            let attributes = group_attributes(&sid, attributes);
+            Row::Group(SidAndAttributes::new(sid, attributes))
+        }),
+        [name, _description, state] => name.parse::<Privilege>().ok().map(|privilege| {
+            Row::Privilege(LuidAndAttributes::new(
+                privilege.luid(),
+                privilege_attributes(state),
+            ))
+        }),
+        _ => None,
+    }
+}
+
+/// Group attribute phrases, in lowercase
+const GROUP_PHRASES: &[(&str, GroupAttributes)] = &[
+    ("mandatory group", GroupAttributes::Mandatory),
+    ("enabled by default", GroupAttributes::EnabledByDefault),
+    ("enabled group", GroupAttributes::Enabled),
+    ("group owner", GroupAttributes::Owner),
+    ("group used for deny only", GroupAttributes::UseForDenyOnly),
+    ("local group", GroupAttributes::Resource),
+    // German
+    ("verbindliche gruppe", GroupAttributes::Mandatory),
+    ("obligatorische gruppe", GroupAttributes::Mandatory),
+    ("standardmäßig aktiviert", GroupAttributes::EnabledByDefault),
+    ("aktivierte gruppe", GroupAttributes::Enabled),
+    ("gruppenbesitzer", GroupAttributes::Owner),
+    (
+        "gruppe wird nur zum verweigern verwendet",
+        GroupAttributes::UseForDenyOnly,
+    ),
+    ("lokale gruppe", GroupAttributes::Resource),
+    // French
+    ("groupe obligatoire", GroupAttributes::Mandatory),
+    ("activé par défaut", GroupAttributes::EnabledByDefault),
+    ("groupe activé", GroupAttributes::Enabled),
+    ("propriétaire du groupe", GroupAttributes::Owner),
+    (
+        "groupe utilisé pour le refus uniquement",
+        GroupAttributes::UseForDenyOnly,
+    ),
+    ("groupe local", GroupAttributes::Resource),
+    // Spanish
+    ("grupo obligatorio", GroupAttributes::Mandatory),
+    (
+        "habilitado de manera predeterminada",
+        GroupAttributes::EnabledByDefault,
+    ),
+    ("grupo habilitado", GroupAttributes::Enabled),
+    ("propietario del grupo", GroupAttributes::Owner),
+    (
+        "grupo usado solo para denegar",
+        GroupAttributes::UseForDenyOnly,
+    ),
+    ("grupo local", GroupAttributes::Resource),
+];
+
+/// Privilege states that mean the privilege is enabled, in lowercase
+const ENABLED_PHRASES: &[&str] = &["enabled", "aktiviert", "activé", "habilitada"];
+
+fn group_attributes(sid: &Sid, text: &str) -> GroupAttributes {
+    let mut attributes = GroupAttributes::empty();
+
+    for phrase in text.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let phrase = phrase.to_lowercase();
+        if let Some(&(_, flag)) = GROUP_PHRASES.iter().find(|(known, _)| *known == phrase) {
+            attributes |= flag;
+        }
+    }
+
+    // whoami does not print these attributes, but they follow from the SID
+    let id_auth = Sid::id_auth_to_number(*sid.id_authority());
+    if id_auth == 16 {
+        attributes |= GroupAttributes::Integrity | GroupAttributes::IntegrityEnabled;
+    } else if id_auth == 5 && sid.sub_authority(0) == Some(5) {
+        attributes |= GroupAttributes::LogonId;
+    }
+
+    attributes
+}
+
+fn privilege_attributes(state: &str) -> PrivilegeAttributes {
+    if ENABLED_PHRASES.contains(&state.trim().to_lowercase().as_str()) {
+        PrivilegeAttributes::Enabled
+    } else {
+        PrivilegeAttributes::empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const WHOAMI_ALL: &str = r#"
+USER INFORMATION
+----------------
+
+User Name         SID
+================= =============================================
+desktop-abc\alice S-1-5-21-1004336348-1177238915-682003330-1001
+
+
+GROUP INFORMATION
+-----------------
+
+Group Name                                                    Type             SID              Attributes
+============================================================= ================ ================ ==================================================
+Everyone                                                      Well-known group S-1-1-0          Mandatory group, Enabled by default, Enabled group
+NT AUTHORITY\Local account and member of Administrators group Well-known group S-1-5-114        Group used for deny only
+BUILTIN\Administrators                                        Alias            S-1-5-32-544     Group used for deny only
+BUILTIN\Users                                                 Alias            S-1-5-32-545     Mandatory group, Enabled by default, Enabled group
+NT AUTHORITY\LogonSessionId_0_240563                          Logon ID         S-1-5-5-0-240563 Mandatory group, Enabled by default, Enabled group
+Mandatory Label\Medium Mandatory Level                        Label            S-1-16-8192
+
+
+PRIVILEGES INFORMATION
+----------------------
+
+Privilege Name                Description                          State
+============================= ==================================== ========
+SeShutdownPrivilege           Shut down the system                 Disabled
+SeChangeNotifyPrivilege       Bypass traverse checking             Enabled
+SeUndockPrivilege             Remove computer from docking station Disabled
+
+
+USER CLAIMS INFORMATION
+-----------------------
+
+User claims unknown.
+
+Kerberos support for Dynamic Access Control on this device has been disabled.
+"#;
+
+    #[test]
+    fn parse_table() {
+        let token = Token::from_whoami(WHOAMI_ALL).unwrap();
+
+        assert_eq!(
+            token.user().sid().to_string(),
+            "S-1-5-21-1004336348-1177238915-682003330-1001"
+        );
+
+        let groups: Vec<_> = token
+            .groups()
+            .iter()
+            .map(|g| (g.sid().to_string(), g.attributes()))
+            .collect();
+        let enabled = GroupAttributes::Mandatory
+            | GroupAttributes::EnabledByDefault
+            | GroupAttributes::Enabled;
+        assert_eq!(
+            groups,
+            vec![
+                ("S-1-1-0".to_string(), enabled),
+                ("S-1-5-114".to_string(), GroupAttributes::UseForDenyOnly),
+                ("S-1-5-32-544".to_string(), GroupAttributes::UseForDenyOnly),
+                ("S-1-5-32-545".to_string(), enabled),
+                (
+                    "S-1-5-5-0-240563".to_string(),
+                    enabled | GroupAttributes::LogonId
+                ),
+                (
+                    "S-1-16-8192".to_string(),
+                    GroupAttributes::Integrity | GroupAttributes::IntegrityEnabled
+                ),
+            ]
+        );
+
+        assert_eq!(token.integrity_level().unwrap().to_string(), "S-1-16-8192");
+
+        let privileges: Vec<_> = token
+            .privileges()
+            .iter()
+            .map(|p| (p.privilege().unwrap(), p.is_enabled()))
+            .collect();
+        assert_eq!(
+            privileges,
+            vec![
+                (Privilege::Shutdown, false),
+                (Privilege::ChangeNotify, true),
+                (Privilege::Undock, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_localized_csv() {
+        // From a German system, with the group table printed with /nh
+        let output = concat!(
+            "\"Benutzername\",\"SID\"\r\n",
+            "\"desktop-abc\\alice\",\"S-1-5-21-1-2-3-1001\"\r\n",
+            "\r\n",
+            "\"Jeder\",\"Bekannte Gruppe\",\"S-1-1-0\",",
+            "\"Verbindliche Gruppe, Standardmäßig aktiviert, Aktivierte Gruppe\"\r\n",
+            "\"VORDEFINIERT\\Administratoren\",\"Alias\",\"S-1-5-32-544\",",
+            "\"Gruppe wird nur zum Verweigern verwendet\"\r\n",
+            "\"VORDEFINIERT\\Benutzer\",\"Alias\",\"S-1-5-32-545\",",
+            "\"Obligatorische Gruppe, Gruppenbesitzer\"\r\n",
+            "\"Verbindliche Beschriftung\\Mittlere Verbindlichkeitsstufe\",",
+            "\"Bezeichnung\",\"S-1-16-8192\",\"\"\r\n",
+            "\r\n",
+            "\"Berechtigungsname\",\"Beschreibung\",\"Status\"\r\n",
+            "\"SeShutdownPrivilege\",\"Herunterfahren des Systems\",\"Deaktiviert\"\r\n",
+            "\"SeChangeNotifyPrivilege\",\"Auslassen der durchsuchenden Überprüfung\",\"Aktiviert\"\r\n",
+        );
+
+        let token = Token::from_whoami(output).unwrap();
+
+        assert_eq!(token.user().sid().to_string(), "S-1-5-21-1-2-3-1001");
+
+        let groups: Vec<_> = token
+            .groups()
+            .iter()
+            .map(|g| (g.sid().to_string(), g.attributes()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                (
+                    "S-1-1-0".to_string(),
+                    GroupAttributes::Mandatory
+                        | GroupAttributes::EnabledByDefault
+                        | GroupAttributes::Enabled
+                ),
+                ("S-1-5-32-544".to_string(), GroupAttributes::UseForDenyOnly),
+                (
+                    "S-1-5-32-545".to_string(),
+                    GroupAttributes::Mandatory | GroupAttributes::Owner
+                ),
+                (
+                    "S-1-16-8192".to_string(),
+                    GroupAttributes::Integrity | GroupAttributes::IntegrityEnabled
+                ),
+            ]
+        );
+
+        assert_eq!(token.integrity_level().unwrap().to_string(), "S-1-16-8192");
+        assert!(token.has_enabled_privilege(Privilege::ChangeNotify));
+        assert!(!token.has_enabled_privilege(Privilege::Shutdown));
+        assert_eq!(token.privileges().len(), 2);
+    }
+
+    #[test]
+    fn unknown_phrases_are_ignored() {
+        // Japanese, which has no phrase table
+        let output = concat!(
+            "\"alice\",\"S-1-5-21-1-2-3-1001\"\n",
+            "\"Everyone\",\"既知のグループ\",\"S-1-1-0\",\"必須グループ, Enabled group\"\n",
+            "\"SeChangeNotifyPrivilege\",\"走査チェックのバイパス\",\"有効\"\n",
+        );
+
+        let token = Token::from_whoami(output).unwrap();
+
+        assert_eq!(token.groups()[0].attributes(), GroupAttributes::Enabled);
+        assert_eq!(token.privileges().len(), 1);
+        assert!(!token.privileges()[0].is_enabled());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Token::from_whoami("").is_err());
+        assert!(Token::from_whoami("\"User\",\"S-1-5-18").is_err());
+    }
+
+    #[test]
+    fn split_csv_quotes() {
+        assert_eq!(
+            split_csv(r#""a","b ""quoted"", c","""#).unwrap(),
+            vec!["a", "b \"quoted\", c", ""]
+        );
+    }
+}
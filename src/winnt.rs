@@ -0,0 +1,434 @@
+//! Portable definitions of the WinAPI types and constants this crate uses.
+//!
+//! On Windows, this simply re-exports `winapi`. Elsewhere, `winapi` is empty,
+//! so the subset of `winnt.h` (plus a few values from `accctrl.h`,
+//! `minwinbase.h`, `winsvc.h`, and `winuser.h`) needed by the pure data
+//! structures is defined here with identical names, values, and layouts.
+
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(dead_code)]
+#![allow(non_upper_case_globals)]
+#![allow(clippy::upper_case_acronyms)]
+
+#[cfg(target_os = "windows")]
+pub use winapi::um::accctrl::*;
+#[cfg(target_os = "windows")]
+pub use winapi::um::minwinbase::*;
+#[cfg(target_os = "windows")]
+pub use winapi::um::winnt::*;
+
+#[cfg(target_os = "windows")]
+pub use winapi::um::winsvc::{
+    SC_MANAGER_ALL_ACCESS, SC_MANAGER_CONNECT, SC_MANAGER_CREATE_SERVICE,
+    SC_MANAGER_ENUMERATE_SERVICE, SC_MANAGER_LOCK, SC_MANAGER_MODIFY_BOOT_CONFIG,
+    SC_MANAGER_QUERY_LOCK_STATUS, SERVICE_ALL_ACCESS, SERVICE_CHANGE_CONFIG,
+    SERVICE_ENUMERATE_DEPENDENTS, SERVICE_INTERROGATE, SERVICE_PAUSE_CONTINUE,
+    SERVICE_QUERY_CONFIG, SERVICE_QUERY_STATUS, SERVICE_START, SERVICE_STOP,
+    SERVICE_USER_DEFINED_CONTROL,
+};
+
+#[cfg(target_os = "windows")]
+pub use winapi::um::winuser::{
+    DESKTOP_CREATEMENU, DESKTOP_CREATEWINDOW, DESKTOP_ENUMERATE, DESKTOP_HOOKCONTROL,
+    DESKTOP_JOURNALPLAYBACK, DESKTOP_JOURNALRECORD, DESKTOP_READOBJECTS, DESKTOP_SWITCHDESKTOP,
+    DESKTOP_WRITEOBJECTS, WINSTA_ACCESSCLIPBOARD, WINSTA_ACCESSGLOBALATOMS, WINSTA_ALL_ACCESS,
+    WINSTA_CREATEDESKTOP, WINSTA_ENUMDESKTOPS, WINSTA_ENUMERATE, WINSTA_EXITWINDOWS,
+    WINSTA_READATTRIBUTES, WINSTA_READSCREEN, WINSTA_WRITEATTRIBUTES,
+};
+
+#[cfg(not(target_os = "windows"))]
+pub use self::portable::*;
+
+#[cfg(not(target_os = "windows"))]
+mod portable {
+    pub type BYTE = u8;
+    pub type WORD = u16;
+    pub type DWORD = u32;
+    pub type UINT = u32;
+
+    // accctrl.h: TRUSTEE_FORM
+    pub const TRUSTEE_IS_SID: DWORD = 0;
+    pub const TRUSTEE_IS_NAME: DWORD = 1;
+    pub const TRUSTEE_BAD_FORM: DWORD = 2;
+    pub const TRUSTEE_IS_OBJECTS_AND_SID: DWORD = 3;
+    pub const TRUSTEE_IS_OBJECTS_AND_NAME: DWORD = 4;
+
+    // accctrl.h: TRUSTEE_TYPE
+    pub const TRUSTEE_IS_UNKNOWN: DWORD = 0;
+    pub const TRUSTEE_IS_USER: DWORD = 1;
+    pub const TRUSTEE_IS_GROUP: DWORD = 2;
+    pub const TRUSTEE_IS_DOMAIN: DWORD = 3;
+    pub const TRUSTEE_IS_ALIAS: DWORD = 4;
+    pub const TRUSTEE_IS_WELL_KNOWN_GROUP: DWORD = 5;
+    pub const TRUSTEE_IS_DELETED: DWORD = 6;
+    pub const TRUSTEE_IS_INVALID: DWORD = 7;
+    pub const TRUSTEE_IS_COMPUTER: DWORD = 8;
+
+    // accctrl.h: MULTIPLE_TRUSTEE_OPERATION
+    pub const NO_MULTIPLE_TRUSTEE: DWORD = 0;
+    pub const TRUSTEE_IS_IMPERSONATE: DWORD = 1;
+
+    // accctrl.h: SE_OBJECT_TYPE
+    pub const SE_UNKNOWN_OBJECT_TYPE: DWORD = 0;
+    pub const SE_FILE_OBJECT: DWORD = 1;
+    pub const SE_SERVICE: DWORD = 2;
+    pub const SE_PRINTER: DWORD = 3;
+    pub const SE_REGISTRY_KEY: DWORD = 4;
+    pub const SE_LMSHARE: DWORD = 5;
+    pub const SE_KERNEL_OBJECT: DWORD = 6;
+    pub const SE_WINDOW_OBJECT: DWORD = 7;
+    pub const SE_DS_OBJECT: DWORD = 8;
+    pub const SE_DS_OBJECT_ALL: DWORD = 9;
+    pub const SE_PROVIDER_DEFINED_OBJECT: DWORD = 10;
+    pub const SE_WMIGUID_OBJECT: DWORD = 11;
+    pub const SE_REGISTRY_WOW64_32KEY: DWORD = 12;
+    pub const SE_REGISTRY_WOW64_64KEY: DWORD = 13;
+
+    // minwinbase.h: LocalAlloc flags
+    pub const LMEM_FIXED: UINT = 0x0000;
+    pub const LMEM_MOVEABLE: UINT = 0x0002;
+    pub const LMEM_NOCOMPACT: UINT = 0x0010;
+    pub const LMEM_NODISCARD: UINT = 0x0020;
+    pub const LMEM_ZEROINIT: UINT = 0x0040;
+    pub const LMEM_DISCARDABLE: UINT = 0x0F00;
+
+    // SIDs
+    pub const SID_REVISION: BYTE = 1;
+    pub const SID_MAX_SUB_AUTHORITIES: BYTE = 15;
+
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    pub struct SID_IDENTIFIER_AUTHORITY {
+        pub Value: [BYTE; 6],
+    }
+
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    pub struct SID {
+        pub Revision: BYTE,
+        pub SubAuthorityCount: BYTE,
+        pub IdentifierAuthority: SID_IDENTIFIER_AUTHORITY,
+        pub SubAuthority: [DWORD; 1],
+    }
+
+    // SID_NAME_USE
+    pub const SidTypeUser: DWORD = 1;
+    pub const SidTypeGroup: DWORD = 2;
+    pub const SidTypeDomain: DWORD = 3;
+    pub const SidTypeAlias: DWORD = 4;
+    pub const SidTypeWellKnownGroup: DWORD = 5;
+    pub const SidTypeDeletedAccount: DWORD = 6;
+    pub const SidTypeInvalid: DWORD = 7;
+    pub const SidTypeUnknown: DWORD = 8;
+    pub const SidTypeComputer: DWORD = 9;
+    pub const SidTypeLabel: DWORD = 10;
+    pub const SidTypeLogonSession: DWORD = 11;
+
+    // Token group attributes
+    pub const SE_GROUP_MANDATORY: DWORD = 0x0000_0001;
+    pub const SE_GROUP_ENABLED_BY_DEFAULT: DWORD = 0x0000_0002;
+    pub const SE_GROUP_ENABLED: DWORD = 0x0000_0004;
+    pub const SE_GROUP_OWNER: DWORD = 0x0000_0008;
+    pub const SE_GROUP_USE_FOR_DENY_ONLY: DWORD = 0x0000_0010;
+    pub const SE_GROUP_INTEGRITY: DWORD = 0x0000_0020;
+    pub const SE_GROUP_INTEGRITY_ENABLED: DWORD = 0x0000_0040;
+    pub const SE_GROUP_LOGON_ID: DWORD = 0xC000_0000;
+    pub const SE_GROUP_RESOURCE: DWORD = 0x2000_0000;
+
+    // Token privilege attributes
+    pub const SE_PRIVILEGE_ENABLED_BY_DEFAULT: DWORD = 0x0000_0001;
+    pub const SE_PRIVILEGE_ENABLED: DWORD = 0x0000_0002;
+    pub const SE_PRIVILEGE_REMOVED: DWORD = 0x0000_0004;
+    pub const SE_PRIVILEGE_USED_FOR_ACCESS: DWORD = 0x8000_0000;
+
+    // Claim security attributes
+    pub const CLAIM_SECURITY_ATTRIBUTE_TYPE_INVALID: WORD = 0x00;
+    pub const CLAIM_SECURITY_ATTRIBUTE_TYPE_INT64: WORD = 0x01;
+    pub const CLAIM_SECURITY_ATTRIBUTE_TYPE_UINT64: WORD = 0x02;
+    pub const CLAIM_SECURITY_ATTRIBUTE_TYPE_STRING: WORD = 0x03;
+    pub const CLAIM_SECURITY_ATTRIBUTE_TYPE_FQBN: WORD = 0x04;
+    pub const CLAIM_SECURITY_ATTRIBUTE_TYPE_SID: WORD = 0x05;
+    pub const CLAIM_SECURITY_ATTRIBUTE_TYPE_BOOLEAN: WORD = 0x06;
+    pub const CLAIM_SECURITY_ATTRIBUTE_TYPE_OCTET_STRING: WORD = 0x10;
+    pub const CLAIM_SECURITY_ATTRIBUTE_NON_INHERITABLE: DWORD = 0x0001;
+    pub const CLAIM_SECURITY_ATTRIBUTE_VALUE_CASE_SENSITIVE: DWORD = 0x0002;
+    pub const CLAIM_SECURITY_ATTRIBUTE_USE_FOR_DENY_ONLY: DWORD = 0x0004;
+    pub const CLAIM_SECURITY_ATTRIBUTE_DISABLED_BY_DEFAULT: DWORD = 0x0008;
+    pub const CLAIM_SECURITY_ATTRIBUTE_DISABLED: DWORD = 0x0010;
+    pub const CLAIM_SECURITY_ATTRIBUTE_MANDATORY: DWORD = 0x0020;
+
+    // ACE types
+    pub const ACCESS_ALLOWED_ACE_TYPE: BYTE = 0x0;
+    pub const ACCESS_DENIED_ACE_TYPE: BYTE = 0x1;
+    pub const SYSTEM_AUDIT_ACE_TYPE: BYTE = 0x2;
+    pub const SYSTEM_ALARM_ACE_TYPE: BYTE = 0x3;
+    pub const ACCESS_ALLOWED_COMPOUND_ACE_TYPE: BYTE = 0x4;
+    pub const ACCESS_ALLOWED_OBJECT_ACE_TYPE: BYTE = 0x5;
+    pub const ACCESS_DENIED_OBJECT_ACE_TYPE: BYTE = 0x6;
+    pub const SYSTEM_AUDIT_OBJECT_ACE_TYPE: BYTE = 0x7;
+    pub const SYSTEM_ALARM_OBJECT_ACE_TYPE: BYTE = 0x8;
+    pub const ACCESS_ALLOWED_CALLBACK_ACE_TYPE: BYTE = 0x9;
+    pub const ACCESS_DENIED_CALLBACK_ACE_TYPE: BYTE = 0xA;
+    pub const ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE: BYTE = 0xB;
+    pub const ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE: BYTE = 0xC;
+    pub const SYSTEM_AUDIT_CALLBACK_ACE_TYPE: BYTE = 0xD;
+    pub const SYSTEM_ALARM_CALLBACK_ACE_TYPE: BYTE = 0xE;
+    pub const SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE: BYTE = 0xF;
+    pub const SYSTEM_ALARM_CALLBACK_OBJECT_ACE_TYPE: BYTE = 0x10;
+    pub const SYSTEM_MANDATORY_LABEL_ACE_TYPE: BYTE = 0x11;
+    pub const SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE: BYTE = 0x12;
+    pub const SYSTEM_SCOPED_POLICY_ID_ACE_TYPE: BYTE = 0x13;
+
+    // Object ACE flags
+    pub const ACE_OBJECT_TYPE_PRESENT: DWORD = 0x1;
+    pub const ACE_INHERITED_OBJECT_TYPE_PRESENT: DWORD = 0x2;
+
+    // ACE flags
+    pub const OBJECT_INHERIT_ACE: BYTE = 0x1;
+    pub const CONTAINER_INHERIT_ACE: BYTE = 0x2;
+    pub const NO_PROPAGATE_INHERIT_ACE: BYTE = 0x4;
+    pub const INHERIT_ONLY_ACE: BYTE = 0x8;
+    pub const INHERITED_ACE: BYTE = 0x10;
+    pub const SUCCESSFUL_ACCESS_ACE_FLAG: BYTE = 0x40;
+    pub const FAILED_ACCESS_ACE_FLAG: BYTE = 0x80;
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy)]
+    pub struct ACE_HEADER {
+        pub AceType: BYTE,
+        pub AceFlags: BYTE,
+        pub AceSize: WORD,
+    }
+
+    // ACL revisions
+    pub const ACL_REVISION: BYTE = 2;
+    pub const ACL_REVISION_DS: BYTE = 4;
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy)]
+    pub struct ACL {
+        pub AclRevision: BYTE,
+        pub Sbz1: BYTE,
+        pub AclSize: WORD,
+        pub AceCount: WORD,
+        pub Sbz2: WORD,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy)]
+    pub struct ACL_SIZE_INFORMATION {
+        pub AceCount: DWORD,
+        pub AclBytesInUse: DWORD,
+        pub AclBytesFree: DWORD,
+    }
+
+    // Security descriptors
+    pub const SECURITY_DESCRIPTOR_REVISION: DWORD = 1;
+
+    pub const SE_OWNER_DEFAULTED: WORD = 0x0001;
+    pub const SE_GROUP_DEFAULTED: WORD = 0x0002;
+    pub const SE_DACL_PRESENT: WORD = 0x0004;
+    pub const SE_DACL_DEFAULTED: WORD = 0x0008;
+    pub const SE_SACL_PRESENT: WORD = 0x0010;
+    pub const SE_SACL_DEFAULTED: WORD = 0x0020;
+    pub const SE_DACL_AUTO_INHERIT_REQ: WORD = 0x0100;
+    pub const SE_SACL_AUTO_INHERIT_REQ: WORD = 0x0200;
+    pub const SE_DACL_AUTO_INHERITED: WORD = 0x0400;
+    pub const SE_SACL_AUTO_INHERITED: WORD = 0x0800;
+    pub const SE_DACL_PROTECTED: WORD = 0x1000;
+    pub const SE_SACL_PROTECTED: WORD = 0x2000;
+    pub const SE_RM_CONTROL_VALID: WORD = 0x4000;
+    pub const SE_SELF_RELATIVE: WORD = 0x8000;
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy)]
+    pub struct SECURITY_DESCRIPTOR_RELATIVE {
+        pub Revision: BYTE,
+        pub Sbz1: BYTE,
+        pub Control: WORD,
+        pub Owner: DWORD,
+        pub Group: DWORD,
+        pub Sacl: DWORD,
+        pub Dacl: DWORD,
+    }
+
+    // Security information
+    pub const OWNER_SECURITY_INFORMATION: DWORD = 0x0000_0001;
+    pub const GROUP_SECURITY_INFORMATION: DWORD = 0x0000_0002;
+    pub const DACL_SECURITY_INFORMATION: DWORD = 0x0000_0004;
+    pub const SACL_SECURITY_INFORMATION: DWORD = 0x0000_0008;
+    pub const LABEL_SECURITY_INFORMATION: DWORD = 0x0000_0010;
+    pub const ATTRIBUTE_SECURITY_INFORMATION: DWORD = 0x0000_0020;
+    pub const SCOPE_SECURITY_INFORMATION: DWORD = 0x0000_0040;
+    pub const BACKUP_SECURITY_INFORMATION: DWORD = 0x0001_0000;
+    pub const PROTECTED_DACL_SECURITY_INFORMATION: DWORD = 0x8000_0000;
+    pub const PROTECTED_SACL_SECURITY_INFORMATION: DWORD = 0x4000_0000;
+    pub const UNPROTECTED_DACL_SECURITY_INFORMATION: DWORD = 0x2000_0000;
+    pub const UNPROTECTED_SACL_SECURITY_INFORMATION: DWORD = 0x1000_0000;
+
+    // Access rights
+    pub const DELETE: DWORD = 0x0001_0000;
+    pub const READ_CONTROL: DWORD = 0x0002_0000;
+    pub const WRITE_DAC: DWORD = 0x0004_0000;
+    pub const WRITE_OWNER: DWORD = 0x0008_0000;
+    pub const SYNCHRONIZE: DWORD = 0x0010_0000;
+    pub const STANDARD_RIGHTS_REQUIRED: DWORD = 0x000F_0000;
+    pub const STANDARD_RIGHTS_READ: DWORD = READ_CONTROL;
+    pub const STANDARD_RIGHTS_WRITE: DWORD = READ_CONTROL;
+    pub const STANDARD_RIGHTS_EXECUTE: DWORD = READ_CONTROL;
+    pub const STANDARD_RIGHTS_ALL: DWORD = 0x001F_0000;
+    pub const SPECIFIC_RIGHTS_ALL: DWORD = 0x0000_FFFF;
+    pub const ACCESS_SYSTEM_SECURITY: DWORD = 0x0100_0000;
+    pub const MAXIMUM_ALLOWED: DWORD = 0x0200_0000;
+    pub const GENERIC_READ: DWORD = 0x8000_0000;
+    pub const GENERIC_WRITE: DWORD = 0x4000_0000;
+    pub const GENERIC_EXECUTE: DWORD = 0x2000_0000;
+    pub const GENERIC_ALL: DWORD = 0x1000_0000;
+
+    pub const FILE_READ_DATA: DWORD = 0x0001;
+    pub const FILE_LIST_DIRECTORY: DWORD = 0x0001;
+    pub const FILE_WRITE_DATA: DWORD = 0x0002;
+    pub const FILE_ADD_FILE: DWORD = 0x0002;
+    pub const FILE_APPEND_DATA: DWORD = 0x0004;
+    pub const FILE_ADD_SUBDIRECTORY: DWORD = 0x0004;
+    pub const FILE_READ_EA: DWORD = 0x0008;
+    pub const FILE_WRITE_EA: DWORD = 0x0010;
+    pub const FILE_EXECUTE: DWORD = 0x0020;
+    pub const FILE_TRAVERSE: DWORD = 0x0020;
+    pub const FILE_DELETE_CHILD: DWORD = 0x0040;
+    pub const FILE_READ_ATTRIBUTES: DWORD = 0x0080;
+    pub const FILE_WRITE_ATTRIBUTES: DWORD = 0x0100;
+    pub const FILE_ALL_ACCESS: DWORD = STANDARD_RIGHTS_REQUIRED | SYNCHRONIZE | 0x1FF;
+    pub const FILE_GENERIC_READ: DWORD =
+        STANDARD_RIGHTS_READ | FILE_READ_DATA | FILE_READ_ATTRIBUTES | FILE_READ_EA | SYNCHRONIZE;
+    pub const FILE_GENERIC_WRITE: DWORD = STANDARD_RIGHTS_WRITE
+        | FILE_WRITE_DATA
+        | FILE_WRITE_ATTRIBUTES
+        | FILE_WRITE_EA
+        | FILE_APPEND_DATA
+        | SYNCHRONIZE;
+    pub const FILE_GENERIC_EXECUTE: DWORD =
+        STANDARD_RIGHTS_EXECUTE | FILE_READ_ATTRIBUTES | FILE_EXECUTE | SYNCHRONIZE;
+
+    pub const KEY_QUERY_VALUE: DWORD = 0x0001;
+    pub const KEY_SET_VALUE: DWORD = 0x0002;
+    pub const KEY_CREATE_SUB_KEY: DWORD = 0x0004;
+    pub const KEY_ENUMERATE_SUB_KEYS: DWORD = 0x0008;
+    pub const KEY_NOTIFY: DWORD = 0x0010;
+    pub const KEY_CREATE_LINK: DWORD = 0x0020;
+    pub const KEY_WOW64_32KEY: DWORD = 0x0200;
+    pub const KEY_WOW64_64KEY: DWORD = 0x0100;
+    pub const KEY_READ: DWORD =
+        (STANDARD_RIGHTS_READ | KEY_QUERY_VALUE | KEY_ENUMERATE_SUB_KEYS | KEY_NOTIFY)
+            & !SYNCHRONIZE;
+    pub const KEY_WRITE: DWORD =
+        (STANDARD_RIGHTS_WRITE | KEY_SET_VALUE | KEY_CREATE_SUB_KEY) & !SYNCHRONIZE;
+    pub const KEY_EXECUTE: DWORD = KEY_READ & !SYNCHRONIZE;
+    pub const KEY_ALL_ACCESS: DWORD = (STANDARD_RIGHTS_ALL
+        | KEY_QUERY_VALUE
+        | KEY_SET_VALUE
+        | KEY_CREATE_SUB_KEY
+        | KEY_ENUMERATE_SUB_KEYS
+        | KEY_NOTIFY
+        | KEY_CREATE_LINK)
+        & !SYNCHRONIZE;
+
+    pub const FILE_CREATE_PIPE_INSTANCE: DWORD = 0x0004;
+
+    pub const PROCESS_TERMINATE: DWORD = 0x0001;
+    pub const PROCESS_CREATE_THREAD: DWORD = 0x0002;
+    pub const PROCESS_SET_SESSIONID: DWORD = 0x0004;
+    pub const PROCESS_VM_OPERATION: DWORD = 0x0008;
+    pub const PROCESS_VM_READ: DWORD = 0x0010;
+    pub const PROCESS_VM_WRITE: DWORD = 0x0020;
+    pub const PROCESS_DUP_HANDLE: DWORD = 0x0040;
+    pub const PROCESS_CREATE_PROCESS: DWORD = 0x0080;
+    pub const PROCESS_SET_QUOTA: DWORD = 0x0100;
+    pub const PROCESS_SET_INFORMATION: DWORD = 0x0200;
+    pub const PROCESS_QUERY_INFORMATION: DWORD = 0x0400;
+    pub const PROCESS_SUSPEND_RESUME: DWORD = 0x0800;
+    pub const PROCESS_QUERY_LIMITED_INFORMATION: DWORD = 0x1000;
+    pub const PROCESS_SET_LIMITED_INFORMATION: DWORD = 0x2000;
+    pub const PROCESS_ALL_ACCESS: DWORD = STANDARD_RIGHTS_REQUIRED | SYNCHRONIZE | 0xFFFF;
+
+    pub const THREAD_TERMINATE: DWORD = 0x0001;
+    pub const THREAD_SUSPEND_RESUME: DWORD = 0x0002;
+    pub const THREAD_GET_CONTEXT: DWORD = 0x0008;
+    pub const THREAD_SET_CONTEXT: DWORD = 0x0010;
+    pub const THREAD_SET_INFORMATION: DWORD = 0x0020;
+    pub const THREAD_QUERY_INFORMATION: DWORD = 0x0040;
+    pub const THREAD_SET_THREAD_TOKEN: DWORD = 0x0080;
+    pub const THREAD_IMPERSONATE: DWORD = 0x0100;
+    pub const THREAD_DIRECT_IMPERSONATION: DWORD = 0x0200;
+    pub const THREAD_SET_LIMITED_INFORMATION: DWORD = 0x0400;
+    pub const THREAD_QUERY_LIMITED_INFORMATION: DWORD = 0x0800;
+    pub const THREAD_RESUME: DWORD = 0x1000;
+    pub const THREAD_ALL_ACCESS: DWORD = STANDARD_RIGHTS_REQUIRED | SYNCHRONIZE | 0xFFFF;
+
+    pub const TOKEN_ASSIGN_PRIMARY: DWORD = 0x0001;
+    pub const TOKEN_DUPLICATE: DWORD = 0x0002;
+    pub const TOKEN_IMPERSONATE: DWORD = 0x0004;
+    pub const TOKEN_QUERY: DWORD = 0x0008;
+    pub const TOKEN_QUERY_SOURCE: DWORD = 0x0010;
+    pub const TOKEN_ADJUST_PRIVILEGES: DWORD = 0x0020;
+    pub const TOKEN_ADJUST_GROUPS: DWORD = 0x0040;
+    pub const TOKEN_ADJUST_DEFAULT: DWORD = 0x0080;
+    pub const TOKEN_ADJUST_SESSIONID: DWORD = 0x0100;
+    pub const TOKEN_ALL_ACCESS: DWORD = STANDARD_RIGHTS_REQUIRED | 0x01FF;
+
+    // accctrl.h: directory service object rights
+    pub const ACTRL_DS_CREATE_CHILD: DWORD = 0x0001;
+    pub const ACTRL_DS_DELETE_CHILD: DWORD = 0x0002;
+    pub const ACTRL_DS_LIST: DWORD = 0x0004;
+    pub const ACTRL_DS_SELF: DWORD = 0x0008;
+    pub const ACTRL_DS_READ_PROP: DWORD = 0x0010;
+    pub const ACTRL_DS_WRITE_PROP: DWORD = 0x0020;
+    pub const ACTRL_DS_DELETE_TREE: DWORD = 0x0040;
+    pub const ACTRL_DS_LIST_OBJECT: DWORD = 0x0080;
+    pub const ACTRL_DS_CONTROL_ACCESS: DWORD = 0x0100;
+
+    // winsvc.h: service and service control manager rights
+    pub const SERVICE_QUERY_CONFIG: DWORD = 0x0001;
+    pub const SERVICE_CHANGE_CONFIG: DWORD = 0x0002;
+    pub const SERVICE_QUERY_STATUS: DWORD = 0x0004;
+    pub const SERVICE_ENUMERATE_DEPENDENTS: DWORD = 0x0008;
+    pub const SERVICE_START: DWORD = 0x0010;
+    pub const SERVICE_STOP: DWORD = 0x0020;
+    pub const SERVICE_PAUSE_CONTINUE: DWORD = 0x0040;
+    pub const SERVICE_INTERROGATE: DWORD = 0x0080;
+    pub const SERVICE_USER_DEFINED_CONTROL: DWORD = 0x0100;
+    pub const SERVICE_ALL_ACCESS: DWORD = STANDARD_RIGHTS_REQUIRED | 0x01FF;
+    pub const SC_MANAGER_CONNECT: DWORD = 0x0001;
+    pub const SC_MANAGER_CREATE_SERVICE: DWORD = 0x0002;
+    pub const SC_MANAGER_ENUMERATE_SERVICE: DWORD = 0x0004;
+    pub const SC_MANAGER_LOCK: DWORD = 0x0008;
+    pub const SC_MANAGER_QUERY_LOCK_STATUS: DWORD = 0x0010;
+    pub const SC_MANAGER_MODIFY_BOOT_CONFIG: DWORD = 0x0020;
+    pub const SC_MANAGER_ALL_ACCESS: DWORD = STANDARD_RIGHTS_REQUIRED | 0x003F;
+
+    // winuser.h: desktop and window station rights
+    pub const DESKTOP_READOBJECTS: DWORD = 0x0001;
+    pub const DESKTOP_CREATEWINDOW: DWORD = 0x0002;
+    pub const DESKTOP_CREATEMENU: DWORD = 0x0004;
+    pub const DESKTOP_HOOKCONTROL: DWORD = 0x0008;
+    pub const DESKTOP_JOURNALRECORD: DWORD = 0x0010;
+    pub const DESKTOP_JOURNALPLAYBACK: DWORD = 0x0020;
+    pub const DESKTOP_ENUMERATE: DWORD = 0x0040;
+    pub const DESKTOP_WRITEOBJECTS: DWORD = 0x0080;
+    pub const DESKTOP_SWITCHDESKTOP: DWORD = 0x0100;
+    pub const WINSTA_ENUMDESKTOPS: DWORD = 0x0001;
+    pub const WINSTA_READATTRIBUTES: DWORD = 0x0002;
+    pub const WINSTA_ACCESSCLIPBOARD: DWORD = 0x0004;
+    pub const WINSTA_CREATEDESKTOP: DWORD = 0x0008;
+    pub const WINSTA_WRITEATTRIBUTES: DWORD = 0x0010;
+    pub const WINSTA_ACCESSGLOBALATOMS: DWORD = 0x0020;
+    pub const WINSTA_EXITWINDOWS: DWORD = 0x0040;
+    pub const WINSTA_ENUMERATE: DWORD = 0x0100;
+    pub const WINSTA_READSCREEN: DWORD = 0x0200;
+    pub const WINSTA_ALL_ACCESS: DWORD = 0x037F;
+
+    pub const SYSTEM_MANDATORY_LABEL_NO_WRITE_UP: DWORD = 0x1;
+    pub const SYSTEM_MANDATORY_LABEL_NO_READ_UP: DWORD = 0x2;
+    pub const SYSTEM_MANDATORY_LABEL_NO_EXECUTE_UP: DWORD = 0x4;
+}
@@ -1,5 +1,6 @@
 use crate::{LocalBox, Sid};
 use std::io;
+#[cfg(target_os = "windows")]
 use std::ptr::{null_mut, NonNull};
 
 /// Wraps [`AllocateAndInitializeSid`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-allocateandinitializesid).
@@ -39,6 +40,7 @@ use std::ptr::{null_mut, NonNull};
 /// assert_eq!(error.unwrap_err().kind(), ErrorKind::InvalidInput);
 /// ```
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn AllocateAndInitializeSid(id_auth: [u8; 6], sub_auths: &[u32]) -> io::Result<LocalBox<Sid>> {
     if sub_auths.is_empty() {
         return Err(io::Error::new(
@@ -86,6 +88,63 @@ pub fn AllocateAndInitializeSid(id_auth: [u8; 6], sub_auths: &[u32]) -> io::Resu
     }
 }
 
+/// Portable equivalent of [`AllocateAndInitializeSid`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-allocateandinitializesid).
+///
+/// Like the WinAPI function, no more than 8 sub-authorities are accepted.
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn AllocateAndInitializeSid(id_auth: [u8; 6], sub_auths: &[u32]) -> io::Result<LocalBox<Sid>> {
+    if sub_auths.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "AllocateAndInitializeSid called with 0 sub_auths",
+        ));
+    }
+
+    if sub_auths.len() > 8 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "AllocateAndInitializeSid called with more than 8 sub_auths",
+        ));
+    }
+
+    initialize_sid(id_auth, sub_auths)
+}
+
+/// Allocate and initialize a SID with up to 15 sub-authorities
+///
+/// This is not limited to 8 sub-authorities like `AllocateAndInitializeSid`.
+/// It is used to implement the portable SID constructors.
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn initialize_sid(id_auth: [u8; 6], sub_auths: &[u32]) -> io::Result<LocalBox<Sid>> {
+    use crate::winnt::{SID, SID_MAX_SUB_AUTHORITIES, SID_REVISION};
+
+    if sub_auths.len() > SID_MAX_SUB_AUTHORITIES as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "SIDs cannot have more than 15 sub_auths",
+        ));
+    }
+
+    let size = crate::wrappers::GetSidLengthRequired(sub_auths.len() as u8);
+    let new_sid: LocalBox<Sid> = unsafe { LocalBox::try_allocate(true, size)? };
+
+    unsafe {
+        let raw = new_sid.as_ptr() as *mut SID;
+
+        (*raw).Revision = SID_REVISION;
+        (*raw).SubAuthorityCount = sub_auths.len() as u8;
+        (*raw).IdentifierAuthority.Value = id_auth;
+
+        let sub_auth_ptr = std::ptr::addr_of_mut!((*raw).SubAuthority) as *mut u32;
+        for (index, sub_auth) in sub_auths.iter().enumerate() {
+            sub_auth_ptr.add(index).write(*sub_auth);
+        }
+    }
+
+    Ok(new_sid)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,10 +1,7 @@
 use crate::constants::SecurityInformation;
-use crate::utilities;
 use crate::SecurityDescriptor;
 use std::ffi::OsString;
 use std::io;
-use std::ptr::null_mut;
-use std::slice;
 
 /// Wraps [`ConvertSecurityDescriptorToStringSecurityDescriptorW`](https://docs.microsoft.com/en-us/windows/win32/api/sddl/nf-sddl-convertsecuritydescriptortostringsecuritydescriptorw)
 ///
@@ -27,11 +24,16 @@ use std::slice;
 ///
 /// assert_eq!(string_sd, &string_sd2);
 /// ```
+#[cfg(target_os = "windows")]
 #[allow(non_snake_case)]
 pub fn ConvertSecurityDescriptorToStringSecurityDescriptor(
     sd: &SecurityDescriptor,
     info: SecurityInformation,
 ) -> io::Result<OsString> {
+    use crate::utilities;
+    use std::ptr::null_mut;
+    use std::slice;
+
     let mut buf_ptr: *mut u16 = null_mut();
     let mut buf_len: u32 = 0;
 
@@ -59,3 +61,32 @@ pub fn ConvertSecurityDescriptorToStringSecurityDescriptor(
 
     Ok(string)
 }
+
+/// Portable equivalent of [`ConvertSecurityDescriptorToStringSecurityDescriptorW`](https://docs.microsoft.com/en-us/windows/win32/api/sddl/nf-sddl-convertsecuritydescriptortostringsecuritydescriptorw)
+///
+/// SIDs are printed with their `sddl.h` alias when they have one that does
+/// not depend on the domain. Conditional and resource attribute ACEs cannot
+/// be printed, and produce an error.
+///
+/// ```
+/// use windows_permissions::wrappers::ConvertSecurityDescriptorToStringSecurityDescriptor;
+/// use windows_permissions::{constants::SecurityInformation, LocalBox, SecurityDescriptor};
+///
+/// let string_sd = "G:S-1-5-10-20";
+/// let sd: LocalBox<SecurityDescriptor> = string_sd.parse().unwrap();
+///
+/// let string_sd2 = ConvertSecurityDescriptorToStringSecurityDescriptor(
+///     &sd,
+///     SecurityInformation::all()
+/// ).unwrap();
+///
+/// assert_eq!(string_sd, &string_sd2);
+/// ```
+#[cfg(not(target_os = "windows"))]
+#[allow(non_snake_case)]
+pub fn ConvertSecurityDescriptorToStringSecurityDescriptor(
+    sd: &SecurityDescriptor,
+    info: SecurityInformation,
+) -> io::Result<OsString> {
+    crate::sddl::format_sd(sd, info).map(OsString::from)
+}
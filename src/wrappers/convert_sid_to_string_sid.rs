@@ -1,8 +1,6 @@
-use crate::utilities;
 use crate::Sid;
 use std::ffi::OsString;
 use std::io;
-use std::ptr::null_mut;
 
 /// Wraps [`ConvertSidToStringSidW`](https://docs.microsoft.com/en-us/windows/win32/api/sddl/nf-sddl-convertsidtostringsidw).
 ///
@@ -16,7 +14,11 @@ use std::ptr::null_mut;
 /// assert_eq!(string_sid, string_sid2);
 /// ```
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn ConvertSidToStringSid(sid: &Sid) -> io::Result<OsString> {
+    use crate::utilities;
+    use std::ptr::null_mut;
+
     let mut buf_ptr: *mut u16 = null_mut();
     let result = unsafe {
         winapi::shared::sddl::ConvertSidToStringSidW(sid as *const _ as *mut _, &mut buf_ptr)
@@ -37,3 +39,10 @@ pub fn ConvertSidToStringSid(sid: &Sid) -> io::Result<OsString> {
         Ok(os_string)
     }
 }
+
+/// Portable equivalent of [`ConvertSidToStringSidW`](https://docs.microsoft.com/en-us/windows/win32/api/sddl/nf-sddl-convertsidtostringsidw).
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn ConvertSidToStringSid(sid: &Sid) -> io::Result<OsString> {
+    Ok(crate::sddl::format_sid(sid).into())
+}
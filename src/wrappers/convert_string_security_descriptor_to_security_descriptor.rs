@@ -1,7 +1,6 @@
-use crate::{utilities, LocalBox, SecurityDescriptor};
+use crate::{LocalBox, SecurityDescriptor};
 use std::ffi::OsStr;
 use std::io;
-use std::ptr::{null_mut, NonNull};
 
 /// Wraps [`ConvertStringSecurityDescriptorToSecurityDescriptorW`](https://docs.microsoft.com/en-us/windows/win32/api/sddl/nf-sddl-convertstringsecuritydescriptortosecuritydescriptorw).
 ///
@@ -18,10 +17,14 @@ use std::ptr::{null_mut, NonNull};
 /// let sd = ConvertStringSecurityDescriptorToSecurityDescriptor(string_sd).unwrap();
 /// assert_eq!(string_sd, &sd.as_sddl().unwrap());
 /// ```
+#[cfg(target_os = "windows")]
 #[allow(non_snake_case)]
 pub fn ConvertStringSecurityDescriptorToSecurityDescriptor<S: AsRef<OsStr> + ?Sized>(
     string: &S,
 ) -> io::Result<LocalBox<SecurityDescriptor>> {
+    use crate::utilities;
+    use std::ptr::{null_mut, NonNull};
+
     let buffer = utilities::buf_from_os(string);
     let mut sd_ptr = null_mut();
 
@@ -45,3 +48,33 @@ pub fn ConvertStringSecurityDescriptorToSecurityDescriptor<S: AsRef<OsStr> + ?Si
         LocalBox::from_raw(ptr)
     })
 }
+
+/// Portable equivalent of [`ConvertStringSecurityDescriptorToSecurityDescriptorW`](https://docs.microsoft.com/en-us/windows/win32/api/sddl/nf-sddl-convertstringsecuritydescriptortosecuritydescriptorw).
+///
+/// Aliases that depend on the local machine or domain (such as `LA` or `DA`)
+/// cannot be resolved, and produce an error. So do conditional and resource
+/// attribute ACEs.
+///
+/// ```
+/// use windows_permissions::wrappers::ConvertStringSecurityDescriptorToSecurityDescriptor;
+///
+/// let string_sd = "G:S-1-5-10-20";
+/// let sd = ConvertStringSecurityDescriptorToSecurityDescriptor(string_sd).unwrap();
+/// assert_eq!(string_sd, &sd.as_sddl().unwrap());
+/// ```
+#[cfg(not(target_os = "windows"))]
+#[allow(non_snake_case)]
+pub fn ConvertStringSecurityDescriptorToSecurityDescriptor<S: AsRef<OsStr> + ?Sized>(
+    string: &S,
+) -> io::Result<LocalBox<SecurityDescriptor>> {
+    let string = string.as_ref().to_str().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Security descriptor string is not valid Unicode",
+        )
+    })?;
+
+    let bytes = crate::sddl::parse_sd(string)?;
+
+    unsafe { LocalBox::from_bytes(&bytes) }
+}
@@ -1,8 +1,6 @@
-use crate::utilities::buf_from_os;
 use crate::{LocalBox, Sid};
 use std::ffi::OsStr;
 use std::io;
-use std::ptr::{null_mut, NonNull};
 
 /// Wraps [`ConvertStringSidToSidW`](https://docs.microsoft.com/en-us/windows/win32/api/sddl/nf-sddl-convertstringsidtosidw).
 ///
@@ -19,7 +17,11 @@ use std::ptr::{null_mut, NonNull};
 /// Panics if the underlying WinAPI call reports success but returns a null
 /// pointer. This should never happen.
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn ConvertStringSidToSid<S: AsRef<OsStr> + ?Sized>(string: &S) -> io::Result<LocalBox<Sid>> {
+    use crate::utilities::buf_from_os;
+    use std::ptr::{null_mut, NonNull};
+
     let buf = buf_from_os(string);
     let mut ptr = null_mut();
 
@@ -38,3 +40,22 @@ pub fn ConvertStringSidToSid<S: AsRef<OsStr> + ?Sized>(string: &S) -> io::Result
         Err(io::Error::last_os_error())
     }
 }
+
+/// Portable equivalent of [`ConvertStringSidToSidW`](https://docs.microsoft.com/en-us/windows/win32/api/sddl/nf-sddl-convertstringsidtosidw).
+///
+/// SID aliases that depend on the current domain or machine (such as `DA` or
+/// `LA`) cannot be resolved, and return an error.
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn ConvertStringSidToSid<S: AsRef<OsStr> + ?Sized>(string: &S) -> io::Result<LocalBox<Sid>> {
+    let string = string.as_ref().to_str().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "SID string is not valid Unicode",
+        )
+    })?;
+
+    let (id_auth, sub_auths) = crate::sddl::parse_sid(string)?;
+
+    crate::wrappers::initialize_sid(id_auth, &sub_auths)
+}
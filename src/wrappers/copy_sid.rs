@@ -21,6 +21,7 @@ use std::io;
 /// assert_eq!(&original, &copy2);
 /// ```
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn CopySid(sid: &Sid) -> io::Result<LocalBox<Sid>> {
     let size = wrappers::GetSidLengthRequired(wrappers::GetSidSubAuthorityCount(sid));
 
@@ -40,3 +41,22 @@ pub fn CopySid(sid: &Sid) -> io::Result<LocalBox<Sid>> {
         Ok(new_sid)
     }
 }
+
+/// Portable equivalent of [`CopySid`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-copysid)
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn CopySid(sid: &Sid) -> io::Result<LocalBox<Sid>> {
+    let size = wrappers::GetSidLengthRequired(wrappers::GetSidSubAuthorityCount(sid));
+
+    let new_sid: LocalBox<Sid> = unsafe { LocalBox::try_allocate(true, size)? };
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(
+            sid as *const _ as *const u8,
+            new_sid.as_ptr() as *mut u8,
+            size,
+        )
+    };
+
+    Ok(new_sid)
+}
@@ -18,6 +18,7 @@ use crate::Sid;
 /// assert!(!EqualSid(&sid1, &sid3));
 /// ```
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn EqualSid(sid1: &Sid, sid2: &Sid) -> bool {
     (unsafe {
         winapi::um::securitybaseapi::EqualSid(
@@ -26,3 +27,19 @@ pub fn EqualSid(sid1: &Sid, sid2: &Sid) -> bool {
         )
     } != 0)
 }
+
+/// Portable equivalent of [`EqualSid`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-equalsid).
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn EqualSid(sid1: &Sid, sid2: &Sid) -> bool {
+    use crate::wrappers::{GetSidLengthRequired, GetSidSubAuthorityCount};
+
+    let len1 = GetSidLengthRequired(GetSidSubAuthorityCount(sid1));
+    let len2 = GetSidLengthRequired(GetSidSubAuthorityCount(sid2));
+
+    len1 == len2
+        && unsafe {
+            std::slice::from_raw_parts(sid1 as *const _ as *const u8, len1)
+                == std::slice::from_raw_parts(sid2 as *const _ as *const u8, len2)
+        }
+}
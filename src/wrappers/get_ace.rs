@@ -1,6 +1,8 @@
 use crate::{Ace, Acl};
 use std::io;
-use std::ptr::{null_mut, NonNull};
+#[cfg(target_os = "windows")]
+use std::ptr::null_mut;
+use std::ptr::NonNull;
 
 /// Wraps [`GetAce`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getace)
 ///
@@ -9,6 +11,7 @@ use std::ptr::{null_mut, NonNull};
 /// (`ERROR_INVALID_PARAMETER`, "The parameter is incorrect") but that is not
 /// documented.
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn GetAce(acl: &Acl, index: u32) -> io::Result<&Ace> {
     debug_assert!(crate::wrappers::IsValidAcl(acl));
 
@@ -25,3 +28,33 @@ pub fn GetAce(acl: &Acl, index: u32) -> io::Result<&Ace> {
         Ok(unsafe { Ace::ref_from_nonnull(ace) })
     }
 }
+
+/// Portable equivalent of [`GetAce`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getace)
+///
+/// Requests beyond the last ACE fail with `ErrorKind::InvalidInput`.
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn GetAce(acl: &Acl, index: u32) -> io::Result<&Ace> {
+    use crate::winnt::{ACE_HEADER, ACL};
+
+    debug_assert!(crate::wrappers::IsValidAcl(acl));
+
+    let header = unsafe { &*(acl as *const _ as *const ACL) };
+
+    if index >= header.AceCount as u32 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "ACE index is out of range",
+        ));
+    }
+
+    let mut ptr = unsafe { (acl as *const _ as *const u8).add(std::mem::size_of::<ACL>()) };
+
+    for _ in 0..index {
+        let ace_size = unsafe { (*(ptr as *const ACE_HEADER)).AceSize };
+        ptr = unsafe { ptr.add(ace_size as usize) };
+    }
+
+    let ace = NonNull::new(ptr as *mut ACE_HEADER).expect("ACE pointer was null");
+    Ok(unsafe { Ace::ref_from_nonnull(ace) })
+}
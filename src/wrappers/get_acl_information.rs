@@ -1,11 +1,12 @@
+use crate::winnt::ACL_SIZE_INFORMATION;
 use crate::Acl;
 use std::io;
-use winapi::um::winnt::ACL_SIZE_INFORMATION;
 
 /// Wraps [`GetAclInformation`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getaclinformation)
 ///
 /// Always uses [`ACL_SIZE_INFORMATION`](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-acl_size_information) as the information class.
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn GetAclInformationSize(acl: &Acl) -> io::Result<ACL_SIZE_INFORMATION> {
     debug_assert!(crate::wrappers::IsValidAcl(acl));
 
@@ -35,3 +36,29 @@ pub fn GetAclInformationSize(acl: &Acl) -> io::Result<ACL_SIZE_INFORMATION> {
         Ok(info)
     }
 }
+
+/// Portable equivalent of [`GetAclInformation`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getaclinformation)
+///
+/// Always uses [`ACL_SIZE_INFORMATION`](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-acl_size_information) as the information class.
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn GetAclInformationSize(acl: &Acl) -> io::Result<ACL_SIZE_INFORMATION> {
+    use crate::winnt::{ACE_HEADER, ACL};
+
+    debug_assert!(crate::wrappers::IsValidAcl(acl));
+
+    let header = unsafe { &*(acl as *const _ as *const ACL) };
+    let mut in_use = std::mem::size_of::<ACL>() as u32;
+
+    for _ in 0..header.AceCount {
+        let ace =
+            unsafe { &*((acl as *const _ as *const u8).add(in_use as usize) as *const ACE_HEADER) };
+        in_use += ace.AceSize as u32;
+    }
+
+    Ok(ACL_SIZE_INFORMATION {
+        AceCount: header.AceCount as u32,
+        AclBytesInUse: in_use,
+        AclBytesFree: (header.AclSize as u32).saturating_sub(in_use),
+    })
+}
@@ -0,0 +1,60 @@
+use crate::constants::SecurityDescriptorControl;
+use crate::SecurityDescriptor;
+use std::io;
+
+/// Wraps [`GetSecurityDescriptorControl`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsecuritydescriptorcontrol)
+///
+/// Returns the control flags and the revision.
+///
+/// ```
+/// use windows_permissions::wrappers::GetSecurityDescriptorControl;
+/// use windows_permissions::constants::SecurityDescriptorControl;
+/// use windows_permissions::{LocalBox, SecurityDescriptor};
+///
+/// let sd: LocalBox<SecurityDescriptor> = "D:P(A;;FA;;;SY)".parse().unwrap();
+/// let (control, revision) = GetSecurityDescriptorControl(&sd).unwrap();
+///
+/// assert!(control.contains(SecurityDescriptorControl::DaclPresent));
+/// assert!(control.contains(SecurityDescriptorControl::DaclProtected));
+/// assert!(!control.contains(SecurityDescriptorControl::SaclPresent));
+/// assert_eq!(revision, 1);
+/// ```
+#[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
+pub fn GetSecurityDescriptorControl(
+    sd: &SecurityDescriptor,
+) -> io::Result<(SecurityDescriptorControl, u32)> {
+    let mut control = 0u16;
+    let mut revision = 0u32;
+
+    let result = unsafe {
+        winapi::um::securitybaseapi::GetSecurityDescriptorControl(
+            sd as *const _ as *mut _,
+            &mut control,
+            &mut revision,
+        )
+    };
+
+    if result == 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok((
+            SecurityDescriptorControl::from_bits_truncate(control),
+            revision,
+        ))
+    }
+}
+
+/// Portable equivalent of [`GetSecurityDescriptorControl`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsecuritydescriptorcontrol)
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn GetSecurityDescriptorControl(
+    sd: &SecurityDescriptor,
+) -> io::Result<(SecurityDescriptorControl, u32)> {
+    let header = unsafe { &*(sd as *const _ as *const crate::winnt::SECURITY_DESCRIPTOR_RELATIVE) };
+
+    Ok((
+        SecurityDescriptorControl::from_bits_truncate(header.Control),
+        header.Revision as u32,
+    ))
+}
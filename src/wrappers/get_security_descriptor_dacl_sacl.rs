@@ -1,16 +1,31 @@
 use crate::{wrappers, Acl, SecurityDescriptor};
 use std::io;
+#[cfg(target_os = "windows")]
 use std::ptr::null_mut;
+#[cfg(target_os = "windows")]
 use winapi::um::winnt::PACL;
 
 macro_rules! get_security_descriptor_acl {
-    ($f:ident; msdn: $msdn:expr) => {
-        get_security_descriptor_acl!(@ $f, concat!("Wraps [`", stringify!($f), "`](", $msdn, ")"));
+    ($f:ident; $raw:ident; msdn: $msdn:expr) => {
+        get_security_descriptor_acl!(@ $f, $raw, concat!("Wraps [`", stringify!($f), "`](", $msdn, ")"));
     };
-    (@ $f:ident, $doc:expr) => {
+    (@ $f:ident, $raw:ident, $doc:expr) => {
         #[doc = $doc]
         #[allow(non_snake_case)]
+        #[cfg(target_os = "windows")]
         pub fn $f(sd: &SecurityDescriptor) -> io::Result<Option<&Acl>> {
+            match $raw(sd)? {
+                // Not present
+                (false, _) => Ok(None),
+                // Present
+                (true, Some(acl)) => Ok(Some(acl)),
+                (true, None) => panic!("$f indicated success but returned NULL"),
+            }
+        }
+
+        /// Get whether the ACL is present, and the ACL unless it is null
+        #[cfg(target_os = "windows")]
+        pub(crate) fn $raw(sd: &SecurityDescriptor) -> io::Result<(bool, Option<&Acl>)> {
             let mut present = 0i32;
             let mut acl_ptr: PACL = null_mut();
             let mut defaulted = 0i32;
@@ -27,31 +42,69 @@ macro_rules! get_security_descriptor_acl {
             if result == 0 {
                 // Failed
                 Err(io::Error::last_os_error())
+            } else if present == 0 || acl_ptr.is_null() {
+                // Not present, or a null ACL
+                Ok((present != 0, None))
             } else {
-                if present == 0 {
-                    // Not present
-                    Ok(None)
-                } else {
-                    // Present
-                    let acl = unsafe {
-                        if acl_ptr.is_null() {
-                            panic!("$f indicated success but returned NULL");
-                        } else {
-                            &*(acl_ptr as *const _)
-                        }
-                    };
-
-                    debug_assert!(wrappers::IsValidAcl(acl));
-
-                    Ok(Some(acl))
-                }
+                let acl = unsafe { &*(acl_ptr as *const _) };
+
+                debug_assert!(wrappers::IsValidAcl(acl));
+
+                Ok((true, Some(acl)))
             }
         }
     };
 }
 
-get_security_descriptor_acl!(GetSecurityDescriptorDacl;
+macro_rules! get_security_descriptor_acl_portable {
+    ($f:ident; $raw:ident; $present:ident; $field:ident; msdn: $msdn:expr) => {
+        get_security_descriptor_acl_portable!(@ $f, $raw, $present, $field,
+            concat!("Portable equivalent of [`", stringify!($f), "`](", $msdn, ")"));
+    };
+    (@ $f:ident, $raw:ident, $present:ident, $field:ident, $doc:expr) => {
+        #[doc = $doc]
+        #[allow(non_snake_case)]
+        #[cfg(not(target_os = "windows"))]
+        pub fn $f(sd: &SecurityDescriptor) -> io::Result<Option<&Acl>> {
+            match $raw(sd)? {
+                (false, _) => Ok(None),
+                (true, Some(acl)) => Ok(Some(acl)),
+                (true, None) => panic!(concat!(stringify!($f), " found a null ACL")),
+            }
+        }
+
+        /// Get whether the ACL is present, and the ACL unless it is null
+        #[cfg(not(target_os = "windows"))]
+        pub(crate) fn $raw(sd: &SecurityDescriptor) -> io::Result<(bool, Option<&Acl>)> {
+            use crate::winnt::{SECURITY_DESCRIPTOR_RELATIVE, $present};
+
+            let header = unsafe { &*(sd as *const _ as *const SECURITY_DESCRIPTOR_RELATIVE) };
+            let present = header.Control & $present != 0;
+
+            if !present || header.$field == 0 {
+                // Not present, or a null ACL
+                return Ok((present, None));
+            }
+
+            let acl = unsafe {
+                &*((sd as *const _ as *const u8).add(header.$field as usize) as *const Acl)
+            };
+
+            debug_assert!(wrappers::IsValidAcl(acl));
+
+            Ok((true, Some(acl)))
+        }
+    };
+}
+
+get_security_descriptor_acl!(GetSecurityDescriptorDacl; security_descriptor_dacl;
+    msdn: "https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsecuritydescriptordacl");
+
+get_security_descriptor_acl!(GetSecurityDescriptorSacl; security_descriptor_sacl;
+    msdn: "https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsecuritydescriptorsacl");
+
+get_security_descriptor_acl_portable!(GetSecurityDescriptorDacl; security_descriptor_dacl; SE_DACL_PRESENT; Dacl;
     msdn: "https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsecuritydescriptordacl");
 
-get_security_descriptor_acl!(GetSecurityDescriptorSacl;
+get_security_descriptor_acl_portable!(GetSecurityDescriptorSacl; security_descriptor_sacl; SE_SACL_PRESENT; Sacl;
     msdn: "https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsecuritydescriptorsacl");
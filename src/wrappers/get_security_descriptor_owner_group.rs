@@ -1,10 +1,13 @@
 use crate::{SecurityDescriptor, Sid};
 use std::io;
+#[cfg(target_os = "windows")]
 use std::ptr::{null_mut, NonNull};
+#[cfg(target_os = "windows")]
 use winapi::ctypes::c_void;
 
 /// Wraps [`GetSecurityDescriptorOwner`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsecuritydescriptorowner)
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn GetSecurityDescriptorOwner(sd: &SecurityDescriptor) -> io::Result<Option<&Sid>> {
     let mut sid_ptr: *mut c_void = null_mut();
     let mut _sid_default: i32 = 0;
@@ -27,6 +30,7 @@ pub fn GetSecurityDescriptorOwner(sd: &SecurityDescriptor) -> io::Result<Option<
 
 /// Wraps [`GetSecurityDescriptorGroup`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsecuritydescriptorgroup)
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn GetSecurityDescriptorGroup(sd: &SecurityDescriptor) -> io::Result<Option<&Sid>> {
     let mut sid_ptr: *mut c_void = null_mut();
     let mut _sid_default: i32 = 0;
@@ -46,3 +50,29 @@ pub fn GetSecurityDescriptorGroup(sd: &SecurityDescriptor) -> io::Result<Option<
 
     Ok(NonNull::new(sid_ptr).map(|p| unsafe { &*(p.as_ptr() as *const Sid) }))
 }
+
+/// Get a SID at an offset in a self-relative security descriptor
+#[cfg(not(target_os = "windows"))]
+fn relative_sid(sd: &SecurityDescriptor, offset: u32) -> Option<&Sid> {
+    if offset == 0 {
+        None
+    } else {
+        Some(unsafe { &*((sd as *const _ as *const u8).add(offset as usize) as *const Sid) })
+    }
+}
+
+/// Portable equivalent of [`GetSecurityDescriptorOwner`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsecuritydescriptorowner)
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn GetSecurityDescriptorOwner(sd: &SecurityDescriptor) -> io::Result<Option<&Sid>> {
+    let header = unsafe { &*(sd as *const _ as *const crate::winnt::SECURITY_DESCRIPTOR_RELATIVE) };
+    Ok(relative_sid(sd, header.Owner))
+}
+
+/// Portable equivalent of [`GetSecurityDescriptorGroup`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsecuritydescriptorgroup)
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn GetSecurityDescriptorGroup(sd: &SecurityDescriptor) -> io::Result<Option<&Sid>> {
+    let header = unsafe { &*(sd as *const _ as *const crate::winnt::SECURITY_DESCRIPTOR_RELATIVE) };
+    Ok(relative_sid(sd, header.Group))
+}
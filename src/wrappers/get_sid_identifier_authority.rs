@@ -11,9 +11,21 @@ use crate::Sid;
 /// assert_eq!(&id_auth, GetSidIdentifierAuthority(&sid));
 /// ```
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn GetSidIdentifierAuthority(sid: &Sid) -> &[u8; 6] {
     let ptr = unsafe {
         &*winapi::um::securitybaseapi::GetSidIdentifierAuthority(sid as *const _ as *mut _)
     };
     &ptr.Value
 }
+
+/// Portable equivalent of [`GetSidIdentifierAuthority`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsididentifierauthority).
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn GetSidIdentifierAuthority(sid: &Sid) -> &[u8; 6] {
+    unsafe {
+        &(*(sid as *const _ as *const crate::winnt::SID))
+            .IdentifierAuthority
+            .Value
+    }
+}
@@ -8,12 +8,20 @@
 /// assert_eq!(GetSidLengthRequired(3), 20);
 /// ```
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn GetSidLengthRequired(sub_auth_count: u8) -> usize {
     // Assumptions:
     // - None. The function is guaranteed by the WinAPI not to fail
     unsafe { winapi::um::securitybaseapi::GetSidLengthRequired(sub_auth_count) as usize }
 }
 
+/// Portable equivalent of [`GetSidLengthRequired`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsidlengthrequired).
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn GetSidLengthRequired(sub_auth_count: u8) -> usize {
+    8 + 4 * sub_auth_count as usize
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -24,10 +24,24 @@ use crate::Sid;
 /// // assert_eq!(GetSidSubAuthority(&sid, 2), None);
 /// ```
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub unsafe fn GetSidSubAuthority(sid: &Sid, sub_auth: u8) -> *mut u32 {
     winapi::um::securitybaseapi::GetSidSubAuthority(sid as *const _ as *mut _, sub_auth as u32)
 }
 
+/// Portable equivalent of [`GetSidSubAuthority`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsidsubauthority).
+///
+/// # Safety
+///
+/// The `Sid` structure *must* have enough sub authorities. This is *not* checked
+/// by this function. Accessing a higher index is undefined behavior.
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub unsafe fn GetSidSubAuthority(sid: &Sid, sub_auth: u8) -> *mut u32 {
+    let sid = sid as *const _ as *mut crate::winnt::SID;
+    (std::ptr::addr_of_mut!((*sid).SubAuthority) as *mut u32).add(sub_auth as usize)
+}
+
 /// Wraps [`GetSidSubAuthority`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsidsubauthority)
 /// with a runtime check.
 ///
@@ -12,6 +12,14 @@ use crate::Sid;
 /// assert_eq!(sid2.sub_authority_count(), 15);
 /// ```
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn GetSidSubAuthorityCount(sid: &Sid) -> u8 {
     unsafe { *winapi::um::securitybaseapi::GetSidSubAuthorityCount(sid as *const _ as *mut _) }
 }
+
+/// Portable equivalent of [`GetSidSubAuthorityCount`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsidsubauthoritycount).
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn GetSidSubAuthorityCount(sid: &Sid) -> u8 {
+    unsafe { (*(sid as *const _ as *const crate::winnt::SID)).SubAuthorityCount }
+}
@@ -0,0 +1,313 @@
+use crate::constants::{ClaimFlags, GroupAttributes, PrivilegeAttributes};
+use crate::token::{ClaimAttribute, ClaimValues, Luid, LuidAndAttributes, SidAndAttributes};
+use crate::{utilities, wrappers, LocalBox, Sid};
+use std::io;
+use std::os::windows::io::AsRawHandle;
+use std::{mem, slice};
+use winapi::shared::winerror::{ERROR_INSUFFICIENT_BUFFER, ERROR_INVALID_PARAMETER};
+use winapi::um::winnt::*;
+
+/// `TOKEN_WRITE_RESTRICTED` from `TOKEN_ACCESS_INFORMATION::Flags`, which
+/// winapi does not define
+const TOKEN_WRITE_RESTRICTED: u32 = 0x8;
+
+/// `TokenIsLessPrivilegedAppContainer`, which winapi does not define
+const TOKEN_IS_LESS_PRIVILEGED_APP_CONTAINER: u32 = 46;
+
+/// Wraps [`GetTokenInformation`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-gettokeninformation).
+///
+/// `class` is a [`TOKEN_INFORMATION_CLASS`](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ne-winnt-token_information_class)
+/// value. The returned buffer holds the structure corresponding to that
+/// class, and is automatically resized until it is large enough.
+///
+/// The structure may contain pointers into the buffer itself, so it must be
+/// interpreted in place.
+#[allow(non_snake_case)]
+pub fn GetTokenInformation<H: AsRawHandle>(token: &H, class: u32) -> io::Result<Vec<u8>> {
+    let (mut buffer, len) = query::<H, u8>(token, class)?;
+    buffer.truncate(len);
+    Ok(buffer)
+}
+
+/// Wraps [`GetTokenInformation`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-gettokeninformation)
+///
+/// Always uses `TokenUser` as the information class.
+#[allow(non_snake_case)]
+pub fn GetTokenInformationUser<H: AsRawHandle>(token: &H) -> io::Result<SidAndAttributes> {
+    let (buffer, _) = query::<H, u64>(token, TokenUser)?;
+    unsafe { sid_and_attributes(&(*(buffer.as_ptr() as *const TOKEN_USER)).User) }
+}
+
+/// Wraps [`GetTokenInformation`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-gettokeninformation)
+///
+/// `class` must be an information class that returns a
+/// [`TOKEN_GROUPS`](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-token_groups)
+/// structure: `TokenGroups`, `TokenRestrictedSids`, `TokenLogonSid`,
+/// `TokenCapabilities`, `TokenDeviceGroups` or `TokenRestrictedDeviceGroups`.
+#[allow(non_snake_case, non_upper_case_globals)]
+pub fn GetTokenInformationGroups<H: AsRawHandle>(
+    token: &H,
+    class: u32,
+) -> io::Result<Vec<SidAndAttributes>> {
+    match class {
+        TokenGroups
+        | TokenRestrictedSids
+        | TokenLogonSid
+        | TokenCapabilities
+        | TokenDeviceGroups
+        | TokenRestrictedDeviceGroups => {}
+        _ => return Err(unsupported_class(class)),
+    }
+
+    let (buffer, _) = query::<H, u64>(token, class)?;
+
+    unsafe {
+        let groups = &*(buffer.as_ptr() as *const TOKEN_GROUPS);
+        raw_slice(groups.Groups.as_ptr(), groups.GroupCount as usize)
+            .iter()
+            .map(|group| sid_and_attributes(group))
+            .collect()
+    }
+}
+
+/// Wraps [`GetTokenInformation`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-gettokeninformation)
+///
+/// Always uses `TokenPrivileges` as the information class.
+#[allow(non_snake_case)]
+pub fn GetTokenInformationPrivileges<H: AsRawHandle>(
+    token: &H,
+) -> io::Result<Vec<LuidAndAttributes>> {
+    let (buffer, _) = query::<H, u64>(token, TokenPrivileges)?;
+
+    let privileges = unsafe {
+        let privileges = &*(buffer.as_ptr() as *const TOKEN_PRIVILEGES);
+        raw_slice(
+            privileges.Privileges.as_ptr(),
+            privileges.PrivilegeCount as usize,
+        )
+    };
+
+    Ok(privileges
+        .iter()
+        .map(|p| {
+            LuidAndAttributes::new(
+                Luid {
+                    high_part: p.Luid.HighPart,
+                    low_part: p.Luid.LowPart,
+                },
+                PrivilegeAttributes::from_bits_truncate(p.Attributes),
+            )
+        })
+        .collect())
+}
+
+/// Wraps [`GetTokenInformation`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-gettokeninformation)
+///
+/// `class` must be an information class that returns a single SID:
+/// `TokenOwner`, `TokenPrimaryGroup`, `TokenIntegrityLevel` or
+/// `TokenAppContainerSid`. Returns `None` if the token has no such SID.
+#[allow(non_snake_case, non_upper_case_globals)]
+pub fn GetTokenInformationSid<H: AsRawHandle>(
+    token: &H,
+    class: u32,
+) -> io::Result<Option<LocalBox<Sid>>> {
+    let (buffer, _) = query::<H, u64>(token, class)?;
+    let ptr = buffer.as_ptr();
+
+    let sid = unsafe {
+        match class {
+            TokenOwner => (*(ptr as *const TOKEN_OWNER)).Owner,
+            TokenPrimaryGroup => (*(ptr as *const TOKEN_PRIMARY_GROUP)).PrimaryGroup,
+            TokenIntegrityLevel => (*(ptr as *const TOKEN_MANDATORY_LABEL)).Label.Sid,
+            TokenAppContainerSid => {
+                (*(ptr as *const TOKEN_APPCONTAINER_INFORMATION)).TokenAppContainer
+            }
+            _ => return Err(unsupported_class(class)),
+        }
+    };
+
+    if sid.is_null() {
+        Ok(None)
+    } else {
+        unsafe { wrappers::CopySid(&*(sid as *const Sid)).map(Some) }
+    }
+}
+
+/// Wraps [`GetTokenInformation`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-gettokeninformation)
+///
+/// Always uses `TokenAccessInformation` as the information class, and checks
+/// whether `TOKEN_WRITE_RESTRICTED` is set in its flags.
+#[allow(non_snake_case)]
+pub fn GetTokenInformationWriteRestricted<H: AsRawHandle>(token: &H) -> io::Result<bool> {
+    let (buffer, _) = query::<H, u64>(token, TokenAccessInformation)?;
+    let info = unsafe { &*(buffer.as_ptr() as *const TOKEN_ACCESS_INFORMATION) };
+    Ok(info.Flags & TOKEN_WRITE_RESTRICTED != 0)
+}
+
+/// Wraps [`GetTokenInformation`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-gettokeninformation)
+///
+/// Always uses `TokenIsLessPrivilegedAppContainer` as the information class.
+/// Versions of Windows that predate that class cannot create less privileged
+/// AppContainer tokens, so this returns `false` if the class is rejected.
+#[allow(non_snake_case)]
+pub fn GetTokenInformationLessPrivilegedAppContainer<H: AsRawHandle>(
+    token: &H,
+) -> io::Result<bool> {
+    match query::<H, u32>(token, TOKEN_IS_LESS_PRIVILEGED_APP_CONTAINER) {
+        Ok((buffer, _)) => Ok(buffer[0] != 0),
+        Err(e) if e.raw_os_error() == Some(ERROR_INVALID_PARAMETER as i32) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Wraps [`GetTokenInformation`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-gettokeninformation)
+///
+/// `class` must be an information class that returns a
+/// [`CLAIM_SECURITY_ATTRIBUTES_INFORMATION`](https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-claim_security_attributes_information)
+/// structure: `TokenUserClaimAttributes`, `TokenDeviceClaimAttributes`,
+/// `TokenRestrictedUserClaimAttributes` or
+/// `TokenRestrictedDeviceClaimAttributes`.
+#[allow(non_snake_case, non_upper_case_globals)]
+pub fn GetTokenInformationClaims<H: AsRawHandle>(
+    token: &H,
+    class: u32,
+) -> io::Result<Vec<ClaimAttribute>> {
+    match class {
+        TokenUserClaimAttributes
+        | TokenDeviceClaimAttributes
+        | TokenRestrictedUserClaimAttributes
+        | TokenRestrictedDeviceClaimAttributes => {}
+        _ => return Err(unsupported_class(class)),
+    }
+
+    let (buffer, _) = query::<H, u64>(token, class)?;
+
+    unsafe {
+        let info = &*(buffer.as_ptr() as *const CLAIM_SECURITY_ATTRIBUTES_INFORMATION);
+        raw_slice(
+            *info.Attribute.pAttributeV1() as *const CLAIM_SECURITY_ATTRIBUTE_V1,
+            info.AttributeCount as usize,
+        )
+        .iter()
+        .map(|claim| claim_attribute(claim))
+        .collect()
+    }
+}
+
+/// Call `GetTokenInformation`, growing a buffer of `T` until the result fits
+///
+/// Returns the buffer and the number of bytes written to it. `T` controls the
+/// alignment of the buffer, so the structures in it can be read in place.
+fn query<H: AsRawHandle, T: Copy + Default>(token: &H, class: u32) -> io::Result<(Vec<T>, usize)> {
+    let mut len = 64u32;
+
+    loop {
+        let count = (len as usize).div_ceil(mem::size_of::<T>());
+        let mut buffer = vec![T::default(); count];
+        let capacity = (count * mem::size_of::<T>()) as u32;
+
+        let result = unsafe {
+            winapi::um::securitybaseapi::GetTokenInformation(
+                token.as_raw_handle() as *mut _,
+                class,
+                buffer.as_mut_ptr() as *mut _,
+                capacity,
+                &mut len,
+            )
+        };
+
+        if result != 0 {
+            return Ok((buffer, len as usize));
+        }
+
+        let error = io::Error::last_os_error();
+
+        // len was updated to the required size by the API call
+        if error.raw_os_error() != Some(ERROR_INSUFFICIENT_BUFFER as i32) {
+            return Err(error);
+        }
+    }
+}
+
+fn unsupported_class(class: u32) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("Unsupported token information class {}", class),
+    )
+}
+
+/// Build a slice from a pointer and count, tolerating null when empty
+unsafe fn raw_slice<'a, T>(ptr: *const T, len: usize) -> &'a [T] {
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(ptr, len)
+    }
+}
+
+unsafe fn sid_and_attributes(raw: &SID_AND_ATTRIBUTES) -> io::Result<SidAndAttributes> {
+    Ok(SidAndAttributes::new(
+        wrappers::CopySid(&*(raw.Sid as *const Sid))?,
+        GroupAttributes::from_bits_truncate(raw.Attributes),
+    ))
+}
+
+unsafe fn wide_string(ptr: *const u16) -> String {
+    let len = utilities::search_buffer(&0x00, ptr);
+    String::from_utf16_lossy(slice::from_raw_parts(ptr, len))
+}
+
+unsafe fn claim_attribute(raw: &CLAIM_SECURITY_ATTRIBUTE_V1) -> io::Result<ClaimAttribute> {
+    let count = raw.ValueCount as usize;
+
+    let values = match raw.ValueType {
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_INT64 => {
+            ClaimValues::Int64(raw_slice(*raw.Values.pInt64(), count).to_vec())
+        }
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_UINT64 => {
+            ClaimValues::Uint64(raw_slice(*raw.Values.pUint64(), count).to_vec())
+        }
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_BOOLEAN => ClaimValues::Boolean(
+            raw_slice(*raw.Values.pUint64(), count)
+                .iter()
+                .map(|&v| v != 0)
+                .collect(),
+        ),
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_STRING => ClaimValues::String(
+            raw_slice(*raw.Values.ppString() as *const *const u16, count)
+                .iter()
+                .map(|&s| wide_string(s))
+                .collect(),
+        ),
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_FQBN => ClaimValues::Fqbn(
+            raw_slice(*raw.Values.pFqbn(), count)
+                .iter()
+                .map(|v| (v.Version, wide_string(v.Name)))
+                .collect(),
+        ),
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_SID => ClaimValues::Sid(
+            raw_slice(*raw.Values.pOctetString(), count)
+                .iter()
+                .map(|v| wrappers::CopySid(&*(v.pValue as *const Sid)))
+                .collect::<io::Result<_>>()?,
+        ),
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_OCTET_STRING => ClaimValues::OctetString(
+            raw_slice(*raw.Values.pOctetString(), count)
+                .iter()
+                .map(|v| raw_slice(v.pValue as *const u8, v.ValueLength as usize).to_vec())
+                .collect(),
+        ),
+        other => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unknown claim value type {}", other),
+            ))
+        }
+    };
+
+    Ok(ClaimAttribute::new(
+        wide_string(raw.Name),
+        ClaimFlags::from_bits_truncate(raw.Flags),
+        values,
+    ))
+}
@@ -2,6 +2,43 @@ use crate::Acl;
 
 /// Wraps [`IsValidAcl`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-isvalidacl)
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn IsValidAcl(acl: &Acl) -> bool {
     (unsafe { winapi::um::securitybaseapi::IsValidAcl(acl as *const _ as *mut _) }) != 0
 }
+
+/// Portable equivalent of [`IsValidAcl`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-isvalidacl)
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn IsValidAcl(acl: &Acl) -> bool {
+    use crate::winnt::{ACE_HEADER, ACL, ACL_REVISION, ACL_REVISION_DS};
+
+    let header = unsafe { &*(acl as *const _ as *const ACL) };
+    let header_size = std::mem::size_of::<ACL>();
+
+    if header.AclRevision < ACL_REVISION || header.AclRevision > ACL_REVISION_DS {
+        return false;
+    }
+
+    if (header.AclSize as usize) < header_size {
+        return false;
+    }
+
+    let mut offset = header_size;
+
+    for _ in 0..header.AceCount {
+        if offset + std::mem::size_of::<ACE_HEADER>() > header.AclSize as usize {
+            return false;
+        }
+
+        let ace = unsafe { &*((acl as *const _ as *const u8).add(offset) as *const ACE_HEADER) };
+
+        if ace.AceSize < 8 || offset + ace.AceSize as usize > header.AclSize as usize {
+            return false;
+        }
+
+        offset += ace.AceSize as usize;
+    }
+
+    true
+}
@@ -2,7 +2,23 @@ use crate::SecurityDescriptor;
 
 /// Wraps [`IsValidSecurityDescriptor`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-isvalidsecuritydescriptor)
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn IsValidSecurityDescriptor(sd: &SecurityDescriptor) -> bool {
     (unsafe { winapi::um::securitybaseapi::IsValidSecurityDescriptor(sd as *const _ as *mut _) })
         != 0
 }
+
+/// Portable equivalent of [`IsValidSecurityDescriptor`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-isvalidsecuritydescriptor)
+///
+/// Only self-relative security descriptors are supported.
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn IsValidSecurityDescriptor(sd: &SecurityDescriptor) -> bool {
+    use crate::winnt::{
+        SECURITY_DESCRIPTOR_RELATIVE, SECURITY_DESCRIPTOR_REVISION, SE_SELF_RELATIVE,
+    };
+
+    let header = unsafe { &*(sd as *const _ as *const SECURITY_DESCRIPTOR_RELATIVE) };
+
+    header.Revision as u32 == SECURITY_DESCRIPTOR_REVISION && header.Control & SE_SELF_RELATIVE != 0
+}
@@ -2,6 +2,17 @@ use crate::Sid;
 
 /// Wraps [`IsValidSid`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-isvalidsid)
 #[allow(non_snake_case)]
+#[cfg(target_os = "windows")]
 pub fn IsValidSid(sid: &Sid) -> bool {
     (unsafe { winapi::um::securitybaseapi::IsValidSid(sid as *const _ as *mut _) }) != 0
 }
+
+/// Portable equivalent of [`IsValidSid`](https://docs.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-isvalidsid)
+#[allow(non_snake_case)]
+#[cfg(not(target_os = "windows"))]
+pub fn IsValidSid(sid: &Sid) -> bool {
+    use crate::winnt::{SID, SID_MAX_SUB_AUTHORITIES, SID_REVISION};
+
+    let sid = unsafe { &*(sid as *const _ as *const SID) };
+    sid.Revision == SID_REVISION && sid.SubAuthorityCount <= SID_MAX_SUB_AUTHORITIES
+}
@@ -4,6 +4,10 @@
 //! can sometimes be useful to drop straight down into the raw WinAPI calls.
 //! These functions wrap the unsafe calls in safe objects, and are used to
 //! implement the other functionality in this crate.
+//!
+//! On platforms other than Windows, only the wrappers that operate purely on
+//! in-memory data structures are available. They are implemented in pure Rust
+//! with the same behavior as their WinAPI counterparts.
 
 // Implementation note
 //
@@ -12,80 +16,134 @@
 // test code in those sub-modules. However, tests that require multiple
 // wrapped calls should be placed here.
 
+#[cfg(target_os = "windows")]
 mod add_ace;
 mod allocate_and_initialize_sid;
+#[cfg(target_os = "windows")]
 mod build_trustee_with_name;
+#[cfg(target_os = "windows")]
 mod build_trustee_with_sid;
 mod convert_security_descriptor_to_string_security_descriptor;
 mod convert_sid_to_string_sid;
 mod convert_string_security_descriptor_to_security_descriptor;
 mod convert_string_sid_to_sid;
 mod copy_sid;
+#[cfg(target_os = "windows")]
 mod create_well_known_sid;
 mod equal_sid;
 mod get_ace;
 mod get_acl_information;
+#[cfg(target_os = "windows")]
 mod get_effective_rights_from_acl;
+#[cfg(target_os = "windows")]
 mod get_named_security_info;
+mod get_security_descriptor_control;
 mod get_security_descriptor_dacl_sacl;
 mod get_security_descriptor_owner_group;
+#[cfg(target_os = "windows")]
 mod get_security_info;
 mod get_sid_identifier_authority;
 mod get_sid_length_required;
 mod get_sid_sub_authority;
 mod get_sid_sub_authority_count;
+#[cfg(target_os = "windows")]
+mod get_token_information;
+#[cfg(target_os = "windows")]
 mod get_trustee_form;
+#[cfg(target_os = "windows")]
 mod get_trustee_name;
+#[cfg(target_os = "windows")]
 mod get_windows_account_domain_sid;
 mod is_valid_acl;
 mod is_valid_security_descriptor;
 mod is_valid_sid;
+#[cfg(target_os = "windows")]
 mod lookup_account_name;
+#[cfg(target_os = "windows")]
 mod lookup_account_sid;
+#[cfg(target_os = "windows")]
+mod open_process_token;
+#[cfg(target_os = "windows")]
+mod open_thread_token;
+#[cfg(target_os = "windows")]
 mod set_named_security_info;
+#[cfg(target_os = "windows")]
 mod set_security_info;
 
+#[cfg(target_os = "windows")]
 pub use add_ace::AddAce;
 pub use allocate_and_initialize_sid::AllocateAndInitializeSid;
+#[cfg(target_os = "windows")]
 pub use build_trustee_with_name::{BuildTrusteeWithName, BuildTrusteeWithNameOsStr};
+#[cfg(target_os = "windows")]
 pub use build_trustee_with_sid::BuildTrusteeWithSid;
 pub use convert_security_descriptor_to_string_security_descriptor::ConvertSecurityDescriptorToStringSecurityDescriptor;
 pub use convert_sid_to_string_sid::ConvertSidToStringSid;
 pub use convert_string_security_descriptor_to_security_descriptor::ConvertStringSecurityDescriptorToSecurityDescriptor;
 pub use convert_string_sid_to_sid::ConvertStringSidToSid;
 pub use copy_sid::CopySid;
+#[cfg(target_os = "windows")]
 pub use create_well_known_sid::CreateWellKnownSid;
 pub use equal_sid::EqualSid;
 pub use get_ace::GetAce;
 pub use get_acl_information::GetAclInformationSize;
+#[cfg(target_os = "windows")]
 pub use get_effective_rights_from_acl::GetEffectiveRightsFromAcl;
+#[cfg(target_os = "windows")]
 pub use get_named_security_info::GetNamedSecurityInfo;
+pub use get_security_descriptor_control::GetSecurityDescriptorControl;
 pub use get_security_descriptor_dacl_sacl::{GetSecurityDescriptorDacl, GetSecurityDescriptorSacl};
 pub use get_security_descriptor_owner_group::{
     GetSecurityDescriptorGroup, GetSecurityDescriptorOwner,
 };
+#[cfg(target_os = "windows")]
 pub use get_security_info::GetSecurityInfo;
 pub use get_sid_identifier_authority::GetSidIdentifierAuthority;
 pub use get_sid_length_required::GetSidLengthRequired;
 pub use get_sid_sub_authority::{GetSidSubAuthority, GetSidSubAuthorityChecked};
 pub use get_sid_sub_authority_count::GetSidSubAuthorityCount;
+#[cfg(target_os = "windows")]
+pub use get_token_information::{
+    GetTokenInformation, GetTokenInformationClaims, GetTokenInformationGroups,
+    GetTokenInformationLessPrivilegedAppContainer, GetTokenInformationPrivileges,
+    GetTokenInformationSid, GetTokenInformationUser, GetTokenInformationWriteRestricted,
+};
+#[cfg(target_os = "windows")]
 pub use get_trustee_form::GetTrusteeForm;
+#[cfg(target_os = "windows")]
 pub use get_trustee_name::GetTrusteeName;
+#[cfg(target_os = "windows")]
 pub use get_windows_account_domain_sid::GetWindowsAccountDomainSid;
 pub use is_valid_acl::IsValidAcl;
 pub use is_valid_security_descriptor::IsValidSecurityDescriptor;
 pub use is_valid_sid::IsValidSid;
+#[cfg(target_os = "windows")]
 pub use lookup_account_name::LookupAccountName;
+#[cfg(target_os = "windows")]
 pub use lookup_account_sid::LookupAccountSid;
+#[cfg(target_os = "windows")]
+pub use open_process_token::OpenProcessToken;
+#[cfg(target_os = "windows")]
+pub use open_thread_token::OpenThreadToken;
+#[cfg(target_os = "windows")]
 pub use set_named_security_info::SetNamedSecurityInfo;
+#[cfg(target_os = "windows")]
 pub use set_security_info::SetSecurityInfo;
 
+#[cfg(not(target_os = "windows"))]
+pub(crate) use allocate_and_initialize_sid::initialize_sid;
+pub(crate) use get_security_descriptor_dacl_sacl::{
+    security_descriptor_dacl, security_descriptor_sacl,
+};
+
 #[cfg(test)]
 mod test {
     use super::*;
 
     use crate::Sid;
+    #[cfg(target_os = "windows")]
     use std::ffi::OsString;
+    #[cfg(target_os = "windows")]
     use winapi::um::winnt::{WinCapabilityMusicLibrarySid, WinLocalSid, WinWorldSid};
 
     #[test]
@@ -138,6 +196,7 @@ mod test {
     }
 
     #[test]
+    #[cfg(target_os = "windows")]
     fn well_known_sids_are_equal() {
         let world_sid_1 = CreateWellKnownSid(WinWorldSid, None).unwrap();
         let world_sid_2 = CreateWellKnownSid(WinWorldSid, None).unwrap();
@@ -151,6 +210,7 @@ mod test {
     }
 
     #[test]
+    #[cfg(target_os = "windows")]
     fn well_known_sids_stringify() {
         let world_sid = CreateWellKnownSid(WinWorldSid, None).unwrap();
         let local_sid = CreateWellKnownSid(WinLocalSid, None).unwrap();
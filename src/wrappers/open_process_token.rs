@@ -0,0 +1,35 @@
+use std::io;
+use std::os::windows::io::{FromRawHandle, OwnedHandle, RawHandle};
+use std::ptr::null_mut;
+
+/// Wraps [`OpenProcessToken`](https://docs.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-openprocesstoken).
+///
+/// `desired_access` is a combination of the
+/// [token access rights](https://docs.microsoft.com/en-us/windows/win32/secauthz/access-rights-for-access-token-objects),
+/// such as `TOKEN_QUERY`. The returned handle is closed when dropped.
+///
+/// ```
+/// use windows_permissions::wrappers::OpenProcessToken;
+/// use winapi::um::processthreadsapi::GetCurrentProcess;
+/// use winapi::um::winnt::TOKEN_QUERY;
+///
+/// let token = OpenProcessToken(unsafe { GetCurrentProcess() } as _, TOKEN_QUERY).unwrap();
+/// ```
+#[allow(non_snake_case)]
+pub fn OpenProcessToken(process: RawHandle, desired_access: u32) -> io::Result<OwnedHandle> {
+    let mut token = null_mut();
+
+    let result = unsafe {
+        winapi::um::processthreadsapi::OpenProcessToken(
+            process as *mut _,
+            desired_access,
+            &mut token,
+        )
+    };
+
+    if result == 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(unsafe { OwnedHandle::from_raw_handle(token as RawHandle) })
+    }
+}
@@ -0,0 +1,31 @@
+use std::io;
+use std::os::windows::io::{FromRawHandle, OwnedHandle, RawHandle};
+use std::ptr::null_mut;
+
+/// Wraps [`OpenThreadToken`](https://docs.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-openthreadtoken).
+///
+/// If the thread is not impersonating, this fails with `ERROR_NO_TOKEN`. The
+/// returned handle is closed when dropped.
+#[allow(non_snake_case)]
+pub fn OpenThreadToken(
+    thread: RawHandle,
+    desired_access: u32,
+    open_as_self: bool,
+) -> io::Result<OwnedHandle> {
+    let mut token = null_mut();
+
+    let result = unsafe {
+        winapi::um::processthreadsapi::OpenThreadToken(
+            thread as *mut _,
+            desired_access,
+            open_as_self as i32,
+            &mut token,
+        )
+    };
+
+    if result == 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(unsafe { OwnedHandle::from_raw_handle(token as RawHandle) })
+    }
+}
@@ -0,0 +1,136 @@
+//! # Windows permissions macros
+//!
+//! Compile-time SID and SDDL literals for
+//! [`windows-permissions`](https://docs.rs/windows-permissions).
+//!
+//! [`sid!`] and [`sddl!`] parse their string when the program is compiled.
+//! A string that does not parse is a compile error that points at it. Each
+//! expands to a constant reference into the binary form, which is built
+//! without calling Windows, so both work on every platform and can
+//! initialize `const` items.
+//!
+//! SID aliases that depend on the current domain or machine, like `DA` or
+//! `LA`, cannot be resolved at compile time and are an error.
+//!
+//! The expansions refer to `windows_permissions`, which must also be a
+//! dependency.
+
+#![deny(missing_docs)]
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, LitStr};
+
+/// Report a parse error on the string literal
+fn error(literal: &LitStr, err: std::io::Error) -> TokenStream {
+    syn::Error::new(literal.span(), err)
+        .to_compile_error()
+        .into()
+}
+
+/// Parse a SID at compile time
+///
+/// The SID can be in `S-1-…` form or an SDDL alias. The expansion is a
+/// `&'static Sid`.
+///
+/// ```
+/// use windows_permissions::{LocalBox, Sid};
+/// use windows_permissions_macros::sid;
+///
+/// const ADMINISTRATORS: &Sid = sid!("BA");
+///
+/// let parsed: LocalBox<Sid> = "S-1-5-32-544".parse().unwrap();
+/// assert_eq!(ADMINISTRATORS, &*parsed);
+/// assert_eq!(sid!("S-1-5-21-1-2-3-500").sub_authorities(), &[21, 1, 2, 3, 500]);
+/// ```
+///
+/// Invalid SIDs do not compile:
+///
+/// ```compile_fail
+/// let sid = windows_permissions_macros::sid!("S-1-5-X");
+/// ```
+///
+/// ```compile_fail
+/// let domain_admins = windows_permissions_macros::sid!("DA");
+/// ```
+#[proc_macro]
+pub fn sid(input: TokenStream) -> TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+    let (id_authority, sub_authorities) =
+        match windows_permissions::__private::parse_sid(&literal.value()) {
+            Ok(parts) => parts,
+            Err(err) => return error(&literal, err),
+        };
+    let count = sub_authorities.len();
+
+    let expanded = quote! {{
+        const SID: &::windows_permissions::__private::StaticSid<#count> =
+            &::windows_permissions::__private::StaticSid::new(
+                [#(#id_authority),*],
+                [#(#sub_authorities),*],
+            );
+        const VALUE: &::windows_permissions::Sid = SID.as_sid();
+        VALUE
+    }};
+
+    expanded.into()
+}
+
+/// Parse a security descriptor in SDDL at compile time
+///
+/// The expansion is a `&'static SecurityDescriptor`, in self-relative form.
+///
+/// ```
+/// use windows_permissions::constants::AceType;
+/// use windows_permissions::SecurityDescriptor;
+/// use windows_permissions_macros::{sddl, sid};
+///
+/// const DEFAULT: &SecurityDescriptor = sddl!("O:BAD:P(A;OICI;FA;;;SY)(A;;0x1200a9;;;BU)");
+///
+/// assert_eq!(DEFAULT.owner(), Some(sid!("BA")));
+///
+/// let dacl = DEFAULT.dacl().unwrap();
+/// assert_eq!(dacl.len(), 2);
+/// assert_eq!(dacl.get_ace(1).unwrap().ace_type(), AceType::ACCESS_ALLOWED_ACE_TYPE);
+/// assert_eq!(dacl.get_ace(1).unwrap().sid(), Some(sid!("BU")));
+/// ```
+///
+/// The expansion has no `unsafe` block, so it works in crates that forbid
+/// unsafe code:
+///
+/// ```
+/// #![forbid(unsafe_code)]
+///
+/// use windows_permissions::SecurityDescriptor;
+/// use windows_permissions_macros::sddl;
+///
+/// const LOCKED: &SecurityDescriptor = sddl!("D:P");
+///
+/// assert_eq!(LOCKED.dacl().unwrap().len(), 0);
+/// ```
+///
+/// Invalid SDDL does not compile:
+///
+/// ```compile_fail
+/// let sd = windows_permissions_macros::sddl!("D:(A;;FA;;;NOT_A_SID)");
+/// ```
+#[proc_macro]
+pub fn sddl(input: TokenStream) -> TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+    let bytes = match windows_permissions::__private::sddl_bytes(&literal.value()) {
+        Ok(bytes) => bytes,
+        Err(err) => return error(&literal, err),
+    };
+    let len = bytes.len();
+
+    let expanded = quote! {{
+        const BYTES: &::windows_permissions::__private::Aligned<#len> =
+            &::windows_permissions::__private::Aligned::from_sddl_bytes([#(#bytes),*]);
+        const VALUE: &::windows_permissions::SecurityDescriptor = BYTES.as_security_descriptor();
+        VALUE
+    }};
+
+    expanded.into()
+}